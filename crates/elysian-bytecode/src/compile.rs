@@ -0,0 +1,1057 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Value},
+    module::{
+        property_type, FunctionDefinition, FunctionIdentifier, Layout, Module, NumericType,
+        StructIdentifier, Type, CONTEXT,
+    },
+};
+
+use crate::{BytecodeError, Op, Program};
+
+/// Result of compiling an expression
+///
+/// Structs are kept as their individual members,
+/// each of which lives in its own register.
+#[derive(Debug, Clone)]
+enum Lowered {
+    Boolean(u32),
+    Number(NumericType, u32),
+    Struct(StructIdentifier, Vec<Lowered>),
+}
+
+impl Lowered {
+    fn ty(&self) -> Type {
+        match self {
+            Lowered::Boolean(_) => Type::Boolean,
+            Lowered::Number(n, _) => Type::Number(*n),
+            Lowered::Struct(id, _) => Type::Struct(id.clone()),
+        }
+    }
+
+    fn leaves(&self, out: &mut Vec<u32>) {
+        match self {
+            Lowered::Boolean(v) | Lowered::Number(_, v) => out.push(*v),
+            Lowered::Struct(_, members) => {
+                for member in members {
+                    member.leaves(out)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unary {
+    Neg,
+    Abs,
+    Sign,
+    Round,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp2,
+    Log2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+/// Registers visible to a function being inlined
+#[derive(Debug, Default)]
+struct Frame {
+    function: Option<FunctionIdentifier>,
+    properties: HashMap<PropertyIdentifier, Lowered>,
+    output: Option<Lowered>,
+    /// Offsets of the pending jump targets of `Break` statements in each enclosing loop
+    loops: Vec<Vec<usize>>,
+}
+
+/// Compiles a module into a single program by inlining every call
+///
+/// Registers are allocated as a stack:
+/// temporaries are released at the end of the statement that created them,
+/// and an inlined function's parameters and locals on return.
+pub(crate) struct Compiler<'a> {
+    layout: Layout<'a>,
+    functions: HashMap<&'a FunctionIdentifier, &'a FunctionDefinition>,
+    code: Vec<u32>,
+    next: u32,
+    registers: u32,
+    frames: Vec<Frame>,
+}
+
+impl<'a> Compiler<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        Compiler {
+            layout: Layout::new(module),
+            functions: module
+                .function_definitions
+                .iter()
+                .map(|def| (&def.id, def))
+                .collect(),
+            code: Default::default(),
+            next: 0,
+            registers: 0,
+            frames: Default::default(),
+        }
+    }
+
+    /// Compile a call to the module's entry point,
+    /// whose context occupies the first registers on entry and exit
+    pub fn program(mut self, module: &Module) -> Result<Program, BytecodeError> {
+        let prop = PropertyIdentifier(CONTEXT);
+        let ty = property_type(&prop)?;
+        let context = self.alloc(ty)?;
+
+        self.frames.push(Frame {
+            properties: [(prop.clone(), context.clone())].into_iter().collect(),
+            ..Default::default()
+        });
+
+        let value = self.expr(&module.call(Expr::Read(vec![prop.clone()])))?;
+        self.assign(&context, value)?;
+        self.emit(Op::Halt, &[]);
+
+        Ok(Program {
+            code: self.code,
+            registers: self.registers,
+            context: self.layout.leaf_paths(vec![], ty)?,
+        })
+    }
+
+    fn emit(&mut self, op: Op, operands: &[u32]) {
+        debug_assert_eq!(op.operands(), operands.len());
+        self.code.push(op.word());
+        self.code.extend(operands);
+    }
+
+    fn register(&mut self) -> u32 {
+        let register = self.next;
+        self.next += 1;
+        self.registers = self.registers.max(self.next);
+        register
+    }
+
+    fn alloc(&mut self, ty: &Type) -> Result<Lowered, BytecodeError> {
+        Ok(match ty {
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.alloc(ty)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Type::Boolean => Lowered::Boolean(self.register()),
+            Type::Number(n) => Lowered::Number(*n, self.register()),
+        })
+    }
+
+    fn constant(&mut self, word: u32) -> u32 {
+        let dst = self.register();
+        self.emit(Op::Const, &[dst, word]);
+        dst
+    }
+
+    fn f32_const(&mut self, n: f32) -> u32 {
+        self.constant(n.to_bits())
+    }
+
+    fn unary_op(&mut self, op: Op, a: u32) -> u32 {
+        let dst = self.register();
+        self.emit(op, &[dst, a]);
+        dst
+    }
+
+    fn binary_op(&mut self, op: Op, a: u32, b: u32) -> u32 {
+        let dst = self.register();
+        self.emit(op, &[dst, a, b]);
+        dst
+    }
+
+    fn select(&mut self, cond: u32, a: u32, b: u32) -> u32 {
+        let dst = self.register();
+        self.emit(Op::Select, &[dst, cond, a, b]);
+        dst
+    }
+
+    /// Emit a jump whose target is filled in by `patch`
+    fn jump(&mut self, op: Op, operands: &[u32]) -> usize {
+        self.emit(op, &[operands, &[0]].concat());
+        self.code.len() - 1
+    }
+
+    /// Point a pending jump at the next instruction
+    fn patch(&mut self, site: usize) {
+        self.code[site] = self.code.len() as u32;
+    }
+
+    fn frame(&mut self) -> &mut Frame {
+        self.frames.last_mut().expect("No frame")
+    }
+
+    fn block(&mut self, Block(stmts): &Block) -> Result<(), BytecodeError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), BytecodeError> {
+        let mark = self.next;
+
+        match stmt {
+            Stmt::Block(block) => self.block(block)?,
+            Stmt::Bind { prop, expr } => {
+                let value = self.expr(expr)?;
+                let local = self.local(std::slice::from_ref(prop))?.clone();
+                self.assign(&local, value)?;
+            }
+            Stmt::Write { path, expr } => {
+                let value = self.expr(expr)?;
+                let local = self.local(path)?.clone();
+                self.assign(&local, value)?;
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let Lowered::Boolean(cond) = self.expr(cond)? else {
+                    return Err(BytecodeError::InvalidStmt(format!("If {cond:?}")));
+                };
+
+                let otherwise_site = self.jump(Op::JumpIfZero, &[cond]);
+                self.next = mark;
+                self.stmt(then)?;
+                match otherwise {
+                    Some(otherwise) => {
+                        let end_site = self.jump(Op::Jump, &[]);
+                        self.patch(otherwise_site);
+                        self.stmt(otherwise)?;
+                        self.patch(end_site);
+                    }
+                    None => self.patch(otherwise_site),
+                }
+            }
+            Stmt::Loop { stmt } => {
+                let start = self.code.len() as u32;
+                self.frame().loops.push(vec![]);
+                self.stmt(stmt)?;
+                self.emit(Op::Jump, &[start]);
+                for site in self.frame().loops.pop().unwrap() {
+                    self.patch(site);
+                }
+            }
+            Stmt::Break => {
+                if self.frame().loops.is_empty() {
+                    return Err(BytecodeError::InvalidStmt("Break outside of Loop".into()));
+                }
+                let site = self.jump(Op::Jump, &[]);
+                self.frame().loops.last_mut().unwrap().push(site);
+            }
+            Stmt::Output(expr) => {
+                let value = self.expr(expr)?;
+                let output = self
+                    .frame()
+                    .output
+                    .clone()
+                    .ok_or_else(|| BytecodeError::InvalidStmt(format!("Output {expr:?}")))?;
+                self.assign(&output, value)?;
+            }
+        }
+
+        self.next = mark;
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Lowered, BytecodeError> {
+        Ok(match expr {
+            Expr::Literal(value) => self.literal(value)?,
+            Expr::Struct(id, members) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(id)? {
+                    out.push(match members.get(prop) {
+                        Some(expr) => {
+                            let value = self.expr(expr)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Expr::Read(path) => self.local(path)?.clone(),
+            Expr::Call { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<_, _>>()?;
+                self.call(function, args)?
+            }
+            Expr::Neg(t) => self.unary(Unary::Neg, t)?,
+            Expr::Abs(t) => self.unary(Unary::Abs, t)?,
+            Expr::Sign(t) => self.unary(Unary::Sign, t)?,
+            Expr::Round(t) => self.unary(Unary::Round, t)?,
+            Expr::Sin(t) => self.unary(Unary::Sin, t)?,
+            Expr::Cos(t) => self.unary(Unary::Cos, t)?,
+            Expr::Tan(t) => self.unary(Unary::Tan, t)?,
+            Expr::Asin(t) => self.unary(Unary::Asin, t)?,
+            Expr::Acos(t) => self.unary(Unary::Acos, t)?,
+            Expr::Atan(t) => self.unary(Unary::Atan, t)?,
+            Expr::Exp2(t) => self.unary(Unary::Exp2, t)?,
+            Expr::Log2(t) => self.unary(Unary::Log2, t)?,
+            Expr::Length(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Abs, v)?,
+                v @ Lowered::Struct(..) => {
+                    let dot = self.dot(v.clone(), v)?;
+                    Lowered::Number(NumericType::Float, self.unary_op(Op::FSqrt, dot))
+                }
+                v => return Err(invalid_unary("Length", &v)),
+            },
+            Expr::Normalize(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Sign, v)?,
+                v @ Lowered::Struct(..) => self.normalize(v)?,
+                v => return Err(invalid_unary("Normalize", &v)),
+            },
+            Expr::Add(lhs, rhs) => self.binary(Binary::Add, lhs, rhs)?,
+            Expr::Sub(lhs, rhs) => self.binary(Binary::Sub, lhs, rhs)?,
+            Expr::Mul(lhs, rhs) => self.binary(Binary::Mul, lhs, rhs)?,
+            Expr::Div(lhs, rhs) => self.binary(Binary::Div, lhs, rhs)?,
+            Expr::Mod(lhs, rhs) => self.binary(Binary::Mod, lhs, rhs)?,
+            Expr::Min(lhs, rhs) => self.binary(Binary::Min, lhs, rhs)?,
+            Expr::Max(lhs, rhs) => self.binary(Binary::Max, lhs, rhs)?,
+            Expr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.equal(lhs, rhs)?)
+            }
+            Expr::Ne(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let eq = self.equal(lhs, rhs)?;
+                Lowered::Boolean(self.unary_op(Op::Not, eq))
+            }
+            Expr::Lt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(true, lhs, rhs)?)
+            }
+            Expr::Gt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(false, lhs, rhs)?)
+            }
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (Lowered::Boolean(lhs), Lowered::Boolean(rhs)) => {
+                    let op = if let Expr::And(..) = expr {
+                        Op::And
+                    } else {
+                        Op::Or
+                    };
+                    Lowered::Boolean(self.binary_op(op, lhs, rhs))
+                }
+                (lhs, rhs) => return Err(invalid_binary("Logic", &lhs, &rhs)),
+            },
+            Expr::Dot(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (lhs, rhs) {
+                    (lhs @ Lowered::Number(..), rhs @ Lowered::Number(..)) => {
+                        self.map_binary(Binary::Mul, lhs, rhs)?
+                    }
+                    (lhs, rhs) => Lowered::Number(NumericType::Float, self.dot(lhs, rhs)?),
+                }
+            }
+            Expr::Atan2(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (
+                    Lowered::Number(NumericType::Float, y),
+                    Lowered::Number(NumericType::Float, x),
+                ) => Lowered::Number(NumericType::Float, self.binary_op(Op::FAtan2, y, x)),
+                (lhs, rhs) => return Err(invalid_binary("Atan2", &lhs, &rhs)),
+            },
+            Expr::Mix(lhs, rhs, t) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let Lowered::Number(NumericType::Float, t) = self.expr(t)? else {
+                    return Err(BytecodeError::InvalidExpr(format!("Mix factor {t:?}")));
+                };
+                self.mix(lhs, rhs, t)?
+            }
+            Expr::Clamp(t, min, max) => {
+                let (t, min, max) = (self.expr(t)?, self.expr(min)?, self.expr(max)?);
+                self.clamp(t, min, max)?
+            }
+        })
+    }
+
+    fn literal(&mut self, value: &Value) -> Result<Lowered, BytecodeError> {
+        Ok(match value {
+            Value::Boolean(b) => Lowered::Boolean(self.constant(*b as u32)),
+            Value::Number(Number::UInt(n)) => {
+                Lowered::Number(NumericType::UInt, self.constant(*n as u32))
+            }
+            Value::Number(Number::SInt(n)) => {
+                Lowered::Number(NumericType::SInt, self.constant(*n as i32 as u32))
+            }
+            Value::Number(Number::Float(n)) => {
+                Lowered::Number(NumericType::Float, self.f32_const(*n as f32))
+            }
+            Value::Struct(s) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(&s.id)? {
+                    out.push(match s.members.get(prop) {
+                        Some(value) => {
+                            let value = self.literal(value)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(s.id.clone(), out)
+            }
+        })
+    }
+
+    /// Explicitly zeroed value,
+    /// since registers are reused with stale contents
+    fn zero(&mut self, ty: &Type) -> Result<Lowered, BytecodeError> {
+        Ok(match ty {
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.zero(ty)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            // Zero has the same representation for every scalar type
+            Type::Number(n) => Lowered::Number(*n, self.constant(0)),
+            Type::Boolean => Lowered::Boolean(self.constant(0)),
+        })
+    }
+
+    /// Convert a value to the given type, as required when binding it
+    fn cast(&mut self, value: Lowered, ty: &Type) -> Result<Lowered, BytecodeError> {
+        use NumericType::*;
+
+        Ok(match (value, ty) {
+            (value @ Lowered::Boolean(_), Type::Boolean) => value,
+            (Lowered::Number(from, v), Type::Number(to)) => {
+                let op = match (from, to) {
+                    (Float, SInt) => Op::FToS,
+                    (Float, UInt) => Op::FToU,
+                    (SInt, Float) => Op::SToF,
+                    (UInt, Float) => Op::UToF,
+                    _ => return Ok(Lowered::Number(*to, v)),
+                };
+                Lowered::Number(*to, self.unary_op(op, v))
+            }
+            (Lowered::Struct(id, members), Type::Struct(to)) if id == *to => {
+                let mut out = vec![];
+                for (member, (_, ty)) in members.into_iter().zip(self.layout.fields(to)?) {
+                    out.push(self.cast(member, ty)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (value, ty) => {
+                return Err(BytecodeError::InvalidExpr(format!(
+                    "Can't convert {} to {}",
+                    value.ty().name(),
+                    ty.name()
+                )))
+            }
+        })
+    }
+
+    /// Find the registers at the end of a path
+    fn local(&mut self, path: &[PropertyIdentifier]) -> Result<&Lowered, BytecodeError> {
+        let (root, rest) = path
+            .split_first()
+            .ok_or_else(|| BytecodeError::InvalidRead(path_name(path)))?;
+
+        let layout = self.layout;
+        let local = self
+            .frame()
+            .properties
+            .get(root)
+            .ok_or_else(|| BytecodeError::InvalidRead(path_name(path)))?;
+
+        find(&layout, local, rest)
+    }
+
+    /// Copy a value into a set of registers
+    ///
+    /// Sources which would be overwritten before being read
+    /// are copied into temporaries first.
+    fn assign(&mut self, local: &Lowered, value: Lowered) -> Result<(), BytecodeError> {
+        let value = self.cast(value, &local.ty())?;
+
+        let mut sources = vec![];
+        value.leaves(&mut sources);
+        let mut destinations = vec![];
+        local.leaves(&mut destinations);
+
+        let clobbered = sources.iter().enumerate().any(|(i, source)| {
+            destinations[..i]
+                .iter()
+                .any(|destination| destination == source)
+        });
+        if clobbered {
+            sources = sources
+                .into_iter()
+                .map(|source| self.unary_op(Op::Copy, source))
+                .collect();
+        }
+
+        for (source, destination) in sources.into_iter().zip(destinations) {
+            if source != destination {
+                self.emit(Op::Copy, &[destination, source]);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Inline a call to a function
+    fn call(
+        &mut self,
+        function: &FunctionIdentifier,
+        args: Vec<Lowered>,
+    ) -> Result<Lowered, BytecodeError> {
+        let def = *self
+            .functions
+            .get(function)
+            .ok_or_else(|| BytecodeError::MissingFunction(function.name_unique()))?;
+
+        if self
+            .frames
+            .iter()
+            .any(|frame| frame.function.as_ref() == Some(function))
+        {
+            return Err(BytecodeError::RecursiveCall(function.name_unique()));
+        }
+
+        if args.len() != def.inputs.len() {
+            return Err(BytecodeError::InvalidExpr(format!(
+                "{} takes {} arguments, got {}",
+                function.name_unique(),
+                def.inputs.len(),
+                args.len()
+            )));
+        }
+
+        let output = self.alloc(property_type(&def.output)?)?;
+        let mark = self.next;
+
+        // Parameters are copied, since functions may write to their inputs
+        let mut properties = HashMap::new();
+        for (arg, input) in args.into_iter().zip(def.inputs.iter()) {
+            let param = self.alloc(property_type(&input.id)?)?;
+            self.assign(&param, arg)?;
+            properties.insert(input.id.clone(), param);
+        }
+
+        // Locals are allocated up front so that they outlive
+        // the temporaries of the statement binding them
+        let mut bound = vec![];
+        for stmt in def.block.0.iter() {
+            bound_properties(stmt, &mut bound);
+        }
+        for prop in bound {
+            if let Entry::Vacant(entry) = properties.entry(prop) {
+                let local = self.alloc(property_type(entry.key())?)?;
+                entry.insert(local);
+            }
+        }
+
+        self.frames.push(Frame {
+            function: Some(function.clone()),
+            properties,
+            output: Some(output.clone()),
+            loops: vec![],
+        });
+        self.block(&def.block)?;
+        self.frames.pop();
+
+        self.next = mark;
+        Ok(output)
+    }
+
+    fn unary(&mut self, op: Unary, t: &Expr) -> Result<Lowered, BytecodeError> {
+        let t = self.expr(t)?;
+        self.map_unary(op, t)
+    }
+
+    /// Apply a unary operator to a value, or to each member of a struct
+    fn map_unary(&mut self, op: Unary, t: Lowered) -> Result<Lowered, BytecodeError> {
+        let (n, v) = match t {
+            Lowered::Struct(id, members) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_unary(op, member)?);
+                }
+                return Ok(Lowered::Struct(id, out));
+            }
+            Lowered::Number(n, v) => (n, v),
+            t => return Err(invalid_unary(&format!("{op:?}"), &t)),
+        };
+
+        let v = match (op, n) {
+            (Unary::Neg, NumericType::Float) => self.unary_op(Op::FNeg, v),
+            (Unary::Neg, NumericType::SInt) => {
+                let zero = self.constant(0);
+                self.binary_op(Op::ISub, zero, v)
+            }
+            (Unary::Abs, NumericType::Float) => self.unary_op(Op::FAbs, v),
+            (Unary::Abs, NumericType::SInt) => {
+                let zero = self.constant(0);
+                let neg = self.binary_op(Op::ISub, zero, v);
+                let negative = self.binary_op(Op::SLt, v, zero);
+                self.select(negative, neg, v)
+            }
+            (Unary::Sign, NumericType::Float) => {
+                // Matches f32::signum: +-1 for signed zeroes, NaN for NaN
+                let one = self.f32_const(1.0);
+                let sign = self.binary_op(Op::FCopysign, one, v);
+                let is_number = self.binary_op(Op::FEq, v, v);
+                self.select(is_number, sign, v)
+            }
+            (Unary::Sign, NumericType::SInt) => {
+                let zero = self.constant(0);
+                let positive = self.binary_op(Op::SGt, v, zero);
+                let negative = self.binary_op(Op::SLt, v, zero);
+                self.binary_op(Op::ISub, positive, negative)
+            }
+            (Unary::Round, NumericType::Float) => {
+                // Matches f32::round, rounding half-way cases away from zero
+                let t = self.unary_op(Op::FTrunc, v);
+                let fract = self.binary_op(Op::FSub, v, t);
+                let fract = self.unary_op(Op::FAbs, fract);
+                let half = self.f32_const(0.5);
+                let down = self.binary_op(Op::FLt, fract, half);
+                let one = self.f32_const(1.0);
+                let sign = self.binary_op(Op::FCopysign, one, v);
+                let up = self.binary_op(Op::FAdd, t, sign);
+                self.select(down, t, up)
+            }
+            (Unary::Sin, NumericType::Float) => self.unary_op(Op::FSin, v),
+            (Unary::Cos, NumericType::Float) => self.unary_op(Op::FCos, v),
+            (Unary::Tan, NumericType::Float) => self.unary_op(Op::FTan, v),
+            (Unary::Asin, NumericType::Float) => self.unary_op(Op::FAsin, v),
+            (Unary::Acos, NumericType::Float) => self.unary_op(Op::FAcos, v),
+            (Unary::Atan, NumericType::Float) => self.unary_op(Op::FAtan, v),
+            (Unary::Exp2, NumericType::Float) => self.unary_op(Op::FExp2, v),
+            (Unary::Log2, NumericType::Float) => self.unary_op(Op::FLog2, v),
+            _ => return Err(invalid_unary(&format!("{op:?}"), &Lowered::Number(n, v))),
+        };
+
+        Ok(Lowered::Number(n, v))
+    }
+
+    fn binary(&mut self, op: Binary, lhs: &Expr, rhs: &Expr) -> Result<Lowered, BytecodeError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        self.map_binary(op, lhs, rhs)
+    }
+
+    /// Apply a binary operator to a pair of values
+    ///
+    /// Operations between a struct and a number apply to each member of the struct,
+    /// and operations between structs of the same type apply memberwise,
+    /// except for matrix multiplication.
+    fn map_binary(
+        &mut self,
+        op: Binary,
+        lhs: Lowered,
+        rhs: Lowered,
+    ) -> Result<Lowered, BytecodeError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => {
+                Lowered::Number(a, self.scalar_binary(op, a, x, y))
+            }
+            (Lowered::Struct(id, members), rhs @ Lowered::Number(..)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, member, rhs.clone())?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (lhs @ Lowered::Number(..), Lowered::Struct(id, members)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, lhs.clone(), member)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (Lowered::Struct(id, columns), rhs @ Lowered::Struct(..))
+                if op == Binary::Mul && is_matrix(&id) =>
+            {
+                self.matrix_mul(id, columns, rhs)?
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.map_binary(op, lhs, rhs)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary(&format!("{op:?}"), &lhs, &rhs)),
+        })
+    }
+
+    fn scalar_binary(&mut self, op: Binary, n: NumericType, x: u32, y: u32) -> u32 {
+        use NumericType::*;
+
+        let op = match (op, n) {
+            (Binary::Add, Float) => Op::FAdd,
+            (Binary::Add, _) => Op::IAdd,
+            (Binary::Sub, Float) => Op::FSub,
+            (Binary::Sub, _) => Op::ISub,
+            (Binary::Mul, Float) => Op::FMul,
+            (Binary::Mul, _) => Op::IMul,
+            (Binary::Div, Float) => Op::FDiv,
+            (Binary::Div, SInt) => Op::SDiv,
+            (Binary::Div, UInt) => Op::UDiv,
+            (Binary::Mod, UInt) => Op::URem,
+            (Binary::Mod, n) => return self.rem_euclid(n, x, y),
+            (Binary::Min, Float) => Op::FMin,
+            (Binary::Max, Float) => Op::FMax,
+            (Binary::Min | Binary::Max, n) => {
+                let cond = self.binary_op(
+                    match (op, n) {
+                        (Binary::Min, SInt) => Op::SLt,
+                        (Binary::Min, _) => Op::ULt,
+                        (_, SInt) => Op::SGt,
+                        _ => Op::UGt,
+                    },
+                    x,
+                    y,
+                );
+                return self.select(cond, x, y);
+            }
+        };
+
+        self.binary_op(op, x, y)
+    }
+
+    /// Matches `rem_euclid`, whose result is never negative
+    fn rem_euclid(&mut self, n: NumericType, x: u32, y: u32) -> u32 {
+        let float = n == NumericType::Float;
+
+        // Remainder truncated towards zero
+        let r = if float {
+            let q = self.binary_op(Op::FDiv, x, y);
+            let q = self.unary_op(Op::FTrunc, q);
+            let q = self.binary_op(Op::FMul, q, y);
+            self.binary_op(Op::FSub, x, q)
+        } else {
+            self.binary_op(Op::SRem, x, y)
+        };
+
+        let Ok(Lowered::Number(_, abs_y)) = self.map_unary(Unary::Abs, Lowered::Number(n, y))
+        else {
+            unreachable!()
+        };
+
+        // r < 0 ? r + |y| : r
+        let (add, lt) = if float {
+            (Op::FAdd, Op::FLt)
+        } else {
+            (Op::IAdd, Op::SLt)
+        };
+        let wrapped = self.binary_op(add, r, abs_y);
+        let zero = self.constant(0);
+        let negative = self.binary_op(lt, r, zero);
+        self.select(negative, wrapped, r)
+    }
+
+    /// Multiply a column-major matrix by a vector or another matrix
+    fn matrix_mul(
+        &mut self,
+        id: StructIdentifier,
+        columns: Vec<Lowered>,
+        rhs: Lowered,
+    ) -> Result<Lowered, BytecodeError> {
+        let Lowered::Struct(rhs_id, rhs_members) = rhs else {
+            unreachable!()
+        };
+
+        if !is_matrix(&rhs_id) {
+            return self.matrix_vector_mul(&columns, Lowered::Struct(rhs_id, rhs_members));
+        }
+
+        let mut out = vec![];
+        for column in rhs_members {
+            out.push(self.matrix_vector_mul(&columns, column)?);
+        }
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn matrix_vector_mul(
+        &mut self,
+        columns: &[Lowered],
+        vector: Lowered,
+    ) -> Result<Lowered, BytecodeError> {
+        let invalid = || BytecodeError::InvalidExpr("Matrix multiplication".into());
+
+        let Lowered::Struct(id, components) = vector else {
+            return Err(invalid());
+        };
+
+        let columns = columns
+            .iter()
+            .map(|column| floats(column).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let components = floats(&Lowered::Struct(id.clone(), components)).ok_or_else(invalid)?;
+
+        if columns.len() != components.len() || columns.iter().any(|c| c.len() != columns.len()) {
+            return Err(invalid());
+        }
+
+        let mut out = vec![];
+        for row in 0..columns.len() {
+            let products = columns
+                .iter()
+                .zip(components.iter())
+                .map(|(column, component)| (column[row], *component))
+                .collect::<Vec<_>>();
+            out.push(Lowered::Number(
+                NumericType::Float,
+                self.sum_products(&products),
+            ));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    /// Sum of the products of each pair of registers, accumulated left to right
+    fn sum_products(&mut self, products: &[(u32, u32)]) -> u32 {
+        let mut sum = None;
+        for (a, b) in products {
+            let product = self.binary_op(Op::FMul, *a, *b);
+            sum = Some(match sum {
+                Some(sum) => self.binary_op(Op::FAdd, sum, product),
+                None => product,
+            });
+        }
+        sum.expect("Empty sum")
+    }
+
+    fn dot(&mut self, lhs: Lowered, rhs: Lowered) -> Result<u32, BytecodeError> {
+        let (a, b) = match (floats(&lhs), floats(&rhs)) {
+            (Some(a), Some(b)) if lhs.ty() == rhs.ty() && !a.is_empty() => (a, b),
+            _ => return Err(invalid_binary("Dot", &lhs, &rhs)),
+        };
+
+        Ok(self.sum_products(&a.into_iter().zip(b).collect::<Vec<_>>()))
+    }
+
+    /// Matches glam's `normalize_or_zero`
+    fn normalize(&mut self, v: Lowered) -> Result<Lowered, BytecodeError> {
+        let dot = self.dot(v.clone(), v.clone())?;
+
+        let one = self.f32_const(1.0);
+        let length = self.unary_op(Op::FSqrt, dot);
+        let rcp = self.binary_op(Op::FDiv, one, length);
+
+        let abs = self.unary_op(Op::FAbs, rcp);
+        let infinity = self.f32_const(f32::INFINITY);
+        let finite = self.binary_op(Op::FLt, abs, infinity);
+        let zero = self.f32_const(0.0);
+        let positive = self.binary_op(Op::FGt, rcp, zero);
+        let valid = self.binary_op(Op::And, finite, positive);
+
+        let Lowered::Struct(id, members) = v else {
+            unreachable!()
+        };
+
+        let mut out = vec![];
+        for member in members {
+            let Lowered::Number(NumericType::Float, member) = member else {
+                return Err(invalid_unary("Normalize", &member));
+            };
+            let scaled = self.binary_op(Op::FMul, member, rcp);
+            out.push(Lowered::Number(
+                NumericType::Float,
+                self.select(valid, scaled, zero),
+            ));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    /// Whether two values are equal
+    fn equal(&mut self, lhs: Lowered, rhs: Lowered) -> Result<u32, BytecodeError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Boolean(x), Lowered::Boolean(y)) => self.binary_op(Op::IEq, x, y),
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => self.binary_op(
+                match a {
+                    NumericType::Float => Op::FEq,
+                    _ => Op::IEq,
+                },
+                x,
+                y,
+            ),
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = self.constant(1);
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    let eq = self.equal(lhs, rhs)?;
+                    out = self.binary_op(Op::And, out, eq);
+                }
+                out
+            }
+            (lhs, rhs) => return Err(invalid_binary("Eq", &lhs, &rhs)),
+        })
+    }
+
+    /// Ordered comparison of two numbers
+    fn compare(&mut self, less: bool, lhs: Lowered, rhs: Lowered) -> Result<u32, BytecodeError> {
+        let (n, x, y) = match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => (a, x, y),
+            (lhs, rhs) => return Err(invalid_binary(if less { "Lt" } else { "Gt" }, &lhs, &rhs)),
+        };
+
+        let op = match (n, less) {
+            (NumericType::Float, true) => Op::FLt,
+            (NumericType::Float, false) => Op::FGt,
+            (NumericType::SInt, true) => Op::SLt,
+            (NumericType::SInt, false) => Op::SGt,
+            (NumericType::UInt, true) => Op::ULt,
+            (NumericType::UInt, false) => Op::UGt,
+        };
+        Ok(self.binary_op(op, x, y))
+    }
+
+    fn mix(&mut self, lhs: Lowered, rhs: Lowered, t: u32) -> Result<Lowered, BytecodeError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(NumericType::Float, a), Lowered::Number(NumericType::Float, b)) => {
+                let one = self.f32_const(1.0);
+                let s = self.binary_op(Op::FSub, one, t);
+                let a = self.binary_op(Op::FMul, s, a);
+                let b = self.binary_op(Op::FMul, t, b);
+                Lowered::Number(NumericType::Float, self.binary_op(Op::FAdd, a, b))
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.mix(lhs, rhs, t)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary("Mix", &lhs, &rhs)),
+        })
+    }
+
+    fn clamp(&mut self, t: Lowered, min: Lowered, max: Lowered) -> Result<Lowered, BytecodeError> {
+        Ok(match (t, min, max) {
+            (Lowered::Number(n, t), Lowered::Number(a, min), Lowered::Number(b, max))
+                if n == a && n == b =>
+            {
+                let t = self.scalar_binary(Binary::Max, n, t, min);
+                Lowered::Number(n, self.scalar_binary(Binary::Min, n, t, max))
+            }
+            (Lowered::Struct(id, t), Lowered::Struct(a, min), Lowered::Struct(b, max))
+                if id == a && id == b =>
+            {
+                let mut out = vec![];
+                for ((t, min), max) in t.into_iter().zip(min).zip(max) {
+                    out.push(self.clamp(t, min, max)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (t, min, _) => return Err(invalid_binary("Clamp", &t, &min)),
+        })
+    }
+}
+
+/// Properties bound by a statement, which become locals of the enclosing function
+fn bound_properties(stmt: &Stmt, out: &mut Vec<PropertyIdentifier>) {
+    match stmt {
+        Stmt::Block(Block(stmts)) => {
+            for stmt in stmts {
+                bound_properties(stmt, out);
+            }
+        }
+        Stmt::Bind { prop, .. } if !out.contains(prop) => out.push(prop.clone()),
+        Stmt::Write { path, .. } if path.len() == 1 && !out.contains(&path[0]) => {
+            out.push(path[0].clone())
+        }
+        Stmt::If {
+            then, otherwise, ..
+        } => {
+            bound_properties(then, out);
+            if let Some(otherwise) = otherwise {
+                bound_properties(otherwise, out);
+            }
+        }
+        Stmt::Loop { stmt } => bound_properties(stmt, out),
+        _ => (),
+    }
+}
+
+/// Find the member of a value at the end of a path
+fn find<'a>(
+    layout: &Layout,
+    mut value: &'a Lowered,
+    path: &[PropertyIdentifier],
+) -> Result<&'a Lowered, BytecodeError> {
+    for prop in path {
+        let Lowered::Struct(id, members) = value else {
+            return Err(BytecodeError::InvalidRead(path_name(path)));
+        };
+
+        let index = layout
+            .fields(id)?
+            .iter()
+            .position(|(cand, _)| *cand == prop)
+            .ok_or_else(|| BytecodeError::MissingField {
+                field: prop.name().to_string(),
+                ty: id.name().to_string(),
+            })?;
+
+        value = &members[index];
+    }
+
+    Ok(value)
+}
+
+pub(crate) fn path_name(path: &[PropertyIdentifier]) -> String {
+    path.iter()
+        .map(|prop| prop.name())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn is_matrix(id: &StructIdentifier) -> bool {
+    matches!(id.name(), "Matrix2" | "Matrix3" | "Matrix4")
+}
+
+/// Leaves of a value made up entirely of floats
+fn floats(value: &Lowered) -> Option<Vec<u32>> {
+    match value {
+        Lowered::Number(NumericType::Float, v) => Some(vec![*v]),
+        Lowered::Struct(_, members) => {
+            let mut out = vec![];
+            for member in members {
+                out.extend(floats(member)?);
+            }
+            Some(out)
+        }
+        _ => None,
+    }
+}
+
+fn invalid_unary(op: &str, t: &Lowered) -> BytecodeError {
+    BytecodeError::InvalidExpr(format!("{op}({})", t.ty().name()))
+}
+
+fn invalid_binary(op: &str, lhs: &Lowered, rhs: &Lowered) -> BytecodeError {
+    BytecodeError::InvalidExpr(format!("{op}({}, {})", lhs.ty().name(), rhs.ty().name()))
+}
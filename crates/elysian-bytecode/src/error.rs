@@ -0,0 +1,50 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_ir::module::LayoutError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BytecodeError {
+    MissingFunction(String),
+    Layout(LayoutError),
+    MissingField { field: String, ty: String },
+    InvalidRead(String),
+    InvalidExpr(String),
+    InvalidStmt(String),
+    RecursiveCall(String),
+    InvalidProgram(String),
+    TooManyRegisters { registers: u32, max: u32 },
+    Kernel(String),
+}
+
+impl Display for BytecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BytecodeError::MissingFunction(name) => {
+                f.write_str(&format!("No function definition for {name}"))
+            }
+            BytecodeError::Layout(e) => e.fmt(f),
+            BytecodeError::MissingField { field, ty } => {
+                f.write_str(&format!("No field {field} in struct {ty}"))
+            }
+            BytecodeError::InvalidRead(path) => f.write_str(&format!("Invalid read of {path}")),
+            BytecodeError::InvalidExpr(expr) => f.write_str(&format!("Invalid expression {expr}")),
+            BytecodeError::InvalidStmt(stmt) => f.write_str(&format!("Invalid statement {stmt}")),
+            BytecodeError::RecursiveCall(name) => {
+                f.write_str(&format!("Can't inline recursive call to {name}"))
+            }
+            BytecodeError::InvalidProgram(e) => f.write_str(&format!("Invalid program: {e}")),
+            BytecodeError::TooManyRegisters { registers, max } => f.write_str(&format!(
+                "Program uses {registers} registers, but the kernel supports at most {max}"
+            )),
+            BytecodeError::Kernel(e) => f.write_str(&format!("Failed to build kernel: {e}")),
+        }
+    }
+}
+
+impl Error for BytecodeError {}
+
+impl From<LayoutError> for BytecodeError {
+    fn from(value: LayoutError) -> Self {
+        BytecodeError::Layout(value)
+    }
+}
@@ -0,0 +1,229 @@
+// Interpreter for Elysian bytecode programs
+//
+// Each invocation runs the program over a single context,
+// which is read from the contexts buffer and written back in place.
+// The OP_* opcodes, MAX_REGISTERS, WORKGROUP_SIZE, HEADER_LEN and CONTEXT_LEN_INDEX
+// are defined by a header generated from the elysian-bytecode crate.
+
+layout(local_size_x = WORKGROUP_SIZE) in;
+
+layout(set = 0, binding = 0) readonly buffer Program {
+    uint words[];
+} program;
+
+layout(set = 0, binding = 1) buffer Contexts {
+    uint contexts[];
+};
+
+uint registers[MAX_REGISTERS];
+uint pc;
+
+uint operand(uint i) {
+    return program.words[HEADER_LEN + pc + 1u + i];
+}
+
+uint to_bits(float x) {
+    return floatBitsToUint(x);
+}
+
+uint to_bool(bool b) {
+    return b ? 1u : 0u;
+}
+
+uint unary(uint op, uint a) {
+    float x = uintBitsToFloat(a);
+
+    uint result;
+    switch (op) {
+    case OP_COPY:
+        result = a;
+        break;
+    case OP_NOT:
+        result = to_bool(a == 0u);
+        break;
+    case OP_F_NEG:
+        result = to_bits(-x);
+        break;
+    case OP_F_ABS:
+        result = a & 0x7fffffffu;
+        break;
+    case OP_F_TRUNC:
+        result = to_bits(trunc(x));
+        break;
+    case OP_F_SQRT:
+        result = to_bits(sqrt(x));
+        break;
+    case OP_F_SIN:
+        result = to_bits(sin(x));
+        break;
+    case OP_F_COS:
+        result = to_bits(cos(x));
+        break;
+    case OP_F_TAN:
+        result = to_bits(tan(x));
+        break;
+    case OP_F_ASIN:
+        result = to_bits(asin(x));
+        break;
+    case OP_F_ACOS:
+        result = to_bits(acos(x));
+        break;
+    case OP_F_ATAN:
+        result = to_bits(atan(x));
+        break;
+    case OP_F_EXP2:
+        result = to_bits(exp2(x));
+        break;
+    case OP_F_LOG2:
+        result = to_bits(log2(x));
+        break;
+    case OP_F_TO_S:
+        result = uint(int(x));
+        break;
+    case OP_F_TO_U:
+        result = uint(x);
+        break;
+    case OP_S_TO_F:
+        result = to_bits(float(int(a)));
+        break;
+    case OP_U_TO_F:
+        result = to_bits(float(a));
+        break;
+    default:
+        result = 0u;
+        break;
+    }
+    return result;
+}
+
+uint binary(uint op, uint a, uint b) {
+    float x = uintBitsToFloat(a);
+    float y = uintBitsToFloat(b);
+    int s = int(a);
+    int t = int(b);
+
+    uint result;
+    switch (op) {
+    case OP_AND:
+        result = a & b;
+        break;
+    case OP_OR:
+        result = a | b;
+        break;
+    case OP_F_ADD:
+        result = to_bits(x + y);
+        break;
+    case OP_F_SUB:
+        result = to_bits(x - y);
+        break;
+    case OP_F_MUL:
+        result = to_bits(x * y);
+        break;
+    case OP_F_DIV:
+        result = to_bits(x / y);
+        break;
+    case OP_F_MIN:
+        result = to_bits(min(x, y));
+        break;
+    case OP_F_MAX:
+        result = to_bits(max(x, y));
+        break;
+    case OP_F_ATAN2:
+        result = to_bits(atan(x, y));
+        break;
+    case OP_F_COPYSIGN:
+        result = (a & 0x7fffffffu) | (b & 0x80000000u);
+        break;
+    case OP_F_EQ:
+        result = to_bool(x == y);
+        break;
+    case OP_F_LT:
+        result = to_bool(x < y);
+        break;
+    case OP_F_GT:
+        result = to_bool(x > y);
+        break;
+    case OP_I_ADD:
+        result = a + b;
+        break;
+    case OP_I_SUB:
+        result = a - b;
+        break;
+    case OP_I_MUL:
+        result = a * b;
+        break;
+    case OP_I_EQ:
+        result = to_bool(a == b);
+        break;
+    case OP_S_DIV:
+        result = uint(s / t);
+        break;
+    case OP_S_REM:
+        result = uint(s % t);
+        break;
+    case OP_S_LT:
+        result = to_bool(s < t);
+        break;
+    case OP_S_GT:
+        result = to_bool(s > t);
+        break;
+    case OP_U_DIV:
+        result = a / b;
+        break;
+    case OP_U_REM:
+        result = a % b;
+        break;
+    case OP_U_LT:
+        result = to_bool(a < b);
+        break;
+    case OP_U_GT:
+        result = to_bool(a > b);
+        break;
+    default:
+        result = 0u;
+        break;
+    }
+    return result;
+}
+
+void main() {
+    uint context_len = program.words[CONTEXT_LEN_INDEX];
+    uint base = gl_GlobalInvocationID.x * context_len;
+    if (base + context_len > uint(contexts.length())) {
+        return;
+    }
+
+    for (uint i = 0u; i < context_len; i++) {
+        registers[i] = contexts[base + i];
+    }
+
+    pc = 0u;
+    while (true) {
+        uint op = program.words[HEADER_LEN + pc];
+
+        if (op >= OP_COPY && op < OP_AND) {
+            registers[operand(0u)] = unary(op, registers[operand(1u)]);
+            pc += 3u;
+        } else if (op >= OP_AND && op <= OP_U_GT) {
+            registers[operand(0u)] = binary(op, registers[operand(1u)], registers[operand(2u)]);
+            pc += 4u;
+        } else if (op == OP_CONST) {
+            registers[operand(0u)] = operand(1u);
+            pc += 3u;
+        } else if (op == OP_SELECT) {
+            uint cond = registers[operand(1u)];
+            registers[operand(0u)] = cond != 0u ? registers[operand(2u)] : registers[operand(3u)];
+            pc += 5u;
+        } else if (op == OP_JUMP) {
+            pc = operand(0u);
+        } else if (op == OP_JUMP_IF_ZERO) {
+            pc = registers[operand(0u)] == 0u ? operand(1u) : pc + 3u;
+        } else {
+            break;
+        }
+    }
+
+    for (uint i = 0u; i < context_len; i++) {
+        contexts[base + i] = registers[i];
+    }
+}
@@ -0,0 +1,60 @@
+use naga::{
+    back::wgsl::WriterFlags,
+    front::glsl::{Frontend, Options},
+    valid::{Capabilities, ValidationFlags, Validator},
+    ShaderStage,
+};
+
+use crate::{BytecodeError, Op, CONTEXT_LEN_INDEX, HEADER_LEN};
+
+/// Size of the register file of each kernel invocation
+pub const MAX_REGISTERS: u32 = 1024;
+
+/// Number of contexts evaluated by each kernel workgroup
+pub const WORKGROUP_SIZE: u32 = 64;
+
+/// Pre-generated WGSL source of the interpreter kernel
+///
+/// Any program can be run by binding its [`words`](crate::Program::words)
+/// as a read-only storage buffer at `@group(0) @binding(0)`,
+/// and an array of contexts as a read-write storage buffer at `@group(0) @binding(1)`.
+/// Each invocation overwrites one context with the program's output,
+/// so `ceil(contexts / WORKGROUP_SIZE)` workgroups should be dispatched.
+pub const KERNEL_WGSL: &str = include_str!("kernel.wgsl");
+
+const KERNEL_GLSL: &str = include_str!("kernel.glsl");
+
+/// GLSL source of the interpreter kernel, from which [`KERNEL_WGSL`] is generated
+pub fn kernel_glsl() -> String {
+    let mut source = "#version 450\n\n".to_string();
+
+    source += &format!("#define MAX_REGISTERS {MAX_REGISTERS}\n");
+    source += &format!("#define WORKGROUP_SIZE {WORKGROUP_SIZE}\n");
+    source += &format!("#define HEADER_LEN {HEADER_LEN}u\n");
+    source += &format!("#define CONTEXT_LEN_INDEX {CONTEXT_LEN_INDEX}u\n\n");
+
+    for op in Op::ALL {
+        source += &format!("#define OP_{} {}u\n", op.name(), op.word());
+    }
+    source += "\n";
+
+    source + KERNEL_GLSL
+}
+
+/// Parse and validate the interpreter kernel
+pub fn kernel_module() -> Result<naga::Module, BytecodeError> {
+    Frontend::default()
+        .parse(&Options::from(ShaderStage::Compute), &kernel_glsl())
+        .map_err(|e| BytecodeError::Kernel(format!("{e:?}")))
+}
+
+/// Generate WGSL source for the interpreter kernel
+pub fn kernel_wgsl() -> Result<String, BytecodeError> {
+    let module = kernel_module()?;
+    let info = Validator::new(ValidationFlags::all(), Capabilities::empty())
+        .validate(&module)
+        .map_err(|e| BytecodeError::Kernel(format!("{e:?}")))?;
+
+    naga::back::wgsl::write_string(&module, &info, WriterFlags::empty())
+        .map_err(|e| BytecodeError::Kernel(e.to_string()))
+}
@@ -0,0 +1,548 @@
+struct Program {
+    words: array<u32>,
+}
+
+struct Contexts {
+    contexts: array<u32>,
+}
+
+@group(0) @binding(0) 
+var<storage> program: Program;
+@group(0) @binding(1) 
+var<storage, read_write> global: Contexts;
+var<private> registers: array<u32, 1024>;
+var<private> pc: u32;
+var<private> gl_GlobalInvocationID: vec3<u32>;
+
+fn operand(i: u32) -> u32 {
+    var i_1: u32;
+
+    i_1 = i;
+    let _e10 = pc;
+    let _e14 = i_1;
+    let _e18 = program.words[(((4u + _e10) + 1u) + _e14)];
+    return _e18;
+}
+
+fn to_bits(x: f32) -> u32 {
+    var x_1: f32;
+
+    x_1 = x;
+    let _e10 = x_1;
+    return bitcast<u32>(_e10);
+}
+
+fn to_bool(b: bool) -> u32 {
+    var b_1: bool;
+    var local: u32;
+
+    b_1 = b;
+    let _e9 = b_1;
+    if _e9 {
+        local = 1u;
+    } else {
+        local = 0u;
+    }
+    let _e13 = local;
+    return _e13;
+}
+
+fn unary(op: u32, a: u32) -> u32 {
+    var op_1: u32;
+    var a_1: u32;
+    var x_2: f32;
+    var result: u32;
+
+    op_1 = op;
+    a_1 = a;
+    let _e12 = a_1;
+    x_2 = bitcast<f32>(_e12);
+    let _e16 = op_1;
+    switch _e16 {
+        case 5u: {
+            let _e18 = a_1;
+            result = _e18;
+        }
+        case 6u: {
+            let _e20 = a_1;
+            let _e23 = a_1;
+            let _e26 = to_bool((_e23 == 0u));
+            result = _e26;
+        }
+        case 7u: {
+            let _e28 = x_2;
+            let _e30 = x_2;
+            let _e32 = to_bits(-(_e30));
+            result = _e32;
+        }
+        case 8u: {
+            let _e34 = a_1;
+            result = (_e34 & 2147483647u);
+        }
+        case 9u: {
+            let _e39 = x_2;
+            let _e42 = x_2;
+            let _e44 = to_bits(trunc(_e42));
+            result = _e44;
+        }
+        case 10u: {
+            let _e47 = x_2;
+            let _e50 = x_2;
+            let _e52 = to_bits(sqrt(_e50));
+            result = _e52;
+        }
+        case 11u: {
+            let _e55 = x_2;
+            let _e58 = x_2;
+            let _e60 = to_bits(sin(_e58));
+            result = _e60;
+        }
+        case 12u: {
+            let _e63 = x_2;
+            let _e66 = x_2;
+            let _e68 = to_bits(cos(_e66));
+            result = _e68;
+        }
+        case 13u: {
+            let _e71 = x_2;
+            let _e74 = x_2;
+            let _e76 = to_bits(tan(_e74));
+            result = _e76;
+        }
+        case 14u: {
+            let _e79 = x_2;
+            let _e82 = x_2;
+            let _e84 = to_bits(asin(_e82));
+            result = _e84;
+        }
+        case 15u: {
+            let _e87 = x_2;
+            let _e90 = x_2;
+            let _e92 = to_bits(acos(_e90));
+            result = _e92;
+        }
+        case 16u: {
+            let _e95 = x_2;
+            let _e98 = x_2;
+            let _e100 = to_bits(atan(_e98));
+            result = _e100;
+        }
+        case 17u: {
+            let _e103 = x_2;
+            let _e106 = x_2;
+            let _e108 = to_bits(exp2(_e106));
+            result = _e108;
+        }
+        case 18u: {
+            let _e111 = x_2;
+            let _e114 = x_2;
+            let _e116 = to_bits(log2(_e114));
+            result = _e116;
+        }
+        case 19u: {
+            let _e118 = x_2;
+            result = u32(i32(_e118));
+        }
+        case 20u: {
+            let _e122 = x_2;
+            result = u32(_e122);
+        }
+        case 21u: {
+            let _e125 = a_1;
+            let _e128 = a_1;
+            let _e131 = to_bits(f32(i32(_e128)));
+            result = _e131;
+        }
+        case 22u: {
+            let _e133 = a_1;
+            let _e135 = a_1;
+            let _e137 = to_bits(f32(_e135));
+            result = _e137;
+        }
+        default: {
+            result = 0u;
+        }
+    }
+    let _e139 = result;
+    return _e139;
+}
+
+fn binary(op_2: u32, a_2: u32, b_2: u32) -> u32 {
+    var op_3: u32;
+    var a_3: u32;
+    var b_3: u32;
+    var x_3: f32;
+    var y: f32;
+    var s: i32;
+    var t: i32;
+    var result_1: u32;
+
+    op_3 = op_2;
+    a_3 = a_2;
+    b_3 = b_2;
+    let _e14 = a_3;
+    x_3 = bitcast<f32>(_e14);
+    let _e18 = b_3;
+    y = bitcast<f32>(_e18);
+    let _e21 = a_3;
+    s = i32(_e21);
+    let _e24 = b_3;
+    t = i32(_e24);
+    let _e28 = op_3;
+    switch _e28 {
+        case 23u: {
+            let _e30 = a_3;
+            let _e31 = b_3;
+            result_1 = (_e30 & _e31);
+        }
+        case 24u: {
+            let _e34 = a_3;
+            let _e35 = b_3;
+            result_1 = (_e34 | _e35);
+        }
+        case 25u: {
+            let _e38 = x_3;
+            let _e39 = y;
+            let _e41 = x_3;
+            let _e42 = y;
+            let _e44 = to_bits((_e41 + _e42));
+            result_1 = _e44;
+        }
+        case 26u: {
+            let _e46 = x_3;
+            let _e47 = y;
+            let _e49 = x_3;
+            let _e50 = y;
+            let _e52 = to_bits((_e49 - _e50));
+            result_1 = _e52;
+        }
+        case 27u: {
+            let _e54 = x_3;
+            let _e55 = y;
+            let _e57 = x_3;
+            let _e58 = y;
+            let _e60 = to_bits((_e57 * _e58));
+            result_1 = _e60;
+        }
+        case 28u: {
+            let _e62 = x_3;
+            let _e63 = y;
+            let _e65 = x_3;
+            let _e66 = y;
+            let _e68 = to_bits((_e65 / _e66));
+            result_1 = _e68;
+        }
+        case 29u: {
+            let _e72 = x_3;
+            let _e73 = y;
+            let _e77 = x_3;
+            let _e78 = y;
+            let _e80 = to_bits(min(_e77, _e78));
+            result_1 = _e80;
+        }
+        case 30u: {
+            let _e84 = x_3;
+            let _e85 = y;
+            let _e89 = x_3;
+            let _e90 = y;
+            let _e92 = to_bits(max(_e89, _e90));
+            result_1 = _e92;
+        }
+        case 31u: {
+            let _e96 = x_3;
+            let _e97 = y;
+            let _e101 = x_3;
+            let _e102 = y;
+            let _e104 = to_bits(atan2(_e101, _e102));
+            result_1 = _e104;
+        }
+        case 32u: {
+            let _e106 = a_3;
+            let _e109 = b_3;
+            result_1 = ((_e106 & 2147483647u) | (_e109 & 2147483648u));
+        }
+        case 33u: {
+            let _e114 = x_3;
+            let _e115 = y;
+            let _e117 = x_3;
+            let _e118 = y;
+            let _e120 = to_bool((_e117 == _e118));
+            result_1 = _e120;
+        }
+        case 34u: {
+            let _e122 = x_3;
+            let _e123 = y;
+            let _e125 = x_3;
+            let _e126 = y;
+            let _e128 = to_bool((_e125 < _e126));
+            result_1 = _e128;
+        }
+        case 35u: {
+            let _e130 = x_3;
+            let _e131 = y;
+            let _e133 = x_3;
+            let _e134 = y;
+            let _e136 = to_bool((_e133 > _e134));
+            result_1 = _e136;
+        }
+        case 36u: {
+            let _e138 = a_3;
+            let _e139 = b_3;
+            result_1 = (_e138 + _e139);
+        }
+        case 37u: {
+            let _e142 = a_3;
+            let _e143 = b_3;
+            result_1 = (_e142 - _e143);
+        }
+        case 38u: {
+            let _e146 = a_3;
+            let _e147 = b_3;
+            result_1 = (_e146 * _e147);
+        }
+        case 39u: {
+            let _e150 = a_3;
+            let _e151 = b_3;
+            let _e153 = a_3;
+            let _e154 = b_3;
+            let _e156 = to_bool((_e153 == _e154));
+            result_1 = _e156;
+        }
+        case 40u: {
+            let _e158 = s;
+            let _e159 = t;
+            result_1 = u32((_e158 / _e159));
+        }
+        case 41u: {
+            let _e163 = s;
+            let _e164 = t;
+            result_1 = u32((_e163 % _e164));
+        }
+        case 42u: {
+            let _e168 = s;
+            let _e169 = t;
+            let _e171 = s;
+            let _e172 = t;
+            let _e174 = to_bool((_e171 < _e172));
+            result_1 = _e174;
+        }
+        case 43u: {
+            let _e176 = s;
+            let _e177 = t;
+            let _e179 = s;
+            let _e180 = t;
+            let _e182 = to_bool((_e179 > _e180));
+            result_1 = _e182;
+        }
+        case 44u: {
+            let _e184 = a_3;
+            let _e185 = b_3;
+            result_1 = (_e184 / _e185);
+        }
+        case 45u: {
+            let _e188 = a_3;
+            let _e189 = b_3;
+            result_1 = (_e188 % _e189);
+        }
+        case 46u: {
+            let _e192 = a_3;
+            let _e193 = b_3;
+            let _e195 = a_3;
+            let _e196 = b_3;
+            let _e198 = to_bool((_e195 < _e196));
+            result_1 = _e198;
+        }
+        case 47u: {
+            let _e200 = a_3;
+            let _e201 = b_3;
+            let _e203 = a_3;
+            let _e204 = b_3;
+            let _e206 = to_bool((_e203 > _e204));
+            result_1 = _e206;
+        }
+        default: {
+            result_1 = 0u;
+        }
+    }
+    let _e208 = result_1;
+    return _e208;
+}
+
+fn main_1() {
+    var context_len: u32;
+    var base: u32;
+    var i_2: u32;
+    var op_4: u32;
+    var cond: u32;
+    var local_1: u32;
+    var local_2: u32;
+    var i_3: u32;
+
+    let _e10 = program.words[3];
+    context_len = _e10;
+    let _e13 = gl_GlobalInvocationID;
+    let _e15 = context_len;
+    base = (_e13.x * _e15);
+    let _e18 = base;
+    let _e19 = context_len;
+    if ((_e18 + _e19) > u32(i32(arrayLength((&global.contexts))))) {
+        {
+            return;
+        }
+    }
+    i_2 = 0u;
+    loop {
+        let _e27 = i_2;
+        let _e28 = context_len;
+        if !((_e27 < _e28)) {
+            break;
+        }
+        {
+            let _e34 = i_2;
+            let _e36 = base;
+            let _e37 = i_2;
+            let _e40 = global.contexts[(_e36 + _e37)];
+            registers[_e34] = _e40;
+        }
+        continuing {
+            let _e31 = i_2;
+            i_2 = (_e31 + 1u);
+        }
+    }
+    pc = 0u;
+    loop {
+        if !(true) {
+            break;
+        }
+        {
+            let _e45 = pc;
+            let _e49 = program.words[(4u + _e45)];
+            op_4 = _e49;
+            let _e51 = op_4;
+            let _e54 = op_4;
+            if ((_e51 >= 5u) && (_e54 < 23u)) {
+                {
+                    let _e60 = operand(0u);
+                    let _e65 = operand(1u);
+                    let _e68 = op_4;
+                    let _e71 = operand(1u);
+                    let _e73 = registers[_e71];
+                    let _e74 = unary(_e68, _e73);
+                    registers[_e60] = _e74;
+                    let _e75 = pc;
+                    pc = (_e75 + 3u);
+                }
+            } else {
+                let _e78 = op_4;
+                let _e81 = op_4;
+                if ((_e78 >= 23u) && (_e81 <= 47u)) {
+                    {
+                        let _e87 = operand(0u);
+                        let _e92 = operand(1u);
+                        let _e97 = operand(2u);
+                        let _e100 = op_4;
+                        let _e103 = operand(1u);
+                        let _e105 = registers[_e103];
+                        let _e108 = operand(2u);
+                        let _e110 = registers[_e108];
+                        let _e111 = binary(_e100, _e105, _e110);
+                        registers[_e87] = _e111;
+                        let _e112 = pc;
+                        pc = (_e112 + 4u);
+                    }
+                } else {
+                    let _e115 = op_4;
+                    if (_e115 == 3u) {
+                        {
+                            let _e120 = operand(0u);
+                            let _e124 = operand(1u);
+                            registers[_e120] = _e124;
+                            let _e125 = pc;
+                            pc = (_e125 + 3u);
+                        }
+                    } else {
+                        let _e128 = op_4;
+                        if (_e128 == 4u) {
+                            {
+                                let _e133 = operand(1u);
+                                let _e135 = registers[_e133];
+                                cond = _e135;
+                                let _e139 = operand(0u);
+                                let _e141 = cond;
+                                if (_e141 != 0u) {
+                                    let _e146 = operand(2u);
+                                    let _e148 = registers[_e146];
+                                    local_1 = _e148;
+                                } else {
+                                    let _e151 = operand(3u);
+                                    let _e153 = registers[_e151];
+                                    local_1 = _e153;
+                                }
+                                let _e155 = local_1;
+                                registers[_e139] = _e155;
+                                let _e156 = pc;
+                                pc = (_e156 + 5u);
+                            }
+                        } else {
+                            let _e159 = op_4;
+                            if (_e159 == 1u) {
+                                {
+                                    let _e164 = operand(0u);
+                                    pc = _e164;
+                                }
+                            } else {
+                                let _e165 = op_4;
+                                if (_e165 == 2u) {
+                                    {
+                                        let _e170 = operand(0u);
+                                        let _e172 = registers[_e170];
+                                        if (_e172 == 0u) {
+                                            let _e177 = operand(1u);
+                                            local_2 = _e177;
+                                        } else {
+                                            let _e178 = pc;
+                                            local_2 = (_e178 + 3u);
+                                        }
+                                        let _e182 = local_2;
+                                        pc = _e182;
+                                    }
+                                } else {
+                                    {
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    i_3 = 0u;
+    loop {
+        let _e185 = i_3;
+        let _e186 = context_len;
+        if !((_e185 < _e186)) {
+            break;
+        }
+        {
+            let _e192 = base;
+            let _e193 = i_3;
+            let _e196 = i_3;
+            let _e198 = registers[_e196];
+            global.contexts[(_e192 + _e193)] = _e198;
+        }
+        continuing {
+            let _e189 = i_3;
+            i_3 = (_e189 + 1u);
+        }
+    }
+    return;
+}
+
+@compute @workgroup_size(64, 1, 1) 
+fn main(@builtin(global_invocation_id) param: vec3<u32>) {
+    gl_GlobalInvocationID = param;
+    main_1();
+    return;
+}
@@ -0,0 +1,238 @@
+//! Compile Elysian IR into bytecode for a register machine
+//!
+//! Programs can be run on the CPU through [`Program::run`] or [`Evaluate`](elysian_ir::module::Evaluate),
+//! or on the GPU by uploading their [`words`](Program::words) to the fixed interpreter kernel
+//! in [`KERNEL_WGSL`], so that changing a shape never requires recompiling a shader.
+
+mod compile;
+mod error;
+mod kernel;
+mod op;
+mod program;
+
+pub use error::*;
+pub use kernel::*;
+pub use op::*;
+pub use program::*;
+
+use elysian_ir::module::Module;
+
+use crate::compile::Compiler;
+
+/// Compile a module into a bytecode program
+pub fn module_to_bytecode(module: &Module) -> Result<Program, BytecodeError> {
+    Compiler::new(module).program(module)
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_core::{expr::IntoPath, number::Number, property_identifier::PropertyIdentifier};
+    use elysian_interpreter::Interpreted;
+    use elysian_ir::{
+        ast::{
+            Struct, Value, DISTANCE, GRADIENT_2D, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y,
+            Z,
+        },
+        module::{AsModule, Evaluate, SpecializationData, StructIdentifier, CONTEXT},
+    };
+    use elysian_shapes::{
+        field::{Circle, Point},
+        modify::{IntoGradientNormals, IntoIsosurface, IntoTranslate},
+        select::Select,
+        voronoi::{voronoi, CELL_ID},
+    };
+
+    use super::*;
+
+    fn get(s: &Struct, path: &[PropertyIdentifier]) -> f64 {
+        let (first, rest) = path.split_first().unwrap();
+        match (s.members.get(first).unwrap(), rest.is_empty()) {
+            (Value::Struct(s), false) => get(s, rest),
+            (Value::Boolean(b), true) => *b as u8 as f64,
+            (Value::Number(Number::Float(n)), true) => *n,
+            (Value::Number(Number::SInt(n)), true) => *n as f64,
+            (Value::Number(Number::UInt(n)), true) => *n as f64,
+            (value, _) => panic!("Unexpected {value:?}"),
+        }
+    }
+
+    /// Run a module's program on the CPU,
+    /// and check the given properties against the interpreter at each of the given points
+    fn compare(module: &Module, properties: &[&[PropertyIdentifier]], points: &[&[f64]]) {
+        let program = module_to_bytecode(module).unwrap();
+        program.validate().unwrap();
+
+        for point in points {
+            let (position, vector, axes) = match point.len() {
+                2 => (POSITION_2D, VECTOR2, &[X, Y][..]),
+                _ => (POSITION_3D, VECTOR3, &[X, Y, Z][..]),
+            };
+
+            let mut value = Struct::new(StructIdentifier(vector));
+            for (axis, n) in axes.iter().zip(point.iter()) {
+                value = value.set(axis.clone().into(), (*n).into());
+            }
+            let context =
+                Struct::new(StructIdentifier(CONTEXT)).set(position.into(), Value::Struct(value));
+
+            let expected = Interpreted(module).evaluate(context.clone()).unwrap();
+            let found = program.evaluate(context).unwrap();
+
+            for path in [&[DISTANCE.into()][..]].iter().chain(properties) {
+                let (expected, found) = (get(&expected, path), get(&found, path));
+                assert!(
+                    (expected - found).abs() <= 1e-4 * expected.abs().max(1.0),
+                    "{path:?} at {point:?}: {expected} != {found}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_bytecode_circle() {
+        let module = Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            &[
+                &[GRADIENT_2D.into(), X.into()],
+                &[GRADIENT_2D.into(), Y.into()],
+            ],
+            &[&[0.0, 0.0], &[0.25, -0.5], &[1.0, 1.0], &[-1.5, 0.75]],
+        );
+    }
+
+    #[test]
+    fn test_bytecode_translated_point() {
+        let module = Point
+            .translate([0.25, -0.5])
+            .isosurface(0.75)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            &[&[GRADIENT_2D.into(), X.into()]],
+            &[&[0.0, 0.0], &[0.25, -0.5], &[-1.0, 2.0]],
+        );
+    }
+
+    #[test]
+    fn test_bytecode_point_3d() {
+        let module = Point.module(&SpecializationData::new_3d()).finalize();
+
+        compare(&module, &[], &[&[0.0, 0.0, 0.0], &[1.0, -2.0, 0.5]]);
+    }
+
+    #[test]
+    fn test_bytecode_select() {
+        let module = Select::new(Circle::new(0.5))
+            .case(POSITION_2D.path().push(X).read().lt(0.0), Point)
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(&module, &[], &[&[-1.0, 0.5], &[1.0, 0.5], &[0.0, 0.0]]);
+    }
+
+    #[test]
+    fn test_bytecode_voronoi() {
+        let module = voronoi([[0.0, 0.0], [1.0, 0.5], [-0.5, 1.0]])
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            &[&[CELL_ID.into()]],
+            &[&[0.1, 0.1], &[0.9, 0.4], &[-0.5, 2.0], &[3.0, -3.0]],
+        );
+    }
+
+    #[test]
+    fn test_bytecode_words() {
+        let program =
+            module_to_bytecode(&Point.module(&SpecializationData::new_2d()).finalize()).unwrap();
+        let words = program.words().unwrap();
+
+        assert_eq!(words[..2], [MAGIC, VERSION]);
+        assert_eq!(words[CONTEXT_LEN_INDEX] as usize, program.context.len());
+        assert_eq!(words[HEADER_LEN..], program.code);
+
+        let program = Program {
+            registers: MAX_REGISTERS + 1,
+            ..program
+        };
+        assert!(matches!(
+            program.words(),
+            Err(BytecodeError::TooManyRegisters { .. })
+        ));
+    }
+
+    #[test]
+    fn test_bytecode_validate() {
+        let program = |code: Vec<u32>| Program {
+            code,
+            registers: 2,
+            context: vec![],
+        };
+
+        let copy = Op::Copy.word();
+        let jump = Op::Jump.word();
+        assert!(program(vec![copy, 0, 1, 0]).validate().is_ok());
+        assert!(program(vec![copy, 0, 2, 0]).validate().is_err());
+        assert!(program(vec![copy, 0]).validate().is_err());
+        assert!(program(vec![jump, 1, 0]).validate().is_err());
+        assert!(program(vec![u32::MAX]).validate().is_err());
+    }
+
+    #[test]
+    fn test_bytecode_disassemble() {
+        let program = Program {
+            code: vec![
+                Op::Const.word(),
+                1,
+                1.0f32.to_bits(),
+                Op::FAdd.word(),
+                0,
+                0,
+                1,
+                Op::JumpIfZero.word(),
+                1,
+                11,
+                Op::Halt.word(),
+            ],
+            registers: 2,
+            context: vec![],
+        };
+
+        assert_eq!(
+            program.to_string(),
+            "0000 CONST r1, 0x3f800000\n\
+             0003 F_ADD r0, r0, r1\n\
+             0007 JUMP_IF_ZERO r1, @0011\n\
+             0010 HALT\n"
+        );
+    }
+
+    #[test]
+    fn test_kernel_wgsl() {
+        let source = kernel_wgsl().unwrap();
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src")
+            .join("kernel.wgsl");
+
+        if std::env::var_os("ELYSIAN_BLESS").is_some() {
+            std::fs::write(&path, source).unwrap();
+            return;
+        }
+
+        assert!(
+            KERNEL_WGSL == source,
+            "Generated kernel differs from {}, rerun with ELYSIAN_BLESS=1 to update",
+            path.display()
+        );
+    }
+}
@@ -0,0 +1,220 @@
+/// Bytecode instruction
+///
+/// Each instruction is encoded as its opcode word followed by its operands,
+/// which are register indices unless noted otherwise.
+/// Registers hold raw 32-bit words:
+/// floats are stored as their bits, booleans as 0 or 1.
+///
+/// Unary and binary operations occupy contiguous ranges of opcodes,
+/// which the GPU kernel relies on to dispatch them.
+#[repr(u32)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Op {
+    /// Stop execution
+    Halt,
+    /// `pc = target`, where `target` is an immediate code offset
+    Jump,
+    /// `if cond == 0 { pc = target }`
+    JumpIfZero,
+    /// `dst = word`, where `word` is an immediate
+    Const,
+    /// `dst = if cond != 0 { a } else { b }`
+    Select,
+    // Unary operations: `dst = op(a)`
+    /// `dst = a`
+    Copy,
+    /// `dst = a == 0`
+    Not,
+    FNeg,
+    FAbs,
+    FTrunc,
+    FSqrt,
+    FSin,
+    FCos,
+    FTan,
+    FAsin,
+    FAcos,
+    FAtan,
+    FExp2,
+    FLog2,
+    /// Saturating float to signed integer conversion
+    FToS,
+    /// Saturating float to unsigned integer conversion
+    FToU,
+    SToF,
+    UToF,
+    // Binary operations: `dst = op(a, b)`
+    /// `dst = a & b`
+    And,
+    /// `dst = a | b`
+    Or,
+    FAdd,
+    FSub,
+    FMul,
+    FDiv,
+    FMin,
+    FMax,
+    /// `dst = atan2(a, b)`
+    FAtan2,
+    /// Magnitude of `a` with the sign of `b`
+    FCopysign,
+    FEq,
+    FLt,
+    FGt,
+    IAdd,
+    ISub,
+    IMul,
+    IEq,
+    SDiv,
+    SRem,
+    SLt,
+    SGt,
+    UDiv,
+    URem,
+    ULt,
+    UGt,
+}
+
+impl Op {
+    pub const ALL: [Op; 48] = [
+        Op::Halt,
+        Op::Jump,
+        Op::JumpIfZero,
+        Op::Const,
+        Op::Select,
+        Op::Copy,
+        Op::Not,
+        Op::FNeg,
+        Op::FAbs,
+        Op::FTrunc,
+        Op::FSqrt,
+        Op::FSin,
+        Op::FCos,
+        Op::FTan,
+        Op::FAsin,
+        Op::FAcos,
+        Op::FAtan,
+        Op::FExp2,
+        Op::FLog2,
+        Op::FToS,
+        Op::FToU,
+        Op::SToF,
+        Op::UToF,
+        Op::And,
+        Op::Or,
+        Op::FAdd,
+        Op::FSub,
+        Op::FMul,
+        Op::FDiv,
+        Op::FMin,
+        Op::FMax,
+        Op::FAtan2,
+        Op::FCopysign,
+        Op::FEq,
+        Op::FLt,
+        Op::FGt,
+        Op::IAdd,
+        Op::ISub,
+        Op::IMul,
+        Op::IEq,
+        Op::SDiv,
+        Op::SRem,
+        Op::SLt,
+        Op::SGt,
+        Op::UDiv,
+        Op::URem,
+        Op::ULt,
+        Op::UGt,
+    ];
+
+    pub fn from_word(word: u32) -> Option<Op> {
+        Op::ALL.get(word as usize).copied()
+    }
+
+    pub fn word(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Number of operand words following the opcode
+    pub fn operands(&self) -> usize {
+        match self {
+            Op::Halt => 0,
+            Op::Jump => 1,
+            Op::JumpIfZero | Op::Const => 2,
+            Op::Select => 4,
+            op if op.is_unary() => 2,
+            _ => 3,
+        }
+    }
+
+    pub fn is_unary(&self) -> bool {
+        (Op::Copy.word()..Op::And.word()).contains(&self.word())
+    }
+
+    pub fn is_binary(&self) -> bool {
+        self.word() >= Op::And.word()
+    }
+
+    /// Mnemonic used by the disassembler and as the kernel's `OP_` constant
+    pub fn name(&self) -> &'static str {
+        match self {
+            Op::Halt => "HALT",
+            Op::Jump => "JUMP",
+            Op::JumpIfZero => "JUMP_IF_ZERO",
+            Op::Const => "CONST",
+            Op::Copy => "COPY",
+            Op::Select => "SELECT",
+            Op::Not => "NOT",
+            Op::And => "AND",
+            Op::Or => "OR",
+            Op::FNeg => "F_NEG",
+            Op::FAbs => "F_ABS",
+            Op::FTrunc => "F_TRUNC",
+            Op::FSqrt => "F_SQRT",
+            Op::FSin => "F_SIN",
+            Op::FCos => "F_COS",
+            Op::FTan => "F_TAN",
+            Op::FAsin => "F_ASIN",
+            Op::FAcos => "F_ACOS",
+            Op::FAtan => "F_ATAN",
+            Op::FExp2 => "F_EXP2",
+            Op::FLog2 => "F_LOG2",
+            Op::FAdd => "F_ADD",
+            Op::FSub => "F_SUB",
+            Op::FMul => "F_MUL",
+            Op::FDiv => "F_DIV",
+            Op::FMin => "F_MIN",
+            Op::FMax => "F_MAX",
+            Op::FAtan2 => "F_ATAN2",
+            Op::FCopysign => "F_COPYSIGN",
+            Op::FEq => "F_EQ",
+            Op::FLt => "F_LT",
+            Op::FGt => "F_GT",
+            Op::IAdd => "I_ADD",
+            Op::ISub => "I_SUB",
+            Op::IMul => "I_MUL",
+            Op::IEq => "I_EQ",
+            Op::SDiv => "S_DIV",
+            Op::SRem => "S_REM",
+            Op::SLt => "S_LT",
+            Op::SGt => "S_GT",
+            Op::UDiv => "U_DIV",
+            Op::URem => "U_REM",
+            Op::ULt => "U_LT",
+            Op::UGt => "U_GT",
+            Op::FToS => "F_TO_S",
+            Op::FToU => "F_TO_U",
+            Op::SToF => "S_TO_F",
+            Op::UToF => "U_TO_F",
+        }
+    }
+
+    /// Indices of operands holding immediates rather than registers
+    pub(crate) fn immediates(&self) -> &'static [usize] {
+        match self {
+            Op::Jump => &[0],
+            Op::JumpIfZero | Op::Const => &[1],
+            _ => &[],
+        }
+    }
+}
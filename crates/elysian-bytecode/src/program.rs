@@ -0,0 +1,360 @@
+use std::fmt::Display;
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Struct, Value},
+    module::{property_type, Evaluate, EvaluateError, NumericType, Type},
+};
+
+use crate::{compile::path_name, BytecodeError, Op, MAX_REGISTERS};
+
+/// First word of an encoded program
+pub const MAGIC: u32 = u32::from_le_bytes(*b"ELYB");
+
+/// Version of the bytecode format
+pub const VERSION: u32 = 1;
+
+/// Number of words preceding the code of an encoded program:
+/// `[MAGIC, VERSION, registers, context_len]`
+pub const HEADER_LEN: usize = 4;
+
+/// Index of the header word holding the number of context leaves
+pub const CONTEXT_LEN_INDEX: usize = 3;
+
+/// A module compiled into a flat sequence of register machine instructions
+///
+/// All functions are inlined into a single body,
+/// so programs contain no calls and run to completion in one pass over the interpreter loop.
+/// The context is flattened into its scalar leaves,
+/// which occupy the first registers on entry and hold the resulting context on exit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    /// Encoded instructions
+    pub code: Vec<u32>,
+    /// Number of registers used by the program
+    pub registers: u32,
+    /// Path to each scalar leaf of the context alongside its type, in register order
+    pub context: Vec<(Vec<PropertyIdentifier>, Type)>,
+}
+
+impl Program {
+    /// Encode the program for upload to the GPU kernel
+    pub fn words(&self) -> Result<Vec<u32>, BytecodeError> {
+        if self.registers > MAX_REGISTERS {
+            return Err(BytecodeError::TooManyRegisters {
+                registers: self.registers,
+                max: MAX_REGISTERS,
+            });
+        }
+
+        let mut words = vec![MAGIC, VERSION, self.registers, self.context.len() as u32];
+        words.extend(self.code.iter().copied());
+        Ok(words)
+    }
+
+    /// Decode the instruction at the given offset,
+    /// returning its operands and the offset of the next instruction
+    fn decode(&self, pc: usize) -> Result<(Op, &[u32], usize), BytecodeError> {
+        let word = *self
+            .code
+            .get(pc)
+            .ok_or_else(|| invalid(pc, "Ran past the end of the program"))?;
+        let op = Op::from_word(word).ok_or_else(|| invalid(pc, &format!("Bad opcode {word}")))?;
+        let next = pc + 1 + op.operands();
+        let operands = self
+            .code
+            .get(pc + 1..next)
+            .ok_or_else(|| invalid(pc, "Truncated instruction"))?;
+        Ok((op, operands, next))
+    }
+
+    /// Iterate over each instruction alongside its offset
+    pub fn instructions(
+        &self,
+    ) -> impl Iterator<Item = Result<(usize, Op, &[u32]), BytecodeError>> + '_ {
+        let mut pc = 0;
+        std::iter::from_fn(move || {
+            if pc >= self.code.len() {
+                return None;
+            }
+            Some(match self.decode(pc) {
+                Ok((op, operands, next)) => {
+                    let offset = pc;
+                    pc = next;
+                    Ok((offset, op, operands))
+                }
+                Err(e) => {
+                    pc = self.code.len();
+                    Err(e)
+                }
+            })
+        })
+    }
+
+    /// Check that every instruction is well-formed,
+    /// addresses a register within the program's register count,
+    /// and jumps to the start of an instruction
+    pub fn validate(&self) -> Result<(), BytecodeError> {
+        let instructions = self.instructions().collect::<Result<Vec<_>, _>>()?;
+
+        if self.context.len() > self.registers as usize {
+            return Err(BytecodeError::InvalidProgram(format!(
+                "Context has {} leaves, but only {} registers are available",
+                self.context.len(),
+                self.registers
+            )));
+        }
+
+        for (pc, op, operands) in instructions.iter() {
+            for (i, operand) in operands.iter().enumerate() {
+                if op.immediates().contains(&i) {
+                    continue;
+                }
+                if *operand >= self.registers {
+                    return Err(invalid(*pc, &format!("Bad register {operand}")));
+                }
+            }
+
+            if let Op::Jump | Op::JumpIfZero = op {
+                let target = *operands.last().unwrap() as usize;
+                if !instructions.iter().any(|(cand, ..)| *cand == target) {
+                    return Err(invalid(*pc, &format!("Bad jump target {target}")));
+                }
+            }
+        }
+
+        match instructions.last() {
+            Some((_, Op::Halt | Op::Jump, _)) => Ok(()),
+            _ => Err(BytecodeError::InvalidProgram(
+                "Program doesn't end in HALT".into(),
+            )),
+        }
+    }
+
+    /// Run the program over a set of registers on the CPU
+    ///
+    /// This is the reference implementation of the GPU kernel,
+    /// and uses the same 32-bit arithmetic.
+    pub fn run(&self, registers: &mut [u32]) -> Result<(), BytecodeError> {
+        if registers.len() < self.registers as usize {
+            return Err(BytecodeError::InvalidProgram(format!(
+                "Program uses {} registers, but only {} were provided",
+                self.registers,
+                registers.len()
+            )));
+        }
+
+        let mut pc = 0;
+        loop {
+            let (op, operands, next) = self.decode(pc)?;
+            let get = |i: usize| -> Result<u32, BytecodeError> {
+                registers
+                    .get(operands[i] as usize)
+                    .copied()
+                    .ok_or_else(|| invalid(pc, &format!("Bad register {}", operands[i])))
+            };
+
+            let result = match op {
+                Op::Halt => return Ok(()),
+                Op::Jump => {
+                    pc = operands[0] as usize;
+                    continue;
+                }
+                Op::JumpIfZero => {
+                    pc = if get(0)? == 0 {
+                        operands[1] as usize
+                    } else {
+                        next
+                    };
+                    continue;
+                }
+                Op::Const => operands[1],
+                Op::Select => {
+                    if get(1)? != 0 {
+                        get(2)?
+                    } else {
+                        get(3)?
+                    }
+                }
+                op if op.is_unary() => unary(op, get(1)?),
+                op => binary(op, get(1)?, get(2)?),
+            };
+
+            *registers
+                .get_mut(operands[0] as usize)
+                .ok_or_else(|| invalid(pc, &format!("Bad register {}", operands[0])))? = result;
+            pc = next;
+        }
+    }
+}
+
+fn invalid(pc: usize, message: &str) -> BytecodeError {
+    BytecodeError::InvalidProgram(format!("{message} at {pc}"))
+}
+
+fn unary(op: Op, a: u32) -> u32 {
+    let x = f32::from_bits(a);
+    let float = |f: fn(f32) -> f32| f(x).to_bits();
+
+    match op {
+        Op::Copy => a,
+        Op::Not => (a == 0) as u32,
+        Op::FNeg => float(|x| -x),
+        Op::FAbs => a & 0x7fff_ffff,
+        Op::FTrunc => float(f32::trunc),
+        Op::FSqrt => float(f32::sqrt),
+        Op::FSin => float(f32::sin),
+        Op::FCos => float(f32::cos),
+        Op::FTan => float(f32::tan),
+        Op::FAsin => float(f32::asin),
+        Op::FAcos => float(f32::acos),
+        Op::FAtan => float(f32::atan),
+        Op::FExp2 => float(f32::exp2),
+        Op::FLog2 => float(f32::log2),
+        Op::FToS => x as i32 as u32,
+        Op::FToU => x as u32,
+        Op::SToF => (a as i32 as f32).to_bits(),
+        Op::UToF => (a as f32).to_bits(),
+        _ => unreachable!("{op:?} is not a unary operation"),
+    }
+}
+
+fn binary(op: Op, a: u32, b: u32) -> u32 {
+    let (x, y) = (f32::from_bits(a), f32::from_bits(b));
+    let (s, t) = (a as i32, b as i32);
+
+    // Integer division by zero leaves the dividend unchanged and yields a zero remainder,
+    // as in WGSL
+    match op {
+        Op::And => a & b,
+        Op::Or => a | b,
+        Op::FAdd => (x + y).to_bits(),
+        Op::FSub => (x - y).to_bits(),
+        Op::FMul => (x * y).to_bits(),
+        Op::FDiv => (x / y).to_bits(),
+        Op::FMin => x.min(y).to_bits(),
+        Op::FMax => x.max(y).to_bits(),
+        Op::FAtan2 => x.atan2(y).to_bits(),
+        Op::FCopysign => (a & 0x7fff_ffff) | (b & 0x8000_0000),
+        Op::FEq => (x == y) as u32,
+        Op::FLt => (x < y) as u32,
+        Op::FGt => (x > y) as u32,
+        Op::IAdd => a.wrapping_add(b),
+        Op::ISub => a.wrapping_sub(b),
+        Op::IMul => a.wrapping_mul(b),
+        Op::IEq => (a == b) as u32,
+        Op::SDiv => s.checked_div(t).unwrap_or(s) as u32,
+        Op::SRem => s.checked_rem(t).unwrap_or(0) as u32,
+        Op::SLt => (s < t) as u32,
+        Op::SGt => (s > t) as u32,
+        Op::UDiv => a.checked_div(b).unwrap_or(a),
+        Op::URem => a.checked_rem(b).unwrap_or(0),
+        Op::ULt => (a < b) as u32,
+        Op::UGt => (a > b) as u32,
+        _ => unreachable!("{op:?} is not a binary operation"),
+    }
+}
+
+/// Disassembly listing, one instruction per line
+impl Display for Program {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for instruction in self.instructions() {
+            let Ok((pc, op, operands)) = instruction else {
+                return writeln!(f, "<invalid>");
+            };
+
+            write!(f, "{pc:04} {}", op.name())?;
+            for (i, operand) in operands.iter().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                match op {
+                    Op::Jump | Op::JumpIfZero if i == operands.len() - 1 => {
+                        write!(f, "{sep}@{operand:04}")?
+                    }
+                    Op::Const if i == 1 => write!(f, "{sep}{operand:#010x}")?,
+                    _ => write!(f, "{sep}r{operand}")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Evaluate<'a> for Program {
+    fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
+        let mut registers = vec![0; self.registers as usize];
+        for ((path, ty), register) in self.context.iter().zip(registers.iter_mut()) {
+            if let Some(value) = read(&context, path) {
+                *register =
+                    encode(value, ty).ok_or_else(|| BytecodeError::InvalidRead(path_name(path)))?;
+            }
+        }
+
+        self.run(&mut registers)?;
+
+        let mut out = context;
+        for ((path, ty), register) in self.context.iter().zip(registers) {
+            out = write(out, path, decode(register, ty))?;
+        }
+        Ok(out)
+    }
+}
+
+fn read<'a>(s: &'a Struct, path: &[PropertyIdentifier]) -> Option<&'a Value> {
+    let (first, rest) = path.split_first()?;
+    match (s.members.get(first)?, rest.is_empty()) {
+        (value, true) => Some(value),
+        (Value::Struct(s), false) => read(s, rest),
+        _ => None,
+    }
+}
+
+fn write(
+    mut s: Struct,
+    path: &[PropertyIdentifier],
+    value: Value,
+) -> Result<Struct, BytecodeError> {
+    let (first, rest) = path
+        .split_first()
+        .ok_or_else(|| BytecodeError::InvalidRead(path_name(path)))?;
+
+    let value = if rest.is_empty() {
+        value
+    } else {
+        let inner = match s.members.remove(first) {
+            Some(Value::Struct(inner)) => inner,
+            _ => match property_type(first)? {
+                Type::Struct(id) => Struct::new(id.clone()),
+                _ => return Err(BytecodeError::InvalidRead(path_name(path))),
+            },
+        };
+        Value::Struct(write(inner, rest, value)?)
+    };
+
+    s.members.insert(first.clone(), value);
+    Ok(s)
+}
+
+/// Register representation of a scalar value
+fn encode(value: &Value, ty: &Type) -> Option<u32> {
+    Some(match (value, ty) {
+        (Value::Boolean(b), Type::Boolean) => *b as u32,
+        (Value::Number(Number::Float(n)), Type::Number(NumericType::Float)) => {
+            (*n as f32).to_bits()
+        }
+        (Value::Number(Number::SInt(n)), Type::Number(NumericType::SInt)) => *n as i32 as u32,
+        (Value::Number(Number::UInt(n)), Type::Number(NumericType::UInt)) => *n as u32,
+        _ => return None,
+    })
+}
+
+fn decode(register: u32, ty: &Type) -> Value {
+    match ty {
+        Type::Boolean => Value::Boolean(register != 0),
+        Type::Number(NumericType::Float) => f32::from_bits(register).into(),
+        Type::Number(NumericType::SInt) => (register as i32).into(),
+        Type::Number(NumericType::UInt) => register.into(),
+        Type::Struct(_) => unreachable!("Struct {} has no register representation", ty.name()),
+    }
+}
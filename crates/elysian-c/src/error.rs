@@ -0,0 +1,32 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CError {
+    MissingFunction(String),
+    MissingStruct(String),
+    MissingProperty(String),
+    MissingField { field: String, ty: String },
+    InvalidRead(String),
+    InvalidExpr(String),
+    InvalidStmt(String),
+}
+
+impl Display for CError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CError::MissingFunction(name) => {
+                f.write_str(&format!("No function definition for {name}"))
+            }
+            CError::MissingStruct(name) => f.write_str(&format!("No struct definition for {name}")),
+            CError::MissingProperty(name) => f.write_str(&format!("No type for property {name}")),
+            CError::MissingField { field, ty } => {
+                f.write_str(&format!("No field {field} in struct {ty}"))
+            }
+            CError::InvalidRead(path) => f.write_str(&format!("Invalid read of {path}")),
+            CError::InvalidExpr(expr) => f.write_str(&format!("Invalid expression {expr}")),
+            CError::InvalidStmt(stmt) => f.write_str(&format!("Invalid statement {stmt}")),
+        }
+    }
+}
+
+impl Error for CError {}
@@ -0,0 +1,410 @@
+//! Convert Elysian IR into self-contained C99 source
+
+mod error;
+mod names;
+mod writer;
+
+pub use error::*;
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::{
+    ast::Expr,
+    module::{Module, StructIdentifier, Type, CONTEXT},
+};
+
+use crate::{
+    names::{property_type, sanitize, Names},
+    writer::{helpers, FunctionWriter, Functions},
+};
+
+/// Builder for a C header and source file implementing an Elysian module
+///
+/// The header declares a plain struct for each of the module's types,
+/// including its context and vectors,
+/// alongside a single entry point taking and returning a context by value:
+///
+/// ```c
+/// prefix_Context prefix_evaluate(prefix_Context context);
+/// ```
+///
+/// Struct fields are named after their properties,
+/// and all other symbols are private to the generated source.
+/// Floats are single precision, and integers 32 bits wide.
+#[derive(Debug, Clone)]
+pub struct CBuilder<'a> {
+    module: &'a Module,
+    prefix: String,
+    header_name: Option<String>,
+}
+
+impl<'a> CBuilder<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        CBuilder {
+            module,
+            prefix: "elysian".to_string(),
+            header_name: None,
+        }
+    }
+
+    /// Set the prefix applied to every generated symbol
+    ///
+    /// Modules with distinct prefixes can be linked into the same program.
+    pub fn prefix(mut self, prefix: impl AsRef<str>) -> Self {
+        self.prefix = sanitize(prefix.as_ref());
+        self
+    }
+
+    /// Set the name under which the source file includes the header,
+    /// defaulting to `<prefix>.h`
+    pub fn header_name(mut self, header_name: impl Into<String>) -> Self {
+        self.header_name = Some(header_name.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CSource, CError> {
+        let module = self.module;
+        let prefix = &self.prefix;
+        let names = Names::new(module, prefix);
+
+        let functions: Functions = module
+            .function_definitions
+            .iter()
+            .map(|def| (def.id.clone(), def))
+            .collect();
+
+        let entry_def = functions
+            .get(&module.entry_point)
+            .ok_or_else(|| CError::MissingFunction(module.entry_point.name_unique()))?;
+        let output = property_type(&entry_def.output)?;
+        let context = PropertyIdentifier(CONTEXT);
+        let entry_name = format!("{prefix}_evaluate");
+
+        let header_name = self
+            .header_name
+            .clone()
+            .unwrap_or_else(|| format!("{prefix}.h"));
+
+        // Header
+        let guard = sanitize(&header_name).to_uppercase();
+        let mut header = format!(
+            "#ifndef {guard}\n\
+             #define {guard}\n\
+             \n\
+             #include <stdbool.h>\n\
+             #include <stdint.h>\n\
+             \n\
+             #ifdef __cplusplus\n\
+             extern \"C\" {{\n\
+             #endif\n\
+             \n"
+        );
+
+        for id in struct_order(module, &names)? {
+            header += &struct_definition(&names, &id)?;
+            header += "\n";
+        }
+
+        header += &format!(
+            "{} {entry_name}({} context);\n",
+            names.ty(output)?,
+            names.ty(property_type(&context)?)?
+        );
+
+        header += "\n\
+                   #ifdef __cplusplus\n\
+                   }\n\
+                   #endif\n\
+                   \n\
+                   #endif\n";
+
+        // Source
+        let mut source = format!("#include \"{header_name}\"\n\n#include <math.h>\n\n");
+        source += &helpers(prefix);
+        source += "\n";
+
+        for def in module.function_definitions.iter() {
+            source += &FunctionWriter::new(&names, &functions).signature(def)?;
+            source += ";\n";
+        }
+        source += "\n";
+
+        for def in module.function_definitions.iter() {
+            source += &FunctionWriter::new(&names, &functions).function(def)?;
+            source += "\n";
+        }
+
+        source += &FunctionWriter::new(&names, &functions).entry(
+            &entry_name,
+            &context,
+            output,
+            &module.call(Expr::Read(vec![context.clone()])),
+        )?;
+
+        Ok(CSource {
+            source_name: format!(
+                "{}.c",
+                header_name.strip_suffix(".h").unwrap_or(&header_name)
+            ),
+            header_name,
+            header,
+            source,
+        })
+    }
+}
+
+/// Generated C header and source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CSource {
+    pub header_name: String,
+    pub source_name: String,
+    pub header: String,
+    pub source: String,
+}
+
+impl CSource {
+    /// Write the header and source into a directory, returning their paths
+    pub fn write(&self, dir: impl AsRef<Path>) -> std::io::Result<(PathBuf, PathBuf)> {
+        let dir = dir.as_ref();
+        let header = dir.join(&self.header_name);
+        let source = dir.join(&self.source_name);
+        std::fs::write(&header, &self.header)?;
+        std::fs::write(&source, &self.source)?;
+        Ok((header, source))
+    }
+}
+
+/// Convert a module into C source with the default prefix
+pub fn module_to_c(module: &Module) -> Result<CSource, CError> {
+    CBuilder::new(module).build()
+}
+
+/// Struct definitions ordered such that each follows the types of its fields
+fn struct_order(module: &Module, names: &Names) -> Result<Vec<StructIdentifier>, CError> {
+    fn visit(
+        id: &StructIdentifier,
+        names: &Names,
+        visited: &mut HashSet<StructIdentifier>,
+        out: &mut Vec<StructIdentifier>,
+    ) -> Result<(), CError> {
+        if !visited.insert(id.clone()) {
+            return Ok(());
+        }
+
+        for (_, _, ty) in names.fields(id)? {
+            if let Type::Struct(field_id) = ty {
+                visit(field_id, names, visited, out)?;
+            }
+        }
+
+        out.push(id.clone());
+        Ok(())
+    }
+
+    let mut visited = HashSet::new();
+    let mut out = vec![];
+    for def in module.struct_definitions.iter() {
+        visit(&def.id, names, &mut visited, &mut out)?;
+    }
+    Ok(out)
+}
+
+fn struct_definition(names: &Names, id: &StructIdentifier) -> Result<String, CError> {
+    let name = names.struct_name(id)?;
+    let mut out = format!("typedef struct {name} {{\n");
+
+    let fields = names.fields(id)?;
+    if fields.is_empty() {
+        out += "    char empty;\n";
+    }
+
+    for (_, field, ty) in fields {
+        out += &format!("    {} {field};\n", names.ty(ty)?);
+    }
+
+    out += &format!("}} {name};\n");
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use std::process::Command;
+
+    use elysian_core::number::Number;
+    use elysian_interpreter::Interpreted;
+    use elysian_ir::{
+        ast::{Struct, Value, POSITION_2D, VECTOR2, X, Y},
+        module::{AsModule, Evaluate, SpecializationData},
+    };
+    use elysian_shapes::{
+        field::{Circle, Point},
+        modify::{IntoGradientNormals, IntoIsosurface, IntoTranslate},
+    };
+
+    use super::*;
+
+    /// Scalar leaves of a struct type alongside their C accessors and IR paths
+    fn leaves(
+        names: &Names,
+        id: &StructIdentifier,
+        code: &str,
+        path: &[PropertyIdentifier],
+        out: &mut Vec<(String, Vec<PropertyIdentifier>)>,
+    ) {
+        for (prop, field, ty) in names.fields(id).unwrap() {
+            let code = format!("{code}.{field}");
+            let path: Vec<_> = path.iter().cloned().chain([prop.clone()]).collect();
+            match ty {
+                Type::Struct(id) => leaves(names, id, &code, &path, out),
+                _ => out.push((code, path)),
+            }
+        }
+    }
+
+    fn get(s: &Struct, path: &[PropertyIdentifier]) -> Option<Value> {
+        let (first, rest) = path.split_first()?;
+        let value = s.members.get(first)?.clone();
+        if rest.is_empty() {
+            return Some(value);
+        }
+        match value {
+            Value::Struct(s) => get(&s, rest),
+            _ => None,
+        }
+    }
+
+    /// Compile a module with the system C compiler,
+    /// and check its output against the interpreter at each of the given points
+    fn compare(module: &Module, name: &str, points: &[(f64, f64)]) {
+        let source = CBuilder::new(module).prefix(name).build().unwrap();
+        let names = Names::new(module, name);
+
+        let context_id = StructIdentifier(CONTEXT);
+        let context_ty = names.struct_name(&context_id).unwrap().to_string();
+        let mut outputs = vec![];
+        leaves(&names, &context_id, "out", &[], &mut outputs);
+
+        let position = names.field(&context_id, &POSITION_2D.into()).unwrap();
+        let x = names.field(&StructIdentifier(VECTOR2), &X.into()).unwrap();
+        let y = names.field(&StructIdentifier(VECTOR2), &Y.into()).unwrap();
+
+        let mut main = format!(
+            "#include <stdio.h>\n#include \"{}\"\n\nint main(void) {{\n",
+            source.header_name
+        );
+        for (px, py) in points {
+            main += &format!(
+                "    {{\n        {context_ty} in = {{0}};\n        in.{position}.{x} = {px:?}f;\n        in.{position}.{y} = {py:?}f;\n        {context_ty} out = {name}_evaluate(in);\n"
+            );
+            for (code, _) in outputs.iter() {
+                main += &format!("        printf(\"%.9g\\n\", (double){code});\n");
+            }
+            main += "    }\n";
+        }
+        main += "    return 0;\n}\n";
+
+        let dir = std::env::temp_dir().join(format!("elysian-c-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (_, source_path) = source.write(&dir).unwrap();
+        let main_path = dir.join("main.c");
+        std::fs::write(&main_path, main).unwrap();
+        let exe = dir.join(name);
+
+        let cc = std::env::var_os("CC").unwrap_or_else(|| "cc".into());
+        let status = Command::new(cc)
+            .args(["-std=c99", "-pedantic", "-Wall", "-O1", "-o"])
+            .arg(&exe)
+            .arg(&source_path)
+            .arg(&main_path)
+            .arg("-lm")
+            .status()
+            .expect("Failed to run the C compiler");
+        assert!(status.success(), "Failed to compile {}", dir.display());
+
+        let output = Command::new(&exe).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let mut found = stdout.lines();
+
+        for (px, py) in points {
+            let context = Struct::new(context_id.clone()).set(
+                POSITION_2D.into(),
+                Value::Struct(
+                    Struct::new(StructIdentifier(VECTOR2))
+                        .set(X.into(), (*px).into())
+                        .set(Y.into(), (*py).into()),
+                ),
+            );
+            let expected = Interpreted(module).evaluate(context).unwrap();
+
+            for (code, path) in outputs.iter() {
+                let found: f64 = found.next().unwrap().parse().unwrap();
+                let Some(expected) = get(&expected, path) else {
+                    continue;
+                };
+
+                let expected = match expected {
+                    Value::Boolean(b) => b as u8 as f64,
+                    Value::Number(Number::Float(n)) => n,
+                    Value::Number(Number::SInt(n)) => n as f64,
+                    Value::Number(Number::UInt(n)) => n as f64,
+                    value => panic!("Unexpected {value:?} at {code}"),
+                };
+
+                assert!(
+                    (expected - found).abs() <= 1e-4 * expected.abs().max(1.0),
+                    "{code} at ({px}, {py}): {expected} != {found}"
+                );
+            }
+        }
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_c_circle() {
+        let module = Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            "circle",
+            &[(0.0, 0.0), (0.25, -0.5), (1.0, 1.0), (-1.5, 0.75)],
+        );
+    }
+
+    #[test]
+    fn test_c_translated_point() {
+        let module = Point
+            .translate([0.25, -0.5])
+            .isosurface(0.75)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(&module, "point", &[(0.0, 0.0), (0.25, -0.5), (-1.0, 2.0)]);
+    }
+
+    #[test]
+    fn test_c_provenance() {
+        let module = Point
+            .translate([0.25, -0.5])
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        let source = module_to_c(&module).unwrap().source;
+
+        let translate = source
+            .lines()
+            .find(|line| line.starts_with("/* Generated by Translate ("))
+            .expect("No provenance for Translate");
+        assert!(translate.contains(" in Modify ("), "{translate}");
+        assert!(source.contains("/* Generated by Point ("));
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{HashMap, HashSet};
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::module::{
+    self, properties, FunctionIdentifier, Module, NumericType, StructDefinition, StructIdentifier,
+    Type,
+};
+
+use crate::CError;
+
+/// C spellings of the identifiers in a module
+///
+/// Struct types and their fields keep their readable IR names,
+/// so that the generated header forms a stable interface.
+/// Functions and locals are private to the generated source,
+/// and use unique names to avoid collisions.
+#[derive(Debug, Clone)]
+pub(crate) struct Names<'a> {
+    prefix: String,
+    structs: HashMap<StructIdentifier, (String, &'a StructDefinition)>,
+    fields: HashMap<(StructIdentifier, PropertyIdentifier), String>,
+}
+
+impl<'a> Names<'a> {
+    pub fn new(module: &'a Module, prefix: &str) -> Self {
+        let mut structs = HashMap::new();
+        let mut fields = HashMap::new();
+        let mut struct_names = HashSet::new();
+
+        for def in module.struct_definitions.iter() {
+            let mut name = format!("{prefix}_{}", sanitize(def.id.name()));
+            if !struct_names.insert(name.clone()) {
+                name = format!("{prefix}_{}", sanitize(&def.id.name_unique()));
+            }
+            structs.insert(def.id.clone(), (name, def));
+
+            let mut field_names = HashSet::new();
+            for field in def.fields.iter() {
+                let mut name = sanitize(field.id.name());
+                if KEYWORDS.contains(&name.as_str()) || !field_names.insert(name.clone()) {
+                    name = sanitize(&field.id.name_unique());
+                }
+                fields.insert((def.id.clone(), field.id.clone()), name);
+            }
+        }
+
+        Names {
+            prefix: prefix.to_string(),
+            structs,
+            fields,
+        }
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn struct_definition(&self, id: &StructIdentifier) -> Result<&'a StructDefinition, CError> {
+        self.structs
+            .get(id)
+            .map(|(_, def)| *def)
+            .ok_or_else(|| CError::MissingStruct(id.name().to_string()))
+    }
+
+    pub fn struct_name(&self, id: &StructIdentifier) -> Result<&str, CError> {
+        self.structs
+            .get(id)
+            .map(|(name, _)| name.as_str())
+            .ok_or_else(|| CError::MissingStruct(id.name().to_string()))
+    }
+
+    pub fn field(&self, id: &StructIdentifier, prop: &PropertyIdentifier) -> Result<&str, CError> {
+        self.fields
+            .get(&(id.clone(), prop.clone()))
+            .map(String::as_str)
+            .ok_or_else(|| CError::MissingField {
+                field: prop.name().to_string(),
+                ty: id.name().to_string(),
+            })
+    }
+
+    /// Fields of a struct alongside their C names and types, in declaration order
+    pub fn fields(
+        &self,
+        id: &StructIdentifier,
+    ) -> Result<Vec<(&'a PropertyIdentifier, &str, &'static Type)>, CError> {
+        self.struct_definition(id)?
+            .fields
+            .iter()
+            .map(|field| {
+                Ok((
+                    &field.id,
+                    self.field(id, &field.id)?,
+                    field_type(id, &field.id)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn function(&self, id: &FunctionIdentifier) -> String {
+        format!("{}_{}", self.prefix, sanitize(&id.name_unique()))
+    }
+
+    pub fn local(&self, prop: &PropertyIdentifier) -> String {
+        sanitize(&prop.name_unique())
+    }
+
+    pub fn ty(&self, ty: &Type) -> Result<String, CError> {
+        Ok(match ty {
+            Type::Boolean => "bool".to_string(),
+            Type::Number(NumericType::UInt) => "uint32_t".to_string(),
+            Type::Number(NumericType::SInt) => "int32_t".to_string(),
+            Type::Number(NumericType::Float) => "float".to_string(),
+            Type::Struct(id) => self.struct_name(id)?.to_string(),
+        })
+    }
+}
+
+pub(crate) fn property_type(prop: &PropertyIdentifier) -> Result<&'static Type, CError> {
+    properties()
+        .get(prop)
+        .ok_or_else(|| CError::MissingProperty(prop.name().to_string()))
+}
+
+pub(crate) fn field_type(
+    structure: &StructIdentifier,
+    prop: &PropertyIdentifier,
+) -> Result<&'static Type, CError> {
+    module::field_type(structure, prop)
+        .ok_or_else(|| CError::MissingProperty(prop.name().to_string()))
+}
+
+/// Replace any characters that can't appear in a C identifier
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if out.starts_with(|c: char| c.is_ascii_digit()) || out.is_empty() {
+        out.insert(0, '_');
+    }
+
+    out
+}
+
+/// C99 keywords, which can't be used as field names
+const KEYWORDS: &[&str] = &[
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else",
+    "enum", "extern", "float", "for", "goto", "if", "inline", "int", "long", "register",
+    "restrict", "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef",
+    "union", "unsigned", "void", "volatile", "while", "bool", "true", "false",
+];
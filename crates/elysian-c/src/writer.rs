@@ -0,0 +1,965 @@
+use std::collections::{HashMap, HashSet};
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Value},
+    module::{FunctionDefinition, FunctionIdentifier, NumericType, StructIdentifier, Type},
+};
+
+use crate::{
+    names::{field_type, property_type, Names},
+    CError,
+};
+
+/// Function definitions keyed by identifier
+pub(crate) type Functions<'a> = HashMap<FunctionIdentifier, &'a FunctionDefinition>;
+
+/// Math routines without a direct C99 equivalent,
+/// matching the semantics of the Rust backends
+const HELPERS: &str = "\
+static inline float PREFIX_sign_f(float x) {
+    return isnan(x) ? x : copysignf(1.0f, x);
+}
+
+static inline int32_t PREFIX_sign_i(int32_t x) {
+    return (x > 0) - (x < 0);
+}
+
+static inline int32_t PREFIX_abs_i(int32_t x) {
+    return x < 0 ? -x : x;
+}
+
+static inline float PREFIX_rem_f(float lhs, float rhs) {
+    float r = fmodf(lhs, rhs);
+    return r < 0.0f ? r + fabsf(rhs) : r;
+}
+
+static inline int32_t PREFIX_rem_i(int32_t lhs, int32_t rhs) {
+    int32_t r = lhs % rhs;
+    return r < 0 ? (rhs < 0 ? r - rhs : r + rhs) : r;
+}
+
+static inline int32_t PREFIX_min_i(int32_t lhs, int32_t rhs) {
+    return lhs < rhs ? lhs : rhs;
+}
+
+static inline int32_t PREFIX_max_i(int32_t lhs, int32_t rhs) {
+    return lhs > rhs ? lhs : rhs;
+}
+
+static inline uint32_t PREFIX_min_u(uint32_t lhs, uint32_t rhs) {
+    return lhs < rhs ? lhs : rhs;
+}
+
+static inline uint32_t PREFIX_max_u(uint32_t lhs, uint32_t rhs) {
+    return lhs > rhs ? lhs : rhs;
+}
+";
+
+pub(crate) fn helpers(prefix: &str) -> String {
+    HELPERS.replace("PREFIX", prefix)
+}
+
+/// Result of writing an expression
+///
+/// Struct operands always name an lvalue, so that their members can be accessed directly.
+#[derive(Debug, Clone)]
+struct Operand {
+    code: String,
+    ty: Type,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unary {
+    Neg,
+    Abs,
+    Sign,
+    Round,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp2,
+    Log2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+/// Writes a single function body as C source
+pub(crate) struct FunctionWriter<'a, 'b> {
+    names: &'b Names<'a>,
+    functions: &'b Functions<'a>,
+    scope: HashSet<PropertyIdentifier>,
+    out: String,
+    indent: usize,
+    temps: usize,
+}
+
+impl<'a, 'b> FunctionWriter<'a, 'b> {
+    pub fn new(names: &'b Names<'a>, functions: &'b Functions<'a>) -> Self {
+        FunctionWriter {
+            names,
+            functions,
+            scope: Default::default(),
+            out: Default::default(),
+            indent: 0,
+            temps: 0,
+        }
+    }
+
+    /// Declaration of a generated function, without a trailing semicolon or body
+    pub fn signature(&self, def: &FunctionDefinition) -> Result<String, CError> {
+        let ret = self.names.ty(property_type(&def.output)?)?;
+        let params = def
+            .inputs
+            .iter()
+            .map(|input| {
+                Ok(format!(
+                    "{} {}",
+                    self.names.ty(property_type(&input.id)?)?,
+                    self.names.local(&input.id)
+                ))
+            })
+            .collect::<Result<Vec<_>, CError>>()?;
+
+        let params = if params.is_empty() {
+            "void".to_string()
+        } else {
+            params.join(", ")
+        };
+
+        Ok(format!(
+            "static {ret} {}({params})",
+            self.names.function(&def.id)
+        ))
+    }
+
+    /// Write a function definition
+    ///
+    /// Every property bound within the function is declared up front,
+    /// zero-initialized, since bindings may be read outside the block that writes them.
+    pub fn function(mut self, def: &FunctionDefinition) -> Result<String, CError> {
+        let output = property_type(&def.output)?;
+
+        if let Some(provenance) = &def.provenance {
+            self.line(format!("/* Generated by {provenance} */"));
+        }
+        self.line(format!("{} {{", self.signature(def)?));
+        self.indent += 1;
+
+        self.scope
+            .extend(def.inputs.iter().map(|input| input.id.clone()));
+
+        let mut locals = vec![];
+        block_locals(&def.block, &mut locals);
+        for prop in locals {
+            if self.scope.insert(prop.clone()) {
+                let ty = property_type(&prop)?;
+                self.line(format!(
+                    "{} {} = {};",
+                    self.names.ty(ty)?,
+                    self.names.local(&prop),
+                    self.zero(ty)?
+                ));
+            }
+        }
+
+        self.line(format!(
+            "{} out = {};",
+            self.names.ty(output)?,
+            self.zero(output)?
+        ));
+
+        self.block(&def.block)?;
+
+        self.line("return out;");
+        self.indent -= 1;
+        self.line("}");
+
+        Ok(self.out)
+    }
+
+    /// Write a public function calling `call` with a single `input`
+    pub fn entry(
+        mut self,
+        name: &str,
+        input: &PropertyIdentifier,
+        output: &Type,
+        call: &Expr,
+    ) -> Result<String, CError> {
+        self.line(format!(
+            "{} {name}({} {}) {{",
+            self.names.ty(output)?,
+            self.names.ty(property_type(input)?)?,
+            self.names.local(input)
+        ));
+        self.indent += 1;
+
+        self.scope.insert(input.clone());
+        let result = self.expr(call)?;
+        self.line(format!("return {};", result.code));
+
+        self.indent -= 1;
+        self.line("}");
+
+        Ok(self.out)
+    }
+
+    fn line(&mut self, line: impl AsRef<str>) {
+        for _ in 0..self.indent {
+            self.out += "    ";
+        }
+        self.out += line.as_ref();
+        self.out += "\n";
+    }
+
+    fn block(&mut self, Block(stmts): &Block) -> Result<(), CError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write a statement within its own scope
+    fn scoped(&mut self, stmt: &Stmt) -> Result<(), CError> {
+        self.indent += 1;
+        match stmt {
+            Stmt::Block(block) => self.block(block)?,
+            stmt => self.stmt(stmt)?,
+        }
+        self.indent -= 1;
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), CError> {
+        match stmt {
+            Stmt::Block(block) => {
+                self.line("{");
+                self.indent += 1;
+                self.block(block)?;
+                self.indent -= 1;
+                self.line("}");
+            }
+            Stmt::Bind { prop, expr } => {
+                let value = self.expr(expr)?;
+                self.line(format!("{} = {};", self.names.local(prop), value.code));
+            }
+            Stmt::Write { path, expr } => {
+                let value = self.expr(expr)?;
+                let target = self.path(path)?;
+                self.line(format!("{} = {};", target.code, value.code));
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let cond = self.expr(cond)?;
+                if cond.ty != Type::Boolean {
+                    return Err(CError::InvalidStmt(format!("If {}", cond.ty.name())));
+                }
+
+                self.line(format!("if ({}) {{", cond.code));
+                self.scoped(then)?;
+                if let Some(otherwise) = otherwise {
+                    self.line("} else {");
+                    self.scoped(otherwise)?;
+                }
+                self.line("}");
+            }
+            Stmt::Loop { stmt } => {
+                self.line("for (;;) {");
+                self.scoped(stmt)?;
+                self.line("}");
+            }
+            Stmt::Break => self.line("break;"),
+            Stmt::Output(expr) => {
+                let value = self.expr(expr)?;
+                self.line(format!("out = {};", value.code));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Operand, CError> {
+        Ok(match expr {
+            Expr::Literal(value) => {
+                let ty = value_type(value);
+                let code = self.literal(value)?;
+                self.operand(ty, code)
+            }
+            Expr::Struct(id, members) => {
+                let mut out = vec![];
+                for (prop, _, ty) in self.names.fields(id)? {
+                    out.push(match members.get(prop) {
+                        Some(expr) => self.expr(expr)?.code,
+                        None => self.zero(ty)?,
+                    });
+                }
+                self.temp(Type::Struct(id.clone()), braces(out))?
+            }
+            Expr::Read(path) => self.path(path)?,
+            Expr::Call { function, args } => {
+                let def = self
+                    .functions
+                    .get(function)
+                    .ok_or_else(|| CError::MissingFunction(function.name_unique()))?;
+
+                if args.len() != def.inputs.len() {
+                    return Err(CError::InvalidExpr(format!(
+                        "{} takes {} arguments, got {}",
+                        function.name_unique(),
+                        def.inputs.len(),
+                        args.len()
+                    )));
+                }
+
+                let args = args
+                    .iter()
+                    .map(|arg| Ok(self.expr(arg)?.code))
+                    .collect::<Result<Vec<_>, CError>>()?;
+
+                let code = format!("{}({})", self.names.function(function), args.join(", "));
+                self.operand(property_type(&def.output)?.clone(), code)
+            }
+            Expr::Neg(t) => self.unary(Unary::Neg, t)?,
+            Expr::Abs(t) => self.unary(Unary::Abs, t)?,
+            Expr::Sign(t) => self.unary(Unary::Sign, t)?,
+            Expr::Round(t) => self.unary(Unary::Round, t)?,
+            Expr::Sin(t) => self.unary(Unary::Sin, t)?,
+            Expr::Cos(t) => self.unary(Unary::Cos, t)?,
+            Expr::Tan(t) => self.unary(Unary::Tan, t)?,
+            Expr::Asin(t) => self.unary(Unary::Asin, t)?,
+            Expr::Acos(t) => self.unary(Unary::Acos, t)?,
+            Expr::Atan(t) => self.unary(Unary::Atan, t)?,
+            Expr::Exp2(t) => self.unary(Unary::Exp2, t)?,
+            Expr::Log2(t) => self.unary(Unary::Log2, t)?,
+            Expr::Length(t) => match self.expr(t)? {
+                t @ Operand {
+                    ty: Type::Struct(_),
+                    ..
+                } => {
+                    let dot = self.dot(&t, &t)?;
+                    Operand {
+                        code: format!("sqrtf({dot})"),
+                        ty: Type::Number(NumericType::Float),
+                    }
+                }
+                t => self.map_unary(Unary::Abs, t)?,
+            },
+            Expr::Normalize(t) => match self.expr(t)? {
+                t @ Operand {
+                    ty: Type::Struct(_),
+                    ..
+                } => self.normalize(t)?,
+                t => self.map_unary(Unary::Sign, t)?,
+            },
+            Expr::Add(lhs, rhs) => self.binary(Binary::Add, lhs, rhs)?,
+            Expr::Sub(lhs, rhs) => self.binary(Binary::Sub, lhs, rhs)?,
+            Expr::Mul(lhs, rhs) => self.binary(Binary::Mul, lhs, rhs)?,
+            Expr::Div(lhs, rhs) => self.binary(Binary::Div, lhs, rhs)?,
+            Expr::Mod(lhs, rhs) => self.binary(Binary::Mod, lhs, rhs)?,
+            Expr::Min(lhs, rhs) => self.binary(Binary::Min, lhs, rhs)?,
+            Expr::Max(lhs, rhs) => self.binary(Binary::Max, lhs, rhs)?,
+            Expr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Operand {
+                    code: self.equal(&lhs, &rhs)?,
+                    ty: Type::Boolean,
+                }
+            }
+            Expr::Ne(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Operand {
+                    code: format!("(!{})", self.equal(&lhs, &rhs)?),
+                    ty: Type::Boolean,
+                }
+            }
+            Expr::Lt(lhs, rhs) => self.compare("<", lhs, rhs)?,
+            Expr::Gt(lhs, rhs) => self.compare(">", lhs, rhs)?,
+            Expr::And(lhs, rhs) => self.logic("&&", lhs, rhs)?,
+            Expr::Or(lhs, rhs) => self.logic("||", lhs, rhs)?,
+            Expr::Dot(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (&lhs.ty, &rhs.ty) {
+                    (Type::Number(_), Type::Number(_)) => self.map_binary(Binary::Mul, lhs, rhs)?,
+                    _ => Operand {
+                        code: self.dot(&lhs, &rhs)?,
+                        ty: Type::Number(NumericType::Float),
+                    },
+                }
+            }
+            Expr::Atan2(lhs, rhs) => {
+                let (y, x) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (&y.ty, &x.ty) {
+                    (Type::Number(NumericType::Float), Type::Number(NumericType::Float)) => {
+                        Operand {
+                            code: format!("atan2f({}, {})", y.code, x.code),
+                            ty: Type::Number(NumericType::Float),
+                        }
+                    }
+                    _ => return Err(invalid_binary("Atan2", &y, &x)),
+                }
+            }
+            Expr::Mix(lhs, rhs, t) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let t = self.expr(t)?;
+                if t.ty != Type::Number(NumericType::Float) {
+                    return Err(CError::InvalidExpr(format!("Mix factor {}", t.ty.name())));
+                }
+                let t = self.share(t)?;
+
+                self.map(&[lhs, rhs, t], |_, args| {
+                    let [a, b, t] = args else { unreachable!() };
+                    Ok(format!("((1.0f - {t}) * {a} + {t} * {b})"))
+                })?
+            }
+            Expr::Clamp(t, min, max) => {
+                let (t, min, max) = (self.expr(t)?, self.expr(min)?, self.expr(max)?);
+                let prefix = self.names.prefix().to_string();
+                self.map(&[t, min, max], |ty, args| {
+                    let [t, min, max] = args else { unreachable!() };
+                    Ok(match ty {
+                        Type::Number(NumericType::Float) => {
+                            format!("fminf(fmaxf({t}, {min}), {max})")
+                        }
+                        Type::Number(NumericType::SInt) => {
+                            format!("{prefix}_min_i({prefix}_max_i({t}, {min}), {max})")
+                        }
+                        Type::Number(NumericType::UInt) => {
+                            format!("{prefix}_min_u({prefix}_max_u({t}, {min}), {max})")
+                        }
+                        ty => return Err(CError::InvalidExpr(format!("Clamp({})", ty.name()))),
+                    })
+                })?
+            }
+        })
+    }
+
+    /// Find the local or field at the end of a path
+    fn path(&self, path: &[PropertyIdentifier]) -> Result<Operand, CError> {
+        let path_name = || {
+            path.iter()
+                .map(|prop| prop.name())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let (root, rest) = path
+            .split_first()
+            .ok_or_else(|| CError::InvalidRead(path_name()))?;
+
+        if !self.scope.contains(root) {
+            return Err(CError::InvalidRead(path_name()));
+        }
+
+        let mut code = self.names.local(root);
+        let mut ty = property_type(root)?;
+
+        for prop in rest {
+            let Type::Struct(id) = ty else {
+                return Err(CError::InvalidRead(path_name()));
+            };
+            code += ".";
+            code += self.names.field(id, prop)?;
+            ty = field_type(id, prop)?;
+        }
+
+        Ok(Operand {
+            code,
+            ty: ty.clone(),
+        })
+    }
+
+    fn literal(&self, value: &Value) -> Result<String, CError> {
+        Ok(match value {
+            Value::Boolean(b) => b.to_string(),
+            Value::Number(Number::UInt(n)) => format!("{}u", *n as u32),
+            Value::Number(Number::SInt(n)) if *n < 0 => format!("({})", *n as i32),
+            Value::Number(Number::SInt(n)) => format!("{}", *n as i32),
+            Value::Number(Number::Float(n)) => float_literal(*n as f32),
+            Value::Struct(s) => {
+                let mut out = vec![];
+                for (prop, _, ty) in self.names.fields(&s.id)? {
+                    out.push(match s.members.get(prop) {
+                        Some(value) => self.literal(value)?,
+                        None => self.zero(ty)?,
+                    });
+                }
+                braces(out)
+            }
+        })
+    }
+
+    /// Initializer for the zero value of a type
+    fn zero(&self, ty: &Type) -> Result<String, CError> {
+        Ok(match ty {
+            Type::Boolean => "false".to_string(),
+            Type::Number(NumericType::UInt) => "0u".to_string(),
+            Type::Number(NumericType::SInt) => "0".to_string(),
+            Type::Number(NumericType::Float) => "0.0f".to_string(),
+            Type::Struct(id) => braces(
+                self.names
+                    .fields(id)?
+                    .into_iter()
+                    .map(|(_, _, ty)| self.zero(ty))
+                    .collect::<Result<Vec<_>, _>>()?,
+            ),
+        })
+    }
+
+    /// Wrap the result of an expression,
+    /// binding it to a temporary if it's a struct
+    fn operand(&mut self, ty: Type, code: String) -> Operand {
+        if let Type::Struct(_) = ty {
+            self.temp(ty, code).expect("Struct type has a name")
+        } else {
+            Operand { code, ty }
+        }
+    }
+
+    fn temp(&mut self, ty: Type, init: String) -> Result<Operand, CError> {
+        let name = format!("t{}", self.temps);
+        self.temps += 1;
+        self.line(format!("{} {name} = {init};", self.names.ty(&ty)?));
+        Ok(Operand { code: name, ty })
+    }
+
+    /// Bind an operand to a temporary if it would be expensive to repeat
+    fn share(&mut self, operand: Operand) -> Result<Operand, CError> {
+        if operand
+            .code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+        {
+            Ok(operand)
+        } else {
+            self.temp(operand.ty, operand.code)
+        }
+    }
+
+    /// Apply `f` to corresponding scalar leaves of a set of operands
+    ///
+    /// Struct operands must share a type, and are mapped memberwise,
+    /// while scalar operands are broadcast to each member.
+    fn map(
+        &mut self,
+        operands: &[Operand],
+        mut f: impl FnMut(&Type, &[String]) -> Result<String, CError>,
+    ) -> Result<Operand, CError> {
+        let structs: Vec<_> = operands
+            .iter()
+            .filter(|operand| matches!(operand.ty, Type::Struct(_)))
+            .collect();
+
+        let Some(first) = structs.first() else {
+            let ty = numeric_type(operands);
+            let codes: Vec<_> = operands.iter().map(|op| op.code.clone()).collect();
+            let code = f(&ty, &codes)?;
+            return Ok(Operand { code, ty });
+        };
+
+        let ty = first.ty.clone();
+        if structs.iter().any(|operand| operand.ty != ty) {
+            return Err(CError::InvalidExpr(format!(
+                "Mismatched operands {}",
+                operands
+                    .iter()
+                    .map(|operand| operand.ty.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let mut shared = vec![];
+        for operand in operands {
+            shared.push(self.share(operand.clone())?);
+        }
+
+        let init = self.map_leaves(&ty, &shared, &mut f)?;
+        self.temp(ty, init)
+    }
+
+    fn map_leaves(
+        &self,
+        ty: &Type,
+        operands: &[Operand],
+        f: &mut impl FnMut(&Type, &[String]) -> Result<String, CError>,
+    ) -> Result<String, CError> {
+        let Type::Struct(id) = ty else {
+            let codes: Vec<_> = operands.iter().map(|op| op.code.clone()).collect();
+            return f(ty, &codes);
+        };
+
+        let mut members = vec![];
+        for (_, field, field_ty) in self.names.fields(id)? {
+            let operands: Vec<_> = operands
+                .iter()
+                .map(|operand| match &operand.ty {
+                    Type::Struct(cand) if cand == id => Operand {
+                        code: format!("{}.{field}", operand.code),
+                        ty: field_ty.clone(),
+                    },
+                    _ => operand.clone(),
+                })
+                .collect();
+            members.push(self.map_leaves(field_ty, &operands, f)?);
+        }
+
+        Ok(braces(members))
+    }
+
+    fn unary(&mut self, op: Unary, t: &Expr) -> Result<Operand, CError> {
+        let t = self.expr(t)?;
+        self.map_unary(op, t)
+    }
+
+    fn map_unary(&mut self, op: Unary, t: Operand) -> Result<Operand, CError> {
+        let prefix = self.names.prefix().to_string();
+        self.map(&[t], |ty, args| {
+            let [t] = args else { unreachable!() };
+            let Type::Number(n) = ty else {
+                return Err(CError::InvalidExpr(format!("{op:?}({})", ty.name())));
+            };
+
+            Ok(match (op, n) {
+                (Unary::Neg, NumericType::Float | NumericType::SInt) => format!("(-{t})"),
+                (Unary::Abs, NumericType::Float) => format!("fabsf({t})"),
+                (Unary::Abs, NumericType::SInt) => format!("{prefix}_abs_i({t})"),
+                (Unary::Sign, NumericType::Float) => format!("{prefix}_sign_f({t})"),
+                (Unary::Sign, NumericType::SInt) => format!("{prefix}_sign_i({t})"),
+                (Unary::Round, NumericType::Float) => format!("roundf({t})"),
+                (Unary::Sin, NumericType::Float) => format!("sinf({t})"),
+                (Unary::Cos, NumericType::Float) => format!("cosf({t})"),
+                (Unary::Tan, NumericType::Float) => format!("tanf({t})"),
+                (Unary::Asin, NumericType::Float) => format!("asinf({t})"),
+                (Unary::Acos, NumericType::Float) => format!("acosf({t})"),
+                (Unary::Atan, NumericType::Float) => format!("atanf({t})"),
+                (Unary::Exp2, NumericType::Float) => format!("exp2f({t})"),
+                (Unary::Log2, NumericType::Float) => format!("log2f({t})"),
+                _ => return Err(CError::InvalidExpr(format!("{op:?}({})", ty.name()))),
+            })
+        })
+    }
+
+    fn binary(&mut self, op: Binary, lhs: &Expr, rhs: &Expr) -> Result<Operand, CError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        self.map_binary(op, lhs, rhs)
+    }
+
+    /// Apply a binary operator to a pair of operands
+    ///
+    /// Operations between a struct and a number apply to each member of the struct,
+    /// and operations between structs of the same type apply memberwise,
+    /// except for matrix multiplication.
+    fn map_binary(&mut self, op: Binary, lhs: Operand, rhs: Operand) -> Result<Operand, CError> {
+        if let (Type::Struct(id), Type::Struct(_)) = (&lhs.ty, &rhs.ty) {
+            if op == Binary::Mul && is_matrix(id) {
+                return self.matrix_mul(lhs, rhs);
+            }
+        }
+
+        let prefix = self.names.prefix().to_string();
+        self.map(&[lhs, rhs], |ty, args| {
+            let [x, y] = args else { unreachable!() };
+            let Type::Number(n) = ty else {
+                return Err(CError::InvalidExpr(format!("{op:?}({})", ty.name())));
+            };
+
+            Ok(match (op, n) {
+                (Binary::Add, _) => format!("({x} + {y})"),
+                (Binary::Sub, _) => format!("({x} - {y})"),
+                (Binary::Mul, _) => format!("({x} * {y})"),
+                (Binary::Div, _) => format!("({x} / {y})"),
+                (Binary::Mod, NumericType::Float) => format!("{prefix}_rem_f({x}, {y})"),
+                (Binary::Mod, NumericType::SInt) => format!("{prefix}_rem_i({x}, {y})"),
+                (Binary::Mod, NumericType::UInt) => format!("({x} % {y})"),
+                (Binary::Min, NumericType::Float) => format!("fminf({x}, {y})"),
+                (Binary::Min, NumericType::SInt) => format!("{prefix}_min_i({x}, {y})"),
+                (Binary::Min, NumericType::UInt) => format!("{prefix}_min_u({x}, {y})"),
+                (Binary::Max, NumericType::Float) => format!("fmaxf({x}, {y})"),
+                (Binary::Max, NumericType::SInt) => format!("{prefix}_max_i({x}, {y})"),
+                (Binary::Max, NumericType::UInt) => format!("{prefix}_max_u({x}, {y})"),
+            })
+        })
+    }
+
+    /// Multiply a column-major matrix by a vector or another matrix
+    fn matrix_mul(&mut self, lhs: Operand, rhs: Operand) -> Result<Operand, CError> {
+        let Type::Struct(lhs_id) = &lhs.ty else {
+            unreachable!()
+        };
+        let Type::Struct(rhs_id) = &rhs.ty else {
+            unreachable!()
+        };
+
+        let columns = self
+            .names
+            .fields(lhs_id)?
+            .into_iter()
+            .map(|(_, field, ty)| self.float_leaves(&format!("{}.{field}", lhs.code), ty))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let init = if is_matrix(rhs_id) {
+            let mut out = vec![];
+            for (_, field, ty) in self.names.fields(rhs_id)? {
+                let components = self.float_leaves(&format!("{}.{field}", rhs.code), ty)?;
+                out.push(matrix_vector_mul(&columns, &components)?);
+            }
+            braces(out)
+        } else {
+            let components = self.float_leaves(&rhs.code, &rhs.ty)?;
+            matrix_vector_mul(&columns, &components)?
+        };
+
+        let ty = if is_matrix(rhs_id) { lhs.ty } else { rhs.ty };
+        self.temp(ty, init)
+    }
+
+    /// Accessors for the leaves of a value made up entirely of floats
+    fn float_leaves(&self, code: &str, ty: &Type) -> Result<Vec<String>, CError> {
+        let leaves = self.leaves(code, ty)?;
+        if leaves
+            .iter()
+            .any(|(_, ty)| *ty != Type::Number(NumericType::Float))
+        {
+            return Err(CError::InvalidExpr(format!(
+                "{} is not a float vector",
+                ty.name()
+            )));
+        }
+        Ok(leaves.into_iter().map(|(code, _)| code).collect())
+    }
+
+    /// Accessors for the scalar leaves of a value, ordered depth-first
+    fn leaves(&self, code: &str, ty: &Type) -> Result<Vec<(String, Type)>, CError> {
+        let Type::Struct(id) = ty else {
+            return Ok(vec![(code.to_string(), ty.clone())]);
+        };
+
+        let mut out = vec![];
+        for (_, field, ty) in self.names.fields(id)? {
+            out.extend(self.leaves(&format!("{code}.{field}"), ty)?);
+        }
+        Ok(out)
+    }
+
+    fn dot(&self, lhs: &Operand, rhs: &Operand) -> Result<String, CError> {
+        if lhs.ty != rhs.ty {
+            return Err(invalid_binary("Dot", lhs, rhs));
+        }
+
+        let a = self.float_leaves(&lhs.code, &lhs.ty)?;
+        let b = self.float_leaves(&rhs.code, &rhs.ty)?;
+        if a.is_empty() {
+            return Err(invalid_binary("Dot", lhs, rhs));
+        }
+
+        Ok(format!(
+            "({})",
+            a.iter()
+                .zip(b.iter())
+                .map(|(a, b)| format!("{a} * {b}"))
+                .collect::<Vec<_>>()
+                .join(" + ")
+        ))
+    }
+
+    /// Matches glam's `normalize_or_zero`
+    fn normalize(&mut self, v: Operand) -> Result<Operand, CError> {
+        let dot = self.dot(&v, &v)?;
+        let rcp = self.temp(
+            Type::Number(NumericType::Float),
+            format!("1.0f / sqrtf({dot})"),
+        )?;
+        let valid = self.temp(
+            Type::Boolean,
+            format!("isfinite({0}) && {0} > 0.0f", rcp.code),
+        )?;
+
+        self.map(&[v, rcp, valid], |_, args| {
+            let [x, rcp, valid] = args else {
+                unreachable!()
+            };
+            Ok(format!("({valid} ? {x} * {rcp} : 0.0f)"))
+        })
+    }
+
+    fn equal(&self, lhs: &Operand, rhs: &Operand) -> Result<String, CError> {
+        let compatible = match (&lhs.ty, &rhs.ty) {
+            (Type::Number(_), Type::Number(_)) => true,
+            (a, b) => a == b,
+        };
+        if !compatible {
+            return Err(invalid_binary("Eq", lhs, rhs));
+        }
+
+        let a = self.leaves(&lhs.code, &lhs.ty)?;
+        let b = self.leaves(&rhs.code, &rhs.ty)?;
+        if a.is_empty() {
+            return Ok("true".to_string());
+        }
+
+        Ok(format!(
+            "({})",
+            a.iter()
+                .zip(b.iter())
+                .map(|((a, _), (b, _))| format!("{a} == {b}"))
+                .collect::<Vec<_>>()
+                .join(" && ")
+        ))
+    }
+
+    fn compare(&mut self, op: &str, lhs: &Expr, rhs: &Expr) -> Result<Operand, CError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        match (&lhs.ty, &rhs.ty) {
+            (Type::Number(_), Type::Number(_)) => Ok(Operand {
+                code: format!("({} {op} {})", lhs.code, rhs.code),
+                ty: Type::Boolean,
+            }),
+            _ => Err(invalid_binary(op, &lhs, &rhs)),
+        }
+    }
+
+    fn logic(&mut self, op: &str, lhs: &Expr, rhs: &Expr) -> Result<Operand, CError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        match (&lhs.ty, &rhs.ty) {
+            (Type::Boolean, Type::Boolean) => Ok(Operand {
+                code: format!("({} {op} {})", lhs.code, rhs.code),
+                ty: Type::Boolean,
+            }),
+            _ => Err(invalid_binary(op, &lhs, &rhs)),
+        }
+    }
+}
+
+/// Properties bound or written anywhere within a block, in order of first appearance
+fn block_locals(Block(stmts): &Block, out: &mut Vec<PropertyIdentifier>) {
+    for stmt in stmts {
+        stmt_locals(stmt, out);
+    }
+}
+
+fn stmt_locals(stmt: &Stmt, out: &mut Vec<PropertyIdentifier>) {
+    let mut push = |prop: &PropertyIdentifier| {
+        if !out.contains(prop) {
+            out.push(prop.clone())
+        }
+    };
+
+    match stmt {
+        Stmt::Block(block) => block_locals(block, out),
+        Stmt::Bind { prop, .. } => push(prop),
+        Stmt::Write { path, .. } => {
+            if let Some(root) = path.first() {
+                push(root)
+            }
+        }
+        Stmt::If {
+            then, otherwise, ..
+        } => {
+            stmt_locals(then, out);
+            if let Some(otherwise) = otherwise {
+                stmt_locals(otherwise, out);
+            }
+        }
+        Stmt::Loop { stmt } => stmt_locals(stmt, out),
+        Stmt::Break | Stmt::Output(_) => (),
+    }
+}
+
+fn matrix_vector_mul(columns: &[Vec<String>], components: &[String]) -> Result<String, CError> {
+    if columns.len() != components.len() || columns.iter().any(|c| c.len() != columns.len()) {
+        return Err(CError::InvalidExpr("Matrix multiplication".into()));
+    }
+
+    let rows = (0..columns.len())
+        .map(|row| {
+            format!(
+                "({})",
+                columns
+                    .iter()
+                    .zip(components)
+                    .map(|(column, component)| format!("{} * {component}", column[row]))
+                    .collect::<Vec<_>>()
+                    .join(" + ")
+            )
+        })
+        .collect();
+
+    Ok(braces(rows))
+}
+
+/// Brace-enclosed initializer list
+///
+/// C has no empty initializers, so structs without fields hold a placeholder member.
+pub(crate) fn braces(members: Vec<String>) -> String {
+    if members.is_empty() {
+        "{ 0 }".to_string()
+    } else {
+        format!("{{ {} }}", members.join(", "))
+    }
+}
+
+fn float_literal(n: f32) -> String {
+    if n.is_nan() {
+        "NAN".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 {
+            "INFINITY".to_string()
+        } else {
+            "(-INFINITY)".to_string()
+        }
+    } else if n.is_sign_negative() {
+        format!("({n:?}f)")
+    } else {
+        format!("{n:?}f")
+    }
+}
+
+fn value_type(value: &Value) -> Type {
+    match value {
+        Value::Boolean(_) => Type::Boolean,
+        Value::Number(Number::UInt(_)) => Type::Number(NumericType::UInt),
+        Value::Number(Number::SInt(_)) => Type::Number(NumericType::SInt),
+        Value::Number(Number::Float(_)) => Type::Number(NumericType::Float),
+        Value::Struct(s) => Type::Struct(s.id.clone()),
+    }
+}
+
+/// Type resulting from arithmetic between scalars,
+/// following C's promotion of integers to floats
+fn numeric_type(operands: &[Operand]) -> Type {
+    if operands
+        .iter()
+        .any(|operand| operand.ty == Type::Number(NumericType::Float))
+    {
+        Type::Number(NumericType::Float)
+    } else {
+        operands
+            .first()
+            .map(|operand| operand.ty.clone())
+            .unwrap_or(Type::Number(NumericType::Float))
+    }
+}
+
+fn is_matrix(id: &StructIdentifier) -> bool {
+    matches!(id.name(), "Matrix2" | "Matrix3" | "Matrix4")
+}
+
+fn invalid_binary(op: &str, lhs: &Operand, rhs: &Operand) -> CError {
+    CError::InvalidExpr(format!("{op}({}, {})", lhs.ty.name(), rhs.ty.name()))
+}
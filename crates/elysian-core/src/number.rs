@@ -4,7 +4,7 @@ use std::{
 };
 
 use elysian_math::{
-    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Max, Min, Mix, Round, Sign, Sin, Tan,
+    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Exp2, Log2, Max, Min, Mix, Round, Sign, Sin, Tan,
 };
 
 #[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
@@ -227,6 +227,24 @@ impl Atan for Number {
     }
 }
 
+impl Exp2 for Number {
+    fn exp2(self) -> Self {
+        match self {
+            Number::Float(a) => Number::Float(a.exp2()),
+            _ => panic!("Invalid Exp2"),
+        }
+    }
+}
+
+impl Log2 for Number {
+    fn log2(self) -> Self {
+        match self {
+            Number::Float(a) => Number::Float(a.log2()),
+            _ => panic!("Invalid Log2"),
+        }
+    }
+}
+
 impl Atan2 for Number {
     fn atan2(self, rhs: Self) -> Self {
         match (self, rhs) {
@@ -1,4 +1,4 @@
-use elysian_math::glam::Mat4;
+use elysian_math::glam::{Mat3, Mat4};
 
 use crate::number::Number;
 use std::fmt::Debug;
@@ -111,6 +111,28 @@ where
     }
 }
 
+impl From<Mat3> for Value {
+    fn from(value: Mat3) -> Self {
+        Value::Matrix3([
+            [
+                value.x_axis.x.into(),
+                value.x_axis.y.into(),
+                value.x_axis.z.into(),
+            ],
+            [
+                value.y_axis.x.into(),
+                value.y_axis.y.into(),
+                value.y_axis.z.into(),
+            ],
+            [
+                value.z_axis.x.into(),
+                value.z_axis.y.into(),
+                value.z_axis.z.into(),
+            ],
+        ])
+    }
+}
+
 impl From<Mat4> for Value {
     fn from(value: Mat4) -> Self {
         Value::Matrix4([
@@ -0,0 +1,61 @@
+use std::{error::Error, fmt::Display};
+
+use cranelift_module::ModuleError;
+use elysian_ir::module::LayoutError;
+
+#[derive(Debug)]
+pub enum CraneliftError {
+    Isa(String),
+    Module(Box<ModuleError>),
+    MissingFunction(String),
+    Layout(LayoutError),
+    MissingField { field: String, ty: String },
+    InvalidRead(String),
+    InvalidExpr(String),
+    InvalidStmt(String),
+    InvalidValue(String),
+}
+
+impl Display for CraneliftError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CraneliftError::Isa(e) => f.write_str(&format!("Unsupported host: {e}")),
+            CraneliftError::Module(e) => f.write_str(&format!("Failed to compile module: {e}")),
+            CraneliftError::MissingFunction(name) => {
+                f.write_str(&format!("No function definition for {name}"))
+            }
+            CraneliftError::Layout(e) => e.fmt(f),
+            CraneliftError::MissingField { field, ty } => {
+                f.write_str(&format!("No field {field} in struct {ty}"))
+            }
+            CraneliftError::InvalidRead(path) => f.write_str(&format!("Invalid read of {path}")),
+            CraneliftError::InvalidExpr(expr) => f.write_str(&format!("Invalid expression {expr}")),
+            CraneliftError::InvalidStmt(stmt) => f.write_str(&format!("Invalid statement {stmt}")),
+            CraneliftError::InvalidValue(value) => {
+                f.write_str(&format!("Value {value} does not match its context type"))
+            }
+        }
+    }
+}
+
+impl Error for CraneliftError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CraneliftError::Module(e) => Some(e),
+            CraneliftError::Layout(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<ModuleError> for CraneliftError {
+    fn from(value: ModuleError) -> Self {
+        CraneliftError::Module(Box::new(value))
+    }
+}
+
+impl From<LayoutError> for CraneliftError {
+    fn from(value: LayoutError) -> Self {
+        CraneliftError::Layout(value)
+    }
+}
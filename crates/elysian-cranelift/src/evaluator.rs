@@ -0,0 +1,181 @@
+use std::{fmt::Debug, sync::Mutex};
+
+use cranelift_codegen::{
+    ir::AbiParam,
+    settings::{self, Configurable},
+};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_jit::{JITBuilder, JITModule};
+use cranelift_module::{default_libcall_names, Linkage, Module as _};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Struct, Value},
+    module::{
+        Evaluate, EvaluateError, FunctionDefinition, FunctionIdentifier, InputDefinition, Layout,
+        Module, StructIdentifier, Type, CONTEXT,
+    },
+};
+
+use crate::{
+    intrinsic::Intrinsic,
+    lower::{signature, FunctionLowering, Functions},
+    CraneliftError,
+};
+
+pub const CRANELIFT_EVALUATE: FunctionIdentifier =
+    FunctionIdentifier::new("cranelift_evaluate", 81722532958167386019574383409011254021);
+
+type EvaluateFn = unsafe extern "C" fn(*const f64, *mut f64);
+
+/// Evaluator that compiles a module to native code in-process with Cranelift
+///
+/// Numbers are lowered at 32-bit precision, matching the static backend.
+pub struct CraneliftCompiled {
+    module: Module,
+    len: usize,
+    function: EvaluateFn,
+    // Owns the memory backing `function`;
+    // the lock is never taken outside of drop, and only serves to make this type Sync
+    jit: Mutex<Option<JITModule>>,
+}
+
+impl CraneliftCompiled {
+    /// Compile a finalized module, wrapping its entry point in [`CRANELIFT_EVALUATE`]
+    pub fn new(module: &Module) -> Result<Self, CraneliftError> {
+        let mut module = module.clone();
+        module.function_definitions.push(FunctionDefinition {
+            id: CRANELIFT_EVALUATE,
+            public: true,
+            inputs: vec![InputDefinition {
+                id: CONTEXT.into(),
+                mutable: false,
+            }],
+            output: CONTEXT.into(),
+            block: Block(vec![Stmt::Output(
+                module.call(Expr::Read(vec![CONTEXT.into()])),
+            )]),
+            provenance: None,
+        });
+
+        let mut flags = settings::builder();
+        for (name, value) in [
+            ("opt_level", "speed"),
+            ("use_colocated_libcalls", "false"),
+            ("is_pic", "false"),
+        ] {
+            flags
+                .set(name, value)
+                .map_err(|e| CraneliftError::Isa(e.to_string()))?;
+        }
+
+        let isa = cranelift_native::builder()
+            .map_err(|e| CraneliftError::Isa(e.to_string()))?
+            .finish(settings::Flags::new(flags))
+            .map_err(|e| CraneliftError::Isa(e.to_string()))?;
+
+        let mut builder = JITBuilder::with_isa(isa, default_libcall_names());
+        Intrinsic::register(&mut builder);
+        let mut jit = JITModule::new(builder);
+
+        let layout = Layout::new(&module);
+
+        // Declare every function up-front, so that calls can be lowered in any order
+        let mut functions = Functions::new();
+        for def in module.function_definitions.iter() {
+            let signature = signature(&jit, &layout, def)?;
+            let id = jit.declare_function(&def.name_unique(), Linkage::Local, &signature)?;
+            functions.insert(def.id.clone(), (id, def));
+        }
+
+        let mut context = jit.make_context();
+        let mut builder_context = FunctionBuilderContext::new();
+
+        for def in module.function_definitions.iter() {
+            let (id, _) = functions[&def.id];
+            context.func.signature = signature(&jit, &layout, def)?;
+
+            let builder = FunctionBuilder::new(&mut context.func, &mut builder_context);
+            FunctionLowering::new(&mut jit, layout, &functions, builder).function(def)?;
+
+            jit.define_function(id, &mut context)?;
+            jit.clear_context(&mut context);
+        }
+
+        let pointer = jit.target_config().pointer_type();
+        let mut trampoline = jit.make_signature();
+        trampoline.params.push(AbiParam::new(pointer));
+        trampoline.params.push(AbiParam::new(pointer));
+        let evaluate = jit.declare_function("elysian_evaluate", Linkage::Export, &trampoline)?;
+
+        let context_ty = Type::Struct(StructIdentifier(CONTEXT));
+        context.func.signature = trampoline;
+        let builder = FunctionBuilder::new(&mut context.func, &mut builder_context);
+        FunctionLowering::new(&mut jit, layout, &functions, builder)
+            .trampoline(&CRANELIFT_EVALUATE, &context_ty)?;
+
+        jit.define_function(evaluate, &mut context)?;
+        jit.clear_context(&mut context);
+        jit.finalize_definitions()?;
+
+        // Safety: the trampoline was declared with a matching signature
+        // in the host's default calling convention
+        let function = unsafe {
+            std::mem::transmute::<*const u8, EvaluateFn>(jit.get_finalized_function(evaluate))
+        };
+
+        let len = layout.leaves(&context_ty)?.len();
+
+        Ok(CraneliftCompiled {
+            module,
+            len,
+            function,
+            jit: Mutex::new(Some(jit)),
+        })
+    }
+}
+
+impl Debug for CraneliftCompiled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CraneliftCompiled")
+            .field("module", &self.module)
+            .field("len", &self.len)
+            .field("function", &self.function)
+            .finish()
+    }
+}
+
+impl Drop for CraneliftCompiled {
+    fn drop(&mut self) {
+        if let Some(jit) = self.jit.get_mut().ok().and_then(Option::take) {
+            // Safety: `function` is not callable once self is dropped
+            unsafe { jit.free_memory() };
+        }
+    }
+}
+
+impl<'a> Evaluate<'a> for CraneliftCompiled {
+    fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
+        let ty = Type::Struct(StructIdentifier(CONTEXT));
+
+        let layout = Layout::new(&self.module);
+
+        let mut input = Vec::with_capacity(self.len);
+        layout.flatten(&ty, Some(&Value::Struct(context)), &mut input)?;
+        if input.len() != self.len {
+            return Err(Box::new(CraneliftError::InvalidValue(format!(
+                "Context buffer of length {}, expected {}",
+                input.len(),
+                self.len
+            ))));
+        }
+
+        let mut output = vec![0.0; self.len];
+
+        // Safety: both buffers hold the number of slots the compiled function expects
+        unsafe { (self.function)(input.as_ptr(), output.as_mut_ptr()) };
+
+        match layout.unflatten(&ty, &mut output.into_iter())? {
+            Value::Struct(s) => Ok(s),
+            v => Err(Box::new(CraneliftError::InvalidValue(v.to_string()))),
+        }
+    }
+}
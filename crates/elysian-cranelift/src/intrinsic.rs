@@ -0,0 +1,157 @@
+use cranelift_codegen::ir::{types, AbiParam, Signature, Type};
+use cranelift_jit::JITBuilder;
+
+/// Math routines without a native Cranelift instruction,
+/// called into the host's Rust implementations
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Intrinsic {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Exp2,
+    Log2,
+    Round,
+    RemFloat,
+    RemSInt,
+    RemUInt,
+}
+
+impl Intrinsic {
+    pub const ALL: [Intrinsic; 13] = [
+        Intrinsic::Sin,
+        Intrinsic::Cos,
+        Intrinsic::Tan,
+        Intrinsic::Asin,
+        Intrinsic::Acos,
+        Intrinsic::Atan,
+        Intrinsic::Atan2,
+        Intrinsic::Exp2,
+        Intrinsic::Log2,
+        Intrinsic::Round,
+        Intrinsic::RemFloat,
+        Intrinsic::RemSInt,
+        Intrinsic::RemUInt,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Intrinsic::Sin => "elysian_sin",
+            Intrinsic::Cos => "elysian_cos",
+            Intrinsic::Tan => "elysian_tan",
+            Intrinsic::Asin => "elysian_asin",
+            Intrinsic::Acos => "elysian_acos",
+            Intrinsic::Atan => "elysian_atan",
+            Intrinsic::Atan2 => "elysian_atan2",
+            Intrinsic::Exp2 => "elysian_exp2",
+            Intrinsic::Log2 => "elysian_log2",
+            Intrinsic::Round => "elysian_round",
+            Intrinsic::RemFloat => "elysian_rem_float",
+            Intrinsic::RemSInt => "elysian_rem_sint",
+            Intrinsic::RemUInt => "elysian_rem_uint",
+        }
+    }
+
+    fn address(&self) -> *const u8 {
+        match self {
+            Intrinsic::Sin => sin as *const u8,
+            Intrinsic::Cos => cos as *const u8,
+            Intrinsic::Tan => tan as *const u8,
+            Intrinsic::Asin => asin as *const u8,
+            Intrinsic::Acos => acos as *const u8,
+            Intrinsic::Atan => atan as *const u8,
+            Intrinsic::Atan2 => atan2 as *const u8,
+            Intrinsic::Exp2 => exp2 as *const u8,
+            Intrinsic::Log2 => log2 as *const u8,
+            Intrinsic::Round => round as *const u8,
+            Intrinsic::RemFloat => rem_float as *const u8,
+            Intrinsic::RemSInt => rem_sint as *const u8,
+            Intrinsic::RemUInt => rem_uint as *const u8,
+        }
+    }
+
+    fn ty(&self) -> Type {
+        match self {
+            Intrinsic::RemSInt | Intrinsic::RemUInt => types::I64,
+            _ => types::F32,
+        }
+    }
+
+    fn arity(&self) -> usize {
+        match self {
+            Intrinsic::Atan2 | Intrinsic::RemFloat | Intrinsic::RemSInt | Intrinsic::RemUInt => 2,
+            _ => 1,
+        }
+    }
+
+    pub fn signature(&self, mut signature: Signature) -> Signature {
+        let ty = self.ty();
+        signature
+            .params
+            .extend(std::iter::repeat_n(AbiParam::new(ty), self.arity()));
+        signature.returns.push(AbiParam::new(ty));
+        signature
+    }
+
+    /// Register every intrinsic with a JIT builder
+    pub fn register(builder: &mut JITBuilder) {
+        for intrinsic in Intrinsic::ALL {
+            builder.symbol(intrinsic.name(), intrinsic.address());
+        }
+    }
+}
+
+extern "C" fn sin(x: f32) -> f32 {
+    x.sin()
+}
+
+extern "C" fn cos(x: f32) -> f32 {
+    x.cos()
+}
+
+extern "C" fn tan(x: f32) -> f32 {
+    x.tan()
+}
+
+extern "C" fn asin(x: f32) -> f32 {
+    x.asin()
+}
+
+extern "C" fn acos(x: f32) -> f32 {
+    x.acos()
+}
+
+extern "C" fn atan(x: f32) -> f32 {
+    x.atan()
+}
+
+extern "C" fn atan2(y: f32, x: f32) -> f32 {
+    y.atan2(x)
+}
+
+extern "C" fn exp2(x: f32) -> f32 {
+    x.exp2()
+}
+
+extern "C" fn log2(x: f32) -> f32 {
+    x.log2()
+}
+
+extern "C" fn round(x: f32) -> f32 {
+    x.round()
+}
+
+extern "C" fn rem_float(lhs: f32, rhs: f32) -> f32 {
+    lhs.rem_euclid(rhs)
+}
+
+extern "C" fn rem_sint(lhs: i64, rhs: i64) -> i64 {
+    lhs.rem_euclid(rhs)
+}
+
+extern "C" fn rem_uint(lhs: u64, rhs: u64) -> u64 {
+    lhs.rem_euclid(rhs)
+}
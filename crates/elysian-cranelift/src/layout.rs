@@ -0,0 +1,18 @@
+use cranelift_codegen::ir::{types, Type as ClifType};
+use elysian_ir::module::{NumericType, Type};
+
+/// Size in bytes of each scalar slot in a flattened value
+///
+/// Struct values are passed between functions as their scalar leaves,
+/// in the order given by [`Layout::leaves`](elysian_ir::module::Layout::leaves).
+pub(crate) const SLOT_SIZE: u32 = 8;
+
+/// Native representation of a scalar type
+pub(crate) fn clif_type(ty: &Type) -> ClifType {
+    match ty {
+        Type::Boolean => types::I8,
+        Type::Number(NumericType::UInt | NumericType::SInt) => types::I64,
+        Type::Number(NumericType::Float) => types::F32,
+        Type::Struct(_) => panic!("Struct {} has no scalar type", ty.name()),
+    }
+}
@@ -0,0 +1,57 @@
+//! Compile modules into native code in-process via Cranelift
+
+mod error;
+mod evaluator;
+mod intrinsic;
+mod layout;
+mod lower;
+
+pub use error::*;
+pub use evaluator::*;
+
+#[cfg(test)]
+mod test {
+    use elysian_interpreter::Interpreted;
+    use elysian_ir::{
+        ast::{Struct, Value, DISTANCE, POSITION_2D, VECTOR2, X, Y},
+        module::{AsModule, Evaluate, SpecializationData, StructIdentifier, CONTEXT},
+    };
+    use elysian_shapes::{field::Circle, modify::IntoGradientNormals};
+
+    use super::*;
+
+    #[test]
+    fn test_cranelift_circle() {
+        let module = Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        let compiled = CraneliftCompiled::new(&module).unwrap();
+
+        for (x, y) in [(0.0, 0.0), (0.25, -0.5), (1.0, 1.0)] {
+            let context = Struct::new(StructIdentifier(CONTEXT)).set(
+                POSITION_2D.into(),
+                Value::Struct(
+                    Struct::new(StructIdentifier(VECTOR2))
+                        .set(X.into(), x.into())
+                        .set(Y.into(), y.into()),
+                ),
+            );
+
+            let expected: f32 = Interpreted(&module)
+                .evaluate(context.clone())
+                .unwrap()
+                .get(&DISTANCE.into())
+                .into();
+
+            let found: f32 = compiled
+                .evaluate(context)
+                .unwrap()
+                .get(&DISTANCE.into())
+                .into();
+
+            assert!((expected - found).abs() < 1e-5, "{expected} != {found}");
+        }
+    }
+}
@@ -0,0 +1,1157 @@
+use std::collections::HashMap;
+
+use cranelift_codegen::{
+    entity::EntityRef,
+    ir::{
+        condcodes::{FloatCC, IntCC},
+        types, AbiParam, Block as ClifBlock, InstBuilder, MemFlags, Signature, StackSlotData,
+        StackSlotKind, Value as ClifValue,
+    },
+};
+use cranelift_frontend::{FunctionBuilder, Variable};
+use cranelift_jit::JITModule;
+use cranelift_module::{FuncId, Linkage, Module as _};
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Value},
+    module::{
+        property_type, FunctionDefinition, FunctionIdentifier, Layout, NumericType,
+        StructIdentifier, Type,
+    },
+};
+
+use crate::{
+    intrinsic::Intrinsic,
+    layout::{clif_type, SLOT_SIZE},
+    CraneliftError,
+};
+
+/// Function definitions alongside their declarations in the JIT module
+pub(crate) type Functions<'a> = HashMap<FunctionIdentifier, (FuncId, &'a FunctionDefinition)>;
+
+/// Result of lowering an expression
+///
+/// Structs are kept as their individual members,
+/// so that reads and writes lower to SSA values rather than memory accesses.
+#[derive(Debug, Clone)]
+enum Lowered {
+    Boolean(ClifValue),
+    Number(NumericType, ClifValue),
+    Struct(StructIdentifier, Vec<Lowered>),
+}
+
+impl Lowered {
+    fn ty(&self) -> Type {
+        match self {
+            Lowered::Boolean(_) => Type::Boolean,
+            Lowered::Number(n, _) => Type::Number(*n),
+            Lowered::Struct(id, _) => Type::Struct(id.clone()),
+        }
+    }
+
+    fn leaves(&self, out: &mut Vec<ClifValue>) {
+        match self {
+            Lowered::Boolean(v) | Lowered::Number(_, v) => out.push(*v),
+            Lowered::Struct(_, members) => {
+                for member in members {
+                    member.leaves(out)
+                }
+            }
+        }
+    }
+}
+
+/// Variables backing a property bound within a function
+#[derive(Debug, Clone)]
+enum Local {
+    Boolean(Variable),
+    Number(NumericType, Variable),
+    Struct(StructIdentifier, Vec<(PropertyIdentifier, Local)>),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unary {
+    Neg,
+    Abs,
+    Sign,
+    Round,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp2,
+    Log2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+/// Signature of a lowered function
+///
+/// Takes a pointer to which its output is written,
+/// followed by the scalar leaves of each input.
+pub(crate) fn signature(
+    jit: &JITModule,
+    layout: &Layout,
+    def: &FunctionDefinition,
+) -> Result<Signature, CraneliftError> {
+    let mut signature = jit.make_signature();
+    signature
+        .params
+        .push(AbiParam::new(jit.target_config().pointer_type()));
+
+    for input in def.inputs.iter() {
+        for leaf in layout.leaves(property_type(&input.id)?)? {
+            signature.params.push(AbiParam::new(clif_type(&leaf)));
+        }
+    }
+
+    Ok(signature)
+}
+
+/// Lowers a single function body to Cranelift IR
+pub(crate) struct FunctionLowering<'a, 'b> {
+    jit: &'a mut JITModule,
+    layout: Layout<'a>,
+    functions: &'a Functions<'a>,
+    builder: FunctionBuilder<'b>,
+    locals: HashMap<PropertyIdentifier, Local>,
+    variables: usize,
+    output: Option<(ClifValue, Type)>,
+    loops: Vec<ClifBlock>,
+}
+
+impl<'a, 'b> FunctionLowering<'a, 'b> {
+    pub fn new(
+        jit: &'a mut JITModule,
+        layout: Layout<'a>,
+        functions: &'a Functions<'a>,
+        builder: FunctionBuilder<'b>,
+    ) -> Self {
+        FunctionLowering {
+            jit,
+            layout,
+            functions,
+            builder,
+            locals: Default::default(),
+            variables: 0,
+            output: None,
+            loops: vec![],
+        }
+    }
+
+    /// Lower a function definition with the signature given by [`signature`]
+    pub fn function(mut self, def: &FunctionDefinition) -> Result<(), CraneliftError> {
+        let entry = self.builder.create_block();
+        self.builder.append_block_params_for_function_params(entry);
+        self.builder.switch_to_block(entry);
+
+        let params = self.builder.block_params(entry).to_vec();
+        let (ret, params) = params.split_first().expect("No output parameter");
+        self.output = Some((*ret, property_type(&def.output)?.clone()));
+
+        let mut params = params.iter().copied();
+        for input in def.inputs.iter() {
+            let ty = property_type(&input.id)?;
+            let value = self.collect_leaves(ty, &mut params)?;
+            self.bind(&input.id, value)?;
+        }
+
+        self.block(&def.block)?;
+        self.finish();
+        Ok(())
+    }
+
+    /// Lower an `extern "C" fn(*const f64, *mut f64)` trampoline calling `function`,
+    /// converting its input and output between scalar slots and native types
+    pub fn trampoline(
+        mut self,
+        function: &FunctionIdentifier,
+        ty: &Type,
+    ) -> Result<(), CraneliftError> {
+        let entry = self.builder.create_block();
+        self.builder.append_block_params_for_function_params(entry);
+        self.builder.switch_to_block(entry);
+
+        let params = self.builder.block_params(entry).to_vec();
+        let [input, output] = params[..] else {
+            panic!("Invalid trampoline signature");
+        };
+
+        let context = self.load_external(ty, input, &mut 0)?;
+        let context = self.call(function, vec![context])?;
+        self.store_external(&context, output, &mut 0);
+
+        self.finish();
+        Ok(())
+    }
+
+    fn finish(mut self) {
+        self.builder.ins().return_(&[]);
+        self.builder.seal_all_blocks();
+        self.builder.finalize();
+    }
+
+    fn block(&mut self, Block(stmts): &Block) -> Result<(), CraneliftError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), CraneliftError> {
+        match stmt {
+            Stmt::Block(block) => self.block(block)?,
+            Stmt::Bind { prop, expr } => {
+                let value = self.expr(expr)?;
+                self.bind(prop, value)?;
+            }
+            Stmt::Write { path, expr } => {
+                let value = self.expr(expr)?;
+                let (root, _) = path
+                    .split_first()
+                    .ok_or_else(|| CraneliftError::InvalidStmt("Write to empty path".into()))?;
+
+                if path.len() == 1 {
+                    self.bind(root, value)?;
+                } else {
+                    let local = self.local(path)?.clone();
+                    self.assign(&local, value)?;
+                }
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let Lowered::Boolean(cond) = self.expr(cond)? else {
+                    return Err(CraneliftError::InvalidStmt(format!("If {cond:?}")));
+                };
+
+                let then_block = self.builder.create_block();
+                let merge_block = self.builder.create_block();
+                let else_block = if otherwise.is_some() {
+                    self.builder.create_block()
+                } else {
+                    merge_block
+                };
+
+                self.builder
+                    .ins()
+                    .brif(cond, then_block, &[], else_block, &[]);
+
+                self.builder.switch_to_block(then_block);
+                self.stmt(then)?;
+                self.builder.ins().jump(merge_block, &[]);
+
+                if let Some(otherwise) = otherwise {
+                    self.builder.switch_to_block(else_block);
+                    self.stmt(otherwise)?;
+                    self.builder.ins().jump(merge_block, &[]);
+                }
+
+                self.builder.switch_to_block(merge_block);
+            }
+            Stmt::Loop { stmt } => {
+                let header = self.builder.create_block();
+                let exit = self.builder.create_block();
+
+                self.builder.ins().jump(header, &[]);
+                self.builder.switch_to_block(header);
+
+                self.loops.push(exit);
+                self.stmt(stmt)?;
+                self.loops.pop();
+
+                self.builder.ins().jump(header, &[]);
+                self.builder.switch_to_block(exit);
+            }
+            Stmt::Break => {
+                let exit = *self
+                    .loops
+                    .last()
+                    .ok_or_else(|| CraneliftError::InvalidStmt("Break outside of Loop".into()))?;
+                self.builder.ins().jump(exit, &[]);
+
+                // Any statements following the break are unreachable
+                let unreachable = self.builder.create_block();
+                self.builder.switch_to_block(unreachable);
+            }
+            Stmt::Output(expr) => {
+                let value = self.expr(expr)?;
+                let (ret, ty) = self
+                    .output
+                    .clone()
+                    .ok_or_else(|| CraneliftError::InvalidStmt(format!("Output {expr:?}")))?;
+                let value = self.cast(value, &ty)?;
+                self.store(&value, ret, &mut 0);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Lowered, CraneliftError> {
+        Ok(match expr {
+            Expr::Literal(value) => self.literal(value)?,
+            Expr::Struct(id, members) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(id)? {
+                    out.push(match members.get(prop) {
+                        Some(expr) => {
+                            let value = self.expr(expr)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Expr::Read(path) => {
+                let local = self.local(path)?.clone();
+                self.read(&local)
+            }
+            Expr::Call { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<_, _>>()?;
+                self.call(function, args)?
+            }
+            Expr::Neg(t) => self.unary(Unary::Neg, t)?,
+            Expr::Abs(t) => self.unary(Unary::Abs, t)?,
+            Expr::Sign(t) => self.unary(Unary::Sign, t)?,
+            Expr::Round(t) => self.unary(Unary::Round, t)?,
+            Expr::Sin(t) => self.unary(Unary::Sin, t)?,
+            Expr::Cos(t) => self.unary(Unary::Cos, t)?,
+            Expr::Tan(t) => self.unary(Unary::Tan, t)?,
+            Expr::Asin(t) => self.unary(Unary::Asin, t)?,
+            Expr::Acos(t) => self.unary(Unary::Acos, t)?,
+            Expr::Atan(t) => self.unary(Unary::Atan, t)?,
+            Expr::Exp2(t) => self.unary(Unary::Exp2, t)?,
+            Expr::Log2(t) => self.unary(Unary::Log2, t)?,
+            Expr::Length(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Abs, v)?,
+                v @ Lowered::Struct(..) => {
+                    let dot = self.dot(v.clone(), v)?;
+                    Lowered::Number(NumericType::Float, self.builder.ins().sqrt(dot))
+                }
+                v => return Err(invalid_unary("Length", &v)),
+            },
+            Expr::Normalize(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Sign, v)?,
+                v @ Lowered::Struct(..) => self.normalize(v)?,
+                v => return Err(invalid_unary("Normalize", &v)),
+            },
+            Expr::Add(lhs, rhs) => self.binary(Binary::Add, lhs, rhs)?,
+            Expr::Sub(lhs, rhs) => self.binary(Binary::Sub, lhs, rhs)?,
+            Expr::Mul(lhs, rhs) => self.binary(Binary::Mul, lhs, rhs)?,
+            Expr::Div(lhs, rhs) => self.binary(Binary::Div, lhs, rhs)?,
+            Expr::Mod(lhs, rhs) => self.binary(Binary::Mod, lhs, rhs)?,
+            Expr::Min(lhs, rhs) => self.binary(Binary::Min, lhs, rhs)?,
+            Expr::Max(lhs, rhs) => self.binary(Binary::Max, lhs, rhs)?,
+            Expr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.equal(lhs, rhs)?)
+            }
+            Expr::Ne(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let eq = self.equal(lhs, rhs)?;
+                Lowered::Boolean(self.builder.ins().icmp_imm(IntCC::Equal, eq, 0))
+            }
+            Expr::Lt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(FloatCC::LessThan, lhs, rhs)?)
+            }
+            Expr::Gt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(FloatCC::GreaterThan, lhs, rhs)?)
+            }
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (Lowered::Boolean(lhs), Lowered::Boolean(rhs)) => {
+                    Lowered::Boolean(if let Expr::And(..) = expr {
+                        self.builder.ins().band(lhs, rhs)
+                    } else {
+                        self.builder.ins().bor(lhs, rhs)
+                    })
+                }
+                (lhs, rhs) => return Err(invalid_binary("Logic", &lhs, &rhs)),
+            },
+            Expr::Dot(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (lhs, rhs) {
+                    (lhs @ Lowered::Number(..), rhs @ Lowered::Number(..)) => {
+                        self.map_binary(Binary::Mul, lhs, rhs)?
+                    }
+                    (lhs, rhs) => Lowered::Number(NumericType::Float, self.dot(lhs, rhs)?),
+                }
+            }
+            Expr::Atan2(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (
+                    Lowered::Number(NumericType::Float, y),
+                    Lowered::Number(NumericType::Float, x),
+                ) => Lowered::Number(
+                    NumericType::Float,
+                    self.intrinsic(Intrinsic::Atan2, &[y, x])?,
+                ),
+                (lhs, rhs) => return Err(invalid_binary("Atan2", &lhs, &rhs)),
+            },
+            Expr::Mix(lhs, rhs, t) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let Lowered::Number(NumericType::Float, t) = self.expr(t)? else {
+                    return Err(CraneliftError::InvalidExpr(format!("Mix factor {t:?}")));
+                };
+                self.mix(lhs, rhs, t)?
+            }
+            Expr::Clamp(t, min, max) => {
+                let (t, min, max) = (self.expr(t)?, self.expr(min)?, self.expr(max)?);
+                self.clamp(t, min, max)?
+            }
+        })
+    }
+
+    fn literal(&mut self, value: &Value) -> Result<Lowered, CraneliftError> {
+        Ok(match value {
+            Value::Boolean(b) => Lowered::Boolean(self.builder.ins().iconst(types::I8, *b as i64)),
+            Value::Number(Number::UInt(n)) => Lowered::Number(
+                NumericType::UInt,
+                self.builder.ins().iconst(types::I64, *n as i64),
+            ),
+            Value::Number(Number::SInt(n)) => {
+                Lowered::Number(NumericType::SInt, self.builder.ins().iconst(types::I64, *n))
+            }
+            Value::Number(Number::Float(n)) => {
+                Lowered::Number(NumericType::Float, self.builder.ins().f32const(*n as f32))
+            }
+            Value::Struct(s) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(&s.id)? {
+                    out.push(match s.members.get(prop) {
+                        Some(value) => {
+                            let value = self.literal(value)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(s.id.clone(), out)
+            }
+        })
+    }
+
+    fn zero(&mut self, ty: &Type) -> Result<Lowered, CraneliftError> {
+        Ok(match ty {
+            Type::Boolean => Lowered::Boolean(self.builder.ins().iconst(types::I8, 0)),
+            Type::Number(NumericType::Float) => {
+                Lowered::Number(NumericType::Float, self.builder.ins().f32const(0.0))
+            }
+            Type::Number(n) => Lowered::Number(*n, self.builder.ins().iconst(types::I64, 0)),
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.zero(ty)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+        })
+    }
+
+    /// Convert a value to the given type, as required when binding it
+    fn cast(&mut self, value: Lowered, ty: &Type) -> Result<Lowered, CraneliftError> {
+        Ok(match (value, ty) {
+            (value @ Lowered::Boolean(_), Type::Boolean) => value,
+            (Lowered::Number(from, v), Type::Number(to)) => {
+                let ins = self.builder.ins();
+                let v = match (from, to) {
+                    (from, to) if from == *to => v,
+                    (NumericType::Float, NumericType::SInt) => ins.fcvt_to_sint_sat(types::I64, v),
+                    (NumericType::Float, NumericType::UInt) => ins.fcvt_to_uint_sat(types::I64, v),
+                    (NumericType::SInt, NumericType::Float) => ins.fcvt_from_sint(types::F32, v),
+                    (NumericType::UInt, NumericType::Float) => ins.fcvt_from_uint(types::F32, v),
+                    _ => v,
+                };
+                Lowered::Number(*to, v)
+            }
+            (Lowered::Struct(id, members), Type::Struct(to)) if id == *to => {
+                let mut out = vec![];
+                for (member, (_, ty)) in members.into_iter().zip(self.layout.fields(to)?) {
+                    out.push(self.cast(member, ty)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (value, ty) => {
+                return Err(CraneliftError::InvalidExpr(format!(
+                    "Can't convert {} to {}",
+                    value.ty().name(),
+                    ty.name()
+                )))
+            }
+        })
+    }
+
+    /// Rebuild a value of the given type from its scalar leaves
+    fn collect_leaves(
+        &self,
+        ty: &Type,
+        leaves: &mut impl Iterator<Item = ClifValue>,
+    ) -> Result<Lowered, CraneliftError> {
+        if let Type::Struct(id) = ty {
+            let mut out = vec![];
+            for (_, ty) in self.layout.fields(id)? {
+                out.push(self.collect_leaves(ty, leaves)?);
+            }
+            return Ok(Lowered::Struct(id.clone(), out));
+        }
+
+        let leaf = leaves
+            .next()
+            .ok_or_else(|| CraneliftError::InvalidValue(format!("Missing {}", ty.name())))?;
+
+        Ok(match ty {
+            Type::Boolean => Lowered::Boolean(leaf),
+            Type::Number(n) => Lowered::Number(*n, leaf),
+            Type::Struct(_) => unreachable!(),
+        })
+    }
+
+    /// Read a value from consecutive native slots
+    fn load(
+        &mut self,
+        ty: &Type,
+        ptr: ClifValue,
+        offset: &mut i32,
+    ) -> Result<Lowered, CraneliftError> {
+        let mut leaves = vec![];
+        for leaf in self.layout.leaves(ty)? {
+            leaves.push(self.builder.ins().load(
+                clif_type(&leaf),
+                MemFlags::trusted(),
+                ptr,
+                *offset,
+            ));
+            *offset += SLOT_SIZE as i32;
+        }
+
+        self.collect_leaves(ty, &mut leaves.into_iter())
+    }
+
+    /// Write a value to consecutive native slots
+    fn store(&mut self, value: &Lowered, ptr: ClifValue, offset: &mut i32) {
+        let mut leaves = vec![];
+        value.leaves(&mut leaves);
+        for leaf in leaves {
+            self.builder
+                .ins()
+                .store(MemFlags::trusted(), leaf, ptr, *offset);
+            *offset += SLOT_SIZE as i32;
+        }
+    }
+
+    /// Read a value from consecutive `f64` slots
+    fn load_external(
+        &mut self,
+        ty: &Type,
+        ptr: ClifValue,
+        offset: &mut i32,
+    ) -> Result<Lowered, CraneliftError> {
+        let mut leaves = vec![];
+        for leaf in self.layout.leaves(ty)? {
+            let ins = self.builder.ins();
+            let v = ins.load(types::F64, MemFlags::trusted(), ptr, *offset);
+            leaves.push(match leaf {
+                Type::Boolean => {
+                    let zero = self.builder.ins().f64const(0.0);
+                    self.builder.ins().fcmp(FloatCC::NotEqual, v, zero)
+                }
+                Type::Number(NumericType::UInt) => {
+                    self.builder.ins().fcvt_to_uint_sat(types::I64, v)
+                }
+                Type::Number(NumericType::SInt) => {
+                    self.builder.ins().fcvt_to_sint_sat(types::I64, v)
+                }
+                Type::Number(NumericType::Float) => self.builder.ins().fdemote(types::F32, v),
+                Type::Struct(_) => unreachable!(),
+            });
+            *offset += SLOT_SIZE as i32;
+        }
+
+        self.collect_leaves(ty, &mut leaves.into_iter())
+    }
+
+    /// Write a value to consecutive `f64` slots
+    fn store_external(&mut self, value: &Lowered, ptr: ClifValue, offset: &mut i32) {
+        let v = match value {
+            Lowered::Struct(_, members) => {
+                for member in members {
+                    self.store_external(member, ptr, offset);
+                }
+                return;
+            }
+            Lowered::Boolean(v) => {
+                let v = self.builder.ins().uextend(types::I64, *v);
+                self.builder.ins().fcvt_from_uint(types::F64, v)
+            }
+            Lowered::Number(NumericType::UInt, v) => {
+                self.builder.ins().fcvt_from_uint(types::F64, *v)
+            }
+            Lowered::Number(NumericType::SInt, v) => {
+                self.builder.ins().fcvt_from_sint(types::F64, *v)
+            }
+            Lowered::Number(NumericType::Float, v) => self.builder.ins().fpromote(types::F64, *v),
+        };
+
+        self.builder
+            .ins()
+            .store(MemFlags::trusted(), v, ptr, *offset);
+        *offset += SLOT_SIZE as i32;
+    }
+
+    fn create_local(&mut self, ty: &Type) -> Result<Local, CraneliftError> {
+        if let Type::Struct(id) = ty {
+            let mut fields = vec![];
+            for (prop, ty) in self.layout.fields(id)? {
+                fields.push((prop.clone(), self.create_local(ty)?));
+            }
+            return Ok(Local::Struct(id.clone(), fields));
+        }
+
+        let var = Variable::new(self.variables);
+        self.variables += 1;
+        self.builder.declare_var(var, clif_type(ty));
+
+        Ok(match ty {
+            Type::Boolean => Local::Boolean(var),
+            Type::Number(n) => Local::Number(*n, var),
+            Type::Struct(_) => unreachable!(),
+        })
+    }
+
+    /// Find the local at the end of a path
+    fn local(&self, path: &[PropertyIdentifier]) -> Result<&Local, CraneliftError> {
+        let path_name = || {
+            path.iter()
+                .map(|prop| prop.name())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let (root, rest) = path
+            .split_first()
+            .ok_or_else(|| CraneliftError::InvalidRead(path_name()))?;
+
+        let mut local = self
+            .locals
+            .get(root)
+            .ok_or_else(|| CraneliftError::InvalidRead(path_name()))?;
+
+        for prop in rest {
+            let Local::Struct(id, fields) = local else {
+                return Err(CraneliftError::InvalidRead(path_name()));
+            };
+
+            local = fields
+                .iter()
+                .find_map(|(cand, local)| (cand == prop).then_some(local))
+                .ok_or_else(|| CraneliftError::MissingField {
+                    field: prop.name().to_string(),
+                    ty: id.name().to_string(),
+                })?;
+        }
+
+        Ok(local)
+    }
+
+    fn read(&mut self, local: &Local) -> Lowered {
+        match local {
+            Local::Boolean(var) => Lowered::Boolean(self.builder.use_var(*var)),
+            Local::Number(n, var) => Lowered::Number(*n, self.builder.use_var(*var)),
+            Local::Struct(id, fields) => Lowered::Struct(
+                id.clone(),
+                fields.iter().map(|(_, local)| self.read(local)).collect(),
+            ),
+        }
+    }
+
+    /// Bind a value to a property, creating its local if necessary
+    fn bind(&mut self, prop: &PropertyIdentifier, value: Lowered) -> Result<(), CraneliftError> {
+        let local = match self.locals.get(prop) {
+            Some(local) => local.clone(),
+            None => {
+                let local = self.create_local(property_type(prop)?)?;
+                self.locals.insert(prop.clone(), local.clone());
+                local
+            }
+        };
+
+        self.assign(&local, value)
+    }
+
+    fn assign(&mut self, local: &Local, value: Lowered) -> Result<(), CraneliftError> {
+        let ty = match local {
+            Local::Boolean(_) => Type::Boolean,
+            Local::Number(n, _) => Type::Number(*n),
+            Local::Struct(id, _) => Type::Struct(id.clone()),
+        };
+
+        match (local, self.cast(value, &ty)?) {
+            (Local::Boolean(var), Lowered::Boolean(v))
+            | (Local::Number(_, var), Lowered::Number(_, v)) => self.builder.def_var(*var, v),
+            (Local::Struct(_, fields), Lowered::Struct(_, members)) => {
+                for ((_, local), member) in fields.iter().zip(members) {
+                    self.assign(local, member)?;
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        function: &FunctionIdentifier,
+        args: Vec<Lowered>,
+    ) -> Result<Lowered, CraneliftError> {
+        let (id, def) = *self
+            .functions
+            .get(function)
+            .ok_or_else(|| CraneliftError::MissingFunction(function.name_unique()))?;
+
+        if args.len() != def.inputs.len() {
+            return Err(CraneliftError::InvalidExpr(format!(
+                "{} takes {} arguments, got {}",
+                function.name_unique(),
+                def.inputs.len(),
+                args.len()
+            )));
+        }
+
+        let ty = property_type(&def.output)?;
+        let size = self.layout.leaves(ty)?.len() as u32 * SLOT_SIZE;
+        let slot = self.builder.create_sized_stack_slot(StackSlotData::new(
+            StackSlotKind::ExplicitSlot,
+            size,
+            SLOT_SIZE.trailing_zeros() as u8,
+        ));
+        let ret = self
+            .builder
+            .ins()
+            .stack_addr(self.jit.target_config().pointer_type(), slot, 0);
+
+        let mut params = vec![ret];
+        for (arg, input) in args.into_iter().zip(def.inputs.iter()) {
+            self.cast(arg, property_type(&input.id)?)?
+                .leaves(&mut params);
+        }
+
+        let func_ref = self.jit.declare_func_in_func(id, self.builder.func);
+        self.builder.ins().call(func_ref, &params);
+
+        self.load(ty, ret, &mut 0)
+    }
+
+    fn intrinsic(
+        &mut self,
+        intrinsic: Intrinsic,
+        args: &[ClifValue],
+    ) -> Result<ClifValue, CraneliftError> {
+        let signature = intrinsic.signature(self.jit.make_signature());
+        let id = self
+            .jit
+            .declare_function(intrinsic.name(), Linkage::Import, &signature)?;
+        let func_ref = self.jit.declare_func_in_func(id, self.builder.func);
+        let call = self.builder.ins().call(func_ref, args);
+        Ok(self.builder.inst_results(call)[0])
+    }
+
+    fn unary(&mut self, op: Unary, t: &Expr) -> Result<Lowered, CraneliftError> {
+        let t = self.expr(t)?;
+        self.map_unary(op, t)
+    }
+
+    /// Apply a unary operator to a value, or to each member of a struct
+    fn map_unary(&mut self, op: Unary, t: Lowered) -> Result<Lowered, CraneliftError> {
+        let (n, v) = match t {
+            Lowered::Struct(id, members) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_unary(op, member)?);
+                }
+                return Ok(Lowered::Struct(id, out));
+            }
+            Lowered::Number(n, v) => (n, v),
+            t => return Err(invalid_unary(&format!("{op:?}"), &t)),
+        };
+
+        let ins = self.builder.ins();
+        let v = match (op, n) {
+            (Unary::Neg, NumericType::Float) => ins.fneg(v),
+            (Unary::Neg, NumericType::SInt) => ins.ineg(v),
+            (Unary::Abs, NumericType::Float) => ins.fabs(v),
+            (Unary::Abs, NumericType::SInt) => ins.iabs(v),
+            (Unary::Sign, NumericType::Float) => {
+                // Matches f32::signum: +-1 for signed zeroes, NaN for NaN
+                let one = ins.f32const(1.0);
+                let sign = self.builder.ins().fcopysign(one, v);
+                let nan = self.builder.ins().fcmp(FloatCC::Unordered, v, v);
+                self.builder.ins().select(nan, v, sign)
+            }
+            (Unary::Sign, NumericType::SInt) => {
+                let one = ins.iconst(types::I64, 1);
+                let clamped = self.builder.ins().smin(v, one);
+                let minus_one = self.builder.ins().iconst(types::I64, -1);
+                self.builder.ins().smax(clamped, minus_one)
+            }
+            (Unary::Round, NumericType::Float) => self.intrinsic(Intrinsic::Round, &[v])?,
+            (Unary::Sin, NumericType::Float) => self.intrinsic(Intrinsic::Sin, &[v])?,
+            (Unary::Cos, NumericType::Float) => self.intrinsic(Intrinsic::Cos, &[v])?,
+            (Unary::Tan, NumericType::Float) => self.intrinsic(Intrinsic::Tan, &[v])?,
+            (Unary::Asin, NumericType::Float) => self.intrinsic(Intrinsic::Asin, &[v])?,
+            (Unary::Acos, NumericType::Float) => self.intrinsic(Intrinsic::Acos, &[v])?,
+            (Unary::Atan, NumericType::Float) => self.intrinsic(Intrinsic::Atan, &[v])?,
+            (Unary::Exp2, NumericType::Float) => self.intrinsic(Intrinsic::Exp2, &[v])?,
+            (Unary::Log2, NumericType::Float) => self.intrinsic(Intrinsic::Log2, &[v])?,
+            _ => return Err(invalid_unary(&format!("{op:?}"), &Lowered::Number(n, v))),
+        };
+
+        Ok(Lowered::Number(n, v))
+    }
+
+    fn binary(&mut self, op: Binary, lhs: &Expr, rhs: &Expr) -> Result<Lowered, CraneliftError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        self.map_binary(op, lhs, rhs)
+    }
+
+    /// Apply a binary operator to a pair of values
+    ///
+    /// Operations between a struct and a number apply to each member of the struct,
+    /// and operations between structs of the same type apply memberwise,
+    /// except for matrix multiplication.
+    fn map_binary(
+        &mut self,
+        op: Binary,
+        lhs: Lowered,
+        rhs: Lowered,
+    ) -> Result<Lowered, CraneliftError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => {
+                Lowered::Number(a, self.scalar_binary(op, a, x, y)?)
+            }
+            (Lowered::Struct(id, members), rhs @ Lowered::Number(..)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, member, rhs.clone())?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (lhs @ Lowered::Number(..), Lowered::Struct(id, members)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, lhs.clone(), member)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (Lowered::Struct(id, columns), rhs @ Lowered::Struct(..))
+                if op == Binary::Mul && is_matrix(&id) =>
+            {
+                self.matrix_mul(id, columns, rhs)?
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.map_binary(op, lhs, rhs)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary(&format!("{op:?}"), &lhs, &rhs)),
+        })
+    }
+
+    fn scalar_binary(
+        &mut self,
+        op: Binary,
+        n: NumericType,
+        x: ClifValue,
+        y: ClifValue,
+    ) -> Result<ClifValue, CraneliftError> {
+        use NumericType::*;
+
+        let ins = self.builder.ins();
+        Ok(match (op, n) {
+            (Binary::Add, Float) => ins.fadd(x, y),
+            (Binary::Add, _) => ins.iadd(x, y),
+            (Binary::Sub, Float) => ins.fsub(x, y),
+            (Binary::Sub, _) => ins.isub(x, y),
+            (Binary::Mul, Float) => ins.fmul(x, y),
+            (Binary::Mul, _) => ins.imul(x, y),
+            (Binary::Div, Float) => ins.fdiv(x, y),
+            (Binary::Div, SInt) => ins.sdiv(x, y),
+            (Binary::Div, UInt) => ins.udiv(x, y),
+            (Binary::Mod, Float) => self.intrinsic(Intrinsic::RemFloat, &[x, y])?,
+            (Binary::Mod, SInt) => self.intrinsic(Intrinsic::RemSInt, &[x, y])?,
+            (Binary::Mod, UInt) => self.intrinsic(Intrinsic::RemUInt, &[x, y])?,
+            (Binary::Min, Float) => ins.fmin(x, y),
+            (Binary::Min, SInt) => ins.smin(x, y),
+            (Binary::Min, UInt) => ins.umin(x, y),
+            (Binary::Max, Float) => ins.fmax(x, y),
+            (Binary::Max, SInt) => ins.smax(x, y),
+            (Binary::Max, UInt) => ins.umax(x, y),
+        })
+    }
+
+    /// Multiply a column-major matrix by a vector or another matrix
+    fn matrix_mul(
+        &mut self,
+        id: StructIdentifier,
+        columns: Vec<Lowered>,
+        rhs: Lowered,
+    ) -> Result<Lowered, CraneliftError> {
+        let Lowered::Struct(rhs_id, rhs_members) = rhs else {
+            unreachable!()
+        };
+
+        if !is_matrix(&rhs_id) {
+            return self.matrix_vector_mul(&columns, Lowered::Struct(rhs_id, rhs_members));
+        }
+
+        let mut out = vec![];
+        for column in rhs_members {
+            out.push(self.matrix_vector_mul(&columns, column)?);
+        }
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn matrix_vector_mul(
+        &mut self,
+        columns: &[Lowered],
+        vector: Lowered,
+    ) -> Result<Lowered, CraneliftError> {
+        let invalid = || CraneliftError::InvalidExpr("Matrix multiplication".into());
+
+        let Lowered::Struct(id, components) = vector else {
+            return Err(invalid());
+        };
+
+        let columns = columns
+            .iter()
+            .map(|column| floats(column).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let components = floats(&Lowered::Struct(id.clone(), components)).ok_or_else(invalid)?;
+
+        if columns.len() != components.len() || columns.iter().any(|c| c.len() != columns.len()) {
+            return Err(invalid());
+        }
+
+        let mut out = vec![];
+        for row in 0..columns.len() {
+            let mut sum = self.builder.ins().fmul(columns[0][row], components[0]);
+            for (column, component) in columns.iter().zip(components.iter()).skip(1) {
+                let product = self.builder.ins().fmul(column[row], *component);
+                sum = self.builder.ins().fadd(sum, product);
+            }
+            out.push(Lowered::Number(NumericType::Float, sum));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn dot(&mut self, lhs: Lowered, rhs: Lowered) -> Result<ClifValue, CraneliftError> {
+        let (a, b) = match (floats(&lhs), floats(&rhs)) {
+            (Some(a), Some(b)) if lhs.ty() == rhs.ty() && !a.is_empty() => (a, b),
+            _ => return Err(invalid_binary("Dot", &lhs, &rhs)),
+        };
+
+        let mut sum = self.builder.ins().fmul(a[0], b[0]);
+        for (a, b) in a.into_iter().zip(b).skip(1) {
+            let product = self.builder.ins().fmul(a, b);
+            sum = self.builder.ins().fadd(sum, product);
+        }
+        Ok(sum)
+    }
+
+    /// Matches glam's `normalize_or_zero`
+    fn normalize(&mut self, v: Lowered) -> Result<Lowered, CraneliftError> {
+        let dot = self.dot(v.clone(), v.clone())?;
+
+        let ins = self.builder.ins();
+        let length = ins.sqrt(dot);
+        let one = self.builder.ins().f32const(1.0);
+        let rcp = self.builder.ins().fdiv(one, length);
+
+        let abs = self.builder.ins().fabs(rcp);
+        let inf = self.builder.ins().f32const(f32::INFINITY);
+        let finite = self.builder.ins().fcmp(FloatCC::LessThan, abs, inf);
+        let zero = self.builder.ins().f32const(0.0);
+        let positive = self.builder.ins().fcmp(FloatCC::GreaterThan, rcp, zero);
+        let valid = self.builder.ins().band(finite, positive);
+
+        let Lowered::Struct(id, members) = v else {
+            unreachable!()
+        };
+
+        let mut out = vec![];
+        for member in members {
+            let Lowered::Number(NumericType::Float, member) = member else {
+                return Err(invalid_unary("Normalize", &member));
+            };
+            let scaled = self.builder.ins().fmul(member, rcp);
+            out.push(Lowered::Number(
+                NumericType::Float,
+                self.builder.ins().select(valid, scaled, zero),
+            ));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn equal(&mut self, lhs: Lowered, rhs: Lowered) -> Result<ClifValue, CraneliftError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Boolean(x), Lowered::Boolean(y)) => {
+                self.builder.ins().icmp(IntCC::Equal, x, y)
+            }
+            (Lowered::Number(NumericType::Float, x), Lowered::Number(NumericType::Float, y)) => {
+                self.builder.ins().fcmp(FloatCC::Equal, x, y)
+            }
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => {
+                self.builder.ins().icmp(IntCC::Equal, x, y)
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = self.builder.ins().iconst(types::I8, 1);
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    let eq = self.equal(lhs, rhs)?;
+                    out = self.builder.ins().band(out, eq);
+                }
+                out
+            }
+            (lhs, rhs) => return Err(invalid_binary("Eq", &lhs, &rhs)),
+        })
+    }
+
+    /// Ordered comparison of two numbers,
+    /// where `cc` is one of [`FloatCC::LessThan`] or [`FloatCC::GreaterThan`]
+    fn compare(
+        &mut self,
+        cc: FloatCC,
+        lhs: Lowered,
+        rhs: Lowered,
+    ) -> Result<ClifValue, CraneliftError> {
+        let less = cc == FloatCC::LessThan;
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => match a {
+                NumericType::Float => self.builder.ins().fcmp(cc, x, y),
+                NumericType::SInt => self.builder.ins().icmp(
+                    if less {
+                        IntCC::SignedLessThan
+                    } else {
+                        IntCC::SignedGreaterThan
+                    },
+                    x,
+                    y,
+                ),
+                NumericType::UInt => self.builder.ins().icmp(
+                    if less {
+                        IntCC::UnsignedLessThan
+                    } else {
+                        IntCC::UnsignedGreaterThan
+                    },
+                    x,
+                    y,
+                ),
+            },
+            (lhs, rhs) => return Err(invalid_binary(&format!("{cc}"), &lhs, &rhs)),
+        })
+    }
+
+    fn mix(&mut self, lhs: Lowered, rhs: Lowered, t: ClifValue) -> Result<Lowered, CraneliftError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(NumericType::Float, a), Lowered::Number(NumericType::Float, b)) => {
+                let one = self.builder.ins().f32const(1.0);
+                let s = self.builder.ins().fsub(one, t);
+                let a = self.builder.ins().fmul(s, a);
+                let b = self.builder.ins().fmul(t, b);
+                Lowered::Number(NumericType::Float, self.builder.ins().fadd(a, b))
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.mix(lhs, rhs, t)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary("Mix", &lhs, &rhs)),
+        })
+    }
+
+    fn clamp(&mut self, t: Lowered, min: Lowered, max: Lowered) -> Result<Lowered, CraneliftError> {
+        Ok(match (t, min, max) {
+            (Lowered::Number(n, t), Lowered::Number(a, min), Lowered::Number(b, max))
+                if n == a && n == b =>
+            {
+                let ins = self.builder.ins();
+                let v = match n {
+                    NumericType::Float => {
+                        let t = ins.fmax(t, min);
+                        self.builder.ins().fmin(t, max)
+                    }
+                    NumericType::SInt => {
+                        let t = ins.smax(t, min);
+                        self.builder.ins().smin(t, max)
+                    }
+                    NumericType::UInt => {
+                        let t = ins.umax(t, min);
+                        self.builder.ins().umin(t, max)
+                    }
+                };
+                Lowered::Number(n, v)
+            }
+            (Lowered::Struct(id, t), Lowered::Struct(a, min), Lowered::Struct(b, max))
+                if id == a && id == b =>
+            {
+                let mut out = vec![];
+                for ((t, min), max) in t.into_iter().zip(min).zip(max) {
+                    out.push(self.clamp(t, min, max)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (t, min, _) => return Err(invalid_binary("Clamp", &t, &min)),
+        })
+    }
+}
+
+fn is_matrix(id: &StructIdentifier) -> bool {
+    matches!(id.name(), "Matrix2" | "Matrix3" | "Matrix4")
+}
+
+/// Leaves of a value made up entirely of floats
+fn floats(value: &Lowered) -> Option<Vec<ClifValue>> {
+    match value {
+        Lowered::Number(NumericType::Float, v) => Some(vec![*v]),
+        Lowered::Struct(_, members) => {
+            let mut out = vec![];
+            for member in members {
+                out.extend(floats(member)?);
+            }
+            Some(out)
+        }
+        _ => None,
+    }
+}
+
+fn invalid_unary(op: &str, t: &Lowered) -> CraneliftError {
+    CraneliftError::InvalidExpr(format!("{op}({})", t.ty().name()))
+}
+
+fn invalid_binary(op: &str, lhs: &Lowered, rhs: &Lowered) -> CraneliftError {
+    CraneliftError::InvalidExpr(format!("{op}({}, {})", lhs.ty().name(), rhs.ty().name()))
+}
@@ -48,6 +48,7 @@ macro_rules! elysian_function_inner {
 
             output: $ret.into(),
             block: elysian_proc_macros::elysian_block! $body,
+            provenance: None,
         }
     };
 }
@@ -0,0 +1,374 @@
+//! Convert Elysian IR into GLSL via `elysian-naga`
+
+use std::{error::Error, fmt::Display};
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::{
+    ast::{Block, Expr, Stmt},
+    module::{
+        FunctionDefinition, FunctionIdentifier, InputDefinition, Module as ElysianModule,
+        StructIdentifier, CONTEXT,
+    },
+};
+use elysian_naga::{
+    EntryPointDefinition, EntryPointInput, EntryPointOutput, NagaBuildError, NagaBuilder,
+};
+use indexmap::IndexMap;
+use naga::{
+    back::glsl::{Options, PipelineOptions},
+    proc::{BoundsCheckPolicies, BoundsCheckPolicy},
+    valid::{Capabilities, ValidationFlags},
+    Binding, ResourceBinding, ShaderStage,
+};
+
+pub use naga::back::glsl::{BindingMap, Version, WriterFlags};
+
+pub const GLSL_MAIN: FunctionIdentifier = FunctionIdentifier::new("glsl_main", 9845054226895852557);
+
+#[derive(Debug)]
+pub enum GlslError {
+    Build(NagaBuildError),
+    Glsl(naga::back::glsl::Error),
+}
+
+impl Display for GlslError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GlslError::Build(e) => f.write_str(&format!("Failed to build naga module: {e}")),
+            GlslError::Glsl(e) => f.write_str(&format!("Failed to write GLSL: {e}")),
+        }
+    }
+}
+
+impl Error for GlslError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GlslError::Build(e) => Some(e),
+            GlslError::Glsl(e) => Some(e),
+        }
+    }
+}
+
+impl From<NagaBuildError> for GlslError {
+    fn from(value: NagaBuildError) -> Self {
+        GlslError::Build(value)
+    }
+}
+
+impl From<naga::back::glsl::Error> for GlslError {
+    fn from(value: naga::back::glsl::Error) -> Self {
+        GlslError::Glsl(value)
+    }
+}
+
+/// Builder for a single-stage GLSL shader wrapping an Elysian module
+///
+/// Inputs and uniforms are written into a fresh context before calling
+/// the module's entry point, and outputs are read from the resulting context.
+/// Locations and uniform bindings are assigned in declaration order
+/// unless specified explicitly.
+///
+/// Function provenance is not emitted, as naga's GLSL writer
+/// doesn't expose the names it assigns to functions.
+pub struct GlslBuilder<'a> {
+    module: &'a ElysianModule,
+    stage: ShaderStage,
+    options: Options,
+    inputs: Vec<(PropertyIdentifier, EntryPointInput)>,
+    outputs: Vec<(PropertyIdentifier, Binding)>,
+}
+
+impl<'a> GlslBuilder<'a> {
+    /// Create a builder for the given module
+    ///
+    /// Expects a module that has not yet been finalized,
+    /// so inputs and uniforms are included in its context struct.
+    pub fn new(module: &'a ElysianModule) -> Self {
+        GlslBuilder {
+            module,
+            stage: ShaderStage::Fragment,
+            options: Options::default(),
+            inputs: Default::default(),
+            outputs: Default::default(),
+        }
+    }
+
+    pub fn stage(mut self, stage: ShaderStage) -> Self {
+        self.stage = stage;
+        self
+    }
+
+    /// Set GLSL version, writer flags and binding map
+    ///
+    /// Uniforms absent from the binding map are assigned the next free index on build.
+    pub fn options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn version(mut self, version: Version) -> Self {
+        self.options.version = version;
+        self
+    }
+
+    /// Read a property from the next free input location
+    pub fn input(self, prop: impl Into<PropertyIdentifier>) -> Self {
+        let location = self
+            .inputs
+            .iter()
+            .filter(|(_, input)| {
+                matches!(input, EntryPointInput::Binding(Binding::Location { .. }))
+            })
+            .count() as u32;
+
+        self.input_binding(prop, location_binding(location))
+    }
+
+    /// Read a property from the given input binding
+    pub fn input_binding(mut self, prop: impl Into<PropertyIdentifier>, binding: Binding) -> Self {
+        self.inputs
+            .push((prop.into(), EntryPointInput::Binding(binding)));
+        self
+    }
+
+    /// Read a property from the next free uniform binding in group 0
+    pub fn uniform(self, prop: impl Into<PropertyIdentifier>) -> Self {
+        let binding = self
+            .inputs
+            .iter()
+            .filter(|(_, input)| matches!(input, EntryPointInput::Uniform(_)))
+            .count() as u32;
+
+        self.uniform_binding(prop, ResourceBinding { group: 0, binding })
+    }
+
+    /// Read a property from the given uniform binding
+    pub fn uniform_binding(
+        mut self,
+        prop: impl Into<PropertyIdentifier>,
+        binding: ResourceBinding,
+    ) -> Self {
+        self.inputs
+            .push((prop.into(), EntryPointInput::Uniform(binding)));
+        self
+    }
+
+    /// Write a property to the next free output location
+    pub fn output(self, prop: impl Into<PropertyIdentifier>) -> Self {
+        let location = self
+            .outputs
+            .iter()
+            .filter(|(_, binding)| matches!(binding, Binding::Location { .. }))
+            .count() as u32;
+
+        self.output_binding(prop, location_binding(location))
+    }
+
+    /// Write a property to the given output binding
+    pub fn output_binding(mut self, prop: impl Into<PropertyIdentifier>, binding: Binding) -> Self {
+        self.outputs.push((prop.into(), binding));
+        self
+    }
+
+    /// Wrap the module's entry point in [`GLSL_MAIN`],
+    /// which copies inputs and uniforms into the context.
+    pub fn main_module(&self) -> ElysianModule {
+        let mut stmts = vec![Stmt::Bind {
+            prop: CONTEXT.into(),
+            expr: Expr::Struct(StructIdentifier(CONTEXT), IndexMap::new()),
+        }];
+
+        stmts.extend(self.inputs.iter().map(|(prop, _)| Stmt::Write {
+            path: vec![CONTEXT.into(), prop.clone()],
+            expr: Expr::Read(vec![prop.clone()]),
+        }));
+
+        stmts.push(Stmt::Output(
+            self.module.call(Expr::Read(vec![CONTEXT.into()])),
+        ));
+
+        let mut module = self.module.clone();
+        module.function_definitions.push(FunctionDefinition {
+            id: GLSL_MAIN,
+            public: true,
+            inputs: self
+                .inputs
+                .iter()
+                .map(|(prop, _)| InputDefinition {
+                    id: prop.clone(),
+                    mutable: false,
+                })
+                .collect(),
+            output: CONTEXT.into(),
+            block: Block(stmts),
+            provenance: None,
+        });
+        module.finalize()
+    }
+
+    pub fn build(mut self) -> Result<String, GlslError> {
+        let module = self.main_module();
+
+        // Assign GLSL binding indices to any uniforms missing from the binding map
+        for (_, input) in self.inputs.iter() {
+            if let EntryPointInput::Uniform(binding) = input {
+                if !self.options.binding_map.contains_key(binding) {
+                    let index = self
+                        .options
+                        .binding_map
+                        .values()
+                        .max()
+                        .map(|index| index + 1)
+                        .unwrap_or_default();
+                    self.options.binding_map.insert(binding.clone(), index);
+                }
+            }
+        }
+
+        let (naga_module, module_info) = NagaBuilder::new(&module)
+            .entry_point(EntryPointDefinition {
+                name: "main".to_string(),
+                stage: self.stage,
+                function: GLSL_MAIN,
+                inputs: self.inputs.into_iter().map(|(_, input)| input).collect(),
+                output: EntryPointOutput::Fields(self.outputs),
+            })
+            .build(ValidationFlags::all(), Capabilities::default())?;
+
+        let pipeline_options = PipelineOptions {
+            shader_stage: self.stage,
+            entry_point: "main".to_string(),
+            multiview: None,
+        };
+
+        let mut buf = String::default();
+
+        let mut writer = naga::back::glsl::Writer::new(
+            &mut buf,
+            &naga_module,
+            &module_info,
+            &self.options,
+            &pipeline_options,
+            BoundsCheckPolicies {
+                index: BoundsCheckPolicy::Unchecked,
+                buffer: BoundsCheckPolicy::Unchecked,
+                image_load: BoundsCheckPolicy::Unchecked,
+                image_store: BoundsCheckPolicy::Unchecked,
+                binding_array: BoundsCheckPolicy::Unchecked,
+            },
+        )?;
+
+        writer.write()?;
+
+        Ok(buf)
+    }
+}
+
+fn location_binding(location: u32) -> Binding {
+    Binding::Location {
+        location,
+        interpolation: None,
+        sampling: None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_ir::{
+        ast::{DISTANCE, GRADIENT_2D, POSITION_2D, TIME},
+        module::{AsModule, SpecializationData},
+    };
+    use elysian_shapes::field::Circle;
+    use naga::{
+        front::glsl::{Frontend, Options},
+        valid::{Capabilities, ValidationFlags, Validator},
+        ShaderStage,
+    };
+
+    use super::*;
+
+    fn circle() -> ElysianModule {
+        Circle::new(0.5).module(&SpecializationData::new_2d())
+    }
+
+    fn circle_glsl(module: &ElysianModule, version: Version) -> GlslBuilder<'_> {
+        GlslBuilder::new(module)
+            .version(version)
+            .input(POSITION_2D)
+            .output(DISTANCE)
+            .output(GRADIENT_2D)
+    }
+
+    /// Parse and validate GLSL with naga's frontend,
+    /// which only accepts desktop versions 440 and up
+    fn reparse(source: &str) {
+        let module = Frontend::default()
+            .parse(&Options::from(ShaderStage::Fragment), source)
+            .unwrap_or_else(|e| panic!("{e:?}\n{source}"));
+
+        Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+    }
+
+    /// Check that GLSL below 4.10 and ES 3.10 avoids layout qualifiers they lack,
+    /// as naga's frontend can't parse it at its own version
+    fn assert_legacy_layouts(source: &str) {
+        for line in source.lines() {
+            assert!(
+                !line.contains("binding ="),
+                "Explicit bindings are unavailable: {line}"
+            );
+
+            let input = line.split_whitespace().any(|word| word == "in");
+            assert!(
+                !(input && line.starts_with("layout(")),
+                "Explicit input locations are unavailable: {line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_desktop_450() {
+        let module = circle();
+        let source = circle_glsl(&module, Version::Desktop(450))
+            .uniform(TIME)
+            .build()
+            .unwrap();
+        assert!(source.starts_with("#version 450 core\n"));
+        assert!(source.contains("uniform"));
+        reparse(&source);
+    }
+
+    #[test]
+    fn test_desktop_330() {
+        let module = circle();
+        let source = circle_glsl(&module, Version::Desktop(330))
+            .uniform(TIME)
+            .build()
+            .unwrap();
+        assert!(source.starts_with("#version 330 core\n"));
+        assert!(source.contains("uniform"));
+        assert_legacy_layouts(&source);
+    }
+
+    #[test]
+    fn test_webgl2() {
+        let module = circle();
+        let source = circle_glsl(
+            &module,
+            Version::Embedded {
+                version: 300,
+                is_webgl: true,
+            },
+        )
+        .uniform(TIME)
+        .build()
+        .unwrap();
+        assert!(source.starts_with("#version 300 es\n"));
+        // ES fragment shaders have no default float precision
+        assert!(source.contains("precision highp float;"));
+        assert!(source.contains("uniform"));
+        assert_legacy_layouts(&source);
+    }
+}
@@ -13,8 +13,8 @@ use elysian_ir::{
     module::{FunctionDefinition, FunctionIdentifier, Module, StructIdentifier, CONTEXT},
 };
 use elysian_math::{
-    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Dot, Length, Max, Min, Mix, Normalize, Round, Sign,
-    Sin, Tan,
+    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Dot, Exp2, Length, Log2, Max, Min, Mix, Normalize,
+    Round, Sign, Sin, Tan,
 };
 
 pub struct Interpreter {
@@ -313,6 +313,16 @@ impl Interpreter {
                 println!("Atan");
                 self.evaluate_expr(op).atan()
             }
+            Expr::Exp2(op) => {
+                #[cfg(feature = "print")]
+                println!("Exp2");
+                self.evaluate_expr(op).exp2()
+            }
+            Expr::Log2(op) => {
+                #[cfg(feature = "print")]
+                println!("Log2");
+                self.evaluate_expr(op).log2()
+            }
             Expr::Length(op) => {
                 #[cfg(feature = "print")]
                 println!("Length");
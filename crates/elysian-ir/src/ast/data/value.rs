@@ -6,8 +6,8 @@ use std::{
 
 use elysian_math::{
     glam::{Vec2, Vec3, Vec4},
-    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Dot, Length, Max, Min, Mix, Normalize, Round, Sign,
-    Sin, Tan,
+    Abs, Acos, Asin, Atan, Atan2, Clamp, Cos, Dot, Exp2, Length, Log2, Max, Min, Mix, Normalize,
+    Round, Sign, Sin, Tan,
 };
 
 use crate::module::StructIdentifier;
@@ -324,6 +324,24 @@ impl Atan for Value {
     }
 }
 
+impl Exp2 for Value {
+    fn exp2(self) -> Self {
+        match &self {
+            Value::Number(a) => a.clone().exp2().into(),
+            _ => panic!("Invalid Exp2 {:#?}", self),
+        }
+    }
+}
+
+impl Log2 for Value {
+    fn log2(self) -> Self {
+        match &self {
+            Value::Number(a) => a.clone().log2().into(),
+            _ => panic!("Invalid Log2 {:#?}", self),
+        }
+    }
+}
+
 impl Atan2 for Value {
     fn atan2(self, rhs: Self) -> Self {
         match (&self, &rhs) {
@@ -34,12 +34,19 @@ pub enum Expr {
     Asin(BoxExpr),
     Acos(BoxExpr),
     Atan(BoxExpr),
+    Exp2(BoxExpr),
+    Log2(BoxExpr),
     Length(BoxExpr),
     Normalize(BoxExpr),
     Add(BoxExpr, BoxExpr),
     Sub(BoxExpr, BoxExpr),
     Mul(BoxExpr, BoxExpr),
     Div(BoxExpr, BoxExpr),
+    /// Euclidean remainder, as `rem_euclid`
+    ///
+    /// Unlike the truncated remainder of Rust's `%` and WGSL's `%`,
+    /// the result is never negative, whatever the signs of the operands.
+    /// Every backend lowers it this way.
     Mod(BoxExpr, BoxExpr),
     Eq(BoxExpr, BoxExpr),
     Ne(BoxExpr, BoxExpr),
@@ -275,7 +282,7 @@ impl Expr {
                 .unwrap()
                 .clone(),
             Neg(t) | Abs(t) | Sign(t) | Round(t) | Sin(t) | Cos(t) | Tan(t) | Asin(t) | Acos(t)
-            | Atan(t) => t.ty(function_defs),
+            | Atan(t) | Exp2(t) | Log2(t) => t.ty(function_defs),
             Length(t) => match t.ty(function_defs) {
                 Type::Boolean => panic!("Invalid Length"),
                 Type::Number(n) => Type::Number(n),
@@ -480,6 +487,14 @@ impl Expr {
         Expr::Atan(self.box_expr())
     }
 
+    pub fn exp2(self) -> Expr {
+        Expr::Exp2(self.box_expr())
+    }
+
+    pub fn log2(self) -> Expr {
+        Expr::Log2(self.box_expr())
+    }
+
     pub fn output(self) -> Stmt {
         Stmt::Output(self)
     }
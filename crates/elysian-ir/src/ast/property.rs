@@ -86,6 +86,10 @@ property!(
 pub const ERROR: Identifier = Identifier::new("error", 209621851525461471);
 property!(ERROR, ERROR_PROP, Type::Number(NumericType::Float));
 
+/// Set when `DISTANCE` is a lower bound on the true distance rather than exact
+pub const DISTANCE_BOUND: Identifier = Identifier::new("distance_bound", 2341406567443696772);
+property!(DISTANCE_BOUND, DISTANCE_BOUND_PROP, Type::Boolean);
+
 pub const NUM: Identifier = Identifier::new("num", 1349662877516236181);
 property!(NUM, NUM_PROP, Type::Number(NumericType::Float));
 
@@ -41,6 +41,10 @@ pub struct FunctionDefinition {
     pub inputs: Vec<InputDefinition>,
     pub output: PropertyIdentifier,
     pub block: Block,
+    /// Shapes responsible for generating this function, if known
+    ///
+    /// Excluded from equality and hashing, since it has no bearing on the generated code.
+    pub provenance: Option<Provenance>,
 }
 
 impl FunctionDefinition {
@@ -98,4 +102,7 @@ pub struct InputDefinition {
 }
 
 mod function_identifier;
+mod provenance;
+
 pub use function_identifier::*;
+pub use provenance::*;
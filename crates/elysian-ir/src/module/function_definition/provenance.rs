@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+use crate::module::ErasedHash;
+
+/// A shape involved in generating a function
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Origin {
+    /// Fully-qualified type name of the shape
+    pub ty: &'static str,
+    /// Erased hash of the shape
+    pub hash: u64,
+}
+
+impl Origin {
+    pub fn of<T: ErasedHash>(shape: &T) -> Self {
+        Origin {
+            ty: std::any::type_name::<T>(),
+            hash: shape.erased_hash(),
+        }
+    }
+
+    /// Type name of the shape with module paths removed
+    pub fn name(&self) -> String {
+        let is_path = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+
+        self.ty
+            .split_inclusive(|c: char| !is_path(c))
+            .map(|part| {
+                let path = part.trim_end_matches(|c: char| !is_path(c));
+                let delim = &part[path.len()..];
+                let name = path.rsplit("::").next().unwrap_or(path);
+                format!("{name}{delim}")
+            })
+            .collect()
+    }
+}
+
+impl Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:016x})", self.name(), self.hash)
+    }
+}
+
+/// The shape that generated a function, and the chain of shapes containing it
+///
+/// Carried by [`FunctionDefinition`](super::FunctionDefinition) for debugging purposes,
+/// and emitted as comments by source-generating backends.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Provenance {
+    pub origin: Origin,
+    /// Enclosing shapes, innermost first
+    pub parents: Vec<Origin>,
+}
+
+impl Provenance {
+    pub fn new(origin: Origin) -> Self {
+        Provenance {
+            origin,
+            parents: Default::default(),
+        }
+    }
+}
+
+impl Display for Provenance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.origin)?;
+        for parent in self.parents.iter() {
+            write!(f, " in {parent}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_origin_name() {
+        let origin = |ty| Origin { ty, hash: 0 };
+
+        assert_eq!(origin("elysian_shapes::field::Circle").name(), "Circle");
+        assert_eq!(origin("Point").name(), "Point");
+        assert_eq!(
+            origin("alloc::boxed::Box<dyn elysian_shapes::shape::Shape>").name(),
+            "Box<dyn Shape>"
+        );
+    }
+
+    #[test]
+    fn test_provenance_display() {
+        let provenance = Provenance {
+            origin: Origin {
+                ty: "elysian_shapes::wrap::Translate",
+                hash: 0xff,
+            },
+            parents: vec![Origin {
+                ty: "elysian_shapes::combine::Combine",
+                hash: 1,
+            }],
+        };
+
+        assert_eq!(
+            provenance.to_string(),
+            "Translate (00000000000000ff) in Combine (0000000000000001)"
+        );
+    }
+}
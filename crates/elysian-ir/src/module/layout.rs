@@ -0,0 +1,229 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+
+use crate::{
+    ast::{Struct, Value},
+    module::{
+        field_type, properties, Module, NumericType, StructDefinition, StructIdentifier, Type,
+    },
+};
+
+/// Flattened representation of a module's structs
+///
+/// Struct values are broken down into their scalar leaves,
+/// ordered depth-first by field declaration.
+/// Backends built on flat storage share this ordering,
+/// and exchange values with it through [`Layout::flatten`] and [`Layout::unflatten`].
+#[derive(Debug, Copy, Clone)]
+pub struct Layout<'a> {
+    structs: &'a [StructDefinition],
+}
+
+impl<'a> Layout<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        Layout {
+            structs: &module.struct_definitions,
+        }
+    }
+
+    /// Fields of a struct alongside their types, in declaration order
+    pub fn fields(
+        &self,
+        id: &StructIdentifier,
+    ) -> Result<Vec<(&'a PropertyIdentifier, &'static Type)>, LayoutError> {
+        let def = self
+            .structs
+            .iter()
+            .find(|def| def.id == *id)
+            .ok_or_else(|| LayoutError::MissingStruct(id.name().to_string()))?;
+
+        def.fields
+            .iter()
+            .map(|field| {
+                field_type(id, &field.id)
+                    .map(|ty| (&field.id, ty))
+                    .ok_or_else(|| LayoutError::MissingProperty(field.id.name().to_string()))
+            })
+            .collect()
+    }
+
+    /// Scalar types making up a value of the given type
+    pub fn leaves(&self, ty: &Type) -> Result<Vec<Type>, LayoutError> {
+        Ok(self
+            .leaf_paths(vec![], ty)?
+            .into_iter()
+            .map(|(_, ty)| ty)
+            .collect())
+    }
+
+    /// Paths to each scalar leaf of a value, alongside their types
+    pub fn leaf_paths(
+        &self,
+        path: Vec<PropertyIdentifier>,
+        ty: &Type,
+    ) -> Result<Vec<(Vec<PropertyIdentifier>, Type)>, LayoutError> {
+        let Type::Struct(id) = ty else {
+            return Ok(vec![(path, ty.clone())]);
+        };
+
+        let mut out = vec![];
+        for (prop, ty) in self.fields(id)? {
+            let path = path.iter().cloned().chain([prop.clone()]).collect();
+            out.extend(self.leaf_paths(path, ty)?);
+        }
+        Ok(out)
+    }
+
+    /// Append a value to a buffer of scalar leaves, filling absent struct members with zero
+    pub fn flatten(
+        &self,
+        ty: &Type,
+        value: Option<&Value>,
+        out: &mut Vec<f64>,
+    ) -> Result<(), LayoutError> {
+        match (ty, value) {
+            (Type::Boolean, Some(Value::Boolean(b))) => out.push(*b as u8 as f64),
+            (Type::Number(_), Some(Value::Number(n))) => out.push(match n {
+                Number::UInt(u) => *u as f64,
+                Number::SInt(i) => *i as f64,
+                Number::Float(f) => *f,
+            }),
+            (Type::Struct(id), value) => {
+                let s = match value {
+                    Some(Value::Struct(s)) => Some(s),
+                    None => None,
+                    Some(v) => return Err(LayoutError::InvalidValue(v.to_string())),
+                };
+
+                for (prop, ty) in self.fields(id)? {
+                    self.flatten(ty, s.and_then(|s| s.members.get(prop)), out)?;
+                }
+            }
+            (_, None) => out.push(0.0),
+            (_, Some(v)) => return Err(LayoutError::InvalidValue(v.to_string())),
+        }
+
+        Ok(())
+    }
+
+    /// Read a value from a buffer of scalar leaves
+    pub fn unflatten(
+        &self,
+        ty: &Type,
+        values: &mut impl Iterator<Item = f64>,
+    ) -> Result<Value, LayoutError> {
+        if let Type::Struct(id) = ty {
+            let mut out = Struct::new(id.clone());
+            for (prop, ty) in self.fields(id)? {
+                out.set_mut(prop.clone(), self.unflatten(ty, values)?);
+            }
+            return Ok(Value::Struct(out));
+        }
+
+        let value = values
+            .next()
+            .ok_or_else(|| LayoutError::InvalidValue("Truncated buffer".to_string()))?;
+
+        Ok(match ty {
+            Type::Boolean => Value::Boolean(value != 0.0),
+            Type::Number(NumericType::UInt) => Value::Number(Number::UInt(value as u64)),
+            Type::Number(NumericType::SInt) => Value::Number(Number::SInt(value as i64)),
+            Type::Number(NumericType::Float) => Value::Number(Number::Float(value)),
+            Type::Struct(_) => unreachable!(),
+        })
+    }
+}
+
+/// Type of a property, or an error naming it if it has none
+pub fn property_type(prop: &PropertyIdentifier) -> Result<&'static Type, LayoutError> {
+    properties()
+        .get(prop)
+        .ok_or_else(|| LayoutError::MissingProperty(prop.name().to_string()))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayoutError {
+    MissingStruct(String),
+    MissingProperty(String),
+    InvalidValue(String),
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::MissingStruct(name) => {
+                f.write_str(&format!("No struct definition for {name}"))
+            }
+            LayoutError::MissingProperty(name) => {
+                f.write_str(&format!("No type for property {name}"))
+            }
+            LayoutError::InvalidValue(value) => {
+                f.write_str(&format!("Value {value} does not match its type"))
+            }
+        }
+    }
+}
+
+impl Error for LayoutError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        ast::{MATRIX2, MATRIX3, VECTOR2, X, X_AXIS_2, Y, Y_AXIS_2},
+        module::BUILTIN_STRUCTS,
+    };
+
+    fn builtins() -> Module {
+        Module {
+            struct_definitions: BUILTIN_STRUCTS.iter().map(|def| (*def).clone()).collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_matrix_leaves() {
+        let module = builtins();
+        let layout = Layout::new(&module);
+
+        for (id, len) in [(MATRIX2, 4), (MATRIX3, 9)] {
+            let leaves = layout.leaves(&Type::Struct(StructIdentifier(id))).unwrap();
+            assert_eq!(leaves.len(), len);
+            assert!(leaves
+                .iter()
+                .all(|ty| *ty == Type::Number(NumericType::Float)));
+        }
+    }
+
+    #[test]
+    fn test_flatten_round_trip() {
+        let module = builtins();
+        let layout = Layout::new(&module);
+        let ty = Type::Struct(StructIdentifier(MATRIX2));
+
+        let x_axis = Struct::new(StructIdentifier(VECTOR2))
+            .set(X.into(), 1.0.into())
+            .set(Y.into(), 2.0.into());
+        let value = Value::Struct(
+            Struct::new(StructIdentifier(MATRIX2)).set(X_AXIS_2.into(), Value::Struct(x_axis)),
+        );
+
+        let mut buffer = vec![];
+        layout.flatten(&ty, Some(&value), &mut buffer).unwrap();
+        assert_eq!(buffer, [1.0, 2.0, 0.0, 0.0]);
+
+        let Value::Struct(out) = layout.unflatten(&ty, &mut buffer.into_iter()).unwrap() else {
+            panic!("Expected a struct");
+        };
+        let Value::Struct(y_axis) = out.get(&Y_AXIS_2.into()) else {
+            panic!("Expected a vector");
+        };
+        assert_eq!(y_axis.get(&X.into()), Value::Number(Number::Float(0.0)));
+
+        assert_eq!(
+            layout.unflatten(&ty, &mut [1.0].into_iter()),
+            Err(LayoutError::InvalidValue("Truncated buffer".to_string()))
+        );
+    }
+}
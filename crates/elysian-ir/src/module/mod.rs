@@ -2,6 +2,7 @@ mod domains;
 mod erased_hash;
 mod evaluate;
 mod function_definition;
+mod layout;
 mod properties;
 mod specialization_data;
 mod struct_definition;
@@ -11,6 +12,7 @@ pub use domains::*;
 pub use erased_hash::*;
 pub use evaluate::*;
 pub use function_definition::*;
+pub use layout::*;
 pub use properties::*;
 pub use specialization_data::*;
 pub use struct_definition::*;
@@ -80,12 +82,13 @@ impl Hash for Module {
 }
 
 impl Module {
-    pub fn new(
-        shape: &(impl ErasedHash + DomainsDyn),
+    pub fn new<T: ErasedHash + DomainsDyn>(
+        shape: &T,
         spec: &SpecializationData,
         entry_function: FunctionDefinition,
     ) -> Self {
-        let entry_function = entry_function.specialize(&spec.filter(shape.domains_dyn()));
+        let mut entry_function = entry_function.specialize(&spec.filter(shape.domains_dyn()));
+        entry_function.provenance = Some(Provenance::new(Origin::of(shape)));
         Module {
             entry_point: entry_function.id.clone(),
             struct_definitions: Default::default(),
@@ -109,6 +112,26 @@ impl Module {
         }
     }
 
+    /// The shape that generated this module's entry point, if known
+    pub fn origin(&self) -> Option<&Origin> {
+        self.function_definitions
+            .iter()
+            .find(|def| def.id == self.entry_point)?
+            .provenance
+            .as_ref()
+            .map(|provenance| &provenance.origin)
+    }
+
+    /// Record `parent` as enclosing the shapes that generated this module's functions
+    pub fn within(mut self, parent: &Origin) -> Module {
+        for def in self.function_definitions.iter_mut() {
+            if let Some(provenance) = &mut def.provenance {
+                provenance.parents.push(parent.clone());
+            }
+        }
+        self
+    }
+
     pub fn concat(self, rhs: Module) -> Module {
         // Aggregate function definitions
         let function_definitions: Vec<_> = self
@@ -232,23 +255,38 @@ fn expr_props(expr: &Expr) -> Vec<PropertyIdentifier> {
         Expr::Neg(expr)
         | Expr::Abs(expr)
         | Expr::Sign(expr)
+        | Expr::Round(expr)
+        | Expr::Sin(expr)
+        | Expr::Cos(expr)
+        | Expr::Tan(expr)
+        | Expr::Asin(expr)
+        | Expr::Acos(expr)
+        | Expr::Atan(expr)
+        | Expr::Exp2(expr)
+        | Expr::Log2(expr)
         | Expr::Length(expr)
         | Expr::Normalize(expr) => expr_props(expr),
         Expr::Add(lhs, rhs)
         | Expr::Sub(lhs, rhs)
         | Expr::Mul(lhs, rhs)
         | Expr::Div(lhs, rhs)
+        | Expr::Mod(lhs, rhs)
+        | Expr::Eq(lhs, rhs)
+        | Expr::Ne(lhs, rhs)
+        | Expr::And(lhs, rhs)
+        | Expr::Or(lhs, rhs)
         | Expr::Min(lhs, rhs)
         | Expr::Max(lhs, rhs)
         | Expr::Lt(lhs, rhs)
         | Expr::Gt(lhs, rhs)
-        | Expr::Dot(lhs, rhs) => expr_props(lhs).into_iter().chain(expr_props(rhs)).collect(),
-        Expr::Mix(lhs, rhs, t) => expr_props(lhs)
+        | Expr::Dot(lhs, rhs)
+        | Expr::Atan2(lhs, rhs) => expr_props(lhs).into_iter().chain(expr_props(rhs)).collect(),
+        Expr::Mix(a, b, c) | Expr::Clamp(a, b, c) => expr_props(a)
             .into_iter()
-            .chain(expr_props(rhs))
-            .chain(expr_props(t))
+            .chain(expr_props(b))
+            .chain(expr_props(c))
             .collect(),
-        _ => vec![],
+        Expr::Literal(_) => vec![],
     }
 }
 
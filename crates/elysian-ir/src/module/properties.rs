@@ -3,9 +3,9 @@ use std::sync::OnceLock;
 use elysian_core::{property_identifier::PropertyIdentifier, uuid::Uuid};
 use indexmap::IndexMap;
 
-use crate::ast::Property;
+use crate::ast::{Property, MATRIX2, MATRIX3, MATRIX4, VECTOR2, VECTOR3, VECTOR4};
 
-use super::Type;
+use super::{StructIdentifier, Type};
 
 #[macro_export]
 macro_rules! property {
@@ -59,3 +59,23 @@ pub fn properties() -> &'static IndexMap<PropertyIdentifier, Type> {
         props
     })
 }
+
+static VECTOR2_TYPE: Type = Type::Struct(StructIdentifier(VECTOR2));
+static VECTOR3_TYPE: Type = Type::Struct(StructIdentifier(VECTOR3));
+static VECTOR4_TYPE: Type = Type::Struct(StructIdentifier(VECTOR4));
+
+/// Type of the field `prop` within `structure`
+///
+/// Matrix columns share their identifiers across dimensions,
+/// so are resolved by the matrix they belong to rather than by property.
+pub fn field_type(
+    structure: &StructIdentifier,
+    prop: &PropertyIdentifier,
+) -> Option<&'static Type> {
+    match &**structure {
+        m if *m == MATRIX2 => Some(&VECTOR2_TYPE),
+        m if *m == MATRIX3 => Some(&VECTOR3_TYPE),
+        m if *m == MATRIX4 => Some(&VECTOR4_TYPE),
+        _ => properties().get(prop),
+    }
+}
@@ -0,0 +1,66 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_ir::module::LayoutError;
+
+#[derive(Debug)]
+pub enum JitError {
+    Io(std::io::Error),
+    NoCacheDir,
+    Compile(String),
+    CacheCollision(std::path::PathBuf),
+    Load(libloading::Error),
+    Layout(LayoutError),
+    InvalidValue(String),
+}
+
+impl Display for JitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JitError::Io(e) => f.write_str(&format!("JIT I/O error: {e}")),
+            JitError::NoCacheDir => {
+                f.write_str("No user cache directory found, set one with JitCompiler::cache_dir")
+            }
+            JitError::Compile(stderr) => {
+                f.write_str(&format!("Failed to compile module:\n{stderr}"))
+            }
+            JitError::CacheCollision(path) => f.write_str(&format!(
+                "Cached crate at {} was generated from different source",
+                path.display()
+            )),
+            JitError::Load(e) => f.write_str(&format!("Failed to load compiled module: {e}")),
+            JitError::Layout(e) => e.fmt(f),
+            JitError::InvalidValue(value) => {
+                f.write_str(&format!("Value {value} does not match its context type"))
+            }
+        }
+    }
+}
+
+impl Error for JitError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            JitError::Io(e) => Some(e),
+            JitError::Load(e) => Some(e),
+            JitError::Layout(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for JitError {
+    fn from(value: std::io::Error) -> Self {
+        JitError::Io(value)
+    }
+}
+
+impl From<libloading::Error> for JitError {
+    fn from(value: libloading::Error) -> Self {
+        JitError::Load(value)
+    }
+}
+
+impl From<LayoutError> for JitError {
+    fn from(value: LayoutError) -> Self {
+        JitError::Layout(value)
+    }
+}
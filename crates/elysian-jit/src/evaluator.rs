@@ -0,0 +1,74 @@
+use std::ffi::OsStr;
+
+use elysian_ir::{
+    ast::{Struct, Value},
+    module::{Evaluate, EvaluateError, Layout, Module, StructIdentifier, Type, CONTEXT},
+};
+use elysian_syn::{cdylib_context_len, CDYLIB_EVALUATE};
+use libloading::Library;
+
+use crate::JitError;
+
+type EvaluateFn = unsafe extern "C" fn(*const f64, *mut f64);
+
+/// Evaluator backed by a dynamic library built by [`JitCompiler`](crate::JitCompiler)
+#[derive(Debug)]
+pub struct JitCompiled {
+    module: Module,
+    len: usize,
+    function: EvaluateFn,
+    // Keeps `function` loaded
+    _library: Library,
+}
+
+impl JitCompiled {
+    /// Load a library generated from the given finalized module
+    ///
+    /// # Safety
+    ///
+    /// `path` must name a library built from `module` by [`JitCompiler`](crate::JitCompiler),
+    /// or one otherwise exporting a [`CDYLIB_EVALUATE`] function over context buffers
+    /// laid out for `module`.
+    /// Loading runs the library's initialization routines,
+    /// and evaluating through a mismatched function is undefined behavior.
+    pub unsafe fn load(module: &Module, path: impl AsRef<OsStr>) -> Result<Self, JitError> {
+        // Safety: upheld by the caller
+        let library = unsafe { Library::new(path) }?;
+        let function = *unsafe { library.get::<EvaluateFn>(CDYLIB_EVALUATE.as_bytes()) }?;
+
+        Ok(JitCompiled {
+            module: module.clone(),
+            len: cdylib_context_len(module),
+            function,
+            _library: library,
+        })
+    }
+}
+
+impl<'a> Evaluate<'a> for JitCompiled {
+    fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
+        let ty = Type::Struct(StructIdentifier(CONTEXT));
+
+        let layout = Layout::new(&self.module);
+
+        let mut input = Vec::with_capacity(self.len);
+        layout.flatten(&ty, Some(&Value::Struct(context)), &mut input)?;
+        if input.len() != self.len {
+            return Err(Box::new(JitError::InvalidValue(format!(
+                "Context buffer of length {}, expected {}",
+                input.len(),
+                self.len
+            ))));
+        }
+
+        let mut output = vec![0.0; self.len];
+
+        // Safety: both buffers hold the number of slots the generated function expects
+        unsafe { (self.function)(input.as_ptr(), output.as_mut_ptr()) };
+
+        match layout.unflatten(&ty, &mut output.into_iter())? {
+            Value::Struct(s) => Ok(s),
+            v => Err(Box::new(JitError::InvalidValue(v.to_string()))),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! Compile modules into native code at runtime via `elysian-syn` and the local Rust toolchain
+
+mod error;
+mod evaluator;
+
+pub use error::*;
+pub use evaluator::*;
+
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    ffi::OsString,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use elysian_ir::module::{ErasedHash, Module};
+use elysian_syn::module_to_cdylib_string;
+
+/// Builder for runtime compilation of modules into dynamic libraries
+///
+/// Each module is generated as a standalone `cdylib` crate and built offline
+/// with the local `cargo` into a target directory shared between modules.
+/// Libraries are cached on disk keyed by a hash of their generated source,
+/// so modules that differ only by specialization never share a library,
+/// and a library already loaded from a given path always matches its module.
+///
+/// The cache defaults to an `elysian-jit` directory in the user's cache directory,
+/// and is restricted to its owner, as the libraries within are loaded into the process.
+#[derive(Debug, Clone)]
+pub struct JitCompiler {
+    cache_dir: Option<PathBuf>,
+    math_path: PathBuf,
+    cargo: OsString,
+}
+
+impl JitCompiler {
+    /// Create a compiler whose generated crates depend on the `elysian-math` crate at `math_path`
+    pub fn new(math_path: impl Into<PathBuf>) -> Self {
+        JitCompiler {
+            cache_dir: None,
+            math_path: math_path.into(),
+            cargo: std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into()),
+        }
+    }
+
+    /// Set the directory in which generated crates and built libraries are cached
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    /// Set the `cargo` executable used to build generated crates
+    pub fn cargo(mut self, cargo: impl Into<OsString>) -> Self {
+        self.cargo = cargo.into();
+        self
+    }
+
+    /// Build and load a finalized module
+    pub fn compile(&self, module: &Module) -> Result<JitCompiled, JitError> {
+        let library = self.build(module)?;
+        // Safety: the library was just built from this module by elysian-syn,
+        // and has no initialization routines
+        unsafe { JitCompiled::load(module, library) }
+    }
+
+    /// Build a finalized module, returning the path to its dynamic library
+    ///
+    /// Skips compilation if a library built from identical source is already cached,
+    /// and refuses to overwrite a cached crate generated from different source.
+    pub fn build(&self, module: &Module) -> Result<PathBuf, JitError> {
+        let source = module_to_cdylib_string(module);
+        let name = format!("elysian_jit_{:016x}", source.erased_hash());
+        let file_name = format!("{DLL_PREFIX}{name}{DLL_SUFFIX}");
+
+        let cache_dir = match &self.cache_dir {
+            Some(cache_dir) => cache_dir.clone(),
+            None => user_cache_dir().ok_or(JitError::NoCacheDir)?,
+        };
+        create_private_dir(&cache_dir)?;
+
+        let crate_dir = cache_dir.join(&name);
+        let manifest_path = crate_dir.join("Cargo.toml");
+        let source_path = crate_dir.join("src").join("lib.rs");
+        let library_path = crate_dir.join(&file_name);
+
+        match fs::read_to_string(&source_path).ok() {
+            Some(cached) if cached != source => {
+                return Err(JitError::CacheCollision(crate_dir));
+            }
+            Some(_) if library_path.exists() => return Ok(library_path),
+            _ => (),
+        }
+
+        fs::create_dir_all(crate_dir.join("src"))?;
+        fs::write(&manifest_path, self.manifest(&name))?;
+        fs::write(&source_path, source)?;
+
+        let target_dir = cache_dir.join("target");
+        let output = Command::new(&self.cargo)
+            .args(["build", "--lib", "--release", "--offline", "--quiet"])
+            .arg("--manifest-path")
+            .arg(&manifest_path)
+            .arg("--target-dir")
+            .arg(&target_dir)
+            .output()?;
+
+        if !output.status.success() {
+            return Err(JitError::Compile(
+                String::from_utf8_lossy(&output.stderr).into_owned(),
+            ));
+        }
+
+        // Unlink any stale library before replacing it,
+        // so processes that already have it loaded are unaffected
+        if library_path.exists() {
+            fs::remove_file(&library_path)?;
+        }
+        fs::copy(target_dir.join("release").join(&file_name), &library_path)?;
+
+        Ok(library_path)
+    }
+
+    fn manifest(&self, name: &str) -> String {
+        format!(
+            r#"[package]
+name = "{name}"
+version = "0.1.0"
+edition = "2021"
+
+[lib]
+crate-type = ["cdylib"]
+
+[dependencies]
+elysian-math = {{ path = {:?} }}
+
+[workspace]
+"#,
+            self.math_path.display().to_string()
+        )
+    }
+}
+
+/// Per-user cache directory for compiled libraries, if one can be found
+fn user_cache_dir() -> Option<PathBuf> {
+    let absolute = |var: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|path| path.is_absolute())
+    };
+
+    let base = if cfg!(windows) {
+        absolute("LOCALAPPDATA")
+    } else {
+        absolute("XDG_CACHE_HOME").or_else(|| absolute("HOME").map(|home| home.join(".cache")))
+    };
+
+    base.map(|base| base.join("elysian-jit"))
+}
+
+/// Create a directory if absent, and restrict it to its owner
+///
+/// Fails if the directory belongs to another user, as its permissions cannot be changed.
+fn create_private_dir(path: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o700))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_interpreter::Interpreted;
+    use elysian_ir::{
+        ast::{Struct, Value, DISTANCE, POSITION_2D, VECTOR2, X, Y},
+        module::{AsModule, Evaluate, SpecializationData, StructIdentifier, CONTEXT},
+    };
+    use elysian_shapes::{field::Circle, modify::IntoGradientNormals};
+
+    use super::*;
+
+    #[test]
+    fn test_jit_circle() {
+        let module = Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        let compiled =
+            JitCompiler::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("../elysian-math"))
+                .compile(&module)
+                .unwrap();
+
+        for (x, y) in [(0.0, 0.0), (0.25, -0.5), (1.0, 1.0)] {
+            let context = Struct::new(StructIdentifier(CONTEXT)).set(
+                POSITION_2D.into(),
+                Value::Struct(
+                    Struct::new(StructIdentifier(VECTOR2))
+                        .set(X.into(), x.into())
+                        .set(Y.into(), y.into()),
+                ),
+            );
+
+            let expected: f32 = Interpreted(&module)
+                .evaluate(context.clone())
+                .unwrap()
+                .get(&DISTANCE.into())
+                .into();
+
+            let found: f32 = compiled
+                .evaluate(context)
+                .unwrap()
+                .get(&DISTANCE.into())
+                .into();
+
+            assert!((expected - found).abs() < 1e-5, "{expected} != {found}");
+        }
+    }
+
+    /// 2D and 3D specializations of one shape share a hash, but not a library
+    #[test]
+    fn test_specializations_distinct() {
+        let compiler =
+            JitCompiler::new(Path::new(env!("CARGO_MANIFEST_DIR")).join("../elysian-math"));
+
+        let [path_2d, path_3d] =
+            [SpecializationData::new_2d(), SpecializationData::new_3d()].map(|spec| {
+                let module = Circle::new(0.5).module(&spec).finalize();
+                compiler.build(&module).unwrap()
+            });
+
+        assert_ne!(path_2d, path_3d);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cache_dir_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("elysian-jit-test-{}", std::process::id()));
+        fs::create_dir_all(&path).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o777)).unwrap();
+
+        create_private_dir(&path).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        fs::remove_dir_all(&path).unwrap();
+
+        assert_eq!(mode & 0o777, 0o700);
+    }
+}
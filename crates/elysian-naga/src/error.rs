@@ -0,0 +1,104 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_ir::{ast::Expr, module::FunctionIdentifier};
+use naga::{valid::ValidationError, WithSpan};
+
+/// Reason for a failure to convert Elysian IR into `naga` IR
+#[derive(Debug)]
+pub enum NagaBuildErrorKind {
+    MissingType(String),
+    MissingFunction(String),
+    MissingProperty(String),
+    MissingStruct(String),
+    MissingField { field: String, ty: String },
+    NotAStruct(String),
+    InvalidWrite(String),
+    InvalidRead(String),
+    InvalidBinaryOp { lhs: String, rhs: String },
+    InvalidNormalize(String),
+    InvalidEntryPoint(String),
+    Validation(Box<WithSpan<ValidationError>>),
+}
+
+impl Display for NagaBuildErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NagaBuildErrorKind::MissingType(name) => write!(f, "No type for {name}"),
+            NagaBuildErrorKind::MissingFunction(name) => write!(f, "No function for {name}"),
+            NagaBuildErrorKind::MissingProperty(name) => write!(f, "No input type for {name}"),
+            NagaBuildErrorKind::MissingStruct(name) => {
+                write!(f, "No struct definition for {name}")
+            }
+            NagaBuildErrorKind::MissingField { field, ty } => {
+                write!(f, "No field {field} for struct {ty}")
+            }
+            NagaBuildErrorKind::NotAStruct(name) => write!(f, "{name} is not a struct"),
+            NagaBuildErrorKind::InvalidWrite(name) => write!(f, "Invalid write to {name}"),
+            NagaBuildErrorKind::InvalidRead(name) => write!(f, "Invalid read from {name}"),
+            NagaBuildErrorKind::InvalidBinaryOp { lhs, rhs } => {
+                write!(f, "Invalid binary op {lhs}, {rhs}")
+            }
+            NagaBuildErrorKind::InvalidNormalize(name) => write!(f, "Invalid normalize of {name}"),
+            NagaBuildErrorKind::InvalidEntryPoint(desc) => write!(f, "Invalid entry point: {desc}"),
+            NagaBuildErrorKind::Validation(e) => write!(f, "Validation failed: {e}"),
+        }
+    }
+}
+
+/// Failure to convert Elysian IR into `naga` IR,
+/// along with the function and expression being lowered at the time
+#[derive(Debug)]
+pub struct NagaBuildError {
+    pub function: Option<FunctionIdentifier>,
+    pub expr: Option<Box<Expr>>,
+    pub kind: NagaBuildErrorKind,
+}
+
+impl NagaBuildError {
+    pub fn new(kind: NagaBuildErrorKind) -> Self {
+        NagaBuildError {
+            function: None,
+            expr: None,
+            kind,
+        }
+    }
+
+    /// Attach the offending expression, unless a more specific one is already present
+    pub fn with_expr(mut self, expr: &Expr) -> Self {
+        if self.expr.is_none() {
+            self.expr = Some(Box::new(expr.clone()));
+        }
+        self
+    }
+}
+
+impl Display for NagaBuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(function) = &self.function {
+            write!(f, "In function {}: ", function.name_unique())?;
+        }
+
+        write!(f, "{}", self.kind)?;
+
+        if let Some(expr) = &self.expr {
+            write!(f, "\nWhile lowering {expr:#?}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for NagaBuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            NagaBuildErrorKind::Validation(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<WithSpan<ValidationError>> for NagaBuildError {
+    fn from(value: WithSpan<ValidationError>) -> Self {
+        NagaBuildError::new(NagaBuildErrorKind::Validation(Box::new(value)))
+    }
+}
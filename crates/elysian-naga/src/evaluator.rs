@@ -0,0 +1,186 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_core::number::Number;
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Struct, Value, MATRIX2, MATRIX3, MATRIX4, VECTOR2, VECTOR3, VECTOR4},
+    module::{
+        Evaluate, EvaluateError, FunctionDefinition, FunctionIdentifier, InputDefinition, Layout,
+        LayoutError, Module as ElysianModule, NumericType, StructIdentifier, Type, CONTEXT,
+    },
+};
+use naga::{
+    valid::{Capabilities, ValidationFlags},
+    Function, Handle, Module as NagaModule,
+};
+
+use crate::{NagaBuildError, NagaBuilder, NagaInterpreter, NagaValue};
+
+pub const NAGA_EVALUATE: FunctionIdentifier =
+    FunctionIdentifier::new("naga_evaluate", 6120390823515722405);
+
+/// Evaluator that lowers a module to `naga` IR and interprets it on the CPU
+///
+/// Exercises the same lowering path as the shader backends,
+/// at 32-bit float precision.
+#[derive(Debug)]
+pub struct NagaEvaluated {
+    module: ElysianModule,
+    naga_module: NagaModule,
+    function: Handle<Function>,
+}
+
+impl NagaEvaluated {
+    /// Lower a finalized module, wrapping its entry point in [`NAGA_EVALUATE`]
+    pub fn new(module: &ElysianModule) -> Result<Self, NagaBuildError> {
+        let mut module = module.clone();
+        module.function_definitions.push(FunctionDefinition {
+            id: NAGA_EVALUATE,
+            public: true,
+            inputs: vec![InputDefinition {
+                id: CONTEXT.into(),
+                mutable: false,
+            }],
+            output: CONTEXT.into(),
+            block: Block(vec![Stmt::Output(
+                module.call(Expr::Read(vec![CONTEXT.into()])),
+            )]),
+            provenance: None,
+        });
+
+        let (naga_module, _) =
+            NagaBuilder::new(&module).build(ValidationFlags::all(), Capabilities::default())?;
+
+        let function = NagaInterpreter::new(&naga_module)
+            .function(&NAGA_EVALUATE.name_unique())
+            .expect("No naga function for NAGA_EVALUATE");
+
+        Ok(NagaEvaluated {
+            module,
+            naga_module,
+            function,
+        })
+    }
+
+    /// Convert an Elysian value into its `naga` representation,
+    /// filling absent struct members with zero
+    fn value_to_naga(
+        &self,
+        ty: &Type,
+        value: Option<&Value>,
+    ) -> Result<NagaValue, NagaEvaluateError> {
+        Ok(match (ty, value) {
+            (Type::Boolean, Some(Value::Boolean(b))) => NagaValue::Bool(*b),
+            (Type::Boolean, None) => NagaValue::Bool(false),
+            (Type::Number(NumericType::Float), Some(Value::Number(n))) => {
+                NagaValue::F32(f32::from(*n))
+            }
+            (Type::Number(NumericType::Float), None) => NagaValue::F32(0.0),
+            (Type::Number(NumericType::UInt), Some(Value::Number(n))) => {
+                NagaValue::U32(u32::from(*n))
+            }
+            (Type::Number(NumericType::UInt), None) => NagaValue::U32(0),
+            (Type::Number(NumericType::SInt), Some(Value::Number(n))) => {
+                NagaValue::I32(i32::from(*n))
+            }
+            (Type::Number(NumericType::SInt), None) => NagaValue::I32(0),
+            (Type::Struct(id), value) => {
+                let s = match value {
+                    Some(Value::Struct(s)) => Some(s),
+                    None => None,
+                    Some(v) => return Err(NagaEvaluateError::InvalidValue(v.to_string())),
+                };
+
+                let members = Layout::new(&self.module)
+                    .fields(id)?
+                    .into_iter()
+                    .map(|(prop, ty)| self.value_to_naga(ty, s.and_then(|s| s.members.get(prop))))
+                    .collect::<Result<_, _>>()?;
+
+                match id {
+                    v if **v == VECTOR2 || **v == VECTOR3 || **v == VECTOR4 => {
+                        NagaValue::Vector(members)
+                    }
+                    m if **m == MATRIX2 || **m == MATRIX3 || **m == MATRIX4 => {
+                        NagaValue::Matrix(members)
+                    }
+                    _ => NagaValue::Struct(members),
+                }
+            }
+            (_, Some(v)) => return Err(NagaEvaluateError::InvalidValue(v.to_string())),
+        })
+    }
+
+    /// Convert a `naga` value back into its Elysian representation
+    fn value_from_naga(&self, ty: &Type, value: NagaValue) -> Result<Value, NagaEvaluateError> {
+        Ok(match (ty, value) {
+            (Type::Boolean, NagaValue::Bool(b)) => Value::Boolean(b),
+            (Type::Number(NumericType::Float), NagaValue::F32(f)) => {
+                Value::Number(Number::Float(f as f64))
+            }
+            (Type::Number(NumericType::UInt), NagaValue::U32(u)) => {
+                Value::Number(Number::UInt(u as u64))
+            }
+            (Type::Number(NumericType::SInt), NagaValue::I32(i)) => {
+                Value::Number(Number::SInt(i as i64))
+            }
+            (
+                Type::Struct(id),
+                NagaValue::Vector(members)
+                | NagaValue::Matrix(members)
+                | NagaValue::Struct(members),
+            ) => {
+                let mut out = Struct::new(id.clone());
+                for ((prop, ty), member) in Layout::new(&self.module)
+                    .fields(id)?
+                    .into_iter()
+                    .zip(members)
+                {
+                    out.set_mut(prop.clone(), self.value_from_naga(ty, member)?);
+                }
+                Value::Struct(out)
+            }
+            (_, v) => return Err(NagaEvaluateError::InvalidValue(format!("{v:?}"))),
+        })
+    }
+}
+
+impl<'a> Evaluate<'a> for NagaEvaluated {
+    fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
+        let ty = Type::Struct(StructIdentifier(CONTEXT));
+        let context = self.value_to_naga(&ty, Some(&Value::Struct(context)))?;
+
+        let out = NagaInterpreter::new(&self.naga_module)
+            .call(self.function, vec![context])?
+            .ok_or_else(|| NagaEvaluateError::InvalidValue("No return value".to_string()))?;
+
+        match self.value_from_naga(&ty, out)? {
+            Value::Struct(s) => Ok(s),
+            v => Err(Box::new(NagaEvaluateError::InvalidValue(v.to_string()))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NagaEvaluateError {
+    Layout(LayoutError),
+    InvalidValue(String),
+}
+
+impl Display for NagaEvaluateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NagaEvaluateError::Layout(e) => e.fmt(f),
+            NagaEvaluateError::InvalidValue(value) => {
+                f.write_str(&format!("Value {value} does not match its naga type"))
+            }
+        }
+    }
+}
+
+impl Error for NagaEvaluateError {}
+
+impl From<LayoutError> for NagaEvaluateError {
+    fn from(value: LayoutError) -> Self {
+        NagaEvaluateError::Layout(value)
+    }
+}
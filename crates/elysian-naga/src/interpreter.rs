@@ -0,0 +1,752 @@
+//! CPU interpreter for `naga` modules produced by [`NagaBuilder`](crate::NagaBuilder)
+//!
+//! Supports the subset of `naga` IR emitted by the builder:
+//! scalars, vectors, matrices and structs, function calls,
+//! local variables, branches and loops.
+
+use std::{error::Error, fmt::Display};
+
+use naga::{
+    BinaryOperator, Block, Expression, Function, Handle, Literal, MathFunction,
+    Module as NagaModule, ScalarKind, Statement, Type, TypeInner, UnaryOperator,
+};
+
+/// Runtime value of a `naga` expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum NagaValue {
+    Bool(bool),
+    U32(u32),
+    I32(i32),
+    F32(f32),
+    /// Vector of scalars
+    Vector(Vec<NagaValue>),
+    /// Matrix of column vectors
+    Matrix(Vec<NagaValue>),
+    /// Struct members in declaration order
+    Struct(Vec<NagaValue>),
+}
+
+impl NagaValue {
+    /// Zero value for a type, as used to initialize local variables
+    pub fn zero(module: &NagaModule, ty: Handle<Type>) -> Result<Self, NagaInterpreterError> {
+        let scalar = |kind: ScalarKind| match kind {
+            ScalarKind::Bool => Ok(NagaValue::Bool(false)),
+            ScalarKind::Uint => Ok(NagaValue::U32(0)),
+            ScalarKind::Sint => Ok(NagaValue::I32(0)),
+            ScalarKind::Float => Ok(NagaValue::F32(0.0)),
+        };
+
+        match &module.types[ty].inner {
+            TypeInner::Scalar { kind, .. } => scalar(*kind),
+            TypeInner::Vector { size, kind, .. } => {
+                Ok(NagaValue::Vector(vec![scalar(*kind)?; *size as usize]))
+            }
+            TypeInner::Matrix { columns, rows, .. } => Ok(NagaValue::Matrix(vec![
+                NagaValue::Vector(
+                    vec![NagaValue::F32(0.0); *rows as usize]
+                );
+                *columns as usize
+            ])),
+            TypeInner::Struct { members, .. } => Ok(NagaValue::Struct(
+                members
+                    .iter()
+                    .map(|member| NagaValue::zero(module, member.ty))
+                    .collect::<Result<_, _>>()?,
+            )),
+            t => Err(NagaInterpreterError::Unsupported(format!("{t:?}"))),
+        }
+    }
+
+    fn components(&self) -> Option<&[NagaValue]> {
+        match self {
+            NagaValue::Vector(c) | NagaValue::Matrix(c) | NagaValue::Struct(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn components_mut(&mut self) -> Option<&mut Vec<NagaValue>> {
+        match self {
+            NagaValue::Vector(c) | NagaValue::Matrix(c) | NagaValue::Struct(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn as_f32(&self) -> Result<f32, NagaInterpreterError> {
+        match self {
+            NagaValue::F32(f) => Ok(*f),
+            v => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{v:?} is not a float"
+            ))),
+        }
+    }
+
+    fn as_bool(&self) -> Result<bool, NagaInterpreterError> {
+        match self {
+            NagaValue::Bool(b) => Ok(*b),
+            v => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{v:?} is not a bool"
+            ))),
+        }
+    }
+
+    /// Apply a function to each scalar, preserving shape
+    fn map(
+        &self,
+        f: &impl Fn(&NagaValue) -> Result<NagaValue, NagaInterpreterError>,
+    ) -> Result<NagaValue, NagaInterpreterError> {
+        match self {
+            NagaValue::Vector(c) => Ok(NagaValue::Vector(
+                c.iter().map(|v| v.map(f)).collect::<Result<_, _>>()?,
+            )),
+            NagaValue::Matrix(c) => Ok(NagaValue::Matrix(
+                c.iter().map(|v| v.map(f)).collect::<Result<_, _>>()?,
+            )),
+            NagaValue::Struct(_) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{self:?} is not a scalar, vector or matrix"
+            ))),
+            scalar => f(scalar),
+        }
+    }
+
+    /// Apply a function to pairs of scalars, broadcasting scalars across composites
+    fn zip(
+        &self,
+        rhs: &NagaValue,
+        f: &impl Fn(&NagaValue, &NagaValue) -> Result<NagaValue, NagaInterpreterError>,
+    ) -> Result<NagaValue, NagaInterpreterError> {
+        match (self, rhs) {
+            (NagaValue::Vector(a), NagaValue::Vector(b)) if a.len() == b.len() => {
+                Ok(NagaValue::Vector(
+                    a.iter()
+                        .zip(b)
+                        .map(|(a, b)| a.zip(b, f))
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            (NagaValue::Matrix(a), NagaValue::Matrix(b)) if a.len() == b.len() => {
+                Ok(NagaValue::Matrix(
+                    a.iter()
+                        .zip(b)
+                        .map(|(a, b)| a.zip(b, f))
+                        .collect::<Result<_, _>>()?,
+                ))
+            }
+            (NagaValue::Vector(_) | NagaValue::Matrix(_), scalar) if scalar.is_scalar() => {
+                self.map(&|a| f(a, scalar))
+            }
+            (scalar, NagaValue::Vector(_) | NagaValue::Matrix(_)) if scalar.is_scalar() => {
+                rhs.map(&|b| f(scalar, b))
+            }
+            (a, b) if a.is_scalar() && b.is_scalar() => f(a, b),
+            (a, b) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "Mismatched operands {a:?}, {b:?}"
+            ))),
+        }
+    }
+
+    fn is_scalar(&self) -> bool {
+        matches!(
+            self,
+            NagaValue::Bool(_) | NagaValue::U32(_) | NagaValue::I32(_) | NagaValue::F32(_)
+        )
+    }
+
+    fn dot(&self, rhs: &NagaValue) -> Result<f32, NagaInterpreterError> {
+        match (self, rhs) {
+            (NagaValue::Vector(a), NagaValue::Vector(b)) if a.len() == b.len() => a
+                .iter()
+                .zip(b)
+                .try_fold(0.0, |acc, (a, b)| Ok(acc + a.as_f32()? * b.as_f32()?)),
+            (a, b) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "Cannot dot {a:?}, {b:?}"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NagaInterpreterError {
+    Unsupported(String),
+    InvalidOperand(String),
+    InvalidPointer(String),
+    MissingReturn(String),
+}
+
+impl Display for NagaInterpreterError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NagaInterpreterError::Unsupported(desc) => {
+                f.write_str(&format!("Unsupported by naga interpreter: {desc}"))
+            }
+            NagaInterpreterError::InvalidOperand(desc) => {
+                f.write_str(&format!("Invalid operand: {desc}"))
+            }
+            NagaInterpreterError::InvalidPointer(desc) => {
+                f.write_str(&format!("Invalid pointer: {desc}"))
+            }
+            NagaInterpreterError::MissingReturn(name) => {
+                f.write_str(&format!("Function {name} did not return a value"))
+            }
+        }
+    }
+}
+
+impl Error for NagaInterpreterError {}
+
+/// Location of a value within a function's local variables
+#[derive(Debug, Clone)]
+struct Pointer {
+    local: usize,
+    path: Vec<usize>,
+}
+
+/// Result of evaluating an expression
+#[derive(Debug, Clone)]
+enum Evaluated {
+    Value(NagaValue),
+    Pointer(Pointer),
+}
+
+/// Outcome of executing a statement
+enum Flow {
+    Next,
+    Break,
+    Continue,
+    Return(Option<NagaValue>),
+}
+
+/// State of a single function invocation
+struct Frame<'a> {
+    function: &'a Function,
+    arguments: Vec<NagaValue>,
+    locals: Vec<NagaValue>,
+    expressions: Vec<Option<Evaluated>>,
+}
+
+/// Executes functions of a `naga` module on the CPU
+#[derive(Debug, Copy, Clone)]
+pub struct NagaInterpreter<'a> {
+    module: &'a NagaModule,
+}
+
+impl<'a> NagaInterpreter<'a> {
+    pub fn new(module: &'a NagaModule) -> Self {
+        NagaInterpreter { module }
+    }
+
+    /// Find a function by name
+    pub fn function(&self, name: &str) -> Option<Handle<Function>> {
+        self.module
+            .functions
+            .iter()
+            .find(|(_, f)| f.name.as_deref() == Some(name))
+            .map(|(handle, _)| handle)
+    }
+
+    /// Call a function with the given arguments, returning its result if any
+    pub fn call(
+        &self,
+        function: Handle<Function>,
+        arguments: Vec<NagaValue>,
+    ) -> Result<Option<NagaValue>, NagaInterpreterError> {
+        let function = &self.module.functions[function];
+
+        if arguments.len() != function.arguments.len() {
+            return Err(NagaInterpreterError::InvalidOperand(format!(
+                "{} expects {} arguments, got {}",
+                function.name.as_deref().unwrap_or_default(),
+                function.arguments.len(),
+                arguments.len()
+            )));
+        }
+
+        let locals = function
+            .local_variables
+            .iter()
+            .map(|(_, local)| {
+                if local.init.is_some() {
+                    return Err(NagaInterpreterError::Unsupported(
+                        "Local variable initializer".to_string(),
+                    ));
+                }
+                NagaValue::zero(self.module, local.ty)
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut frame = Frame {
+            function,
+            arguments,
+            locals,
+            expressions: vec![None; function.expressions.len()],
+        };
+
+        match self.block(&mut frame, &function.body)? {
+            Flow::Return(value) => Ok(value),
+            _ if function.result.is_none() => Ok(None),
+            _ => Err(NagaInterpreterError::MissingReturn(
+                function.name.clone().unwrap_or_default(),
+            )),
+        }
+    }
+
+    fn block(&self, frame: &mut Frame, block: &Block) -> Result<Flow, NagaInterpreterError> {
+        for stmt in block.iter() {
+            match self.statement(frame, stmt)? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn statement(&self, frame: &mut Frame, stmt: &Statement) -> Result<Flow, NagaInterpreterError> {
+        match stmt {
+            Statement::Emit(range) => {
+                for handle in range.clone() {
+                    if matches!(
+                        frame.function.expressions[handle],
+                        Expression::CallResult(_)
+                    ) {
+                        continue;
+                    }
+                    let value = self.evaluate(frame, handle)?;
+                    frame.expressions[handle.index()] = Some(value);
+                }
+            }
+            Statement::Block(block) => return self.block(frame, block),
+            Statement::If {
+                condition,
+                accept,
+                reject,
+            } => {
+                let condition = self.value(frame, *condition)?.as_bool()?;
+                return self.block(frame, if condition { accept } else { reject });
+            }
+            Statement::Loop {
+                body,
+                continuing,
+                break_if,
+            } => loop {
+                match self.block(frame, body)? {
+                    Flow::Break => break,
+                    Flow::Return(value) => return Ok(Flow::Return(value)),
+                    Flow::Next | Flow::Continue => (),
+                }
+
+                match self.block(frame, continuing)? {
+                    Flow::Return(value) => return Ok(Flow::Return(value)),
+                    Flow::Break => break,
+                    _ => (),
+                }
+
+                if let Some(break_if) = break_if {
+                    if self.value(frame, *break_if)?.as_bool()? {
+                        break;
+                    }
+                }
+            },
+            Statement::Break => return Ok(Flow::Break),
+            Statement::Continue => return Ok(Flow::Continue),
+            Statement::Return { value } => {
+                let value = match value {
+                    Some(value) => Some(self.value(frame, *value)?),
+                    None => None,
+                };
+                return Ok(Flow::Return(value));
+            }
+            Statement::Store { pointer, value } => {
+                let pointer = self.pointer(frame, *pointer)?;
+                let value = self.value(frame, *value)?;
+                *Self::resolve_mut(frame, &pointer)? = value;
+            }
+            Statement::Call {
+                function,
+                arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|arg| self.value(frame, *arg))
+                    .collect::<Result<_, _>>()?;
+
+                let value = self.call(*function, arguments)?;
+
+                if let (Some(result), Some(value)) = (result, value) {
+                    frame.expressions[result.index()] = Some(Evaluated::Value(value));
+                }
+            }
+            stmt => return Err(NagaInterpreterError::Unsupported(format!("{stmt:?}"))),
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn resolve_mut<'b>(
+        frame: &'b mut Frame,
+        pointer: &Pointer,
+    ) -> Result<&'b mut NagaValue, NagaInterpreterError> {
+        let mut value = &mut frame.locals[pointer.local];
+        for index in pointer.path.iter() {
+            value = value
+                .components_mut()
+                .and_then(|c| c.get_mut(*index))
+                .ok_or_else(|| NagaInterpreterError::InvalidPointer(format!("{pointer:?}")))?;
+        }
+        Ok(value)
+    }
+
+    fn resolve(frame: &Frame, pointer: &Pointer) -> Result<NagaValue, NagaInterpreterError> {
+        let mut value = &frame.locals[pointer.local];
+        for index in pointer.path.iter() {
+            value = value
+                .components()
+                .and_then(|c| c.get(*index))
+                .ok_or_else(|| NagaInterpreterError::InvalidPointer(format!("{pointer:?}")))?;
+        }
+        Ok(value.clone())
+    }
+
+    /// Fetch the result of an expression, evaluating it if not yet emitted
+    fn fetch(
+        &self,
+        frame: &Frame,
+        handle: Handle<Expression>,
+    ) -> Result<Evaluated, NagaInterpreterError> {
+        match &frame.expressions[handle.index()] {
+            Some(evaluated) => Ok(evaluated.clone()),
+            None => self.evaluate(frame, handle),
+        }
+    }
+
+    fn value(
+        &self,
+        frame: &Frame,
+        handle: Handle<Expression>,
+    ) -> Result<NagaValue, NagaInterpreterError> {
+        match self.fetch(frame, handle)? {
+            Evaluated::Value(value) => Ok(value),
+            Evaluated::Pointer(pointer) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "Expected value, found pointer {pointer:?}"
+            ))),
+        }
+    }
+
+    fn pointer(
+        &self,
+        frame: &Frame,
+        handle: Handle<Expression>,
+    ) -> Result<Pointer, NagaInterpreterError> {
+        match self.fetch(frame, handle)? {
+            Evaluated::Pointer(pointer) => Ok(pointer),
+            Evaluated::Value(value) => Err(NagaInterpreterError::InvalidPointer(format!(
+                "Expected pointer, found value {value:?}"
+            ))),
+        }
+    }
+
+    fn evaluate(
+        &self,
+        frame: &Frame,
+        handle: Handle<Expression>,
+    ) -> Result<Evaluated, NagaInterpreterError> {
+        let value = match &frame.function.expressions[handle] {
+            Expression::Literal(literal) => match literal {
+                Literal::F64(f) => NagaValue::F32(*f as f32),
+                Literal::F32(f) => NagaValue::F32(*f),
+                Literal::U32(u) => NagaValue::U32(*u),
+                Literal::I32(i) => NagaValue::I32(*i),
+                Literal::Bool(b) => NagaValue::Bool(*b),
+            },
+            Expression::ZeroValue(ty) => NagaValue::zero(self.module, *ty)?,
+            Expression::FunctionArgument(index) => frame
+                .arguments
+                .get(*index as usize)
+                .cloned()
+                .ok_or_else(|| {
+                    NagaInterpreterError::InvalidOperand(format!("No argument {index}"))
+                })?,
+            Expression::LocalVariable(local) => {
+                return Ok(Evaluated::Pointer(Pointer {
+                    local: local.index(),
+                    path: vec![],
+                }))
+            }
+            Expression::Load { pointer } => Self::resolve(frame, &self.pointer(frame, *pointer)?)?,
+            Expression::AccessIndex { base, index } => match self.fetch(frame, *base)? {
+                Evaluated::Pointer(mut pointer) => {
+                    pointer.path.push(*index as usize);
+                    return Ok(Evaluated::Pointer(pointer));
+                }
+                Evaluated::Value(value) => value
+                    .components()
+                    .and_then(|c| c.get(*index as usize))
+                    .cloned()
+                    .ok_or_else(|| {
+                        NagaInterpreterError::InvalidOperand(format!(
+                            "No component {index} in {value:?}"
+                        ))
+                    })?,
+            },
+            Expression::Compose { ty, components } => {
+                let components = components
+                    .iter()
+                    .map(|c| self.value(frame, *c))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                match self.module.types[*ty].inner {
+                    TypeInner::Vector { .. } => NagaValue::Vector(
+                        components
+                            .into_iter()
+                            .flat_map(|c| match c {
+                                NagaValue::Vector(c) => c,
+                                c => vec![c],
+                            })
+                            .collect(),
+                    ),
+                    TypeInner::Matrix { .. } => NagaValue::Matrix(components),
+                    _ => NagaValue::Struct(components),
+                }
+            }
+            Expression::CallResult(_) => {
+                return Err(NagaInterpreterError::InvalidOperand(format!(
+                    "Call result {handle:?} read before call"
+                )))
+            }
+            Expression::Unary { op, expr } => {
+                let value = self.value(frame, *expr)?;
+                match op {
+                    UnaryOperator::Negate => value.map(&|v| match v {
+                        NagaValue::F32(f) => Ok(NagaValue::F32(-f)),
+                        NagaValue::I32(i) => Ok(NagaValue::I32(i.wrapping_neg())),
+                        v => Err(NagaInterpreterError::InvalidOperand(format!("-{v:?}"))),
+                    })?,
+                    UnaryOperator::Not => value.map(&|v| match v {
+                        NagaValue::Bool(b) => Ok(NagaValue::Bool(!b)),
+                        NagaValue::U32(u) => Ok(NagaValue::U32(!u)),
+                        NagaValue::I32(i) => Ok(NagaValue::I32(!i)),
+                        v => Err(NagaInterpreterError::InvalidOperand(format!("!{v:?}"))),
+                    })?,
+                }
+            }
+            Expression::Binary { op, left, right } => {
+                let lhs = self.value(frame, *left)?;
+                let rhs = self.value(frame, *right)?;
+                binary(*op, &lhs, &rhs)?
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => {
+                if self.value(frame, *condition)?.as_bool()? {
+                    self.value(frame, *accept)?
+                } else {
+                    self.value(frame, *reject)?
+                }
+            }
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                ..
+            } => {
+                let arg = self.value(frame, *arg)?;
+                let arg1 = arg1.map(|arg| self.value(frame, arg)).transpose()?;
+                let arg2 = arg2.map(|arg| self.value(frame, arg)).transpose()?;
+                math(*fun, &arg, arg1.as_ref(), arg2.as_ref())?
+            }
+            expr => return Err(NagaInterpreterError::Unsupported(format!("{expr:?}"))),
+        };
+
+        Ok(Evaluated::Value(value))
+    }
+}
+
+fn binary(
+    op: BinaryOperator,
+    lhs: &NagaValue,
+    rhs: &NagaValue,
+) -> Result<NagaValue, NagaInterpreterError> {
+    match (op, lhs, rhs) {
+        // Linear algebra products
+        (BinaryOperator::Multiply, NagaValue::Matrix(columns), NagaValue::Vector(v)) => {
+            matrix_vector(columns, v)
+        }
+        (BinaryOperator::Multiply, NagaValue::Vector(_), NagaValue::Matrix(columns)) => {
+            Ok(NagaValue::Vector(
+                columns
+                    .iter()
+                    .map(|column| lhs.dot(column).map(NagaValue::F32))
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        (BinaryOperator::Multiply, NagaValue::Matrix(columns), NagaValue::Matrix(rhs)) => {
+            Ok(NagaValue::Matrix(
+                rhs.iter()
+                    .map(|column| match column {
+                        NagaValue::Vector(v) => matrix_vector(columns, v),
+                        c => Err(NagaInterpreterError::InvalidOperand(format!("{c:?}"))),
+                    })
+                    .collect::<Result<_, _>>()?,
+            ))
+        }
+        _ => lhs.zip(rhs, &|a, b| binary_scalar(op, a, b)),
+    }
+}
+
+fn matrix_vector(
+    columns: &[NagaValue],
+    v: &[NagaValue],
+) -> Result<NagaValue, NagaInterpreterError> {
+    let mut out: Option<NagaValue> = None;
+    for (column, s) in columns.iter().zip(v) {
+        let term = column.zip(s, &|a, b| binary_scalar(BinaryOperator::Multiply, a, b))?;
+        out = Some(match out {
+            Some(acc) => acc.zip(&term, &|a, b| binary_scalar(BinaryOperator::Add, a, b))?,
+            None => term,
+        });
+    }
+    out.ok_or_else(|| NagaInterpreterError::InvalidOperand("Empty matrix".to_string()))
+}
+
+fn binary_scalar(
+    op: BinaryOperator,
+    lhs: &NagaValue,
+    rhs: &NagaValue,
+) -> Result<NagaValue, NagaInterpreterError> {
+    use BinaryOperator as Op;
+    use NagaValue as V;
+
+    Ok(match (op, lhs, rhs) {
+        (Op::Add, V::F32(a), V::F32(b)) => V::F32(a + b),
+        (Op::Subtract, V::F32(a), V::F32(b)) => V::F32(a - b),
+        (Op::Multiply, V::F32(a), V::F32(b)) => V::F32(a * b),
+        (Op::Divide, V::F32(a), V::F32(b)) => V::F32(a / b),
+        (Op::Modulo, V::F32(a), V::F32(b)) => V::F32(a % b),
+        (Op::Add, V::U32(a), V::U32(b)) => V::U32(a.wrapping_add(*b)),
+        (Op::Subtract, V::U32(a), V::U32(b)) => V::U32(a.wrapping_sub(*b)),
+        (Op::Multiply, V::U32(a), V::U32(b)) => V::U32(a.wrapping_mul(*b)),
+        (Op::Divide, V::U32(a), V::U32(b)) => V::U32(a.checked_div(*b).unwrap_or(*a)),
+        (Op::Modulo, V::U32(a), V::U32(b)) => V::U32(a.checked_rem(*b).unwrap_or(0)),
+        (Op::Add, V::I32(a), V::I32(b)) => V::I32(a.wrapping_add(*b)),
+        (Op::Subtract, V::I32(a), V::I32(b)) => V::I32(a.wrapping_sub(*b)),
+        (Op::Multiply, V::I32(a), V::I32(b)) => V::I32(a.wrapping_mul(*b)),
+        (Op::Divide, V::I32(a), V::I32(b)) => V::I32(a.checked_div(*b).unwrap_or(*a)),
+        (Op::Modulo, V::I32(a), V::I32(b)) => V::I32(a.checked_rem(*b).unwrap_or(0)),
+        (Op::Equal, a, b) => V::Bool(a == b),
+        (Op::NotEqual, a, b) => V::Bool(a != b),
+        (Op::Less, V::F32(a), V::F32(b)) => V::Bool(a < b),
+        (Op::Less, V::U32(a), V::U32(b)) => V::Bool(a < b),
+        (Op::Less, V::I32(a), V::I32(b)) => V::Bool(a < b),
+        (Op::LessEqual, V::F32(a), V::F32(b)) => V::Bool(a <= b),
+        (Op::LessEqual, V::U32(a), V::U32(b)) => V::Bool(a <= b),
+        (Op::LessEqual, V::I32(a), V::I32(b)) => V::Bool(a <= b),
+        (Op::Greater, V::F32(a), V::F32(b)) => V::Bool(a > b),
+        (Op::Greater, V::U32(a), V::U32(b)) => V::Bool(a > b),
+        (Op::Greater, V::I32(a), V::I32(b)) => V::Bool(a > b),
+        (Op::GreaterEqual, V::F32(a), V::F32(b)) => V::Bool(a >= b),
+        (Op::GreaterEqual, V::U32(a), V::U32(b)) => V::Bool(a >= b),
+        (Op::GreaterEqual, V::I32(a), V::I32(b)) => V::Bool(a >= b),
+        (Op::LogicalAnd, V::Bool(a), V::Bool(b)) => V::Bool(*a && *b),
+        (Op::LogicalOr, V::Bool(a), V::Bool(b)) => V::Bool(*a || *b),
+        (op, a, b) => {
+            return Err(NagaInterpreterError::InvalidOperand(format!(
+                "{op:?} {a:?}, {b:?}"
+            )))
+        }
+    })
+}
+
+fn math(
+    fun: MathFunction,
+    arg: &NagaValue,
+    arg1: Option<&NagaValue>,
+    arg2: Option<&NagaValue>,
+) -> Result<NagaValue, NagaInterpreterError> {
+    let missing = || NagaInterpreterError::InvalidOperand(format!("Missing argument for {fun:?}"));
+    let float = |f: fn(f32) -> f32| {
+        arg.map(&|v| match v {
+            NagaValue::F32(x) => Ok(NagaValue::F32(f(*x))),
+            v => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{fun:?} {v:?}"
+            ))),
+        })
+    };
+    let float2 = |f: fn(f32, f32) -> f32| {
+        arg.zip(arg1.ok_or_else(missing)?, &|a, b| {
+            Ok(NagaValue::F32(f(a.as_f32()?, b.as_f32()?)))
+        })
+    };
+
+    match fun {
+        MathFunction::Abs => arg.map(&|v| match v {
+            NagaValue::F32(x) => Ok(NagaValue::F32(x.abs())),
+            NagaValue::I32(x) => Ok(NagaValue::I32(x.wrapping_abs())),
+            NagaValue::U32(x) => Ok(NagaValue::U32(*x)),
+            v => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{fun:?} {v:?}"
+            ))),
+        }),
+        MathFunction::Sign => arg.map(&|v| match v {
+            NagaValue::F32(x) => Ok(NagaValue::F32(if *x > 0.0 {
+                1.0
+            } else if *x < 0.0 {
+                -1.0
+            } else {
+                0.0
+            })),
+            NagaValue::I32(x) => Ok(NagaValue::I32(x.signum())),
+            v => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{fun:?} {v:?}"
+            ))),
+        }),
+        MathFunction::Round => float(f32::round_ties_even),
+        MathFunction::Sin => float(f32::sin),
+        MathFunction::Cos => float(f32::cos),
+        MathFunction::Tan => float(f32::tan),
+        MathFunction::Asin => float(f32::asin),
+        MathFunction::Acos => float(f32::acos),
+        MathFunction::Atan => float(f32::atan),
+        MathFunction::Exp2 => float(f32::exp2),
+        MathFunction::Log2 => float(f32::log2),
+        MathFunction::Atan2 => float2(f32::atan2),
+        MathFunction::Min => arg.zip(arg1.ok_or_else(missing)?, &|a, b| match (a, b) {
+            (NagaValue::F32(a), NagaValue::F32(b)) => Ok(NagaValue::F32(a.min(*b))),
+            (NagaValue::U32(a), NagaValue::U32(b)) => Ok(NagaValue::U32(*a.min(b))),
+            (NagaValue::I32(a), NagaValue::I32(b)) => Ok(NagaValue::I32(*a.min(b))),
+            (a, b) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{fun:?} {a:?}, {b:?}"
+            ))),
+        }),
+        MathFunction::Max => arg.zip(arg1.ok_or_else(missing)?, &|a, b| match (a, b) {
+            (NagaValue::F32(a), NagaValue::F32(b)) => Ok(NagaValue::F32(a.max(*b))),
+            (NagaValue::U32(a), NagaValue::U32(b)) => Ok(NagaValue::U32(*a.max(b))),
+            (NagaValue::I32(a), NagaValue::I32(b)) => Ok(NagaValue::I32(*a.max(b))),
+            (a, b) => Err(NagaInterpreterError::InvalidOperand(format!(
+                "{fun:?} {a:?}, {b:?}"
+            ))),
+        }),
+        MathFunction::Clamp => {
+            let lo = math(MathFunction::Max, arg, arg1, None)?;
+            math(MathFunction::Min, &lo, arg2, None)
+        }
+        MathFunction::Mix => {
+            let (b, t) = (arg1.ok_or_else(missing)?, arg2.ok_or_else(missing)?);
+            let delta = b.zip(arg, &|b, a| binary_scalar(BinaryOperator::Subtract, b, a))?;
+            let scaled = delta.zip(t, &|d, t| binary_scalar(BinaryOperator::Multiply, d, t))?;
+            arg.zip(&scaled, &|a, s| binary_scalar(BinaryOperator::Add, a, s))
+        }
+        MathFunction::Dot => Ok(NagaValue::F32(arg.dot(arg1.ok_or_else(missing)?)?)),
+        MathFunction::Length => match arg {
+            NagaValue::F32(x) => Ok(NagaValue::F32(x.abs())),
+            v => Ok(NagaValue::F32(v.dot(v)?.sqrt())),
+        },
+        MathFunction::Normalize => {
+            let length = arg.dot(arg)?.sqrt();
+            arg.map(&|v| Ok(NagaValue::F32(v.as_f32()? / length)))
+        }
+        fun => Err(NagaInterpreterError::Unsupported(format!("{fun:?}"))),
+    }
+}
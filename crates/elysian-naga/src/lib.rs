@@ -1,25 +1,29 @@
 //! Convert Elysian IR into `naga` IR
 
+mod error;
+mod evaluator;
+mod interpreter;
+
+pub use error::*;
+pub use evaluator::*;
+pub use interpreter::*;
+
 use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
-    ast::{
-        Expr, Stmt, Struct, Value, COLOR, MATRIX2, MATRIX3, MATRIX4, POSITION_2D, VECTOR2, VECTOR3,
-        VECTOR4,
-    },
+    ast::{Expr, Stmt, Struct, Value, MATRIX2, MATRIX3, MATRIX4, VECTOR2, VECTOR3, VECTOR4},
     module::{
         properties, FunctionDefinition, FunctionIdentifier, Module as ElysianModule, NumericType,
-        Type as ElysianType, CONTEXT,
+        StructDefinition, StructIdentifier, Type as ElysianType,
     },
 };
-use elysian_shapes::modify::ASPECT;
 use indexmap::IndexMap;
 use naga::{
-    valid::{Capabilities, ModuleInfo, ValidationError, ValidationFlags, Validator},
-    Arena, BinaryOperator, Block as NagaBlock, EntryPoint, Expression, Function, FunctionArgument,
-    FunctionResult, Handle, Literal, LocalVariable, MathFunction, Module as NagaModule, Range,
-    ScalarKind, ShaderStage, Span, Statement, StructMember, SwizzleComponent, Type as NagaType,
-    TypeInner, UniqueArena, VectorSize, WithSpan,
+    valid::{Capabilities, ModuleInfo, ValidationFlags, Validator},
+    AddressSpace, Arena, BinaryOperator, Binding, Block as NagaBlock, EntryPoint, Expression,
+    Function, FunctionArgument, FunctionResult, GlobalVariable, Handle, Literal, LocalVariable,
+    MathFunction, Module as NagaModule, Range, ResourceBinding, ScalarKind, ShaderStage, Span,
+    Statement, StructMember, Type as NagaType, TypeInner, UniqueArena, VectorSize,
 };
 
 pub const SAFE_NORMALIZE_2: FunctionIdentifier =
@@ -41,6 +45,37 @@ pub struct LocalVariableStore {
     pointers: IndexMap<Handle<LocalVariable>, Handle<Expression>>,
 }
 
+/// Shader entry point wrapping an Elysian function
+///
+/// Each input of `function` is sourced from the corresponding entry in `inputs`,
+/// and its output is returned via `output`.
+#[derive(Debug, Clone)]
+pub struct EntryPointDefinition {
+    pub name: String,
+    pub stage: ShaderStage,
+    pub function: FunctionIdentifier,
+    pub inputs: Vec<EntryPointInput>,
+    pub output: EntryPointOutput,
+}
+
+/// Source of an entry point function input
+#[derive(Debug, Clone)]
+pub enum EntryPointInput {
+    /// Entry point argument, such as a vertex attribute or varying
+    Binding(Binding),
+    /// Global variable in the uniform address space
+    Uniform(ResourceBinding),
+}
+
+/// Destination of an entry point function output
+#[derive(Debug, Clone)]
+pub enum EntryPointOutput {
+    /// Return the output as-is
+    Binding(Binding),
+    /// Return a subset of the fields of a struct output
+    Fields(Vec<(PropertyIdentifier, Binding)>),
+}
+
 pub struct NagaBuilder<'a> {
     input: &'a ElysianModule,
     types: UniqueArena<NagaType>,
@@ -49,6 +84,8 @@ pub struct NagaBuilder<'a> {
     block_stack: Vec<NagaBlock>,
     expressions: Option<ExpressionQueue>,
     local_variables: Option<LocalVariableStore>,
+    global_variables: Arena<GlobalVariable>,
+    entry_points: Vec<EntryPointDefinition>,
 }
 
 impl<'a> NagaBuilder<'a> {
@@ -61,29 +98,40 @@ impl<'a> NagaBuilder<'a> {
             block_stack: Default::default(),
             expressions: Default::default(),
             local_variables: Default::default(),
+            global_variables: Default::default(),
+            entry_points: Default::default(),
         }
     }
 
+    /// Expose an Elysian function as a shader entry point
+    pub fn entry_point(mut self, entry_point: EntryPointDefinition) -> Self {
+        self.entry_points.push(entry_point);
+        self
+    }
+
     pub fn build(
         mut self,
         validation_flags: ValidationFlags,
         capabilities: Capabilities,
-    ) -> Result<(NagaModule, ModuleInfo), WithSpan<ValidationError>> {
+    ) -> Result<(NagaModule, ModuleInfo), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("module_to_naga");
 
-        self.types_to_naga();
-        self.functions_to_naga();
-        let entry_point = self.shadertoy_entry_point();
+        self.types_to_naga()?;
+        self.functions_to_naga()?;
+        let entry_points = std::mem::take(&mut self.entry_points)
+            .iter()
+            .map(|def| self.entry_point_to_naga(def))
+            .collect::<Result<_, _>>()?;
 
         let module = NagaModule {
             types: self.types,
             special_types: Default::default(),
             constants: Default::default(),
-            global_variables: Default::default(),
+            global_variables: self.global_variables,
             const_expressions: Default::default(),
             functions: self.functions,
-            entry_points: vec![entry_point],
+            entry_points,
         };
 
         let mut validator = Validator::new(validation_flags, capabilities);
@@ -92,24 +140,47 @@ impl<'a> NagaBuilder<'a> {
         Ok((module, module_info))
     }
 
-    fn get_type(&self, name: &str) -> (Handle<NagaType>, &NagaType) {
+    /// Create an error attributed to the function currently being lowered
+    fn error(&self, kind: NagaBuildErrorKind) -> NagaBuildError {
+        NagaBuildError {
+            function: self.function.as_ref().map(|function| function.id.clone()),
+            expr: None,
+            kind,
+        }
+    }
+
+    fn get_type(&self, name: &str) -> Result<(Handle<NagaType>, &NagaType), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("get_type");
 
         self.types
             .iter()
-            .find(|(_, v)| v.name.as_ref().map(String::as_str) == Some(name))
-            .unwrap_or_else(|| panic!("No type for {}", name))
+            .find(|(_, v)| v.name.as_deref() == Some(name))
+            .ok_or_else(|| self.error(NagaBuildErrorKind::MissingType(name.to_string())))
     }
 
-    fn get_function(&self, name: &str) -> (Handle<Function>, &Function) {
+    fn get_function(&self, name: &str) -> Result<(Handle<Function>, &Function), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("get_function");
 
         self.functions
             .iter()
-            .find(|(_, v)| v.name.as_ref().map(String::as_str) == Some(name))
-            .unwrap_or_else(|| panic!("No function for {name:}"))
+            .find(|(_, v)| v.name.as_deref() == Some(name))
+            .ok_or_else(|| self.error(NagaBuildErrorKind::MissingFunction(name.to_string())))
+    }
+
+    fn get_struct_definition(
+        &self,
+        id: &StructIdentifier,
+    ) -> Result<&'a StructDefinition, NagaBuildError> {
+        #[cfg(feature = "print")]
+        println!("get_struct_definition");
+
+        self.input
+            .struct_definitions
+            .iter()
+            .find(|cand| cand.id == *id)
+            .ok_or_else(|| self.error(NagaBuildErrorKind::MissingStruct(id.name().to_string())))
     }
 
     fn get_local_variable(&self, name: &str) -> Option<(Handle<LocalVariable>, &LocalVariable)> {
@@ -120,7 +191,7 @@ impl<'a> NagaBuilder<'a> {
             .as_ref()?
             .locals
             .iter()
-            .find(|(_, v)| v.name.as_ref().map(String::as_str) == Some(name))
+            .find(|(_, v)| v.name.as_deref() == Some(name))
     }
 
     fn get_pointer(&self, name: &str) -> Option<&Handle<Expression>> {
@@ -135,11 +206,11 @@ impl<'a> NagaBuilder<'a> {
             .get(&k)
     }
 
-    fn types_to_naga(&mut self) {
+    fn types_to_naga(&mut self) -> Result<(), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("types_to_naga");
 
-        let bool = self.push_type(NagaType {
+        self.push_type(NagaType {
             name: Some("Bool".to_string()),
             inner: TypeInner::Scalar {
                 kind: ScalarKind::Bool,
@@ -147,7 +218,7 @@ impl<'a> NagaBuilder<'a> {
             },
         });
 
-        let uint = self.push_type(NagaType {
+        self.push_type(NagaType {
             name: Some("UInt".to_string()),
             inner: TypeInner::Scalar {
                 kind: ScalarKind::Uint,
@@ -155,7 +226,7 @@ impl<'a> NagaBuilder<'a> {
             },
         });
 
-        let sint = self.push_type(NagaType {
+        self.push_type(NagaType {
             name: Some("SInt".to_string()),
             inner: TypeInner::Scalar {
                 kind: ScalarKind::Sint,
@@ -163,7 +234,7 @@ impl<'a> NagaBuilder<'a> {
             },
         });
 
-        let float = self.push_type(NagaType {
+        self.push_type(NagaType {
             name: Some("Float".to_string()),
             inner: TypeInner::Scalar {
                 kind: ScalarKind::Float,
@@ -171,105 +242,137 @@ impl<'a> NagaBuilder<'a> {
             },
         });
 
-        for def in &self.input.struct_definitions {
-            let ty = match &def.id {
-                v if **v == VECTOR2 || **v == VECTOR3 || **v == VECTOR4 => NagaType {
-                    name: Some(def.id.name().to_string()),
-                    inner: TypeInner::Vector {
-                        size: match &def.id {
-                            d if **d == VECTOR2 => VectorSize::Bi,
-                            d if **d == VECTOR3 => VectorSize::Tri,
-                            d if **d == VECTOR4 => VectorSize::Quad,
-                            _ => unreachable!(),
-                        },
-                        kind: ScalarKind::Float,
-                        width: 4,
+        // Struct definitions may reference structs defined after them,
+        // so lower them in dependency order
+        let mut pending: Vec<_> = self.input.struct_definitions.iter().collect();
+        while !pending.is_empty() {
+            let mut deferred = vec![];
+            for def in pending.iter().copied() {
+                if self.missing_dependency(def)?.is_some() {
+                    deferred.push(def);
+                    continue;
+                }
+
+                self.struct_to_naga(def)?;
+            }
+
+            if deferred.len() == pending.len() {
+                let missing = self.missing_dependency(deferred[0])?.unwrap();
+                return Err(self.error(NagaBuildErrorKind::MissingType(missing)));
+            }
+
+            pending = deferred;
+        }
+
+        Ok(())
+    }
+
+    /// Name of the first struct referenced by `def` that has not yet been lowered
+    fn missing_dependency(&self, def: &StructDefinition) -> Result<Option<String>, NagaBuildError> {
+        for field in def.fields.iter() {
+            if let ElysianType::Struct(s) = self.get_input_type(&field.id)? {
+                if self.get_type(s.name()).is_err() {
+                    return Ok(Some(s.name().to_string()));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn struct_to_naga(&mut self, def: &StructDefinition) -> Result<(), NagaBuildError> {
+        #[cfg(feature = "print")]
+        println!("struct_to_naga");
+
+        let ty = match &def.id {
+            v if **v == VECTOR2 || **v == VECTOR3 || **v == VECTOR4 => NagaType {
+                name: Some(def.id.name().to_string()),
+                inner: TypeInner::Vector {
+                    size: match &def.id {
+                        d if **d == VECTOR2 => VectorSize::Bi,
+                        d if **d == VECTOR3 => VectorSize::Tri,
+                        d if **d == VECTOR4 => VectorSize::Quad,
+                        _ => unreachable!(),
                     },
+                    kind: ScalarKind::Float,
+                    width: 4,
                 },
-                m if **m == MATRIX2 || **m == MATRIX3 || **m == MATRIX4 => NagaType {
-                    name: Some(def.id.name().to_string()),
-                    inner: TypeInner::Matrix {
-                        columns: match &def.id {
-                            d if **d == MATRIX2 => VectorSize::Bi,
-                            d if **d == MATRIX3 => VectorSize::Tri,
-                            d if **d == MATRIX4 => VectorSize::Quad,
-                            _ => unreachable!(),
-                        },
-                        rows: match &def.id {
-                            d if **d == MATRIX2 => VectorSize::Bi,
-                            d if **d == MATRIX3 => VectorSize::Tri,
-                            d if **d == MATRIX4 => VectorSize::Quad,
-                            _ => unreachable!(),
-                        },
-                        width: 4,
+            },
+            m if **m == MATRIX2 || **m == MATRIX3 || **m == MATRIX4 => NagaType {
+                name: Some(def.id.name().to_string()),
+                inner: TypeInner::Matrix {
+                    columns: match &def.id {
+                        d if **d == MATRIX2 => VectorSize::Bi,
+                        d if **d == MATRIX3 => VectorSize::Tri,
+                        d if **d == MATRIX4 => VectorSize::Quad,
+                        _ => unreachable!(),
+                    },
+                    rows: match &def.id {
+                        d if **d == MATRIX2 => VectorSize::Bi,
+                        d if **d == MATRIX3 => VectorSize::Tri,
+                        d if **d == MATRIX4 => VectorSize::Quad,
+                        _ => unreachable!(),
                     },
+                    width: 4,
                 },
-                _ => {
-                    let (members, span) =
-                        def.fields
-                            .iter()
-                            .fold((vec![], 0), |(mut members, total_span), next| {
-                                let (member, span) = match self.get_input_type(&next.id) {
-                                    ElysianType::Boolean => (bool, 1),
-                                    ElysianType::Number(n) => match n {
-                                        NumericType::UInt => (uint, 4),
-                                        NumericType::SInt => (sint, 4),
-                                        NumericType::Float => (float, 4),
-                                    },
-                                    ElysianType::Struct(s) => {
-                                        let (handle, ty) = self.get_type(s.name());
-
-                                        let span = match ty.inner {
-                                            TypeInner::Scalar { width, .. } => width as u32,
-                                            TypeInner::Vector { width, size, .. } => {
-                                                width as u32
-                                                    * match size {
-                                                        VectorSize::Bi => 2,
-                                                        VectorSize::Tri => 3,
-                                                        VectorSize::Quad => 4,
-                                                    }
-                                            }
-                                            TypeInner::Matrix {
-                                                columns,
-                                                rows,
-                                                width,
-                                            } => {
-                                                width as u32
-                                                    * match columns {
-                                                        VectorSize::Bi => 2,
-                                                        VectorSize::Tri => 3,
-                                                        VectorSize::Quad => 4,
-                                                    }
-                                                    * match rows {
-                                                        VectorSize::Bi => 2,
-                                                        VectorSize::Tri => 3,
-                                                        VectorSize::Quad => 4,
-                                                    }
-                                            }
-                                            TypeInner::Struct { span, .. } => span,
-                                            _ => panic!("Invalid Type"),
-                                        };
-
-                                        (handle, span)
-                                    }
-                                };
-                                members.push(StructMember {
-                                    name: Some(next.id.name().to_string()),
-                                    ty: member,
-                                    binding: None,
-                                    offset: total_span,
-                                });
-                                (members, total_span + span)
-                            });
-
-                    NagaType {
-                        name: Some(def.name().to_string()),
-                        inner: TypeInner::Struct { members, span },
-                    }
+            },
+            _ => {
+                let mut members = vec![];
+                let mut span = 0;
+                for next in def.fields.iter() {
+                    let (member, member_span) = match self.get_input_type(&next.id)? {
+                        ElysianType::Boolean => (self.get_type("Bool")?.0, 1),
+                        ElysianType::Number(n) => match n {
+                            NumericType::UInt => (self.get_type("UInt")?.0, 4),
+                            NumericType::SInt => (self.get_type("SInt")?.0, 4),
+                            NumericType::Float => (self.get_type("Float")?.0, 4),
+                        },
+                        ElysianType::Struct(s) => {
+                            let handle = self.get_type(s.name())?.0;
+                            (handle, self.type_span(handle))
+                        }
+                    };
+                    members.push(StructMember {
+                        name: Some(next.id.name().to_string()),
+                        ty: member,
+                        binding: None,
+                        offset: span,
+                    });
+                    span += member_span;
                 }
-            };
 
-            self.types.insert(ty, Span::UNDEFINED);
+                NagaType {
+                    name: Some(def.name().to_string()),
+                    inner: TypeInner::Struct { members, span },
+                }
+            }
+        };
+
+        self.push_type(ty);
+
+        Ok(())
+    }
+
+    fn type_span(&self, ty: Handle<NagaType>) -> u32 {
+        #[cfg(feature = "print")]
+        println!("type_span");
+
+        let size = |size: VectorSize| match size {
+            VectorSize::Bi => 2,
+            VectorSize::Tri => 3,
+            VectorSize::Quad => 4,
+        };
+
+        match self.types[ty].inner {
+            TypeInner::Scalar { width, .. } => width as u32,
+            TypeInner::Vector { width, size: s, .. } => width as u32 * size(s),
+            TypeInner::Matrix {
+                columns,
+                rows,
+                width,
+            } => width as u32 * size(columns) * size(rows),
+            TypeInner::Struct { span, .. } => span,
+            _ => panic!("Invalid Type"),
         }
     }
 
@@ -334,6 +437,32 @@ impl<'a> NagaBuilder<'a> {
         self.body_mut().push(stmt, Span::UNDEFINED);
     }
 
+    /// Floating-point `(x % y + abs(y)) % abs(y)`, which never yields a negative result
+    fn rem_euclid(&mut self, x: Handle<Expression>, y: Handle<Expression>) -> Handle<Expression> {
+        let modulo = |this: &mut Self, left, right| {
+            this.push_expression(Expression::Binary {
+                op: BinaryOperator::Modulo,
+                left,
+                right,
+            })
+        };
+
+        let abs = self.push_expression(Expression::Math {
+            fun: MathFunction::Abs,
+            arg: y,
+            arg1: None,
+            arg2: None,
+            arg3: None,
+        });
+        let rem = modulo(self, x, y);
+        let sum = self.push_expression(Expression::Binary {
+            op: BinaryOperator::Add,
+            left: rem,
+            right: abs,
+        });
+        modulo(self, sum, abs)
+    }
+
     fn push_expression(&mut self, expr: Expression) -> Handle<Expression> {
         #[cfg(feature = "print")]
         println!("push_expression");
@@ -341,6 +470,7 @@ impl<'a> NagaBuilder<'a> {
         let push = match &expr {
             Expression::LocalVariable { .. } => false,
             Expression::FunctionArgument { .. } => false,
+            Expression::GlobalVariable { .. } => false,
             Expression::CallResult { .. } => false,
             Expression::Literal { .. } => false,
             _ => true,
@@ -388,16 +518,30 @@ impl<'a> NagaBuilder<'a> {
         (local, pointer)
     }
 
-    fn get_input_type(&self, id: &PropertyIdentifier) -> &elysian_ir::module::Type {
+    fn get_input_type(
+        &self,
+        id: &PropertyIdentifier,
+    ) -> Result<&'static ElysianType, NagaBuildError> {
         #[cfg(feature = "print")]
         println!("get_input_type");
 
         properties()
             .get(id)
-            .unwrap_or_else(|| panic!("No input type for {}", id.name()))
+            .ok_or_else(|| self.error(NagaBuildErrorKind::MissingProperty(id.name().to_string())))
     }
 
-    fn functions_to_naga(&mut self) {
+    /// Look up the `naga` type corresponding to a property
+    fn get_property_type(
+        &self,
+        id: &PropertyIdentifier,
+    ) -> Result<(Handle<NagaType>, &NagaType), NagaBuildError> {
+        #[cfg(feature = "print")]
+        println!("get_property_type");
+
+        self.get_type(self.get_input_type(id)?.name())
+    }
+
+    fn functions_to_naga(&mut self) -> Result<(), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("functions_to_naga");
 
@@ -431,49 +575,57 @@ impl<'a> NagaBuilder<'a> {
             },
         ];
 
-        let handles = builtins
+        let mut handles = IndexMap::new();
+        for def in builtins
             .iter()
             .chain(self.input.function_definitions.iter())
-            .map(|def| {
-                (
-                    self.functions.append(
-                        Function {
-                            name: Some(def.name_unique()),
-                            arguments: def
-                                .inputs
-                                .iter()
-                                .map(|input| FunctionArgument {
-                                    name: Some(input.id.name().to_string()),
-                                    ty: self.get_type(self.get_input_type(&input.id).name()).0,
-                                    binding: None,
-                                })
-                                .collect(),
-                            result: Some(FunctionResult {
-                                ty: self.get_type(def.output.name()).0,
-                                binding: None,
-                            }),
-                            local_variables: Default::default(),
-                            expressions: Default::default(),
-                            named_expressions: Default::default(),
-                            body: Default::default(),
-                        },
-                        Span::UNDEFINED,
-                    ),
-                    def,
-                )
-            })
-            .collect::<IndexMap<_, _>>();
+        {
+            self.function = Some(def.clone());
+
+            let arguments = def
+                .inputs
+                .iter()
+                .map(|input| {
+                    Ok(FunctionArgument {
+                        name: Some(input.id.name().to_string()),
+                        ty: self.get_property_type(&input.id)?.0,
+                        binding: None,
+                    })
+                })
+                .collect::<Result<_, NagaBuildError>>()?;
+
+            let result = Some(FunctionResult {
+                ty: self.get_property_type(&def.output)?.0,
+                binding: None,
+            });
+
+            let handle = self.functions.append(
+                Function {
+                    name: Some(def.name_unique()),
+                    arguments,
+                    result,
+                    local_variables: Default::default(),
+                    expressions: Default::default(),
+                    named_expressions: Default::default(),
+                    body: Default::default(),
+                },
+                Span::UNDEFINED,
+            );
+
+            handles.insert(handle, def);
+        }
 
         for (handle, def) in handles {
             self.block_stack.push(NagaBlock::new());
 
             self.expressions = Some(ExpressionQueue::default());
             self.local_variables = Some(LocalVariableStore::default());
+            self.function = Some(def.clone());
 
             for (i, input_def) in def.inputs.iter().enumerate() {
                 let (_, local_ptr) = self.push_local_variable(LocalVariable {
                     name: Some(input_def.id.name().to_string()),
-                    ty: self.get_type(self.get_input_type(&input_def.id).name()).0,
+                    ty: self.get_property_type(&input_def.id)?.0,
                     init: None,
                 });
 
@@ -485,9 +637,8 @@ impl<'a> NagaBuilder<'a> {
                 });
             }
 
-            self.function = Some(def.clone());
             for stmt in def.block.0.iter() {
-                self.stmt_to_naga(stmt);
+                self.stmt_to_naga(stmt)?;
             }
             self.function = None;
 
@@ -496,6 +647,8 @@ impl<'a> NagaBuilder<'a> {
             f.local_variables = self.local_variables.take().unwrap().locals;
             f.body = self.block_stack.pop().unwrap();
         }
+
+        Ok(())
     }
 
     fn access_index(
@@ -503,48 +656,55 @@ impl<'a> NagaBuilder<'a> {
         base: Handle<Expression>,
         prev: &PropertyIdentifier,
         next: &PropertyIdentifier,
-    ) -> Expression {
+    ) -> Result<Expression, NagaBuildError> {
         #[cfg(feature = "print")]
         println!("access_index");
 
-        Expression::AccessIndex {
+        let ElysianType::Struct(s) = self.get_input_type(prev)? else {
+            return Err(self.error(NagaBuildErrorKind::NotAStruct(prev.name().to_string())));
+        };
+
+        let index = self
+            .get_struct_definition(s)?
+            .fields
+            .iter()
+            .position(|field| field.id == *next)
+            .ok_or_else(|| {
+                self.error(NagaBuildErrorKind::MissingField {
+                    field: next.name().to_string(),
+                    ty: s.name().to_string(),
+                })
+            })?;
+
+        Ok(Expression::AccessIndex {
             base,
-            index: match self.get_input_type(prev) {
-                ElysianType::Struct(s) => self
-                    .input
-                    .struct_definitions
-                    .iter()
-                    .find(|cand| cand.id == *s)
-                    .unwrap_or_else(|| panic!("No struct definition for {}", prev.name()))
-                    .fields
-                    .iter()
-                    .position(|field| field.id == *next)
-                    .unwrap_or_else(|| panic!("No field {next:#?} for struct {s:#?}"))
-                    as u32,
-                t => panic!("Not a struct: {t:#?}"),
-            },
-        }
+            index: index as u32,
+        })
     }
 
-    fn stmt_to_naga(&mut self, stmt: &Stmt) {
+    fn stmt_to_naga(&mut self, stmt: &Stmt) -> Result<(), NagaBuildError> {
         #[cfg(feature = "print")]
         println!("stmt_to_naga");
 
         match stmt {
-            Stmt::Block(block) => block.0.iter().for_each(|t| self.stmt_to_naga(t)),
+            Stmt::Block(block) => {
+                for stmt in block.0.iter() {
+                    self.stmt_to_naga(stmt)?;
+                }
+            }
             Stmt::Bind { prop, expr } => {
                 let local_ptr = if let Some(k) = self.get_pointer(prop.name()) {
-                    k.clone()
+                    *k
                 } else {
                     let (_, local_ptr) = self.push_local_variable(naga::LocalVariable {
                         name: Some(prop.name().to_string()),
-                        ty: self.get_type(self.get_input_type(prop).name()).0,
+                        ty: self.get_property_type(prop)?.0,
                         init: None,
                     });
                     local_ptr
                 };
 
-                let value = self.expr_to_naga(expr);
+                let value = self.expr_to_naga(expr)?;
 
                 self.push_statement(Statement::Store {
                     pointer: local_ptr,
@@ -554,21 +714,23 @@ impl<'a> NagaBuilder<'a> {
             Stmt::Write { path, expr } => {
                 let mut iter = path.iter();
 
-                let base = iter.next().unwrap();
-
-                let base_expr = if let Some(base_expr) = self.get_pointer(base.name()) {
-                    base_expr.clone()
-                } else {
-                    panic!("Invalid write")
+                let Some(base) = iter.next() else {
+                    return Err(self.error(NagaBuildErrorKind::InvalidWrite(String::default())));
                 };
 
-                let (_, pointer) = iter.fold((base.clone(), base_expr), |(prev, expr), next| {
-                    let expr = self.push_expression(self.access_index(expr, &prev, next));
+                let Some(mut pointer) = self.get_pointer(base.name()).copied() else {
+                    return Err(
+                        self.error(NagaBuildErrorKind::InvalidWrite(base.name().to_string()))
+                    );
+                };
 
-                    (next.clone(), expr)
-                });
+                let mut prev = base;
+                for next in iter {
+                    pointer = self.push_expression(self.access_index(pointer, prev, next)?);
+                    prev = next;
+                }
 
-                let value = self.expr_to_naga(expr);
+                let value = self.expr_to_naga(expr)?;
 
                 self.push_statement(Statement::Store { pointer, value })
             }
@@ -577,16 +739,16 @@ impl<'a> NagaBuilder<'a> {
                 then,
                 otherwise,
             } => {
-                let condition = self.expr_to_naga(cond);
+                let condition = self.expr_to_naga(cond)?;
                 self.flush_expressions();
 
                 self.block_stack.push(NagaBlock::default());
-                self.stmt_to_naga(then);
+                self.stmt_to_naga(then)?;
                 let accept = self.block_stack.pop().unwrap();
 
                 self.block_stack.push(NagaBlock::default());
                 if let Some(otherwise) = otherwise {
-                    self.stmt_to_naga(otherwise)
+                    self.stmt_to_naga(otherwise)?
                 };
                 let reject = self.block_stack.pop().unwrap();
 
@@ -598,7 +760,7 @@ impl<'a> NagaBuilder<'a> {
             }
             Stmt::Loop { stmt } => {
                 self.block_stack.push(NagaBlock::default());
-                self.stmt_to_naga(stmt);
+                self.stmt_to_naga(stmt)?;
                 let loop_body = self.block_stack.pop().unwrap();
 
                 self.push_statement(Statement::Loop {
@@ -609,17 +771,19 @@ impl<'a> NagaBuilder<'a> {
             }
             Stmt::Break => self.push_statement(Statement::Break),
             Stmt::Output(expr) => {
-                let value = self.expr_to_naga(expr);
+                let value = self.expr_to_naga(expr)?;
                 self.push_statement(Statement::Return { value: Some(value) });
             }
         }
+
+        Ok(())
     }
 
-    fn naga_default(&self, ty: &ElysianType) -> Value {
+    fn naga_default(&self, ty: &ElysianType) -> Result<Value, NagaBuildError> {
         #[cfg(feature = "print")]
         println!("naga_default");
 
-        match ty {
+        Ok(match ty {
             ElysianType::Boolean => Value::Boolean(false),
             ElysianType::Number(n) => match n {
                 NumericType::UInt => Value::Number(Number::UInt(0)),
@@ -628,76 +792,64 @@ impl<'a> NagaBuilder<'a> {
             },
             ElysianType::Struct(s) => {
                 let mut out = Struct::new(s.clone());
-                for field in self
-                    .input
-                    .struct_definitions
-                    .iter()
-                    .find(|cand| cand.id == *s)
-                    .unwrap()
-                    .fields
-                    .iter()
-                {
+                for field in self.get_struct_definition(s)?.fields.iter() {
                     out.set_mut(
                         field.id.clone(),
-                        self.naga_default(self.get_input_type(&field.id)),
+                        self.naga_default(self.get_input_type(&field.id)?)?,
                     );
                 }
                 Value::Struct(out)
             }
-        }
+        })
     }
 
-    fn expr_to_naga(&mut self, expr: &Expr) -> Handle<Expression> {
+    fn expr_to_naga(&mut self, expr: &Expr) -> Result<Handle<Expression>, NagaBuildError> {
+        self.expr_to_naga_impl(expr).map_err(|e| e.with_expr(expr))
+    }
+
+    fn expr_to_naga_impl(&mut self, expr: &Expr) -> Result<Handle<Expression>, NagaBuildError> {
         #[cfg(feature = "print")]
         println!("expr_to_naga");
 
-        match expr {
-            Expr::Literal(v) => self.value_to_naga(v),
+        Ok(match expr {
+            Expr::Literal(v) => self.value_to_naga(v)?,
             Expr::Struct(def, members) => {
-                let components = self
-                    .input
-                    .struct_definitions
-                    .iter()
-                    .find(|cand| cand.id == *def)
-                    .unwrap()
-                    .fields
-                    .iter()
-                    .map(|field| -> Expr {
-                        if let Some(member) = members.get(&field.id) {
-                            member.clone()
-                        } else {
-                            Expr::Literal(self.naga_default(&self.get_input_type(&field.id)))
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .into_iter()
-                    .map(|member| self.expr_to_naga(&member))
-                    .collect();
+                let mut components = vec![];
+                for field in self.get_struct_definition(def)?.fields.iter() {
+                    let component = if let Some(member) = members.get(&field.id) {
+                        self.expr_to_naga(member)?
+                    } else {
+                        let value = self.naga_default(self.get_input_type(&field.id)?)?;
+                        self.value_to_naga(&value)?
+                    };
+                    components.push(component);
+                }
 
-                let expr = self.push_expression(Expression::Compose {
-                    ty: self.get_type(def.name()).0,
+                self.push_expression(Expression::Compose {
+                    ty: self.get_type(def.name())?.0,
                     components,
-                });
-
-                expr
+                })
             }
             Expr::Read(path) => {
                 let mut iter = path.iter();
 
-                let base = iter.next().unwrap();
+                let Some(base) = iter.next() else {
+                    return Err(self.error(NagaBuildErrorKind::InvalidRead(String::default())));
+                };
 
-                let base_expr = if let Some(pointer) = self.get_pointer(base.name()) {
-                    self.push_expression(Expression::Load {
-                        pointer: pointer.clone(),
-                    })
-                } else {
-                    panic!("Invalid Read")
+                let Some(pointer) = self.get_pointer(base.name()).copied() else {
+                    return Err(
+                        self.error(NagaBuildErrorKind::InvalidRead(base.name().to_string()))
+                    );
                 };
 
-                let (_, expr) = iter.fold((base.clone(), base_expr), |(prev, expr), next| {
-                    let access = self.push_expression(self.access_index(expr, &prev, next));
-                    (next.clone(), access)
-                });
+                let mut expr = self.push_expression(Expression::Load { pointer });
+
+                let mut prev = base;
+                for next in iter {
+                    expr = self.push_expression(self.access_index(expr, prev, next)?);
+                    prev = next;
+                }
 
                 expr
             }
@@ -705,9 +857,12 @@ impl<'a> NagaBuilder<'a> {
                 function: func,
                 args,
             } => {
-                let f = self.get_function(&func.name_unique()).0;
+                let f = self.get_function(&func.name_unique())?.0;
 
-                let arguments = args.into_iter().map(|arg| self.expr_to_naga(arg)).collect();
+                let arguments = args
+                    .iter()
+                    .map(|arg| self.expr_to_naga(arg))
+                    .collect::<Result<_, _>>()?;
 
                 let expr = self.push_expression(Expression::CallResult(f));
 
@@ -720,14 +875,12 @@ impl<'a> NagaBuilder<'a> {
                 expr
             }
             Expr::Neg(t) => {
-                let expr = self.expr_to_naga(t);
+                let expr = self.expr_to_naga(t)?;
 
-                let expr = self.push_expression(Expression::Unary {
+                self.push_expression(Expression::Unary {
                     op: naga::UnaryOperator::Negate,
                     expr,
-                });
-
-                expr
+                })
             }
             Expr::Add(lhs, rhs)
             | Expr::Sub(lhs, rhs)
@@ -743,41 +896,37 @@ impl<'a> NagaBuilder<'a> {
                 let type_l = lhs.ty(&self.input.function_definitions);
                 let type_r = rhs.ty(&self.input.function_definitions);
 
-                let invalid = |a, b| format!("Invalid Binary Op {}, {}", a, b);
+                let invalid = |a: &str, b: &str| {
+                    Err(NagaBuildErrorKind::InvalidBinaryOp {
+                        lhs: a.to_string(),
+                        rhs: b.to_string(),
+                    })
+                };
 
-                let (lhs, rhs) = match (type_l, type_r) {
-                    (ElysianType::Boolean, ElysianType::Boolean) => (lhs, rhs),
+                let valid = match (&type_l, &type_r) {
+                    (ElysianType::Boolean, ElysianType::Boolean) => Ok(()),
                     (ElysianType::Number(a), ElysianType::Number(b)) => {
                         if a == b {
-                            (lhs, rhs)
+                            Ok(())
                         } else {
-                            panic!("{}", invalid(a.name(), b.name()))
+                            invalid(a.name(), b.name())
                         }
                     }
                     (ElysianType::Struct(s), ElysianType::Number(n)) => match expr {
                         Expr::Mul(_, _) => match s.name() {
                             "Vector2" | "Vector3" | "Vector4" | "Matrix2" | "Matrix3"
-                            | "Matrix4" => (lhs, rhs),
-                            _ => {
-                                panic!("{}", invalid(n.name(), s.name()))
-                            }
+                            | "Matrix4" => Ok(()),
+                            _ => invalid(n.name(), s.name()),
                         },
-                        op => {
-                            panic!("{op:#?} {}", invalid(s.name(), n.name()))
-                        }
+                        _ => invalid(s.name(), n.name()),
                     },
                     (ElysianType::Number(n), ElysianType::Struct(s)) => match expr {
                         Expr::Mul(_, _) => match s.name() {
                             "Vector2" | "Vector3" | "Vector4" | "Matrix2" | "Matrix3"
-                            | "Matrix4" => (lhs, rhs),
-                            _ => {
-                                panic!("{}", invalid(n.name(), s.name()))
-                            }
+                            | "Matrix4" => Ok(()),
+                            _ => invalid(n.name(), s.name()),
                         },
-                        op => {
-                            eprintln!("{op:#?}");
-                            panic!("{}", invalid(n.name(), s.name()))
-                        }
+                        _ => invalid(n.name(), s.name()),
                     },
                     (ElysianType::Struct(a), ElysianType::Struct(b)) => match expr {
                         Expr::Add(_, _) => match (a.name(), b.name()) {
@@ -786,8 +935,8 @@ impl<'a> NagaBuilder<'a> {
                             | ("Vector4", "Vector4")
                             | ("Matrix2", "Matrix2")
                             | ("Matrix3", "Matrix3")
-                            | ("Matrix4", "Matrix4") => (lhs, rhs),
-                            _ => panic!("{}", invalid(a.name(), b.name())),
+                            | ("Matrix4", "Matrix4") => Ok(()),
+                            _ => invalid(a.name(), b.name()),
                         },
                         Expr::Sub(_, _) => match (a.name(), b.name()) {
                             ("Vector2", "Vector2")
@@ -795,8 +944,8 @@ impl<'a> NagaBuilder<'a> {
                             | ("Vector4", "Vector4")
                             | ("Matrix2", "Matrix2")
                             | ("Matrix3", "Matrix3")
-                            | ("Matrix4", "Matrix4") => (lhs, rhs),
-                            _ => panic!("{}", invalid(a.name(), b.name())),
+                            | ("Matrix4", "Matrix4") => Ok(()),
+                            _ => invalid(a.name(), b.name()),
                         },
                         Expr::Mul(_, _) => match (a.name(), b.name()) {
                             ("Vector2", "Vector2")
@@ -810,35 +959,47 @@ impl<'a> NagaBuilder<'a> {
                             | ("Vector4", "Matrix4")
                             | ("Matrix2", "Vector2")
                             | ("Matrix3", "Vector3")
-                            | ("Matrix4", "Vector4") => (lhs, rhs),
-                            _ => panic!("{}", invalid(a.name(), b.name())),
+                            | ("Matrix4", "Vector4") => Ok(()),
+                            _ => invalid(a.name(), b.name()),
                         },
                         Expr::Div(_, _) => match (a.name(), b.name()) {
                             ("Vector2", "Vector2")
                             | ("Vector3", "Vector3")
-                            | ("Vector4", "Vector4") => (lhs, rhs),
-                            _ => panic!("{}", invalid(a.name(), b.name())),
+                            | ("Vector4", "Vector4") => Ok(()),
+                            _ => invalid(a.name(), b.name()),
                         },
                         Expr::Mod(_, _) => match (a.name(), b.name()) {
                             ("Vector2", "Vector2")
                             | ("Vector3", "Vector3")
-                            | ("Vector4", "Vector4") => (lhs, rhs),
-                            _ => panic!("{}", invalid(a.name(), b.name())),
+                            | ("Vector4", "Vector4") => Ok(()),
+                            _ => invalid(a.name(), b.name()),
                         },
                         Expr::Eq(_, _) | Expr::Ne(_, _) | Expr::Lt(_, _) | Expr::Gt(_, _) => {
                             if a == b {
-                                (lhs, rhs)
+                                Ok(())
                             } else {
-                                panic!("{}", invalid(a.name(), b.name()))
+                                invalid(a.name(), b.name())
                             }
                         }
-                        _ => unreachable!(),
+                        _ => invalid(a.name(), b.name()),
                     },
-                    _ => panic!("Invalid Binary Op"),
+                    (a, b) => invalid(a.name(), b.name()),
                 };
 
-                let left = self.expr_to_naga(lhs);
-                let right = self.expr_to_naga(rhs);
+                valid.map_err(|kind| self.error(kind))?;
+
+                let left = self.expr_to_naga(lhs)?;
+                let right = self.expr_to_naga(rhs)?;
+
+                // WGSL's % truncates, where Expr::Mod is a Euclidean remainder
+                if matches!(expr, Expr::Mod(..))
+                    && !matches!(
+                        type_l,
+                        ElysianType::Number(NumericType::UInt | NumericType::SInt)
+                    )
+                {
+                    return Ok(self.rem_euclid(left, right));
+                }
 
                 self.push_expression(Expression::Binary {
                     op: match expr {
@@ -863,9 +1024,9 @@ impl<'a> NagaBuilder<'a> {
             | Expr::Max(lhs, rhs)
             | Expr::Dot(lhs, rhs)
             | Expr::Atan2(lhs, rhs) => {
-                let arg = self.expr_to_naga(lhs);
-                let arg1 = self.expr_to_naga(rhs);
-                let expr = self.push_expression(Expression::Math {
+                let arg = self.expr_to_naga(lhs)?;
+                let arg1 = self.expr_to_naga(rhs)?;
+                self.push_expression(Expression::Math {
                     fun: match expr {
                         Expr::Min(..) => MathFunction::Min,
                         Expr::Max(..) => MathFunction::Max,
@@ -877,9 +1038,7 @@ impl<'a> NagaBuilder<'a> {
                     arg1: Some(arg1),
                     arg2: None,
                     arg3: None,
-                });
-
-                expr
+                })
             }
             Expr::Abs(t)
             | Expr::Sign(t)
@@ -890,8 +1049,10 @@ impl<'a> NagaBuilder<'a> {
             | Expr::Tan(t)
             | Expr::Asin(t)
             | Expr::Acos(t)
-            | Expr::Atan(t) => {
-                let arg = self.expr_to_naga(t);
+            | Expr::Atan(t)
+            | Expr::Exp2(t)
+            | Expr::Log2(t) => {
+                let arg = self.expr_to_naga(t)?;
 
                 self.push_expression(Expression::Math {
                     fun: match expr {
@@ -906,6 +1067,8 @@ impl<'a> NagaBuilder<'a> {
                         Expr::Asin(..) => MathFunction::Asin,
                         Expr::Acos(..) => MathFunction::Acos,
                         Expr::Atan(..) => MathFunction::Atan,
+                        Expr::Exp2(..) => MathFunction::Exp2,
+                        Expr::Log2(..) => MathFunction::Log2,
                         _ => unreachable!(),
                     },
                     arg,
@@ -915,12 +1078,12 @@ impl<'a> NagaBuilder<'a> {
                 })
             }
             Expr::Normalize(t) => {
-                let arg = self.expr_to_naga(t);
+                let arg = self.expr_to_naga(t)?;
 
-                let function_id = &self.function.as_ref().unwrap().id;
-                if *function_id == SAFE_NORMALIZE_2
-                    || *function_id == SAFE_NORMALIZE_3
-                    || *function_id == SAFE_NORMALIZE_4
+                let function_id = self.function.as_ref().map(|function| &function.id);
+                if function_id == Some(&SAFE_NORMALIZE_2)
+                    || function_id == Some(&SAFE_NORMALIZE_3)
+                    || function_id == Some(&SAFE_NORMALIZE_4)
                 {
                     self.push_expression(Expression::Math {
                         fun: MathFunction::Normalize,
@@ -932,12 +1095,18 @@ impl<'a> NagaBuilder<'a> {
                 } else {
                     let function = match t.ty(&self.input.function_definitions) {
                         ElysianType::Struct(s) => match s.name() {
-                            "Vector2" => self.get_function(&SAFE_NORMALIZE_2.name_unique()),
-                            "Vector3" => self.get_function(&SAFE_NORMALIZE_3.name_unique()),
-                            "Vector4" => self.get_function(&SAFE_NORMALIZE_4.name_unique()),
-                            _ => panic!("Invalid Normalize"),
+                            "Vector2" => self.get_function(&SAFE_NORMALIZE_2.name_unique())?,
+                            "Vector3" => self.get_function(&SAFE_NORMALIZE_3.name_unique())?,
+                            "Vector4" => self.get_function(&SAFE_NORMALIZE_4.name_unique())?,
+                            name => {
+                                return Err(self
+                                    .error(NagaBuildErrorKind::InvalidNormalize(name.to_string())))
+                            }
                         },
-                        _ => panic!("Invalid Normalize"),
+                        t => {
+                            return Err(self
+                                .error(NagaBuildErrorKind::InvalidNormalize(t.name().to_string())))
+                        }
                     }
                     .0;
 
@@ -953,36 +1122,32 @@ impl<'a> NagaBuilder<'a> {
                 }
             }
             Expr::Mix(lhs, rhs, t) => {
-                let arg = self.expr_to_naga(lhs);
-                let arg1 = self.expr_to_naga(rhs);
-                let arg2 = self.expr_to_naga(t);
+                let arg = self.expr_to_naga(lhs)?;
+                let arg1 = self.expr_to_naga(rhs)?;
+                let arg2 = self.expr_to_naga(t)?;
 
-                let expr = self.push_expression(Expression::Math {
+                self.push_expression(Expression::Math {
                     fun: MathFunction::Mix,
                     arg,
                     arg1: Some(arg1),
                     arg2: Some(arg2),
                     arg3: None,
-                });
-
-                expr
+                })
             }
             Expr::Clamp(t, min, max) => {
-                let arg = self.expr_to_naga(t);
-                let arg1 = self.expr_to_naga(min);
-                let arg2 = self.expr_to_naga(max);
+                let arg = self.expr_to_naga(t)?;
+                let arg1 = self.expr_to_naga(min)?;
+                let arg2 = self.expr_to_naga(max)?;
 
-                let expr = self.push_expression(Expression::Math {
+                self.push_expression(Expression::Math {
                     fun: MathFunction::Clamp,
                     arg,
                     arg1: Some(arg1),
                     arg2: Some(arg2),
                     arg3: None,
-                });
-
-                expr
+                })
             }
-        }
+        })
     }
 
     fn number_to_naga(number: &Number) -> Expression {
@@ -996,233 +1161,238 @@ impl<'a> NagaBuilder<'a> {
         }
     }
 
-    fn value_to_naga(&mut self, value: &Value) -> Handle<Expression> {
+    fn value_to_naga(&mut self, value: &Value) -> Result<Handle<Expression>, NagaBuildError> {
         #[cfg(feature = "print")]
         println!("value_to_naga");
 
-        match value {
+        Ok(match value {
             Value::Boolean(b) => self.push_expression(Expression::Literal(Literal::Bool(*b))),
             Value::Number(n) => self.push_expression(Self::number_to_naga(n)),
             Value::Struct(s) => {
-                let ty = self.get_type(s.id.name()).0;
+                let ty = self.get_type(s.id.name())?.0;
 
                 let mut components = vec![];
 
-                for field in self
-                    .input
-                    .struct_definitions
-                    .iter()
-                    .find(|cand| cand.id == s.id)
-                    .unwrap_or_else(|| panic!("No struct definition for {}", s.id.name()))
-                    .fields
-                    .iter()
-                {
+                for field in self.get_struct_definition(&s.id)?.fields.iter() {
                     let v = s.get(&field.id);
-                    let v = self.value_to_naga(&v);
+                    let v = self.value_to_naga(&v)?;
                     components.push(v);
                 }
 
-                let expr = self.push_expression(Expression::Compose { ty, components });
-
-                expr
+                self.push_expression(Expression::Compose { ty, components })
             }
-        }
+        })
     }
 
-    fn shadertoy_entry_point(&mut self) -> EntryPoint {
+    fn entry_point_to_naga(
+        &mut self,
+        def: &EntryPointDefinition,
+    ) -> Result<EntryPoint, NagaBuildError> {
         #[cfg(feature = "print")]
-        println!("shadertoy_entry_point");
+        println!("entry_point_to_naga");
 
-        let context_struct = self
+        let Some(function_def) = self
             .input
-            .struct_definitions
+            .function_definitions
             .iter()
-            .find(|cand| *cand.id == CONTEXT)
-            .unwrap();
+            .find(|cand| cand.id == def.function)
+        else {
+            return Err(NagaBuildError {
+                function: Some(def.function.clone()),
+                ..NagaBuildError::new(NagaBuildErrorKind::MissingFunction(
+                    def.function.name_unique(),
+                ))
+            });
+        };
+
+        // Attribute errors to the wrapped function
+        self.function = Some(function_def.clone());
+
+        if function_def.inputs.len() != def.inputs.len() {
+            return Err(self.error(NagaBuildErrorKind::InvalidEntryPoint(format!(
+                "{} must bind every input of {}",
+                def.name,
+                def.function.name()
+            ))));
+        }
 
         self.block_stack.push(NagaBlock::new());
         self.expressions = Some(ExpressionQueue::default());
         self.local_variables = Some(LocalVariableStore::default());
 
-        let (_, frag_color_ptr) = self.push_local_variable(LocalVariable {
-            name: Some("fragColor".to_string()),
-            ty: self.get_type("Vector4").0,
-            init: None,
-        });
-
-        let (_, context_ptr) = self.push_local_variable(LocalVariable {
-            name: Some("context".to_string()),
-            ty: self.get_type("Context").0,
-            init: None,
-        });
-
-        let frag_coord_arg = self.push_expression(Expression::FunctionArgument(0));
-        let resolution_arg = self.push_expression(Expression::FunctionArgument(2));
-
-        let resolution_xy = self.push_expression(Expression::Swizzle {
-            size: VectorSize::Bi,
-            vector: resolution_arg,
-            pattern: SwizzleComponent::XYZW,
-        });
-
-        let uv_expr = self.push_expression(Expression::Binary {
-            op: BinaryOperator::Divide,
-            left: frag_coord_arg,
-            right: resolution_xy,
-        });
-
-        let two = self.push_expression(Expression::Literal(Literal::F32(2.0)));
-
-        let uv_expr = self.push_expression(Expression::Binary {
-            op: BinaryOperator::Multiply,
-            left: uv_expr,
-            right: two,
-        });
-
-        let one = self.push_expression(Expression::Literal(Literal::F32(1.0)));
-
-        let one_v2 = self.push_expression(Expression::Compose {
-            ty: self.get_type("Vector2").0,
-            components: vec![one, one],
-        });
-
-        let uv_expr = self.push_expression(Expression::Binary {
-            op: BinaryOperator::Subtract,
-            left: uv_expr,
-            right: one_v2,
-        });
-
-        let uv_expr = self.push_expression(Expression::Binary {
-            op: BinaryOperator::Multiply,
-            left: uv_expr,
-            right: two,
-        });
-
-        let entry_point = self.get_function(&self.input.entry_point.name_unique()).0;
-
-        let position_2d = self.push_expression(Expression::AccessIndex {
-            base: context_ptr,
-            index: context_struct
-                .fields
-                .iter()
-                .position(|field| *field.id == POSITION_2D)
-                .unwrap() as u32,
-        });
-
-        self.push_statement(Statement::Store {
-            pointer: position_2d,
-            value: uv_expr,
-        });
-
-        let aspect = self.push_expression(Expression::AccessIndex {
-            base: context_ptr,
-            index: context_struct
-                .fields
-                .iter()
-                .position(|field| *field.id == ASPECT)
-                .expect("No aspect field") as u32,
-        });
-
-        let resolution_x = self.push_expression(Expression::AccessIndex {
-            base: resolution_xy,
-            index: 0,
-        });
-
-        let resolution_y = self.push_expression(Expression::AccessIndex {
-            base: resolution_xy,
-            index: 1,
-        });
+        let mut arguments = vec![];
+        let mut args = vec![];
+        for (input, source) in function_def.inputs.iter().zip(def.inputs.iter()) {
+            let (ty, naga_ty) = self.get_property_type(&input.id)?;
+            match source {
+                EntryPointInput::Binding(binding) => {
+                    let mut binding = binding.clone();
+                    binding.apply_default_interpolation(&naga_ty.inner);
+                    args.push(
+                        self.push_expression(Expression::FunctionArgument(arguments.len() as u32)),
+                    );
+                    arguments.push(FunctionArgument {
+                        name: Some(input.id.name().to_string()),
+                        ty,
+                        binding: Some(binding),
+                    });
+                }
+                EntryPointInput::Uniform(binding) => {
+                    let global = self.global_variables.append(
+                        GlobalVariable {
+                            name: Some(input.id.name().to_string()),
+                            space: AddressSpace::Uniform,
+                            binding: Some(binding.clone()),
+                            ty,
+                            init: None,
+                        },
+                        Span::UNDEFINED,
+                    );
+                    let pointer = self.push_expression(Expression::GlobalVariable(global));
+                    args.push(self.push_expression(Expression::Load { pointer }));
+                }
+            }
+        }
 
-        let aspect_expr = self.push_expression(Expression::Binary {
-            op: BinaryOperator::Divide,
-            left: resolution_x,
-            right: resolution_y,
-        });
+        let function = self.get_function(&def.function.name_unique())?.0;
+        let call_result = self.push_expression(Expression::CallResult(function));
 
-        self.push_statement(Statement::Store {
-            pointer: aspect,
-            value: aspect_expr,
+        self.push_statement(Statement::Call {
+            function,
+            arguments: args,
+            result: Some(call_result),
         });
 
-        let context = self.push_expression(Expression::Load {
-            pointer: context_ptr,
-        });
+        let output_ty = self.get_input_type(&function_def.output)?;
+        let (result_ty, value) = match &def.output {
+            EntryPointOutput::Binding(binding) => {
+                let (ty, naga_ty) = self.get_type(output_ty.name())?;
+                let mut binding = binding.clone();
+                binding.apply_default_interpolation(&naga_ty.inner);
+                (
+                    FunctionResult {
+                        ty,
+                        binding: Some(binding),
+                    },
+                    call_result,
+                )
+            }
+            EntryPointOutput::Fields(fields) => {
+                let ElysianType::Struct(output_struct) = output_ty else {
+                    return Err(self.error(NagaBuildErrorKind::NotAStruct(
+                        function_def.output.name().to_string(),
+                    )));
+                };
 
-        let call_result = self.push_expression(Expression::CallResult(entry_point));
+                let struct_def = self.get_struct_definition(output_struct)?;
 
-        self.push_statement(Statement::Call {
-            function: entry_point,
-            arguments: vec![context],
-            result: Some(call_result),
-        });
+                let mut members = vec![];
+                let mut components = vec![];
+                let mut offset = 0;
+                for (prop, binding) in fields {
+                    let index = struct_def
+                        .fields
+                        .iter()
+                        .position(|field| field.id == *prop)
+                        .ok_or_else(|| {
+                            self.error(NagaBuildErrorKind::MissingField {
+                                field: prop.name().to_string(),
+                                ty: output_struct.name().to_string(),
+                            })
+                        })?;
+
+                    let (ty, naga_ty) = self.get_property_type(prop)?;
+                    let mut binding = binding.clone();
+                    binding.apply_default_interpolation(&naga_ty.inner);
+                    let span = self.type_span(ty);
+
+                    members.push(StructMember {
+                        name: Some(prop.name().to_string()),
+                        ty,
+                        binding: Some(binding),
+                        offset,
+                    });
+                    offset += span;
 
-        self.push_statement(Statement::Store {
-            pointer: context_ptr,
-            value: call_result,
-        });
+                    components.push(self.push_expression(Expression::AccessIndex {
+                        base: call_result,
+                        index: index as u32,
+                    }));
+                }
 
-        let color_ptr = self.push_expression(Expression::AccessIndex {
-            base: context_ptr,
-            index: context_struct
-                .fields
-                .iter()
-                .position(|field| *field.id == COLOR)
-                .unwrap() as u32,
-        });
+                let ty = self.push_type(NagaType {
+                    name: Some(format!("{}Output", def.name)),
+                    inner: TypeInner::Struct {
+                        members,
+                        span: offset,
+                    },
+                });
 
-        let color = self.push_expression(Expression::Load { pointer: color_ptr });
+                (
+                    FunctionResult { ty, binding: None },
+                    self.push_expression(Expression::Compose { ty, components }),
+                )
+            }
+        };
 
-        self.push_statement(Statement::Store {
-            pointer: frag_color_ptr,
-            value: color,
-        });
+        self.push_statement(Statement::Return { value: Some(value) });
 
         let expressions = self.expressions.take().unwrap().expressions;
         let local_variables = self.local_variables.take().unwrap().locals;
         let body = self.block_stack.pop().unwrap();
+        self.function = None;
 
-        EntryPoint {
-            name: "mainImage".to_string(),
-            stage: ShaderStage::Fragment,
+        Ok(EntryPoint {
+            name: def.name.clone(),
+            stage: def.stage,
             early_depth_test: None,
             workgroup_size: [0; 3],
             function: Function {
-                name: Some("mainImage".to_string()),
-                arguments: vec![
-                    FunctionArgument {
-                        name: Some("fragCoord".to_string()),
-                        ty: self.get_type("Vector2").0,
-                        binding: Some(naga::Binding::Location {
-                            location: 0,
-                            interpolation: Some(naga::Interpolation::Perspective),
-                            sampling: None,
-                        }),
-                    },
-                    FunctionArgument {
-                        name: Some("fragColor".to_string()),
-                        ty: self.get_type("Vector4").0,
-                        binding: Some(naga::Binding::Location {
-                            location: 2,
-                            interpolation: Some(naga::Interpolation::Perspective),
-                            sampling: None,
-                        }),
-                    },
-                    FunctionArgument {
-                        name: Some("iResolution".to_string()),
-                        ty: self.get_type("Vector3").0,
-                        binding: Some(naga::Binding::Location {
-                            location: 1,
-                            interpolation: Some(naga::Interpolation::Perspective),
-                            sampling: None,
-                        }),
-                    },
-                ],
-                result: None,
+                name: Some(def.name.clone()),
+                arguments,
+                result: Some(result_ty),
                 local_variables,
                 expressions,
                 named_expressions: Default::default(),
                 body,
             },
-        }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_ir::{
+        ast::Block,
+        module::{AsModule, SpecializationData, CONTEXT},
+    };
+    use elysian_shapes::field::Circle;
+
+    use super::*;
+
+    #[test]
+    fn test_invalid_write() {
+        const INVALID: FunctionIdentifier = FunctionIdentifier::new("invalid", 0);
+
+        let mut module = Circle::new(0.5).module(&SpecializationData::new_2d());
+        module.function_definitions.push(FunctionDefinition {
+            id: INVALID,
+            public: true,
+            inputs: vec![],
+            output: CONTEXT.into(),
+            block: Block(vec![Stmt::Write {
+                path: vec![CONTEXT.into()],
+                expr: Expr::Read(vec![CONTEXT.into()]),
+            }]),
+            provenance: None,
+        });
+        let module = module.finalize();
+
+        let err = NagaBuilder::new(&module)
+            .build(ValidationFlags::all(), Capabilities::default())
+            .unwrap_err();
+
+        assert_eq!(err.function, Some(INVALID));
+        assert!(matches!(err.kind, NagaBuildErrorKind::InvalidWrite(_)));
     }
 }
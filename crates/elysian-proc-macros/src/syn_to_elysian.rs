@@ -283,6 +283,8 @@ impl SynToElysian {
                         "asin" => quote!(#receiver.asin()),
                         "acos" => quote!(#receiver.acos()),
                         "atan" => quote!(#receiver.atan()),
+                        "exp2" => quote!(#receiver.exp2()),
+                        "log2" => quote!(#receiver.log2()),
                         "round" => quote!(#receiver.round()),
                         _ => panic!("Unsupported method"),
                     },
@@ -0,0 +1,115 @@
+"""Generated by elysian"""
+
+import numpy as np
+
+
+def _where(mask, new, old):
+    if isinstance(new, dict):
+        return {key: _where(mask, new[key], old[key]) for key in new}
+    return np.where(mask, new, old)
+
+
+def _copy(value):
+    if isinstance(value, dict):
+        return {key: _copy(member) for key, member in value.items()}
+    return value
+
+
+def _broadcast(value, shape):
+    if isinstance(value, dict):
+        return {key: _broadcast(member, shape) for key, member in value.items()}
+    return np.broadcast_to(value, shape)
+
+
+def _sign(x):
+    return np.where(np.isnan(x), x, np.copysign(1.0, x))
+
+
+def _round(x):
+    return np.copysign(np.floor(np.abs(x) + 0.5), x)
+
+
+def _rem(lhs, rhs):
+    return np.mod(lhs, np.abs(rhs))
+
+
+def _div_i(lhs, rhs):
+    return np.sign(lhs) * np.sign(rhs) * (np.abs(lhs) // np.abs(rhs))
+
+
+def _float(x):
+    return np.asarray(x, dtype=np.float64)
+
+
+def _int(x):
+    return np.trunc(x).astype(np.int64)
+
+
+# Generated by Point (7dd9e9be206bbc6e) in Circle (6a73b57513d790c8) in Modify (568481d7a12b99ac)
+def _point_distance_gradient_2d_position_2d_uv(context):
+    out = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["distance"] = np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"]))
+    _t0 = np.divide(1.0, np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"])))
+    _t1 = np.logical_and(np.isfinite(_t0), _t0 > 0.0)
+    _t2 = {"x": np.where(_t1, context["position_2d"]["x"] * _t0, 0.0), "y": np.where(_t1, context["position_2d"]["y"] * _t0, 0.0)}
+    context["gradient_2d"] = _t2
+    _t3 = {"x": np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"])), "y": (((np.arctan2(context["position_2d"]["y"], context["position_2d"]["x"]) / 3.1415927410125732) * 0.5) + 0.5)}
+    context["uv"] = _t3
+    out = _copy(context)
+    return out
+
+
+# Generated by Isosurface (3c173dc6fdbb0ba0) in Circle (6a73b57513d790c8) in Modify (568481d7a12b99ac)
+def _isosurface_distance_position_2d_uv(dist, context):
+    out = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["distance"] = (context["distance"] - dist)
+    context["uv"]["x"] = (context["uv"]["x"] - dist)
+    out = _copy(context)
+    return out
+
+
+# Generated by Circle (6a73b57513d790c8) in Modify (568481d7a12b99ac)
+def _circle_distance_gradient_2d_position_2d_uv(radius, context):
+    out = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = _point_distance_gradient_2d_position_2d_uv(_copy(context))
+    context = _t0
+    _t1 = _isosurface_distance_position_2d_uv(radius, _copy(context))
+    context = _t1
+    out = _copy(context)
+    return out
+
+
+# Generated by GradientNormals (57f4534c536aab57) in Modify (568481d7a12b99ac)
+def _gradient_normals_gradient_2d(context):
+    out = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = {"x": context["gradient_2d"]["x"], "y": context["gradient_2d"]["y"], "z": 1.0}
+    _t1 = np.divide(1.0, np.sqrt((_t0["x"] * _t0["x"] + _t0["y"] * _t0["y"] + _t0["z"] * _t0["z"])))
+    _t2 = np.logical_and(np.isfinite(_t1), _t1 > 0.0)
+    _t3 = {"x": np.where(_t2, _t0["x"] * _t1, 0.0), "y": np.where(_t2, _t0["y"] * _t1, 0.0), "z": np.where(_t2, _t0["z"] * _t1, 0.0)}
+    context["normal"] = _t3
+    out = _copy(context)
+    return out
+
+
+# Generated by Modify (568481d7a12b99ac)
+def _modify_distance_gradient_2d_position_2d_uv(context):
+    out = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = _circle_distance_gradient_2d_position_2d_uv(0.5, _copy(context))
+    _t1 = _gradient_normals_gradient_2d(_t0)
+    out = _t1
+    return out
+
+
+def evaluate(context):
+    """Evaluate the shape for a context of numpy arrays"""
+    with np.errstate(all="ignore"):
+        _t0 = _modify_distance_gradient_2d_position_2d_uv(_copy(context))
+        return _t0
+
+
+def sample(x, y):
+    """Evaluate the shape at arrays of positions, returning its context broadcast to their shape"""
+    x, y = np.broadcast_arrays(_float(x), _float(y))
+    context = {"distance": 0.0, "position_2d": {"x": 0.0, "y": 0.0}, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["position_2d"] = {"x": x, "y": y}
+    return _broadcast(evaluate(context), x.shape)
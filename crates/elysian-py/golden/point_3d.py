@@ -0,0 +1,75 @@
+"""Generated by elysian"""
+
+import numpy as np
+
+
+def _where(mask, new, old):
+    if isinstance(new, dict):
+        return {key: _where(mask, new[key], old[key]) for key in new}
+    return np.where(mask, new, old)
+
+
+def _copy(value):
+    if isinstance(value, dict):
+        return {key: _copy(member) for key, member in value.items()}
+    return value
+
+
+def _broadcast(value, shape):
+    if isinstance(value, dict):
+        return {key: _broadcast(member, shape) for key, member in value.items()}
+    return np.broadcast_to(value, shape)
+
+
+def _sign(x):
+    return np.where(np.isnan(x), x, np.copysign(1.0, x))
+
+
+def _round(x):
+    return np.copysign(np.floor(np.abs(x) + 0.5), x)
+
+
+def _rem(lhs, rhs):
+    return np.mod(lhs, np.abs(rhs))
+
+
+def _div_i(lhs, rhs):
+    return np.sign(lhs) * np.sign(rhs) * (np.abs(lhs) // np.abs(rhs))
+
+
+def _float(x):
+    return np.asarray(x, dtype=np.float64)
+
+
+def _int(x):
+    return np.trunc(x).astype(np.int64)
+
+
+# Generated by Point (7dd9e9be206bbc6e)
+def _point_distance_gradient_3d_position_3d_uv(context):
+    out = {"distance": 0.0, "position_3d": {"x": 0.0, "y": 0.0, "z": 0.0}, "gradient_3d": {"x": 0.0, "y": 0.0, "z": 0.0}, "uv": {"x": 0.0, "y": 0.0}}
+    context["distance"] = np.sqrt((context["position_3d"]["x"] * context["position_3d"]["x"] + context["position_3d"]["y"] * context["position_3d"]["y"] + context["position_3d"]["z"] * context["position_3d"]["z"]))
+    _t0 = np.divide(1.0, np.sqrt((context["position_3d"]["x"] * context["position_3d"]["x"] + context["position_3d"]["y"] * context["position_3d"]["y"] + context["position_3d"]["z"] * context["position_3d"]["z"])))
+    _t1 = np.logical_and(np.isfinite(_t0), _t0 > 0.0)
+    _t2 = {"x": np.where(_t1, context["position_3d"]["x"] * _t0, 0.0), "y": np.where(_t1, context["position_3d"]["y"] * _t0, 0.0), "z": np.where(_t1, context["position_3d"]["z"] * _t0, 0.0)}
+    context["gradient_3d"] = _t2
+    _t3 = {"x": context["position_3d"]["x"], "y": context["position_3d"]["z"]}
+    _t4 = {"x": ((((_sign(context["position_3d"]["z"]) * np.arccos((context["position_3d"]["x"] / np.sqrt((_t3["x"] * _t3["x"] + _t3["y"] * _t3["y"]))))) / 3.1415927410125732) * (-2.0)) + 1.0), "y": (((np.arccos((context["position_3d"]["y"] / np.sqrt((context["position_3d"]["x"] * context["position_3d"]["x"] + context["position_3d"]["y"] * context["position_3d"]["y"] + context["position_3d"]["z"] * context["position_3d"]["z"])))) / 3.1415927410125732) * (-2.0)) + 1.0)}
+    context["uv"] = _t4
+    out = _copy(context)
+    return out
+
+
+def evaluate(context):
+    """Evaluate the shape for a context of numpy arrays"""
+    with np.errstate(all="ignore"):
+        _t0 = _point_distance_gradient_3d_position_3d_uv(_copy(context))
+        return _t0
+
+
+def sample(x, y, z):
+    """Evaluate the shape at arrays of positions, returning its context broadcast to their shape"""
+    x, y, z = np.broadcast_arrays(_float(x), _float(y), _float(z))
+    context = {"distance": 0.0, "position_3d": {"x": 0.0, "y": 0.0, "z": 0.0}, "gradient_3d": {"x": 0.0, "y": 0.0, "z": 0.0}, "uv": {"x": 0.0, "y": 0.0}}
+    context["position_3d"] = {"x": x, "y": y, "z": z}
+    return _broadcast(evaluate(context), x.shape)
@@ -0,0 +1,131 @@
+"""Translated point"""
+
+import numpy as np
+
+
+def _where(mask, new, old):
+    if isinstance(new, dict):
+        return {key: _where(mask, new[key], old[key]) for key in new}
+    return np.where(mask, new, old)
+
+
+def _copy(value):
+    if isinstance(value, dict):
+        return {key: _copy(member) for key, member in value.items()}
+    return value
+
+
+def _broadcast(value, shape):
+    if isinstance(value, dict):
+        return {key: _broadcast(member, shape) for key, member in value.items()}
+    return np.broadcast_to(value, shape)
+
+
+def _sign(x):
+    return np.where(np.isnan(x), x, np.copysign(1.0, x))
+
+
+def _round(x):
+    return np.copysign(np.floor(np.abs(x) + 0.5), x)
+
+
+def _rem(lhs, rhs):
+    return np.mod(lhs, np.abs(rhs))
+
+
+def _div_i(lhs, rhs):
+    return np.sign(lhs) * np.sign(rhs) * (np.abs(lhs) // np.abs(rhs))
+
+
+def _float(x):
+    return np.asarray(x, dtype=np.float64)
+
+
+def _int(x):
+    return np.trunc(x).astype(np.int64)
+
+
+# Generated by Translate (5879cd204938735d) in Modify (187ab93f4b52f77d) in Modify (5ecc036939e9d5fc) in Modify (36953947b4d55c2c)
+def _translate_position_2d(delta_2d, context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = {"x": (context["position_2d"]["x"] - delta_2d["x"]), "y": (context["position_2d"]["y"] - delta_2d["y"])}
+    context["position_2d"] = _t0
+    out = _copy(context)
+    return out
+
+
+# Generated by Point (7dd9e9be206bbc6e) in Modify (187ab93f4b52f77d) in Modify (5ecc036939e9d5fc) in Modify (36953947b4d55c2c)
+def _point_distance_gradient_2d_position_2d_uv(context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["distance"] = np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"]))
+    _t0 = np.divide(1.0, np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"])))
+    _t1 = np.logical_and(np.isfinite(_t0), _t0 > 0.0)
+    _t2 = {"x": np.where(_t1, context["position_2d"]["x"] * _t0, 0.0), "y": np.where(_t1, context["position_2d"]["y"] * _t0, 0.0)}
+    context["gradient_2d"] = _t2
+    _t3 = {"x": np.sqrt((context["position_2d"]["x"] * context["position_2d"]["x"] + context["position_2d"]["y"] * context["position_2d"]["y"])), "y": (((np.arctan2(context["position_2d"]["y"], context["position_2d"]["x"]) / 3.1415927410125732) * 0.5) + 0.5)}
+    context["uv"] = _t3
+    out = _copy(context)
+    return out
+
+
+# Generated by Modify (187ab93f4b52f77d) in Modify (5ecc036939e9d5fc) in Modify (36953947b4d55c2c)
+def _modify_distance_gradient_2d_position_2d_uv(context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = _translate_position_2d({"x": 0.25, "y": (-0.5)}, _copy(context))
+    _t1 = _point_distance_gradient_2d_position_2d_uv(_t0)
+    out = _t1
+    return out
+
+
+# Generated by Isosurface (3c173dc6fdbb0ba0) in Modify (5ecc036939e9d5fc) in Modify (36953947b4d55c2c)
+def _isosurface_distance_position_2d_uv(dist, context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["distance"] = (context["distance"] - dist)
+    context["uv"]["x"] = (context["uv"]["x"] - dist)
+    out = _copy(context)
+    return out
+
+
+# Generated by Modify (5ecc036939e9d5fc) in Modify (36953947b4d55c2c)
+def _modify_distance_gradient_2d_position_2d_uv_1(context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = _modify_distance_gradient_2d_position_2d_uv(_copy(context))
+    _t1 = _isosurface_distance_position_2d_uv(0.75, _t0)
+    out = _t1
+    return out
+
+
+# Generated by GradientNormals (57f4534c536aab57) in Modify (36953947b4d55c2c)
+def _gradient_normals_gradient_2d(context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = {"x": context["gradient_2d"]["x"], "y": context["gradient_2d"]["y"], "z": 1.0}
+    _t1 = np.divide(1.0, np.sqrt((_t0["x"] * _t0["x"] + _t0["y"] * _t0["y"] + _t0["z"] * _t0["z"])))
+    _t2 = np.logical_and(np.isfinite(_t1), _t1 > 0.0)
+    _t3 = {"x": np.where(_t2, _t0["x"] * _t1, 0.0), "y": np.where(_t2, _t0["y"] * _t1, 0.0), "z": np.where(_t2, _t0["z"] * _t1, 0.0)}
+    context["normal"] = _t3
+    out = _copy(context)
+    return out
+
+
+# Generated by Modify (36953947b4d55c2c)
+def _modify_distance_gradient_2d_position_2d_uv_2(context):
+    out = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    _t0 = _modify_distance_gradient_2d_position_2d_uv_1(_copy(context))
+    _t1 = _gradient_normals_gradient_2d(_t0)
+    out = _t1
+    return out
+
+
+def evaluate(context):
+    """Evaluate the shape for a context of numpy arrays"""
+    with np.errstate(all="ignore"):
+        _t0 = _modify_distance_gradient_2d_position_2d_uv_2(_copy(context))
+        return _t0
+
+
+def sample(x, y):
+    """Evaluate the shape at arrays of positions, returning its context broadcast to their shape"""
+    x, y = np.broadcast_arrays(_float(x), _float(y))
+    context = {"position_2d": {"x": 0.0, "y": 0.0}, "distance": 0.0, "gradient_2d": {"x": 0.0, "y": 0.0}, "uv": {"x": 0.0, "y": 0.0}, "normal": {"x": 0.0, "y": 0.0, "z": 0.0}}
+    context["position_2d"] = {"x": x, "y": y}
+    return _broadcast(evaluate(context), x.shape)
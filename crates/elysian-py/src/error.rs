@@ -0,0 +1,34 @@
+use std::{error::Error, fmt::Display};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PyError {
+    MissingFunction(String),
+    MissingStruct(String),
+    MissingProperty(String),
+    MissingField { field: String, ty: String },
+    InvalidRead(String),
+    InvalidExpr(String),
+    InvalidStmt(String),
+}
+
+impl Display for PyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PyError::MissingFunction(name) => {
+                f.write_str(&format!("No function definition for {name}"))
+            }
+            PyError::MissingStruct(name) => {
+                f.write_str(&format!("No struct definition for {name}"))
+            }
+            PyError::MissingProperty(name) => f.write_str(&format!("No type for property {name}")),
+            PyError::MissingField { field, ty } => {
+                f.write_str(&format!("No field {field} in struct {ty}"))
+            }
+            PyError::InvalidRead(path) => f.write_str(&format!("Invalid read of {path}")),
+            PyError::InvalidExpr(expr) => f.write_str(&format!("Invalid expression {expr}")),
+            PyError::InvalidStmt(stmt) => f.write_str(&format!("Invalid statement {stmt}")),
+        }
+    }
+}
+
+impl Error for PyError {}
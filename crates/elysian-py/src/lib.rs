@@ -0,0 +1,296 @@
+//! Convert Elysian IR into Python modules evaluating shapes over numpy arrays
+
+mod error;
+mod names;
+mod writer;
+
+pub use error::*;
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::{
+    ast::{Expr, POSITION_2D, POSITION_3D},
+    module::{Module, StructIdentifier, CONTEXT},
+};
+
+use crate::{
+    names::Names,
+    writer::{FunctionWriter, Functions, HELPERS},
+};
+
+/// Builder for a Python module implementing an Elysian module
+///
+/// The generated module depends only on numpy, and exposes two functions:
+///
+/// ```python
+/// def evaluate(context): ...
+/// def sample(x, y): ...
+/// ```
+///
+/// `evaluate` takes and returns a context as a dict keyed by property name,
+/// whose scalar members may be numpy arrays of any shape.
+/// `sample` builds a context from arrays of position components,
+/// taking an additional `z` for shapes specialized for 3D,
+/// and returns the resulting context broadcast to their shape.
+///
+/// Evaluation is vectorized across array elements,
+/// so conditionals execute both branches and select between them with `np.where`.
+#[derive(Debug, Clone)]
+pub struct PyBuilder<'a> {
+    module: &'a Module,
+    doc: Option<String>,
+}
+
+impl<'a> PyBuilder<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        PyBuilder { module, doc: None }
+    }
+
+    /// Set the module docstring
+    pub fn doc(mut self, doc: impl Into<String>) -> Self {
+        self.doc = Some(doc.into());
+        self
+    }
+
+    pub fn build(self) -> Result<String, PyError> {
+        let module = self.module;
+        let names = Names::new(module);
+
+        let functions: Functions = module
+            .function_definitions
+            .iter()
+            .map(|def| (def.id.clone(), def))
+            .collect();
+
+        let doc = self
+            .doc
+            .unwrap_or_else(|| "Generated by elysian".to_string());
+        let mut out = format!("\"\"\"{doc}\"\"\"\n\nimport numpy as np\n\n\n{HELPERS}");
+
+        for def in module.function_definitions.iter() {
+            out += "\n\n";
+            out += &FunctionWriter::new(&names, &functions).function(def)?;
+        }
+
+        let context = PropertyIdentifier(CONTEXT);
+        out += "\n\n";
+        out += &FunctionWriter::new(&names, &functions).entry(
+            "evaluate",
+            "Evaluate the shape for a context of numpy arrays",
+            &context,
+            &module.call(Expr::Read(vec![context.clone()])),
+        )?;
+
+        let fields = names.fields(&StructIdentifier(CONTEXT))?;
+        let position = [POSITION_2D, POSITION_3D]
+            .into_iter()
+            .map(PropertyIdentifier::from)
+            .find(|position| fields.iter().any(|(field, _, _)| *field == position));
+
+        if let Some(position) = position {
+            out += "\n\n";
+            out += &FunctionWriter::new(&names, &functions).sample(
+                "Evaluate the shape at arrays of positions, \
+                 returning its context broadcast to their shape",
+                "evaluate",
+                &context,
+                &position,
+            )?;
+        }
+
+        Ok(out)
+    }
+}
+
+/// Convert a module into Python source with the default docstring
+pub fn module_to_py(module: &Module) -> Result<String, PyError> {
+    PyBuilder::new(module).build()
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_ir::module::{AsModule, SpecializationData};
+    use elysian_shapes::{
+        field::{Circle, Point},
+        modify::{IntoGradientNormals, IntoIsosurface, IntoTranslate},
+    };
+
+    use super::*;
+
+    fn circle() -> Module {
+        Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize()
+    }
+
+    fn translated_point() -> Module {
+        Point
+            .translate([0.25, -0.5])
+            .isosurface(0.75)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize()
+    }
+
+    /// Compare generated source against a file in `golden/`,
+    /// overwriting it instead if `ELYSIAN_BLESS` is set
+    fn golden(name: &str, source: &str) {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("golden")
+            .join(format!("{name}.py"));
+
+        if std::env::var_os("ELYSIAN_BLESS").is_some() {
+            std::fs::write(&path, source).unwrap();
+            return;
+        }
+
+        let expected = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Failed to read {}: {e}", path.display()));
+        assert!(
+            expected == source,
+            "Generated source differs from {}, rerun with ELYSIAN_BLESS=1 to update",
+            path.display()
+        );
+    }
+
+    #[test]
+    fn test_py_circle() {
+        golden("circle", &module_to_py(&circle()).unwrap());
+    }
+
+    #[test]
+    fn test_py_translated_point() {
+        golden(
+            "translated_point",
+            &PyBuilder::new(&translated_point())
+                .doc("Translated point")
+                .build()
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn test_py_point_3d() {
+        golden(
+            "point_3d",
+            &module_to_py(&Point.module(&SpecializationData::new_3d()).finalize()).unwrap(),
+        );
+    }
+
+    /// Run generated modules under the system Python,
+    /// and check their output against the interpreter
+    #[cfg(feature = "python-tests")]
+    mod python {
+        use std::process::Command;
+
+        use elysian_core::number::Number;
+        use elysian_interpreter::Interpreted;
+        use elysian_ir::{
+            ast::{Struct, Value, DISTANCE, GRADIENT_2D, VECTOR2, X, Y},
+            module::Evaluate,
+        };
+
+        use super::*;
+
+        fn python() -> Option<String> {
+            let python = std::env::var("PYTHON").unwrap_or_else(|_| "python3".to_string());
+            let found = Command::new(&python)
+                .args(["-c", "import numpy"])
+                .status()
+                .map(|status| status.success())
+                .unwrap_or(false);
+            found.then_some(python)
+        }
+
+        fn compare(module: &Module, name: &str, points: &[(f64, f64)]) {
+            let Some(python) = python() else {
+                eprintln!("Skipping {name}: no python3 with numpy");
+                return;
+            };
+
+            let dir =
+                std::env::temp_dir().join(format!("elysian-py-{name}-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(
+                dir.join(format!("{name}.py")),
+                module_to_py(module).unwrap(),
+            )
+            .unwrap();
+
+            let xs: Vec<_> = points.iter().map(|(x, _)| format!("{x:?}")).collect();
+            let ys: Vec<_> = points.iter().map(|(_, y)| format!("{y:?}")).collect();
+            let script = format!(
+                "import {name}\n\
+                 out = {name}.sample([{}], [{}])\n\
+                 for i in range({}):\n    \
+                 print(out['distance'][i], out['gradient_2d']['x'][i], out['gradient_2d']['y'][i])\n",
+                xs.join(", "),
+                ys.join(", "),
+                points.len()
+            );
+
+            let output = Command::new(python)
+                .current_dir(&dir)
+                .args(["-c", &script])
+                .output()
+                .unwrap();
+            assert!(
+                output.status.success(),
+                "{}",
+                String::from_utf8_lossy(&output.stderr)
+            );
+            let stdout = String::from_utf8(output.stdout).unwrap();
+
+            for ((x, y), line) in points.iter().zip(stdout.lines()) {
+                let context = Struct::new(StructIdentifier(CONTEXT)).set(
+                    POSITION_2D.into(),
+                    Value::Struct(
+                        Struct::new(StructIdentifier(VECTOR2))
+                            .set(X.into(), (*x).into())
+                            .set(Y.into(), (*y).into()),
+                    ),
+                );
+                let expected = Interpreted(module).evaluate(context).unwrap();
+                let Value::Struct(gradient) = expected.get(&GRADIENT_2D.into()) else {
+                    panic!("No gradient");
+                };
+                let expected = [
+                    expected.get(&DISTANCE.into()),
+                    gradient.get(&X.into()),
+                    gradient.get(&Y.into()),
+                ];
+
+                for (expected, found) in expected.iter().zip(line.split_whitespace()) {
+                    let Value::Number(Number::Float(expected)) = expected else {
+                        panic!("Unexpected {expected:?}");
+                    };
+                    let found: f64 = found.parse().unwrap();
+                    assert!(
+                        (expected - found).abs() < 1e-6,
+                        "({x}, {y}): {expected} != {found}"
+                    );
+                }
+            }
+
+            std::fs::remove_dir_all(&dir).ok();
+        }
+
+        #[test]
+        fn test_py_circle_numpy() {
+            compare(
+                &circle(),
+                "circle",
+                &[(0.0, 0.0), (0.25, -0.5), (1.0, 1.0), (-1.5, 0.75)],
+            );
+        }
+
+        #[test]
+        fn test_py_translated_point_numpy() {
+            compare(
+                &translated_point(),
+                "translated_point",
+                &[(0.0, 0.0), (0.25, -0.5), (-1.0, 2.0)],
+            );
+        }
+    }
+}
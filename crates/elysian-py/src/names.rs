@@ -0,0 +1,149 @@
+use std::collections::{HashMap, HashSet};
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::module::{self, properties, FunctionIdentifier, Module, StructIdentifier, Type};
+
+use crate::PyError;
+
+/// Python spellings of the identifiers in a module
+///
+/// Structs are represented as dicts keyed by their readable field names,
+/// so that the returned context is convenient to inspect.
+/// Functions are private to the generated module,
+/// and are numbered in definition order where their names collide,
+/// so that output is stable across runs.
+#[derive(Debug, Clone)]
+pub(crate) struct Names<'a> {
+    module: &'a Module,
+    fields: HashMap<(StructIdentifier, PropertyIdentifier), String>,
+    functions: HashMap<FunctionIdentifier, String>,
+}
+
+impl<'a> Names<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        let mut fields = HashMap::new();
+
+        for def in module.struct_definitions.iter() {
+            let mut field_names = HashSet::new();
+            for field in def.fields.iter() {
+                let mut name = field.id.name().to_string();
+                if !field_names.insert(name.clone()) {
+                    name = field.id.name_unique();
+                }
+                fields.insert((def.id.clone(), field.id.clone()), name);
+            }
+        }
+
+        let mut functions = HashMap::new();
+        let mut function_names = HashSet::new();
+        for def in module.function_definitions.iter() {
+            let name = dedupe(
+                &format!("_{}", sanitize(def.id.name())),
+                &mut function_names,
+            );
+            functions.insert(def.id.clone(), name);
+        }
+
+        Names {
+            module,
+            fields,
+            functions,
+        }
+    }
+
+    pub fn field(&self, id: &StructIdentifier, prop: &PropertyIdentifier) -> Result<&str, PyError> {
+        self.fields
+            .get(&(id.clone(), prop.clone()))
+            .map(String::as_str)
+            .ok_or_else(|| PyError::MissingField {
+                field: prop.name().to_string(),
+                ty: id.name().to_string(),
+            })
+    }
+
+    /// Fields of a struct alongside their dict keys and types, in declaration order
+    pub fn fields(
+        &self,
+        id: &StructIdentifier,
+    ) -> Result<Vec<(&'a PropertyIdentifier, &str, &'static Type)>, PyError> {
+        self.module
+            .struct_definitions
+            .iter()
+            .find(|def| def.id == *id)
+            .ok_or_else(|| PyError::MissingStruct(id.name().to_string()))?
+            .fields
+            .iter()
+            .map(|field| {
+                Ok((
+                    &field.id,
+                    self.field(id, &field.id)?,
+                    field_type(id, &field.id)?,
+                ))
+            })
+            .collect()
+    }
+
+    pub fn function(&self, id: &FunctionIdentifier) -> Result<&str, PyError> {
+        self.functions
+            .get(id)
+            .map(String::as_str)
+            .ok_or_else(|| PyError::MissingFunction(id.name_unique()))
+    }
+}
+
+/// Python name for a local, unique among those already taken
+pub(crate) fn local(prop: &PropertyIdentifier, taken: &mut HashSet<String>) -> String {
+    let mut name = sanitize(prop.name()).to_lowercase();
+    if RESERVED.contains(&name.as_str()) {
+        name += "_";
+    }
+    dedupe(&name, taken)
+}
+
+/// Number a name if it's already been taken, and mark the result as taken
+fn dedupe(name: &str, taken: &mut HashSet<String>) -> String {
+    let mut out = name.to_string();
+    let mut n = 1;
+    while !taken.insert(out.clone()) {
+        out = format!("{name}_{n}");
+        n += 1;
+    }
+    out
+}
+
+pub(crate) fn property_type(prop: &PropertyIdentifier) -> Result<&'static Type, PyError> {
+    properties()
+        .get(prop)
+        .ok_or_else(|| PyError::MissingProperty(prop.name().to_string()))
+}
+
+pub(crate) fn field_type(
+    structure: &StructIdentifier,
+    prop: &PropertyIdentifier,
+) -> Result<&'static Type, PyError> {
+    module::field_type(structure, prop)
+        .ok_or_else(|| PyError::MissingProperty(prop.name().to_string()))
+}
+
+/// Replace any characters that can't appear in a Python identifier
+pub(crate) fn sanitize(name: &str) -> String {
+    let mut out: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    if out.starts_with(|c: char| c.is_ascii_digit()) || out.is_empty() {
+        out.insert(0, '_');
+    }
+
+    out
+}
+
+/// Python keywords and names used by the generated code,
+/// which can't be used as locals
+const RESERVED: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "finally", "for", "from", "global", "if", "import",
+    "in", "is", "lambda", "nonlocal", "not", "or", "pass", "raise", "return", "try", "while",
+    "with", "yield", "np", "out",
+];
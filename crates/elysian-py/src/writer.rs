@@ -0,0 +1,1089 @@
+use std::collections::{HashMap, HashSet};
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Value},
+    module::{FunctionDefinition, FunctionIdentifier, NumericType, StructIdentifier, Type},
+};
+
+use crate::{
+    names::{field_type, local, property_type, Names},
+    PyError,
+};
+
+/// Function definitions keyed by identifier
+pub(crate) type Functions<'a> = HashMap<FunctionIdentifier, &'a FunctionDefinition>;
+
+/// Routines without a direct numpy equivalent,
+/// matching the semantics of the Rust backends
+pub(crate) const HELPERS: &str = r#"def _where(mask, new, old):
+    if isinstance(new, dict):
+        return {key: _where(mask, new[key], old[key]) for key in new}
+    return np.where(mask, new, old)
+
+
+def _copy(value):
+    if isinstance(value, dict):
+        return {key: _copy(member) for key, member in value.items()}
+    return value
+
+
+def _broadcast(value, shape):
+    if isinstance(value, dict):
+        return {key: _broadcast(member, shape) for key, member in value.items()}
+    return np.broadcast_to(value, shape)
+
+
+def _sign(x):
+    return np.where(np.isnan(x), x, np.copysign(1.0, x))
+
+
+def _round(x):
+    return np.copysign(np.floor(np.abs(x) + 0.5), x)
+
+
+def _rem(lhs, rhs):
+    return np.mod(lhs, np.abs(rhs))
+
+
+def _div_i(lhs, rhs):
+    return np.sign(lhs) * np.sign(rhs) * (np.abs(lhs) // np.abs(rhs))
+
+
+def _float(x):
+    return np.asarray(x, dtype=np.float64)
+
+
+def _int(x):
+    return np.trunc(x).astype(np.int64)
+"#;
+
+/// Result of writing an expression
+///
+/// Struct operands always name a dict, so that their members can be accessed directly.
+/// Operands naming a local or one of its members are places,
+/// and must be copied before being stored elsewhere.
+#[derive(Debug, Clone)]
+struct Operand {
+    code: String,
+    ty: Type,
+    place: bool,
+}
+
+impl Operand {
+    fn new(code: String, ty: Type) -> Self {
+        Operand {
+            code,
+            ty,
+            place: false,
+        }
+    }
+
+    /// Code producing a value that can be stored without aliasing its source
+    fn value(&self) -> String {
+        match (&self.ty, self.place) {
+            (Type::Struct(_), true) => format!("_copy({})", self.code),
+            _ => self.code.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unary {
+    Neg,
+    Abs,
+    Sign,
+    Round,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp2,
+    Log2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+/// Writes a single function body as vectorized Python
+///
+/// Every scalar is a numpy array of lanes, or a value broadcast across them.
+/// Control flow can't branch per lane, so both sides of each `If` are executed
+/// under a mask, and stores only take effect in active lanes via `np.where`.
+/// Loops run until every lane has broken out.
+pub(crate) struct FunctionWriter<'a, 'b> {
+    names: &'b Names<'a>,
+    functions: &'b Functions<'a>,
+    /// Python names of the properties in scope
+    locals: HashMap<PropertyIdentifier, String>,
+    taken: HashSet<String>,
+    out: String,
+    indent: usize,
+    temps: usize,
+    /// Names of the masks in effect, outermost first
+    masks: Vec<String>,
+    /// Index into `masks` of each enclosing loop's active lanes
+    loops: Vec<usize>,
+}
+
+impl<'a, 'b> FunctionWriter<'a, 'b> {
+    pub fn new(names: &'b Names<'a>, functions: &'b Functions<'a>) -> Self {
+        FunctionWriter {
+            names,
+            functions,
+            locals: Default::default(),
+            taken: Default::default(),
+            out: Default::default(),
+            indent: 0,
+            temps: 0,
+            masks: Default::default(),
+            loops: Default::default(),
+        }
+    }
+
+    /// Write a function definition
+    ///
+    /// Every property bound within the function is initialized to zero up front,
+    /// since bindings may be read outside the block that writes them.
+    pub fn function(mut self, def: &FunctionDefinition) -> Result<String, PyError> {
+        let output = property_type(&def.output)?;
+
+        let params = def
+            .inputs
+            .iter()
+            .map(|input| self.declare(&input.id))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if let Some(provenance) = &def.provenance {
+            self.line(format!("# Generated by {provenance}"));
+        }
+        self.line(format!("def {}({params}):", self.names.function(&def.id)?));
+        self.indent += 1;
+
+        let mut locals = vec![];
+        block_locals(&def.block, &mut locals);
+        for prop in locals {
+            if !self.locals.contains_key(&prop) {
+                let name = self.declare(&prop);
+                let zero = self.zero(property_type(&prop)?)?;
+                self.line(format!("{name} = {zero}"));
+            }
+        }
+
+        let zero = self.zero(output)?;
+        self.line(format!("out = {zero}"));
+
+        self.block(&def.block)?;
+
+        self.line("return out");
+
+        Ok(self.out)
+    }
+
+    /// Write a public function calling `call` with a single `input`,
+    /// with numpy's floating point warnings silenced
+    pub fn entry(
+        mut self,
+        name: &str,
+        doc: &str,
+        input: &PropertyIdentifier,
+        call: &Expr,
+    ) -> Result<String, PyError> {
+        let local = self.declare(input);
+        self.line(format!("def {name}({local}):"));
+        self.indent += 1;
+        self.line(format!("\"\"\"{doc}\"\"\""));
+        self.line("with np.errstate(all=\"ignore\"):");
+        self.indent += 1;
+
+        let result = self.expr(call)?;
+        self.line(format!("return {}", result.code));
+
+        Ok(self.out)
+    }
+
+    /// Write a public function taking one array per component of `position`,
+    /// and passing a context containing them to `entry`
+    pub fn sample(
+        mut self,
+        doc: &str,
+        entry: &str,
+        context: &PropertyIdentifier,
+        position: &PropertyIdentifier,
+    ) -> Result<String, PyError> {
+        let Type::Struct(context_id) = property_type(context)? else {
+            return Err(PyError::InvalidRead(context.name().to_string()));
+        };
+        let Type::Struct(position_id) = property_type(position)? else {
+            return Err(PyError::InvalidRead(position.name().to_string()));
+        };
+
+        let components: Vec<_> = self
+            .names
+            .fields(position_id)?
+            .into_iter()
+            .map(|(_, field, _)| field)
+            .collect();
+        let params = components.join(", ");
+
+        self.line(format!("def sample({params}):"));
+        self.indent += 1;
+        self.line(format!("\"\"\"{doc}\"\"\""));
+        self.line(format!(
+            "{params} = np.broadcast_arrays({})",
+            components
+                .iter()
+                .map(|c| format!("_float({c})"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+
+        let local = self.declare(context);
+        let zero = self.zero(property_type(context)?)?;
+        self.line(format!("{local} = {zero}"));
+        self.line(format!(
+            "{local}[{:?}] = {}",
+            self.names.field(context_id, position)?,
+            dict(
+                components
+                    .iter()
+                    .map(|c| (c.to_string(), c.to_string()))
+                    .collect()
+            )
+        ));
+        self.line(format!(
+            "return _broadcast({entry}({local}), {}.shape)",
+            components.first().copied().unwrap_or("np")
+        ));
+
+        Ok(self.out)
+    }
+
+    /// Bring a property into scope, returning its Python name
+    fn declare(&mut self, prop: &PropertyIdentifier) -> String {
+        let name = local(prop, &mut self.taken);
+        self.locals.insert(prop.clone(), name.clone());
+        name
+    }
+
+    fn line(&mut self, line: impl AsRef<str>) {
+        for _ in 0..self.indent {
+            self.out += "    ";
+        }
+        self.out += line.as_ref();
+        self.out += "\n";
+    }
+
+    fn block(&mut self, Block(stmts): &Block) -> Result<(), PyError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    /// Lanes in which statements currently take effect, or `None` for all lanes
+    fn mask(&self, masks: &[String]) -> Option<String> {
+        masks
+            .iter()
+            .cloned()
+            .reduce(|acc, mask| format!("np.logical_and({acc}, {mask})"))
+    }
+
+    /// Store a value in the active lanes of a place
+    fn store(&mut self, target: &str, value: &Operand) {
+        let value = value.value();
+        match self.mask(&self.masks) {
+            Some(mask) => self.line(format!("{target} = _where({mask}, {value}, {target})")),
+            None => self.line(format!("{target} = {value}")),
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), PyError> {
+        match stmt {
+            Stmt::Block(block) => self.block(block)?,
+            Stmt::Bind { prop, expr } => {
+                let value = self.expr(expr)?;
+                let value = self.cast(value, property_type(prop)?);
+                let local = self.locals[prop].clone();
+                self.store(&local, &value);
+            }
+            Stmt::Write { path, expr } => {
+                let value = self.expr(expr)?;
+                let target = self.path(path)?;
+                let value = self.cast(value, &target.ty);
+                self.store(&target.code, &value);
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let cond = self.expr(cond)?;
+                if cond.ty != Type::Boolean {
+                    return Err(PyError::InvalidStmt(format!("If {}", cond.ty.name())));
+                }
+
+                // Both masks are taken before either branch can change the condition
+                let then_mask = self.temp_name("m");
+                self.line(format!("{then_mask} = {}", cond.code));
+                let else_mask = match otherwise {
+                    Some(_) => {
+                        let else_mask = self.temp_name("m");
+                        self.line(format!("{else_mask} = np.logical_not({then_mask})"));
+                        Some(else_mask)
+                    }
+                    None => None,
+                };
+
+                self.masks.push(then_mask);
+                self.stmt(then)?;
+                self.masks.pop();
+
+                if let (Some(otherwise), Some(else_mask)) = (otherwise, else_mask) {
+                    self.masks.push(else_mask);
+                    self.stmt(otherwise)?;
+                    self.masks.pop();
+                }
+            }
+            Stmt::Loop { stmt } => {
+                let active = self.temp_name("a");
+                let init = self.mask(&self.masks).unwrap_or_else(|| "True".to_string());
+                self.line(format!("{active} = {init}"));
+                self.line(format!("while np.any({active}):"));
+                self.indent += 1;
+
+                self.loops.push(self.masks.len());
+                self.masks.push(active);
+
+                let len = self.out.len();
+                self.stmt(stmt)?;
+                if self.out.len() == len {
+                    self.line("pass");
+                }
+
+                self.masks.pop();
+                self.loops.pop();
+                self.indent -= 1;
+            }
+            Stmt::Break => {
+                let index = *self
+                    .loops
+                    .last()
+                    .ok_or_else(|| PyError::InvalidStmt("Break outside of Loop".into()))?;
+                let active = self.masks[index].clone();
+                let breaking = match self.mask(&self.masks[index + 1..]) {
+                    Some(mask) => format!("np.logical_not({mask})"),
+                    None => "False".to_string(),
+                };
+                self.line(format!("{active} = np.logical_and({active}, {breaking})"));
+            }
+            Stmt::Output(expr) => {
+                let value = self.expr(expr)?;
+                self.store("out", &value);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Operand, PyError> {
+        Ok(match expr {
+            Expr::Literal(value) => Operand::new(self.literal(value)?, value_type(value)),
+            Expr::Struct(id, members) => {
+                let mut out = vec![];
+                for (prop, field, ty) in self.names.fields(id)? {
+                    out.push((
+                        field.to_string(),
+                        match members.get(prop) {
+                            Some(expr) => {
+                                let value = self.expr(expr)?;
+                                self.cast(value, ty).value()
+                            }
+                            None => self.zero(ty)?,
+                        },
+                    ));
+                }
+                self.temp(Type::Struct(id.clone()), dict(out))
+            }
+            Expr::Read(path) => self.path(path)?,
+            Expr::Call { function, args } => {
+                let def = self
+                    .functions
+                    .get(function)
+                    .ok_or_else(|| PyError::MissingFunction(function.name_unique()))?;
+
+                if args.len() != def.inputs.len() {
+                    return Err(PyError::InvalidExpr(format!(
+                        "{} takes {} arguments, got {}",
+                        function.name_unique(),
+                        def.inputs.len(),
+                        args.len()
+                    )));
+                }
+
+                let mut codes = vec![];
+                for (arg, input) in args.iter().zip(def.inputs.iter()) {
+                    let arg = self.expr(arg)?;
+                    codes.push(self.cast(arg, property_type(&input.id)?).value());
+                }
+
+                let code = format!("{}({})", self.names.function(function)?, codes.join(", "));
+                self.operand(property_type(&def.output)?.clone(), code)
+            }
+            Expr::Neg(t) => self.unary(Unary::Neg, t)?,
+            Expr::Abs(t) => self.unary(Unary::Abs, t)?,
+            Expr::Sign(t) => self.unary(Unary::Sign, t)?,
+            Expr::Round(t) => self.unary(Unary::Round, t)?,
+            Expr::Sin(t) => self.unary(Unary::Sin, t)?,
+            Expr::Cos(t) => self.unary(Unary::Cos, t)?,
+            Expr::Tan(t) => self.unary(Unary::Tan, t)?,
+            Expr::Asin(t) => self.unary(Unary::Asin, t)?,
+            Expr::Acos(t) => self.unary(Unary::Acos, t)?,
+            Expr::Atan(t) => self.unary(Unary::Atan, t)?,
+            Expr::Exp2(t) => self.unary(Unary::Exp2, t)?,
+            Expr::Log2(t) => self.unary(Unary::Log2, t)?,
+            Expr::Length(t) => match self.expr(t)? {
+                t @ Operand {
+                    ty: Type::Struct(_),
+                    ..
+                } => {
+                    let dot = self.dot(&t, &t)?;
+                    Operand::new(format!("np.sqrt({dot})"), Type::Number(NumericType::Float))
+                }
+                t => self.map_unary(Unary::Abs, t)?,
+            },
+            Expr::Normalize(t) => match self.expr(t)? {
+                t @ Operand {
+                    ty: Type::Struct(_),
+                    ..
+                } => self.normalize(t)?,
+                t => self.map_unary(Unary::Sign, t)?,
+            },
+            Expr::Add(lhs, rhs) => self.binary(Binary::Add, lhs, rhs)?,
+            Expr::Sub(lhs, rhs) => self.binary(Binary::Sub, lhs, rhs)?,
+            Expr::Mul(lhs, rhs) => self.binary(Binary::Mul, lhs, rhs)?,
+            Expr::Div(lhs, rhs) => self.binary(Binary::Div, lhs, rhs)?,
+            Expr::Mod(lhs, rhs) => self.binary(Binary::Mod, lhs, rhs)?,
+            Expr::Min(lhs, rhs) => self.binary(Binary::Min, lhs, rhs)?,
+            Expr::Max(lhs, rhs) => self.binary(Binary::Max, lhs, rhs)?,
+            Expr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Operand::new(self.equal(&lhs, &rhs)?, Type::Boolean)
+            }
+            Expr::Ne(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Operand::new(
+                    format!("np.logical_not({})", self.equal(&lhs, &rhs)?),
+                    Type::Boolean,
+                )
+            }
+            Expr::Lt(lhs, rhs) => self.compare("<", lhs, rhs)?,
+            Expr::Gt(lhs, rhs) => self.compare(">", lhs, rhs)?,
+            Expr::And(lhs, rhs) => self.logic("np.logical_and", lhs, rhs)?,
+            Expr::Or(lhs, rhs) => self.logic("np.logical_or", lhs, rhs)?,
+            Expr::Dot(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (&lhs.ty, &rhs.ty) {
+                    (Type::Number(_), Type::Number(_)) => self.map_binary(Binary::Mul, lhs, rhs)?,
+                    _ => Operand::new(self.dot(&lhs, &rhs)?, Type::Number(NumericType::Float)),
+                }
+            }
+            Expr::Atan2(lhs, rhs) => {
+                let (y, x) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (&y.ty, &x.ty) {
+                    (Type::Number(NumericType::Float), Type::Number(NumericType::Float)) => {
+                        Operand::new(
+                            format!("np.arctan2({}, {})", y.code, x.code),
+                            Type::Number(NumericType::Float),
+                        )
+                    }
+                    _ => return Err(invalid_binary("Atan2", &y, &x)),
+                }
+            }
+            Expr::Mix(lhs, rhs, t) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let t = self.expr(t)?;
+                if t.ty != Type::Number(NumericType::Float) {
+                    return Err(PyError::InvalidExpr(format!("Mix factor {}", t.ty.name())));
+                }
+                let t = self.share(t);
+
+                self.map(&[lhs, rhs, t], |_, args| {
+                    let [a, b, t] = args else { unreachable!() };
+                    Ok(format!("((1.0 - {t}) * {a} + {t} * {b})"))
+                })?
+            }
+            Expr::Clamp(t, min, max) => {
+                let (t, min, max) = (self.expr(t)?, self.expr(min)?, self.expr(max)?);
+                self.map(&[t, min, max], |ty, args| {
+                    let [t, min, max] = args else { unreachable!() };
+                    Ok(match ty {
+                        Type::Number(NumericType::Float) => {
+                            format!("np.fmin(np.fmax({t}, {min}), {max})")
+                        }
+                        Type::Number(_) => {
+                            format!("np.minimum(np.maximum({t}, {min}), {max})")
+                        }
+                        ty => return Err(PyError::InvalidExpr(format!("Clamp({})", ty.name()))),
+                    })
+                })?
+            }
+        })
+    }
+
+    /// Find the local or member at the end of a path
+    fn path(&self, path: &[PropertyIdentifier]) -> Result<Operand, PyError> {
+        let path_name = || {
+            path.iter()
+                .map(|prop| prop.name())
+                .collect::<Vec<_>>()
+                .join(".")
+        };
+
+        let (root, rest) = path
+            .split_first()
+            .ok_or_else(|| PyError::InvalidRead(path_name()))?;
+
+        let mut code = self
+            .locals
+            .get(root)
+            .ok_or_else(|| PyError::InvalidRead(path_name()))?
+            .clone();
+        let mut ty = property_type(root)?;
+
+        for prop in rest {
+            let Type::Struct(id) = ty else {
+                return Err(PyError::InvalidRead(path_name()));
+            };
+            code += &format!("[{:?}]", self.names.field(id, prop)?);
+            ty = field_type(id, prop)?;
+        }
+
+        Ok(Operand {
+            code,
+            ty: ty.clone(),
+            place: true,
+        })
+    }
+
+    fn literal(&self, value: &Value) -> Result<String, PyError> {
+        Ok(match value {
+            Value::Boolean(true) => "True".to_string(),
+            Value::Boolean(false) => "False".to_string(),
+            Value::Number(Number::UInt(n)) => n.to_string(),
+            Value::Number(Number::SInt(n)) if *n < 0 => format!("({n})"),
+            Value::Number(Number::SInt(n)) => n.to_string(),
+            Value::Number(Number::Float(n)) => float_literal(*n),
+            Value::Struct(s) => {
+                let mut out = vec![];
+                for (prop, field, ty) in self.names.fields(&s.id)? {
+                    out.push((
+                        field.to_string(),
+                        match s.members.get(prop) {
+                            Some(value) => self.literal(value)?,
+                            None => self.zero(ty)?,
+                        },
+                    ));
+                }
+                dict(out)
+            }
+        })
+    }
+
+    /// Zero value of a type
+    fn zero(&self, ty: &Type) -> Result<String, PyError> {
+        Ok(match ty {
+            Type::Boolean => "False".to_string(),
+            Type::Number(NumericType::Float) => "0.0".to_string(),
+            Type::Number(_) => "0".to_string(),
+            Type::Struct(id) => dict(
+                self.names
+                    .fields(id)?
+                    .into_iter()
+                    .map(|(_, field, ty)| Ok((field.to_string(), self.zero(ty)?)))
+                    .collect::<Result<Vec<_>, PyError>>()?,
+            ),
+        })
+    }
+
+    /// Convert a scalar to the numeric type of the place it's stored in
+    fn cast(&self, value: Operand, ty: &Type) -> Operand {
+        match (&value.ty, ty) {
+            (Type::Number(NumericType::Float), Type::Number(NumericType::SInt))
+            | (Type::Number(NumericType::Float), Type::Number(NumericType::UInt)) => {
+                Operand::new(format!("_int({})", value.code), ty.clone())
+            }
+            (Type::Number(NumericType::SInt), Type::Number(NumericType::Float))
+            | (Type::Number(NumericType::UInt), Type::Number(NumericType::Float)) => {
+                Operand::new(format!("_float({})", value.code), ty.clone())
+            }
+            _ => value,
+        }
+    }
+
+    /// Wrap the result of an expression,
+    /// binding it to a temporary if it's a struct
+    fn operand(&mut self, ty: Type, code: String) -> Operand {
+        if let Type::Struct(_) = ty {
+            self.temp(ty, code)
+        } else {
+            Operand::new(code, ty)
+        }
+    }
+
+    fn temp_name(&mut self, prefix: &str) -> String {
+        let name = format!("_{prefix}{}", self.temps);
+        self.temps += 1;
+        name
+    }
+
+    fn temp(&mut self, ty: Type, init: String) -> Operand {
+        let name = self.temp_name("t");
+        self.line(format!("{name} = {init}"));
+        Operand::new(name, ty)
+    }
+
+    /// Bind an operand to a temporary if it would be expensive to repeat
+    fn share(&mut self, operand: Operand) -> Operand {
+        if operand
+            .code
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '[' | ']' | '"'))
+        {
+            operand
+        } else {
+            self.temp(operand.ty, operand.code)
+        }
+    }
+
+    /// Apply `f` to corresponding scalar leaves of a set of operands
+    ///
+    /// Struct operands must share a type, and are mapped memberwise,
+    /// while scalar operands are broadcast to each member.
+    fn map(
+        &mut self,
+        operands: &[Operand],
+        mut f: impl FnMut(&Type, &[String]) -> Result<String, PyError>,
+    ) -> Result<Operand, PyError> {
+        let structs: Vec<_> = operands
+            .iter()
+            .filter(|operand| matches!(operand.ty, Type::Struct(_)))
+            .collect();
+
+        let Some(first) = structs.first() else {
+            let ty = numeric_type(operands);
+            let codes: Vec<_> = operands.iter().map(|op| op.code.clone()).collect();
+            let code = f(&ty, &codes)?;
+            return Ok(Operand::new(code, ty));
+        };
+
+        let ty = first.ty.clone();
+        if structs.iter().any(|operand| operand.ty != ty) {
+            return Err(PyError::InvalidExpr(format!(
+                "Mismatched operands {}",
+                operands
+                    .iter()
+                    .map(|operand| operand.ty.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+
+        let mut shared = vec![];
+        for operand in operands {
+            shared.push(self.share(operand.clone()));
+        }
+
+        let init = self.map_leaves(&ty, &shared, &mut f)?;
+        Ok(self.temp(ty, init))
+    }
+
+    fn map_leaves(
+        &self,
+        ty: &Type,
+        operands: &[Operand],
+        f: &mut impl FnMut(&Type, &[String]) -> Result<String, PyError>,
+    ) -> Result<String, PyError> {
+        let Type::Struct(id) = ty else {
+            let codes: Vec<_> = operands.iter().map(|op| op.code.clone()).collect();
+            return f(ty, &codes);
+        };
+
+        let mut members = vec![];
+        for (_, field, field_ty) in self.names.fields(id)? {
+            let operands: Vec<_> = operands
+                .iter()
+                .map(|operand| match &operand.ty {
+                    Type::Struct(cand) if cand == id => {
+                        Operand::new(format!("{}[{field:?}]", operand.code), field_ty.clone())
+                    }
+                    _ => operand.clone(),
+                })
+                .collect();
+            members.push((field.to_string(), self.map_leaves(field_ty, &operands, f)?));
+        }
+
+        Ok(dict(members))
+    }
+
+    fn unary(&mut self, op: Unary, t: &Expr) -> Result<Operand, PyError> {
+        let t = self.expr(t)?;
+        self.map_unary(op, t)
+    }
+
+    fn map_unary(&mut self, op: Unary, t: Operand) -> Result<Operand, PyError> {
+        self.map(&[t], |ty, args| {
+            let [t] = args else { unreachable!() };
+            let Type::Number(n) = ty else {
+                return Err(PyError::InvalidExpr(format!("{op:?}({})", ty.name())));
+            };
+
+            Ok(match (op, n) {
+                (Unary::Neg, NumericType::Float | NumericType::SInt) => format!("(-{t})"),
+                (Unary::Abs, NumericType::Float | NumericType::SInt) => format!("np.abs({t})"),
+                (Unary::Sign, NumericType::Float) => format!("_sign({t})"),
+                (Unary::Sign, NumericType::SInt) => format!("np.sign({t})"),
+                (Unary::Round, NumericType::Float) => format!("_round({t})"),
+                (Unary::Sin, NumericType::Float) => format!("np.sin({t})"),
+                (Unary::Cos, NumericType::Float) => format!("np.cos({t})"),
+                (Unary::Tan, NumericType::Float) => format!("np.tan({t})"),
+                (Unary::Asin, NumericType::Float) => format!("np.arcsin({t})"),
+                (Unary::Acos, NumericType::Float) => format!("np.arccos({t})"),
+                (Unary::Atan, NumericType::Float) => format!("np.arctan({t})"),
+                (Unary::Exp2, NumericType::Float) => format!("np.exp2({t})"),
+                (Unary::Log2, NumericType::Float) => format!("np.log2({t})"),
+                _ => return Err(PyError::InvalidExpr(format!("{op:?}({})", ty.name()))),
+            })
+        })
+    }
+
+    fn binary(&mut self, op: Binary, lhs: &Expr, rhs: &Expr) -> Result<Operand, PyError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        self.map_binary(op, lhs, rhs)
+    }
+
+    /// Apply a binary operator to a pair of operands
+    ///
+    /// Operations between a struct and a number apply to each member of the struct,
+    /// and operations between structs of the same type apply memberwise,
+    /// except for matrix multiplication.
+    fn map_binary(&mut self, op: Binary, lhs: Operand, rhs: Operand) -> Result<Operand, PyError> {
+        if let (Type::Struct(id), Type::Struct(_)) = (&lhs.ty, &rhs.ty) {
+            if op == Binary::Mul && is_matrix(id) {
+                return self.matrix_mul(lhs, rhs);
+            }
+        }
+
+        self.map(&[lhs, rhs], |ty, args| {
+            let [x, y] = args else { unreachable!() };
+            let Type::Number(n) = ty else {
+                return Err(PyError::InvalidExpr(format!("{op:?}({})", ty.name())));
+            };
+
+            Ok(match (op, n) {
+                (Binary::Add, _) => format!("({x} + {y})"),
+                (Binary::Sub, _) => format!("({x} - {y})"),
+                (Binary::Mul, _) => format!("({x} * {y})"),
+                (Binary::Div, NumericType::Float) => format!("({x} / {y})"),
+                (Binary::Div, _) => format!("_div_i({x}, {y})"),
+                (Binary::Mod, _) => format!("_rem({x}, {y})"),
+                (Binary::Min, NumericType::Float) => format!("np.fmin({x}, {y})"),
+                (Binary::Min, _) => format!("np.minimum({x}, {y})"),
+                (Binary::Max, NumericType::Float) => format!("np.fmax({x}, {y})"),
+                (Binary::Max, _) => format!("np.maximum({x}, {y})"),
+            })
+        })
+    }
+
+    /// Multiply a column-major matrix by a vector or another matrix
+    fn matrix_mul(&mut self, lhs: Operand, rhs: Operand) -> Result<Operand, PyError> {
+        let Type::Struct(lhs_id) = &lhs.ty else {
+            unreachable!()
+        };
+        let Type::Struct(rhs_id) = &rhs.ty else {
+            unreachable!()
+        };
+
+        let columns = self
+            .names
+            .fields(lhs_id)?
+            .into_iter()
+            .map(|(_, field, ty)| self.float_leaves(&format!("{}[{field:?}]", lhs.code), ty))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let init = if is_matrix(rhs_id) {
+            let mut out = vec![];
+            for (_, field, ty) in self.names.fields(rhs_id)? {
+                let components = self.float_leaves(&format!("{}[{field:?}]", rhs.code), ty)?;
+                let Type::Struct(column_id) = ty else {
+                    return Err(PyError::InvalidExpr("Matrix multiplication".into()));
+                };
+                out.push((
+                    field.to_string(),
+                    self.matrix_vector_mul(&columns, &components, column_id)?,
+                ));
+            }
+            dict(out)
+        } else {
+            let components = self.float_leaves(&rhs.code, &rhs.ty)?;
+            self.matrix_vector_mul(&columns, &components, rhs_id)?
+        };
+
+        let ty = if is_matrix(rhs_id) { lhs.ty } else { rhs.ty };
+        Ok(self.temp(ty, init))
+    }
+
+    fn matrix_vector_mul(
+        &self,
+        columns: &[Vec<String>],
+        components: &[String],
+        vector: &StructIdentifier,
+    ) -> Result<String, PyError> {
+        let fields = self.names.fields(vector)?;
+        if columns.len() != components.len()
+            || columns.iter().any(|c| c.len() != columns.len())
+            || fields.len() != columns.len()
+        {
+            return Err(PyError::InvalidExpr("Matrix multiplication".into()));
+        }
+
+        let rows = fields
+            .iter()
+            .enumerate()
+            .map(|(row, (_, field, _))| {
+                (
+                    field.to_string(),
+                    format!(
+                        "({})",
+                        columns
+                            .iter()
+                            .zip(components)
+                            .map(|(column, component)| format!("{} * {component}", column[row]))
+                            .collect::<Vec<_>>()
+                            .join(" + ")
+                    ),
+                )
+            })
+            .collect();
+
+        Ok(dict(rows))
+    }
+
+    /// Accessors for the leaves of a value made up entirely of floats
+    fn float_leaves(&self, code: &str, ty: &Type) -> Result<Vec<String>, PyError> {
+        let leaves = self.leaves(code, ty)?;
+        if leaves
+            .iter()
+            .any(|(_, ty)| *ty != Type::Number(NumericType::Float))
+        {
+            return Err(PyError::InvalidExpr(format!(
+                "{} is not a float vector",
+                ty.name()
+            )));
+        }
+        Ok(leaves.into_iter().map(|(code, _)| code).collect())
+    }
+
+    /// Accessors for the scalar leaves of a value, ordered depth-first
+    fn leaves(&self, code: &str, ty: &Type) -> Result<Vec<(String, Type)>, PyError> {
+        let Type::Struct(id) = ty else {
+            return Ok(vec![(code.to_string(), ty.clone())]);
+        };
+
+        let mut out = vec![];
+        for (_, field, ty) in self.names.fields(id)? {
+            out.extend(self.leaves(&format!("{code}[{field:?}]"), ty)?);
+        }
+        Ok(out)
+    }
+
+    fn dot(&self, lhs: &Operand, rhs: &Operand) -> Result<String, PyError> {
+        if lhs.ty != rhs.ty {
+            return Err(invalid_binary("Dot", lhs, rhs));
+        }
+
+        let a = self.float_leaves(&lhs.code, &lhs.ty)?;
+        let b = self.float_leaves(&rhs.code, &rhs.ty)?;
+        if a.is_empty() {
+            return Err(invalid_binary("Dot", lhs, rhs));
+        }
+
+        Ok(format!(
+            "({})",
+            a.iter()
+                .zip(b.iter())
+                .map(|(a, b)| format!("{a} * {b}"))
+                .collect::<Vec<_>>()
+                .join(" + ")
+        ))
+    }
+
+    /// Matches glam's `normalize_or_zero`
+    fn normalize(&mut self, v: Operand) -> Result<Operand, PyError> {
+        let dot = self.dot(&v, &v)?;
+        let rcp = self.temp(
+            Type::Number(NumericType::Float),
+            format!("np.divide(1.0, np.sqrt({dot}))"),
+        );
+        let valid = self.temp(
+            Type::Boolean,
+            format!("np.logical_and(np.isfinite({0}), {0} > 0.0)", rcp.code),
+        );
+
+        self.map(&[v, rcp, valid], |_, args| {
+            let [x, rcp, valid] = args else {
+                unreachable!()
+            };
+            Ok(format!("np.where({valid}, {x} * {rcp}, 0.0)"))
+        })
+    }
+
+    fn equal(&self, lhs: &Operand, rhs: &Operand) -> Result<String, PyError> {
+        let compatible = match (&lhs.ty, &rhs.ty) {
+            (Type::Number(_), Type::Number(_)) => true,
+            (a, b) => a == b,
+        };
+        if !compatible {
+            return Err(invalid_binary("Eq", lhs, rhs));
+        }
+
+        let a = self.leaves(&lhs.code, &lhs.ty)?;
+        let b = self.leaves(&rhs.code, &rhs.ty)?;
+
+        Ok(a.iter()
+            .zip(b.iter())
+            .map(|((a, _), (b, _))| format!("({a} == {b})"))
+            .reduce(|acc, eq| format!("np.logical_and({acc}, {eq})"))
+            .unwrap_or_else(|| "True".to_string()))
+    }
+
+    fn compare(&mut self, op: &str, lhs: &Expr, rhs: &Expr) -> Result<Operand, PyError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        match (&lhs.ty, &rhs.ty) {
+            (Type::Number(_), Type::Number(_)) => Ok(Operand::new(
+                format!("({} {op} {})", lhs.code, rhs.code),
+                Type::Boolean,
+            )),
+            _ => Err(invalid_binary(op, &lhs, &rhs)),
+        }
+    }
+
+    fn logic(&mut self, function: &str, lhs: &Expr, rhs: &Expr) -> Result<Operand, PyError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        match (&lhs.ty, &rhs.ty) {
+            (Type::Boolean, Type::Boolean) => Ok(Operand::new(
+                format!("{function}({}, {})", lhs.code, rhs.code),
+                Type::Boolean,
+            )),
+            _ => Err(invalid_binary(function, &lhs, &rhs)),
+        }
+    }
+}
+
+/// Properties bound or written anywhere within a block, in order of first appearance
+fn block_locals(Block(stmts): &Block, out: &mut Vec<PropertyIdentifier>) {
+    for stmt in stmts {
+        stmt_locals(stmt, out);
+    }
+}
+
+fn stmt_locals(stmt: &Stmt, out: &mut Vec<PropertyIdentifier>) {
+    let mut push = |prop: &PropertyIdentifier| {
+        if !out.contains(prop) {
+            out.push(prop.clone())
+        }
+    };
+
+    match stmt {
+        Stmt::Block(block) => block_locals(block, out),
+        Stmt::Bind { prop, .. } => push(prop),
+        Stmt::Write { path, .. } => {
+            if let Some(root) = path.first() {
+                push(root)
+            }
+        }
+        Stmt::If {
+            then, otherwise, ..
+        } => {
+            stmt_locals(then, out);
+            if let Some(otherwise) = otherwise {
+                stmt_locals(otherwise, out);
+            }
+        }
+        Stmt::Loop { stmt } => stmt_locals(stmt, out),
+        Stmt::Break | Stmt::Output(_) => (),
+    }
+}
+
+/// Dict display with string keys
+pub(crate) fn dict(members: Vec<(String, String)>) -> String {
+    format!(
+        "{{{}}}",
+        members
+            .into_iter()
+            .map(|(key, value)| format!("{key:?}: {value}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn float_literal(n: f64) -> String {
+    if n.is_nan() {
+        "np.nan".to_string()
+    } else if n.is_infinite() {
+        if n > 0.0 {
+            "np.inf".to_string()
+        } else {
+            "(-np.inf)".to_string()
+        }
+    } else if n.is_sign_negative() {
+        format!("({n:?})")
+    } else {
+        format!("{n:?}")
+    }
+}
+
+fn value_type(value: &Value) -> Type {
+    match value {
+        Value::Boolean(_) => Type::Boolean,
+        Value::Number(Number::UInt(_)) => Type::Number(NumericType::UInt),
+        Value::Number(Number::SInt(_)) => Type::Number(NumericType::SInt),
+        Value::Number(Number::Float(_)) => Type::Number(NumericType::Float),
+        Value::Struct(s) => Type::Struct(s.id.clone()),
+    }
+}
+
+/// Type resulting from arithmetic between scalars,
+/// following numpy's promotion of integers to floats
+fn numeric_type(operands: &[Operand]) -> Type {
+    if operands
+        .iter()
+        .any(|operand| operand.ty == Type::Number(NumericType::Float))
+    {
+        Type::Number(NumericType::Float)
+    } else {
+        operands
+            .first()
+            .map(|operand| operand.ty.clone())
+            .unwrap_or(Type::Number(NumericType::Float))
+    }
+}
+
+fn is_matrix(id: &StructIdentifier) -> bool {
+    matches!(id.name(), "Matrix2" | "Matrix3" | "Matrix4")
+}
+
+fn invalid_binary(op: &str, lhs: &Operand, rhs: &Operand) -> PyError {
+    PyError::InvalidExpr(format!("{op}({}, {})", lhs.ty.name(), rhs.ty.name()))
+}
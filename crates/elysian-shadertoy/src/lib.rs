@@ -1,99 +1,309 @@
 //! Convert Elysian IR into Shadertoy syntax via `elysian-naga`
 
-use std::error::Error;
+mod writer;
 
-use elysian_ir::module::Module as ElysianModule;
-use elysian_naga::NagaBuilder;
+pub use writer::*;
+
+use std::{error::Error, fmt::Display};
+
+use elysian_core::identifier::Identifier;
+use elysian_decl_macros::elysian_function;
+use elysian_ir::{
+    ast::{COLOR, POSITION_2D, TIME, VECTOR2, VECTOR3, VECTOR4, X, Y},
+    module::{FunctionIdentifier, Module as ElysianModule, StructIdentifier, Type, CONTEXT},
+    property,
+};
+use elysian_naga::{
+    EntryPointDefinition, EntryPointInput, EntryPointOutput, NagaBuildError, NagaBuilder,
+};
+use elysian_proc_macros::elysian_stmt;
+use elysian_shapes::modify::ASPECT;
 use naga::{
-    back::glsl::{Options, PipelineOptions, WriterFlags},
-    proc::{BoundsCheckPolicies, BoundsCheckPolicy},
     valid::{Capabilities, ModuleInfo, ValidationFlags},
-    Module as NagaModule, ShaderStage,
+    Binding, Module as NagaModule, ShaderStage,
 };
 
-pub fn module_to_shadertoy(module: &ElysianModule) -> Result<String, Box<dyn Error>> {
-    let (naga_module, module_info) =
-        NagaBuilder::new(module).build(ValidationFlags::all(), Capabilities::default())?;
-    Ok(naga_to_shadertoy(&naga_module, &module_info)?)
+pub const FRAG_COORD: Identifier = Identifier::new("frag_coord", 4909349360752593233);
+property!(
+    FRAG_COORD,
+    FRAG_COORD_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const RESOLUTION: Identifier = Identifier::new("resolution", 2103195845728766093);
+property!(
+    RESOLUTION,
+    RESOLUTION_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+pub const MOUSE: Identifier = Identifier::new("mouse", 13680209852164583308);
+property!(MOUSE, MOUSE_PROP, Type::Struct(StructIdentifier(VECTOR4)));
+
+pub const SHADERTOY_MAIN: FunctionIdentifier =
+    FunctionIdentifier::new("shadertoy_main", 8389094526781468158);
+
+/// Name of the `naga` entry point wrapping [`SHADERTOY_MAIN`]
+pub const SHADERTOY_ENTRY_POINT: &str = "shadertoy_main";
+
+/// Shadertoy-provided inputs, and the properties they are exposed as
+///
+/// `TIME` is shared with the rest of Elysian,
+/// and can be read from the context by any shape.
+pub const SHADERTOY_INPUTS: &[(Identifier, &str)] = &[
+    (FRAG_COORD, "fragCoord"),
+    (RESOLUTION, "iResolution"),
+    (TIME, "iTime"),
+    (MOUSE, "iMouse"),
+];
+
+#[derive(Debug)]
+pub enum ShadertoyError {
+    Build(NagaBuildError),
+    Format(std::fmt::Error),
+    MissingEntryPoint(String),
+    UnboundInput(String),
+    Unsupported(String),
+}
+
+impl Display for ShadertoyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShadertoyError::Build(e) => f.write_str(&format!("Failed to build naga module: {e}")),
+            ShadertoyError::Format(e) => f.write_str(&format!("Failed to write GLSL: {e}")),
+            ShadertoyError::MissingEntryPoint(name) => {
+                f.write_str(&format!("Missing entry point {name}"))
+            }
+            ShadertoyError::UnboundInput(name) => f.write_str(&format!(
+                "No Shadertoy input for entry point argument {name}"
+            )),
+            ShadertoyError::Unsupported(desc) => {
+                f.write_str(&format!("Unsupported by Shadertoy backend: {desc}"))
+            }
+        }
+    }
+}
+
+impl Error for ShadertoyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ShadertoyError::Build(e) => Some(e),
+            ShadertoyError::Format(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<NagaBuildError> for ShadertoyError {
+    fn from(value: NagaBuildError) -> Self {
+        ShadertoyError::Build(value)
+    }
+}
+
+impl From<std::fmt::Error> for ShadertoyError {
+    fn from(value: std::fmt::Error) -> Self {
+        ShadertoyError::Format(value)
+    }
 }
 
+/// Wrap a module's entry point in [`SHADERTOY_MAIN`],
+/// which maps Shadertoy inputs onto the context and returns its color.
+///
+/// Expects a module that has not yet been finalized,
+/// so the Shadertoy properties are included in its context struct.
+pub fn shadertoy_module(module: &ElysianModule) -> ElysianModule {
+    let entry_call = module.call(elysian_stmt! { CONTEXT });
+
+    let main = elysian_function! {
+        fn SHADERTOY_MAIN(FRAG_COORD, RESOLUTION, TIME, MOUSE) -> COLOR {
+            let CONTEXT = CONTEXT {};
+            CONTEXT.POSITION_2D = (
+                (FRAG_COORD / VECTOR2 { X: RESOLUTION.X, Y: RESOLUTION.Y }) * 2.0
+                    - VECTOR2 { X: 1.0, Y: 1.0 }
+            ) * 2.0;
+            CONTEXT.ASPECT = RESOLUTION.X / RESOLUTION.Y;
+            CONTEXT.TIME = TIME;
+            CONTEXT.RESOLUTION = RESOLUTION;
+            CONTEXT.MOUSE = MOUSE;
+            let CONTEXT = #entry_call;
+            return CONTEXT.COLOR;
+        }
+    };
+
+    let mut module = module.clone();
+    module.function_definitions.push(main);
+    module.finalize()
+}
+
+pub fn module_to_shadertoy(module: &ElysianModule) -> Result<String, ShadertoyError> {
+    let module = shadertoy_module(module);
+
+    let (naga_module, module_info) = NagaBuilder::new(&module)
+        .entry_point(EntryPointDefinition {
+            name: SHADERTOY_ENTRY_POINT.to_string(),
+            stage: ShaderStage::Fragment,
+            function: SHADERTOY_MAIN,
+            inputs: (0..SHADERTOY_INPUTS.len() as u32)
+                .map(|location| {
+                    EntryPointInput::Binding(Binding::Location {
+                        location,
+                        interpolation: None,
+                        sampling: None,
+                    })
+                })
+                .collect(),
+            output: EntryPointOutput::Binding(Binding::Location {
+                location: 0,
+                interpolation: None,
+                sampling: None,
+            }),
+        })
+        .build(ValidationFlags::all(), Capabilities::default())?;
+
+    naga_to_shadertoy(&naga_module, &module_info)
+}
+
+/// Write a `naga` module as Shadertoy GLSL
+///
+/// All functions are emitted verbatim, followed by a `mainImage`
+/// that calls the [`SHADERTOY_ENTRY_POINT`] entry point.
+/// Its arguments are bound to Shadertoy inputs by name via [`SHADERTOY_INPUTS`].
 pub fn naga_to_shadertoy(
     naga_module: &NagaModule,
     module_info: &ModuleInfo,
-) -> Result<String, naga::back::glsl::Error> {
-    let mut buf = String::default();
-
-    let options = Options {
-        version: naga::back::glsl::Version::Embedded {
-            version: 310,
-            is_webgl: true,
-        },
-        writer_flags: WriterFlags::INCLUDE_UNUSED_ITEMS,
-        binding_map: Default::default(),
-        zero_initialize_workgroup_memory: false,
-    };
+) -> Result<String, ShadertoyError> {
+    let (index, entry_point) = naga_module
+        .entry_points
+        .iter()
+        .enumerate()
+        .find(|(_, ep)| ep.name == SHADERTOY_ENTRY_POINT)
+        .ok_or_else(|| ShadertoyError::MissingEntryPoint(SHADERTOY_ENTRY_POINT.to_string()))?;
+
+    let args = entry_point
+        .function
+        .arguments
+        .iter()
+        .map(|arg| {
+            let name = arg.name.clone().unwrap_or_default();
+            SHADERTOY_INPUTS
+                .iter()
+                .find(|(id, _)| id.name() == name)
+                .map(|(_, input)| *input)
+                .ok_or(ShadertoyError::UnboundInput(name))
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .join(", ");
+
+    let mut writer = Writer::new(String::default(), naga_module, module_info);
+    writer.write()?;
+    let entry_point_name = writer.entry_point_name(index).to_string();
+    let mut buf = writer.finish();
+
+    buf += &format!(
+        "void mainImage(out vec4 fragColor, in vec2 fragCoord) {{\n    fragColor = {entry_point_name}({args});\n}}\n"
+    );
 
-    let pipeline_options = PipelineOptions {
-        shader_stage: ShaderStage::Fragment,
-        entry_point: "mainImage".to_string(),
-        multiview: None,
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_ir::module::{AsModule, SpecializationData};
+    use elysian_shapes::{color::distance_color, field::Circle, modify::IntoSet};
+    use naga::{
+        front::glsl::{Frontend, Options},
+        valid::Validator,
     };
 
-    let mut writer = naga::back::glsl::Writer::new(
-        &mut buf,
-        &naga_module,
-        &module_info,
-        &options,
-        &pipeline_options,
-        BoundsCheckPolicies {
-            index: BoundsCheckPolicy::Unchecked,
-            buffer: BoundsCheckPolicy::Unchecked,
-            image_load: BoundsCheckPolicy::Unchecked,
-            image_store: BoundsCheckPolicy::Unchecked,
-            binding_array: BoundsCheckPolicy::Unchecked,
-        },
-    )?;
+    use super::*;
 
-    writer.write()?;
+    /// Stand-in for the uniforms Shadertoy provides
+    const PRELUDE: &str = "#version 450
+layout(set = 0, binding = 0) uniform Shadertoy {
+    vec3 iResolution;
+    float iTime;
+    vec4 iMouse;
+};
+";
 
-    // Strip Shadertoy-incompatible GLSL, inline duplicated variables
-    let mut buf: Vec<_> = buf
-        .lines()
-        .flat_map(|line| {
-            if line.starts_with("precision")
-                | line.starts_with("#version")
-                | line.starts_with("layout")
-            {
-                None
-            } else if line.contains("fragColor_1") {
-                Some(line.replace("fragColor_1", "fragColor"))
-            } else {
-                Some(line.to_string())
-            }
-        })
-        .collect();
-
-    // Modify main function
-    let mut iter = buf
-        .iter_mut()
-        .skip_while(|line| !line.starts_with("void main"));
-
-    // Replace main signature
-    let main = iter.next().unwrap();
-    *main = "void mainImage(out vec4 fragColor, in vec2 fragCoord) {".to_string();
-
-    while let Some(line) = iter.next() {
-        if line.contains("Context") {
-            break;
-        } else {
-            *line = String::default();
-        }
+    /// Stand-in for the entry point Shadertoy wraps `mainImage` in
+    const MAIN: &str = "
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    mainImage(outColor, gl_FragCoord.xy);
+}
+";
+
+    fn circle() -> ElysianModule {
+        Circle::new(0.5)
+            .set_post(COLOR, distance_color(1.0))
+            .module(&SpecializationData::new_2d())
+    }
+
+    /// Parse and validate Shadertoy GLSL with naga's frontend
+    fn reparse(source: &str) {
+        let source = format!("{PRELUDE}{source}{MAIN}");
+
+        let module = Frontend::default()
+            .parse(&Options::from(ShaderStage::Fragment), &source)
+            .unwrap_or_else(|e| panic!("{e:?}\n{source}"));
+
+        Validator::new(ValidationFlags::all(), Capabilities::default())
+            .validate(&module)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_main_image() {
+        let source = module_to_shadertoy(&circle()).unwrap();
+        assert!(source.contains("void mainImage(out vec4 fragColor, in vec2 fragCoord)"));
+        assert!(!source.contains("#version"));
+        reparse(&source);
     }
 
-    // Concatenate
-    Ok(buf
-        .into_iter()
-        .filter(|line| !line.is_empty())
-        .map(|line| line + "\n")
-        .collect())
+    /// Sibling loops each declare their own continuing gate
+    ///
+    /// The empty `main` only satisfies naga's frontend, which requires an entry point.
+    #[test]
+    fn test_sibling_loops() {
+        let parse = |source: &str| {
+            let source = format!("#version 450\n{source}\nvoid main() {{}}\n");
+            let module = Frontend::default()
+                .parse(&Options::from(ShaderStage::Fragment), &source)
+                .unwrap_or_else(|e| panic!("{e:?}\n{source}"));
+            let info = Validator::new(ValidationFlags::all(), Capabilities::default())
+                .validate(&module)
+                .unwrap();
+            (module, info)
+        };
+
+        let (module, info) = parse(
+            "
+float loops(float x) {
+    for (int i = 0; i < 2; i++) { x += 1.0; }
+    for (int i = 0; i < 3; i++) { x *= 2.0; }
+    return x;
+}
+",
+        );
+
+        let mut writer = Writer::new(String::default(), &module, &info);
+        writer.write().unwrap();
+        let source = writer.finish();
+
+        assert!(source.contains("bool loop_init_0 = true;"));
+        assert!(source.contains("bool loop_init_1 = true;"));
+        parse(&source);
+    }
+
+    #[test]
+    fn test_inputs() {
+        let source = module_to_shadertoy(&circle()).unwrap();
+        let main_image = &source[source.find("void mainImage").unwrap()..];
+        for (_, input) in SHADERTOY_INPUTS {
+            assert!(main_image.contains(input), "{input} is not bound");
+        }
+        assert!(!source.contains("uniform"));
+    }
 }
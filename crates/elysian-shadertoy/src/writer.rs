@@ -0,0 +1,931 @@
+//! Minimal GLSL ES emitter for the function subset produced by `elysian-naga`
+//!
+//! Unlike `naga::back::glsl`, this never emits a `#version` directive,
+//! precision qualifiers, interface blocks or a `main` function,
+//! leaving the caller in control of the surrounding translation unit.
+
+use std::fmt::Write;
+
+use naga::{
+    proc::{NameKey, Namer},
+    valid::{FunctionInfo, ModuleInfo},
+    BinaryOperator, Block, Expression, FastHashMap, Function, Handle, Literal, LocalVariable,
+    MathFunction, Module, ScalarKind, Statement, SwizzleComponent, TypeInner, UnaryOperator,
+    VectorSize,
+};
+
+use crate::ShadertoyError;
+
+/// GLSL ES 3.00 keywords and built-in functions, plus the Shadertoy prelude
+pub const RESERVED_KEYWORDS: &[&str] = &[
+    // Keywords
+    "attribute",
+    "const",
+    "uniform",
+    "varying",
+    "buffer",
+    "shared",
+    "layout",
+    "centroid",
+    "flat",
+    "smooth",
+    "noperspective",
+    "patch",
+    "sample",
+    "break",
+    "continue",
+    "do",
+    "for",
+    "while",
+    "switch",
+    "case",
+    "default",
+    "if",
+    "else",
+    "in",
+    "out",
+    "inout",
+    "float",
+    "double",
+    "int",
+    "uint",
+    "void",
+    "bool",
+    "true",
+    "false",
+    "invariant",
+    "precise",
+    "discard",
+    "return",
+    "lowp",
+    "mediump",
+    "highp",
+    "precision",
+    "struct",
+    "main",
+    "mat2",
+    "mat3",
+    "mat4",
+    "mat2x2",
+    "mat2x3",
+    "mat2x4",
+    "mat3x2",
+    "mat3x3",
+    "mat3x4",
+    "mat4x2",
+    "mat4x3",
+    "mat4x4",
+    "vec2",
+    "vec3",
+    "vec4",
+    "ivec2",
+    "ivec3",
+    "ivec4",
+    "uvec2",
+    "uvec3",
+    "uvec4",
+    "bvec2",
+    "bvec3",
+    "bvec4",
+    "sampler2D",
+    "sampler3D",
+    "samplerCube",
+    "asm",
+    "class",
+    "union",
+    "enum",
+    "typedef",
+    "template",
+    "this",
+    "goto",
+    "inline",
+    "noinline",
+    "volatile",
+    "public",
+    "static",
+    "extern",
+    "external",
+    "interface",
+    "long",
+    "short",
+    "half",
+    "fixed",
+    "unsigned",
+    "superp",
+    "input",
+    "output",
+    "filter",
+    "sizeof",
+    "cast",
+    "namespace",
+    "using",
+    // Built-in functions
+    "radians",
+    "degrees",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "sinh",
+    "cosh",
+    "tanh",
+    "asinh",
+    "acosh",
+    "atanh",
+    "pow",
+    "exp",
+    "log",
+    "exp2",
+    "log2",
+    "sqrt",
+    "inversesqrt",
+    "abs",
+    "sign",
+    "floor",
+    "trunc",
+    "round",
+    "roundEven",
+    "ceil",
+    "fract",
+    "mod",
+    "modf",
+    "min",
+    "max",
+    "clamp",
+    "mix",
+    "step",
+    "smoothstep",
+    "isnan",
+    "isinf",
+    "floatBitsToInt",
+    "floatBitsToUint",
+    "intBitsToFloat",
+    "uintBitsToFloat",
+    "length",
+    "distance",
+    "dot",
+    "cross",
+    "normalize",
+    "faceforward",
+    "reflect",
+    "refract",
+    "matrixCompMult",
+    "outerProduct",
+    "transpose",
+    "determinant",
+    "inverse",
+    "lessThan",
+    "lessThanEqual",
+    "greaterThan",
+    "greaterThanEqual",
+    "equal",
+    "notEqual",
+    "any",
+    "all",
+    "not",
+    "texture",
+    "textureLod",
+    "texelFetch",
+    "dFdx",
+    "dFdy",
+    "fwidth",
+    // Shadertoy prelude
+    "mainImage",
+    "fragColor",
+    "fragCoord",
+    "iResolution",
+    "iTime",
+    "iTimeDelta",
+    "iFrame",
+    "iFrameRate",
+    "iChannelTime",
+    "iChannelResolution",
+    "iMouse",
+    "iDate",
+    "iSampleRate",
+    "iChannel0",
+    "iChannel1",
+    "iChannel2",
+    "iChannel3",
+];
+
+/// Prefixes reserved for GLSL built-ins, baked expression temporaries and loop gates
+const RESERVED_PREFIXES: &[&str] = &["gl_", "_e", "loop_init_"];
+
+const INDENT: &str = "    ";
+
+/// Writes the type and function definitions of a `naga::Module` as GLSL
+pub struct Writer<'a, W: Write> {
+    out: W,
+    module: &'a Module,
+    info: &'a ModuleInfo,
+    names: FastHashMap<NameKey, String>,
+    /// Number of loops written so far, used to name their continuing gates
+    loops: usize,
+}
+
+impl<'a, W: Write> Writer<'a, W> {
+    pub fn new(out: W, module: &'a Module, info: &'a ModuleInfo) -> Self {
+        let mut names = FastHashMap::default();
+        Namer::default().reset(
+            module,
+            RESERVED_KEYWORDS,
+            &[],
+            &[],
+            RESERVED_PREFIXES,
+            &mut names,
+        );
+
+        Writer {
+            out,
+            module,
+            info,
+            names,
+            loops: 0,
+        }
+    }
+
+    /// The GLSL identifier assigned to the entry point at the given index
+    pub fn entry_point_name(&self, index: usize) -> &str {
+        &self.names[&NameKey::EntryPoint(index as u16)]
+    }
+
+    pub fn finish(self) -> W {
+        self.out
+    }
+
+    /// Write all struct types, followed by all functions in declaration order
+    ///
+    /// Entry points are written last as regular functions,
+    /// leaving their invocation to the caller.
+    pub fn write(&mut self) -> Result<(), ShadertoyError> {
+        for (handle, ty) in self.module.types.iter() {
+            let TypeInner::Struct { members, .. } = &ty.inner else {
+                continue;
+            };
+
+            writeln!(self.out, "struct {} {{", self.names[&NameKey::Type(handle)])?;
+            for (i, member) in members.iter().enumerate() {
+                let ty = self.type_name(&self.module.types[member.ty].inner, Some(member.ty))?;
+                let name = &self.names[&NameKey::StructMember(handle, i as u32)];
+                writeln!(self.out, "{INDENT}{ty} {name};")?;
+            }
+            writeln!(self.out, "}};")?;
+            writeln!(self.out)?;
+        }
+
+        for (handle, function) in self.module.functions.iter() {
+            self.write_function(FunctionKind::Function(handle), function, &self.info[handle])?;
+            writeln!(self.out)?;
+        }
+
+        for (i, entry_point) in self.module.entry_points.iter().enumerate() {
+            self.write_function(
+                FunctionKind::EntryPoint(i as u16),
+                &entry_point.function,
+                self.info.get_entry_point(i),
+            )?;
+            writeln!(self.out)?;
+        }
+
+        Ok(())
+    }
+
+    fn type_name(
+        &self,
+        inner: &TypeInner,
+        handle: Option<Handle<naga::Type>>,
+    ) -> Result<String, ShadertoyError> {
+        let prefix = |kind: ScalarKind| match kind {
+            ScalarKind::Sint => "i",
+            ScalarKind::Uint => "u",
+            ScalarKind::Float => "",
+            ScalarKind::Bool => "b",
+        };
+
+        Ok(match inner {
+            TypeInner::Scalar { kind, .. } => match kind {
+                ScalarKind::Sint => "int",
+                ScalarKind::Uint => "uint",
+                ScalarKind::Float => "float",
+                ScalarKind::Bool => "bool",
+            }
+            .to_string(),
+            TypeInner::Vector { size, kind, .. } => {
+                format!("{}vec{}", prefix(*kind), vector_size(*size))
+            }
+            TypeInner::Matrix { columns, rows, .. } => {
+                if columns == rows {
+                    format!("mat{}", vector_size(*columns))
+                } else {
+                    format!("mat{}x{}", vector_size(*columns), vector_size(*rows))
+                }
+            }
+            TypeInner::Pointer { base, .. } => {
+                self.type_name(&self.module.types[*base].inner, Some(*base))?
+            }
+            TypeInner::ValuePointer {
+                size, kind, width, ..
+            } => match size {
+                Some(size) => self.type_name(
+                    &TypeInner::Vector {
+                        size: *size,
+                        kind: *kind,
+                        width: *width,
+                    },
+                    None,
+                )?,
+                None => self.type_name(
+                    &TypeInner::Scalar {
+                        kind: *kind,
+                        width: *width,
+                    },
+                    None,
+                )?,
+            },
+            TypeInner::Struct { .. } => {
+                let handle = handle.ok_or_else(|| {
+                    ShadertoyError::Unsupported("Anonymous struct type".to_string())
+                })?;
+                self.names[&NameKey::Type(handle)].clone()
+            }
+            t => return Err(ShadertoyError::Unsupported(format!("Type {t:?}"))),
+        })
+    }
+
+    fn expression_type(
+        &self,
+        info: &FunctionInfo,
+        expr: Handle<Expression>,
+    ) -> Result<String, ShadertoyError> {
+        let resolution = &info[expr].ty;
+        let handle = match resolution {
+            naga::proc::TypeResolution::Handle(handle) => Some(*handle),
+            naga::proc::TypeResolution::Value(_) => None,
+        };
+        self.type_name(resolution.inner_with(&self.module.types), handle)
+    }
+
+    fn write_function(
+        &mut self,
+        kind: FunctionKind,
+        function: &Function,
+        info: &FunctionInfo,
+    ) -> Result<(), ShadertoyError> {
+        let result = match &function.result {
+            Some(result) => self.type_name(&self.module.types[result.ty].inner, Some(result.ty))?,
+            None => "void".to_string(),
+        };
+
+        let arguments = function
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let inner = &self.module.types[arg.ty].inner;
+                let qualifier = if let TypeInner::Pointer { .. } = inner {
+                    "inout "
+                } else {
+                    ""
+                };
+
+                Ok(format!(
+                    "{qualifier}{} {}",
+                    self.type_name(inner, Some(arg.ty))?,
+                    self.names[&kind.argument(i as u32)]
+                ))
+            })
+            .collect::<Result<Vec<_>, ShadertoyError>>()?
+            .join(", ");
+
+        writeln!(
+            self.out,
+            "{result} {}({arguments}) {{",
+            self.names[&kind.function()]
+        )?;
+
+        for (local, var) in function.local_variables.iter() {
+            if var.init.is_some() {
+                return Err(ShadertoyError::Unsupported(
+                    "Initialized local variable".to_string(),
+                ));
+            }
+
+            writeln!(
+                self.out,
+                "{INDENT}{} {};",
+                self.type_name(&self.module.types[var.ty].inner, Some(var.ty))?,
+                self.names[&kind.local(local)]
+            )?;
+        }
+
+        let ctx = FunctionContext {
+            kind,
+            function,
+            info,
+        };
+
+        self.write_block(&ctx, &function.body, 1)?;
+
+        writeln!(self.out, "}}")?;
+
+        Ok(())
+    }
+
+    fn write_block(
+        &mut self,
+        ctx: &FunctionContext,
+        block: &Block,
+        level: usize,
+    ) -> Result<(), ShadertoyError> {
+        for stmt in block.iter() {
+            self.write_stmt(ctx, stmt, level)?;
+        }
+        Ok(())
+    }
+
+    fn write_stmt(
+        &mut self,
+        ctx: &FunctionContext,
+        stmt: &Statement,
+        level: usize,
+    ) -> Result<(), ShadertoyError> {
+        let indent = INDENT.repeat(level);
+
+        match stmt {
+            Statement::Emit(range) => {
+                for expr in range.clone() {
+                    if !is_emittable(&ctx.function.expressions[expr])
+                        || is_pointer(ctx.info, &self.module.types, expr)
+                    {
+                        continue;
+                    }
+
+                    let ty = self.expression_type(ctx.info, expr)?;
+                    let value = self.expr(ctx, expr, true)?;
+                    writeln!(self.out, "{indent}{ty} {} = {value};", baked_name(expr))?;
+                }
+            }
+            Statement::Block(block) => {
+                writeln!(self.out, "{indent}{{")?;
+                self.write_block(ctx, block, level + 1)?;
+                writeln!(self.out, "{indent}}}")?;
+            }
+            Statement::If {
+                condition,
+                accept,
+                reject,
+            } => {
+                writeln!(
+                    self.out,
+                    "{indent}if ({}) {{",
+                    self.expr(ctx, *condition, false)?
+                )?;
+                self.write_block(ctx, accept, level + 1)?;
+                if !reject.is_empty() {
+                    writeln!(self.out, "{indent}}} else {{")?;
+                    self.write_block(ctx, reject, level + 1)?;
+                }
+                writeln!(self.out, "{indent}}}")?;
+            }
+            Statement::Loop {
+                body,
+                continuing,
+                break_if,
+            } => {
+                if continuing.is_empty() && break_if.is_none() {
+                    writeln!(self.out, "{indent}while(true) {{")?;
+                    self.write_block(ctx, body, level + 1)?;
+                    writeln!(self.out, "{indent}}}")?;
+                } else {
+                    let gate = format!("loop_init_{}", self.loops);
+                    self.loops += 1;
+                    writeln!(self.out, "{indent}bool {gate} = true;")?;
+                    writeln!(self.out, "{indent}while(true) {{")?;
+                    writeln!(self.out, "{indent}{INDENT}if (!{gate}) {{")?;
+                    self.write_block(ctx, continuing, level + 2)?;
+                    if let Some(break_if) = break_if {
+                        writeln!(
+                            self.out,
+                            "{indent}{INDENT}{INDENT}if ({}) {{ break; }}",
+                            self.expr(ctx, *break_if, false)?
+                        )?;
+                    }
+                    writeln!(self.out, "{indent}{INDENT}}}")?;
+                    writeln!(self.out, "{indent}{INDENT}{gate} = false;")?;
+                    self.write_block(ctx, body, level + 1)?;
+                    writeln!(self.out, "{indent}}}")?;
+                }
+            }
+            Statement::Break => writeln!(self.out, "{indent}break;")?,
+            Statement::Continue => writeln!(self.out, "{indent}continue;")?,
+            Statement::Return { value } => match value {
+                Some(value) => writeln!(
+                    self.out,
+                    "{indent}return {};",
+                    self.expr(ctx, *value, false)?
+                )?,
+                None => writeln!(self.out, "{indent}return;")?,
+            },
+            Statement::Store { pointer, value } => writeln!(
+                self.out,
+                "{indent}{} = {};",
+                self.expr(ctx, *pointer, false)?,
+                self.expr(ctx, *value, false)?
+            )?,
+            Statement::Call {
+                function,
+                arguments,
+                result,
+            } => {
+                let arguments = arguments
+                    .iter()
+                    .map(|arg| self.expr(ctx, *arg, false))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ");
+                let call = format!("{}({arguments})", self.names[&NameKey::Function(*function)]);
+
+                match result {
+                    Some(result) => writeln!(
+                        self.out,
+                        "{indent}{} {} = {call};",
+                        self.expression_type(ctx.info, *result)?,
+                        baked_name(*result)
+                    )?,
+                    None => writeln!(self.out, "{indent}{call};")?,
+                }
+            }
+            s => return Err(ShadertoyError::Unsupported(format!("Statement {s:?}"))),
+        }
+
+        Ok(())
+    }
+
+    /// Produce the GLSL for `expr`
+    ///
+    /// Emitted expressions are referenced via the temporary they were baked into,
+    /// unless `inline` is set, in which case their definition is written out.
+    fn expr(
+        &self,
+        ctx: &FunctionContext,
+        handle: Handle<Expression>,
+        inline: bool,
+    ) -> Result<String, ShadertoyError> {
+        let expr = &ctx.function.expressions[handle];
+
+        if !inline && is_emittable(expr) && !is_pointer(ctx.info, &self.module.types, handle) {
+            return Ok(baked_name(handle));
+        }
+
+        Ok(match expr {
+            Expression::Literal(literal) => match literal {
+                Literal::F32(f) => float_literal(*f as f64),
+                Literal::F64(f) => float_literal(*f),
+                Literal::U32(u) => format!("{u}u"),
+                Literal::I32(i) => format!("{i}"),
+                Literal::Bool(b) => format!("{b}"),
+            },
+            Expression::Compose { ty, components } => format!(
+                "{}({})",
+                self.type_name(&self.module.types[*ty].inner, Some(*ty))?,
+                components
+                    .iter()
+                    .map(|component| self.expr(ctx, *component, false))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ")
+            ),
+            Expression::Splat { size, value } => format!(
+                "{}({})",
+                self.expression_type(ctx.info, handle)
+                    .unwrap_or_else(|_| format!("vec{}", vector_size(*size))),
+                self.expr(ctx, *value, false)?
+            ),
+            Expression::AccessIndex { base, index } => {
+                let base_expr = self.expr(ctx, *base, false)?;
+                let base_ty = ctx.info[*base].ty.inner_with(&self.module.types);
+                let base_ty = match base_ty {
+                    TypeInner::Pointer { base, .. } => &self.module.types[*base].inner,
+                    t => t,
+                };
+
+                match base_ty {
+                    TypeInner::Struct { .. } => {
+                        let ty = match &ctx.info[*base].ty {
+                            naga::proc::TypeResolution::Handle(ty) => {
+                                match self.module.types[*ty].inner {
+                                    TypeInner::Pointer { base, .. } => base,
+                                    _ => *ty,
+                                }
+                            }
+                            naga::proc::TypeResolution::Value(TypeInner::Pointer {
+                                base, ..
+                            }) => *base,
+                            _ => {
+                                return Err(ShadertoyError::Unsupported(
+                                    "Struct access through anonymous type".to_string(),
+                                ))
+                            }
+                        };
+                        format!(
+                            "{base_expr}.{}",
+                            self.names[&NameKey::StructMember(ty, *index)]
+                        )
+                    }
+                    TypeInner::Vector { .. } | TypeInner::ValuePointer { .. } => {
+                        format!("{base_expr}.{}", component(*index))
+                    }
+                    _ => format!("{base_expr}[{index}]"),
+                }
+            }
+            Expression::Swizzle {
+                size,
+                vector,
+                pattern,
+            } => format!(
+                "{}.{}",
+                self.expr(ctx, *vector, false)?,
+                pattern[..vector_size(*size) as usize]
+                    .iter()
+                    .map(|c| match c {
+                        SwizzleComponent::X => 'x',
+                        SwizzleComponent::Y => 'y',
+                        SwizzleComponent::Z => 'z',
+                        SwizzleComponent::W => 'w',
+                    })
+                    .collect::<String>()
+            ),
+            Expression::FunctionArgument(i) => self.names[&ctx.kind.argument(*i)].clone(),
+            Expression::LocalVariable(local) => self.names[&ctx.kind.local(*local)].clone(),
+            Expression::Load { pointer } => self.expr(ctx, *pointer, false)?,
+            Expression::Unary { op, expr } => format!(
+                "({}{})",
+                match op {
+                    UnaryOperator::Negate => "-",
+                    UnaryOperator::Not => {
+                        match ctx.info[*expr]
+                            .ty
+                            .inner_with(&self.module.types)
+                            .scalar_kind()
+                        {
+                            Some(ScalarKind::Bool) => "!",
+                            _ => "~",
+                        }
+                    }
+                },
+                self.expr(ctx, *expr, false)?
+            ),
+            Expression::Binary { op, left, right } => {
+                let left_ty = ctx.info[*left].ty.inner_with(&self.module.types);
+                let right_ty = ctx.info[*right].ty.inner_with(&self.module.types);
+                let l = self.expr(ctx, *left, false)?;
+                let r = self.expr(ctx, *right, false)?;
+
+                let is_vector = matches!(left_ty, TypeInner::Vector { .. })
+                    && matches!(right_ty, TypeInner::Vector { .. });
+                let is_float = left_ty.scalar_kind() == Some(ScalarKind::Float)
+                    || right_ty.scalar_kind() == Some(ScalarKind::Float);
+
+                match op {
+                    BinaryOperator::Modulo if is_float => {
+                        format!("({l} - {r} * trunc({l} / {r}))")
+                    }
+                    BinaryOperator::Equal
+                    | BinaryOperator::NotEqual
+                    | BinaryOperator::Less
+                    | BinaryOperator::LessEqual
+                    | BinaryOperator::Greater
+                    | BinaryOperator::GreaterEqual
+                        if is_vector =>
+                    {
+                        format!(
+                            "{}({l}, {r})",
+                            match op {
+                                BinaryOperator::Equal => "equal",
+                                BinaryOperator::NotEqual => "notEqual",
+                                BinaryOperator::Less => "lessThan",
+                                BinaryOperator::LessEqual => "lessThanEqual",
+                                BinaryOperator::Greater => "greaterThan",
+                                _ => "greaterThanEqual",
+                            }
+                        )
+                    }
+                    op => format!("({l} {} {r})", binary_operator(*op)),
+                }
+            }
+            Expression::Select {
+                condition,
+                accept,
+                reject,
+            } => format!(
+                "({} ? {} : {})",
+                self.expr(ctx, *condition, false)?,
+                self.expr(ctx, *accept, false)?,
+                self.expr(ctx, *reject, false)?
+            ),
+            Expression::Math {
+                fun,
+                arg,
+                arg1,
+                arg2,
+                arg3,
+            } => {
+                let args = [Some(*arg), *arg1, *arg2, *arg3]
+                    .into_iter()
+                    .flatten()
+                    .map(|arg| self.expr(ctx, arg, false))
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join(", ");
+                format!("{}({args})", math_function(*fun)?)
+            }
+            Expression::As {
+                expr,
+                kind,
+                convert: Some(_),
+            } => {
+                let ty = match ctx.info[*expr].ty.inner_with(&self.module.types) {
+                    TypeInner::Vector { size, width, .. } => TypeInner::Vector {
+                        size: *size,
+                        kind: *kind,
+                        width: *width,
+                    },
+                    TypeInner::Scalar { width, .. } => TypeInner::Scalar {
+                        kind: *kind,
+                        width: *width,
+                    },
+                    t => return Err(ShadertoyError::Unsupported(format!("Cast from {t:?}"))),
+                };
+                format!(
+                    "{}({})",
+                    self.type_name(&ty, None)?,
+                    self.expr(ctx, *expr, false)?
+                )
+            }
+            Expression::CallResult(_) => baked_name(handle),
+            e => return Err(ShadertoyError::Unsupported(format!("Expression {e:?}"))),
+        })
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum FunctionKind {
+    Function(Handle<Function>),
+    EntryPoint(u16),
+}
+
+impl FunctionKind {
+    fn function(self) -> NameKey {
+        match self {
+            FunctionKind::Function(handle) => NameKey::Function(handle),
+            FunctionKind::EntryPoint(index) => NameKey::EntryPoint(index),
+        }
+    }
+
+    fn argument(self, index: u32) -> NameKey {
+        match self {
+            FunctionKind::Function(handle) => NameKey::FunctionArgument(handle, index),
+            FunctionKind::EntryPoint(ep) => NameKey::EntryPointArgument(ep, index),
+        }
+    }
+
+    fn local(self, local: Handle<LocalVariable>) -> NameKey {
+        match self {
+            FunctionKind::Function(handle) => NameKey::FunctionLocal(handle, local),
+            FunctionKind::EntryPoint(ep) => NameKey::EntryPointLocal(ep, local),
+        }
+    }
+}
+
+struct FunctionContext<'a> {
+    kind: FunctionKind,
+    function: &'a Function,
+    info: &'a FunctionInfo,
+}
+
+fn baked_name(handle: Handle<Expression>) -> String {
+    format!("_e{}", handle.index())
+}
+
+/// Whether an expression needs to appear in an `Emit` statement before use
+fn is_emittable(expr: &Expression) -> bool {
+    !matches!(
+        expr,
+        Expression::Literal(_)
+            | Expression::Constant(_)
+            | Expression::ZeroValue(_)
+            | Expression::FunctionArgument(_)
+            | Expression::GlobalVariable(_)
+            | Expression::LocalVariable(_)
+            | Expression::CallResult(_)
+    )
+}
+
+fn is_pointer(
+    info: &FunctionInfo,
+    types: &naga::UniqueArena<naga::Type>,
+    expr: Handle<Expression>,
+) -> bool {
+    matches!(
+        info[expr].ty.inner_with(types),
+        TypeInner::Pointer { .. } | TypeInner::ValuePointer { .. }
+    )
+}
+
+fn vector_size(size: VectorSize) -> u8 {
+    match size {
+        VectorSize::Bi => 2,
+        VectorSize::Tri => 3,
+        VectorSize::Quad => 4,
+    }
+}
+
+fn component(index: u32) -> char {
+    ['x', 'y', 'z', 'w'][index as usize]
+}
+
+fn float_literal(f: f64) -> String {
+    if f.is_nan() {
+        "(0.0 / 0.0)".to_string()
+    } else if f.is_infinite() {
+        format!("({}1.0 / 0.0)", if f < 0.0 { "-" } else { "" })
+    } else {
+        format!("{:?}", f as f32)
+    }
+}
+
+fn binary_operator(op: BinaryOperator) -> &'static str {
+    match op {
+        BinaryOperator::Add => "+",
+        BinaryOperator::Subtract => "-",
+        BinaryOperator::Multiply => "*",
+        BinaryOperator::Divide => "/",
+        BinaryOperator::Modulo => "%",
+        BinaryOperator::Equal => "==",
+        BinaryOperator::NotEqual => "!=",
+        BinaryOperator::Less => "<",
+        BinaryOperator::LessEqual => "<=",
+        BinaryOperator::Greater => ">",
+        BinaryOperator::GreaterEqual => ">=",
+        BinaryOperator::And => "&",
+        BinaryOperator::ExclusiveOr => "^",
+        BinaryOperator::InclusiveOr => "|",
+        BinaryOperator::LogicalAnd => "&&",
+        BinaryOperator::LogicalOr => "||",
+        BinaryOperator::ShiftLeft => "<<",
+        BinaryOperator::ShiftRight => ">>",
+    }
+}
+
+fn math_function(fun: MathFunction) -> Result<&'static str, ShadertoyError> {
+    Ok(match fun {
+        MathFunction::Abs => "abs",
+        MathFunction::Min => "min",
+        MathFunction::Max => "max",
+        MathFunction::Clamp => "clamp",
+        MathFunction::Cos => "cos",
+        MathFunction::Cosh => "cosh",
+        MathFunction::Sin => "sin",
+        MathFunction::Sinh => "sinh",
+        MathFunction::Tan => "tan",
+        MathFunction::Tanh => "tanh",
+        MathFunction::Acos => "acos",
+        MathFunction::Asin => "asin",
+        MathFunction::Atan => "atan",
+        MathFunction::Atan2 => "atan",
+        MathFunction::Asinh => "asinh",
+        MathFunction::Acosh => "acosh",
+        MathFunction::Atanh => "atanh",
+        MathFunction::Radians => "radians",
+        MathFunction::Degrees => "degrees",
+        MathFunction::Ceil => "ceil",
+        MathFunction::Floor => "floor",
+        MathFunction::Round => "roundEven",
+        MathFunction::Fract => "fract",
+        MathFunction::Trunc => "trunc",
+        MathFunction::Exp => "exp",
+        MathFunction::Exp2 => "exp2",
+        MathFunction::Log => "log",
+        MathFunction::Log2 => "log2",
+        MathFunction::Pow => "pow",
+        MathFunction::Dot => "dot",
+        MathFunction::Outer => "outerProduct",
+        MathFunction::Cross => "cross",
+        MathFunction::Distance => "distance",
+        MathFunction::Length => "length",
+        MathFunction::Normalize => "normalize",
+        MathFunction::FaceForward => "faceforward",
+        MathFunction::Reflect => "reflect",
+        MathFunction::Refract => "refract",
+        MathFunction::Sign => "sign",
+        MathFunction::Mix => "mix",
+        MathFunction::Step => "step",
+        MathFunction::SmoothStep => "smoothstep",
+        MathFunction::Sqrt => "sqrt",
+        MathFunction::InverseSqrt => "inversesqrt",
+        MathFunction::Inverse => "inverse",
+        MathFunction::Transpose => "transpose",
+        MathFunction::Determinant => "determinant",
+        f => return Err(ShadertoyError::Unsupported(format!("Math function {f:?}"))),
+    })
+}
@@ -13,8 +13,8 @@ use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     ast::{IntoLiteral, DISTANCE, GRADIENT_2D, GRADIENT_3D, VECTOR2, VECTOR3, X, Y, Z},
     module::{
-        AsModule, Domains, DomainsDyn, ErasedHash, FunctionIdentifier, Module, SpecializationData,
-        CONTEXT,
+        AsModule, Domains, DomainsDyn, ErasedHash, FunctionIdentifier, Module, Origin,
+        SpecializationData, CONTEXT,
     },
 };
 use elysian_proc_macros::elysian_expr;
@@ -80,7 +80,7 @@ impl AsModule for CentralDiffGradient {
         let field_module = self.field.module(spec);
         let field_entry = field_module.entry_point.clone();
 
-        field_module.concat(Module::new(
+        field_module.within(&Origin::of(self)).concat(Module::new(
             self,
             spec,
             elysian_function! {
@@ -0,0 +1,194 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::combine::{
+    blend_function, linear_weight, BlendOp, Combinator, DISTANCE_A, DISTANCE_B, WEIGHT_A, WEIGHT_B,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, X},
+    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
+};
+use elysian_proc_macros::elysian_block;
+
+use crate::combine::K;
+
+pub const CHAMFER_UNION: FunctionIdentifier =
+    FunctionIdentifier::new("chamfer_union", 3554047125695738731);
+
+pub const CHAMFER_INTERSECTION: FunctionIdentifier =
+    FunctionIdentifier::new("chamfer_intersection", 7108167788603577983);
+
+pub const CHAMFER_SUBTRACTION: FunctionIdentifier =
+    FunctionIdentifier::new("chamfer_subtraction", 1000950584525057312);
+
+fn chamfer_module<T: Hash + Domains>(
+    this: &T,
+    spec: &SpecializationData,
+    id: &FunctionIdentifier,
+    op: BlendOp,
+    prop: &PropertyIdentifier,
+    k: &Expr,
+) -> Module {
+    let frac_1_sqrt_2 = std::f64::consts::FRAC_1_SQRT_2.literal();
+
+    Module::new(
+        this,
+        spec,
+        blend_function(
+            id,
+            op,
+            prop,
+            &[K],
+            linear_weight(),
+            elysian_block! {
+                let mut DISTANCE = DISTANCE_A;
+                let mut WEIGHT_A = 1.0;
+                let mut WEIGHT_B = 0.0;
+                if DISTANCE_B < DISTANCE {
+                    DISTANCE = DISTANCE_B;
+                    WEIGHT_A = 0.0;
+                    WEIGHT_B = 1.0;
+                }
+                let X = (DISTANCE_A + DISTANCE_B - K) * #frac_1_sqrt_2;
+                if X < DISTANCE {
+                    DISTANCE = X;
+                    WEIGHT_A = #frac_1_sqrt_2;
+                    WEIGHT_B = #frac_1_sqrt_2;
+                }
+            },
+        ),
+    )
+    .with_args([k.clone().into()])
+}
+
+/// Union with a 45 degree chamfer of size `k` along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChamferUnion {
+    prop: PropertyIdentifier,
+    k: Expr,
+}
+
+impl ChamferUnion {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr) -> Self {
+        ChamferUnion {
+            prop: prop.into(),
+            k: k.expr(),
+        }
+    }
+}
+
+impl Hash for ChamferUnion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CHAMFER_UNION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+    }
+}
+
+impl Domains for ChamferUnion {}
+
+impl AsModule for ChamferUnion {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        chamfer_module(
+            self,
+            spec,
+            &CHAMFER_UNION,
+            BlendOp::Union,
+            &self.prop,
+            &self.k,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ChamferUnion {}
+
+/// Intersection with a 45 degree chamfer of size `k` along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChamferIntersection {
+    prop: PropertyIdentifier,
+    k: Expr,
+}
+
+impl ChamferIntersection {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr) -> Self {
+        ChamferIntersection {
+            prop: prop.into(),
+            k: k.expr(),
+        }
+    }
+}
+
+impl Hash for ChamferIntersection {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CHAMFER_INTERSECTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+    }
+}
+
+impl Domains for ChamferIntersection {}
+
+impl AsModule for ChamferIntersection {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        chamfer_module(
+            self,
+            spec,
+            &CHAMFER_INTERSECTION,
+            BlendOp::Intersection,
+            &self.prop,
+            &self.k,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ChamferIntersection {}
+
+/// Subtract the right shape from the left, with a 45 degree chamfer of size `k` along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChamferSubtraction {
+    prop: PropertyIdentifier,
+    k: Expr,
+}
+
+impl ChamferSubtraction {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr) -> Self {
+        ChamferSubtraction {
+            prop: prop.into(),
+            k: k.expr(),
+        }
+    }
+}
+
+impl Hash for ChamferSubtraction {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CHAMFER_SUBTRACTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+    }
+}
+
+impl Domains for ChamferSubtraction {}
+
+impl AsModule for ChamferSubtraction {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        chamfer_module(
+            self,
+            spec,
+            &CHAMFER_SUBTRACTION,
+            BlendOp::Subtraction,
+            &self.prop,
+            &self.k,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ChamferSubtraction {}
@@ -0,0 +1,259 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::combine::{
+    blend_function, linear_weight, BlendOp, Combinator, DISTANCE_A, DISTANCE_B, WEIGHT_A, WEIGHT_B,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, NUM, VECTOR2, X, Y},
+    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
+};
+use elysian_proc_macros::elysian_block;
+
+use crate::combine::{COLUMN_RADIUS, K, N};
+
+pub const COLUMNS_UNION: FunctionIdentifier =
+    FunctionIdentifier::new("columns_union", 6866614328959810893);
+
+pub const COLUMNS_INTERSECTION: FunctionIdentifier =
+    FunctionIdentifier::new("columns_intersection", 4274266087817268107);
+
+pub const COLUMNS_SUBTRACTION: FunctionIdentifier =
+    FunctionIdentifier::new("columns_subtraction", 6309180759604868275);
+
+fn columns_module<T: Hash + Domains>(
+    this: &T,
+    spec: &SpecializationData,
+    id: &FunctionIdentifier,
+    op: BlendOp,
+    prop: &PropertyIdentifier,
+    k: &Expr,
+    n: &Expr,
+) -> Module {
+    let sqrt_2 = std::f64::consts::SQRT_2.literal();
+    let frac_1_sqrt_2 = std::f64::consts::FRAC_1_SQRT_2.literal();
+
+    // Columns stand proud of the seam of a union,
+    // and are carved into the seam of a subtraction or intersection.
+    // Their X and Y axes are rotated 45 degrees from the input distances,
+    // which scales the partial derivatives of a column by 1 / sqrt(2).
+    let distance = if op == BlendOp::Union {
+        elysian_block! {
+            let mut DISTANCE = DISTANCE_A;
+            let mut WEIGHT_A = 1.0;
+            let mut WEIGHT_B = 0.0;
+            if DISTANCE_B < DISTANCE {
+                DISTANCE = DISTANCE_B;
+                WEIGHT_A = 0.0;
+                WEIGHT_B = 1.0;
+            }
+            if DISTANCE_A < K && DISTANCE_B < K {
+                let COLUMN_RADIUS = K * #sqrt_2 / ((N - 1.0) * 2.0 + #sqrt_2);
+                let X = (DISTANCE_A + DISTANCE_B - K) * #frac_1_sqrt_2
+                    + COLUMN_RADIUS * #sqrt_2;
+                let mut Y = (DISTANCE_B - DISTANCE_A) * #frac_1_sqrt_2;
+                if N % 2.0 > 0.5 {
+                    Y = Y + COLUMN_RADIUS;
+                }
+                let Y = (Y + COLUMN_RADIUS) % (COLUMN_RADIUS * 2.0) - COLUMN_RADIUS;
+                if X < DISTANCE {
+                    DISTANCE = X;
+                    WEIGHT_A = #frac_1_sqrt_2;
+                    WEIGHT_B = #frac_1_sqrt_2;
+                }
+                let NUM = VECTOR2 { X: X, Y: Y }.length();
+                if NUM - COLUMN_RADIUS < DISTANCE {
+                    DISTANCE = NUM - COLUMN_RADIUS;
+                    WEIGHT_A = (X - Y) / (NUM * #sqrt_2);
+                    WEIGHT_B = (X + Y) / (NUM * #sqrt_2);
+                }
+            }
+        }
+    } else {
+        elysian_block! {
+            let mut DISTANCE = DISTANCE_A;
+            let mut WEIGHT_A = 1.0;
+            let mut WEIGHT_B = 0.0;
+            if DISTANCE_B < DISTANCE {
+                DISTANCE = DISTANCE_B;
+                WEIGHT_A = 0.0;
+                WEIGHT_B = 1.0;
+            }
+            if DISTANCE_A < K && DISTANCE_B < K {
+                let COLUMN_RADIUS = K * #sqrt_2 / ((N - 1.0) * 2.0 + #sqrt_2);
+                let X = (DISTANCE_A + DISTANCE_B - K) * #frac_1_sqrt_2
+                    - COLUMN_RADIUS * #frac_1_sqrt_2;
+                let mut Y = (DISTANCE_B - DISTANCE_A) * #frac_1_sqrt_2 + COLUMN_RADIUS;
+                if N % 2.0 > 0.5 {
+                    Y = Y + COLUMN_RADIUS;
+                }
+                let Y = (Y + COLUMN_RADIUS) % (COLUMN_RADIUS * 2.0) - COLUMN_RADIUS;
+                let NUM = VECTOR2 { X: X, Y: Y }.length();
+                if X > COLUMN_RADIUS - NUM {
+                    if X < DISTANCE {
+                        DISTANCE = X;
+                        WEIGHT_A = #frac_1_sqrt_2;
+                        WEIGHT_B = #frac_1_sqrt_2;
+                    }
+                } else {
+                    if COLUMN_RADIUS - NUM < DISTANCE {
+                        DISTANCE = COLUMN_RADIUS - NUM;
+                        WEIGHT_A = (Y - X) / (NUM * #sqrt_2);
+                        WEIGHT_B = -(X + Y) / (NUM * #sqrt_2);
+                    }
+                }
+            }
+        }
+    };
+
+    Module::new(
+        this,
+        spec,
+        blend_function(id, op, prop, &[K, N], linear_weight(), distance),
+    )
+    .with_args([k.clone().into(), n.clone().into()])
+}
+
+/// Union with `n` columns spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnsUnion {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl ColumnsUnion {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        ColumnsUnion {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for ColumnsUnion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        COLUMNS_UNION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for ColumnsUnion {}
+
+impl AsModule for ColumnsUnion {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        columns_module(
+            self,
+            spec,
+            &COLUMNS_UNION,
+            BlendOp::Union,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ColumnsUnion {}
+
+/// Intersection with `n` columns spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnsIntersection {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl ColumnsIntersection {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        ColumnsIntersection {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for ColumnsIntersection {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        COLUMNS_INTERSECTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for ColumnsIntersection {}
+
+impl AsModule for ColumnsIntersection {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        columns_module(
+            self,
+            spec,
+            &COLUMNS_INTERSECTION,
+            BlendOp::Intersection,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ColumnsIntersection {}
+
+/// Subtract the right shape from the left, with `n` columns spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColumnsSubtraction {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl ColumnsSubtraction {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        ColumnsSubtraction {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for ColumnsSubtraction {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        COLUMNS_SUBTRACTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for ColumnsSubtraction {}
+
+impl AsModule for ColumnsSubtraction {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        columns_module(
+            self,
+            spec,
+            &COLUMNS_SUBTRACTION,
+            BlendOp::Subtraction,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ColumnsSubtraction {}
@@ -0,0 +1,81 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::combine::{
+    blend_function, BlendOp, Combinator, DISTANCE_A, DISTANCE_B, WEIGHT_A, WEIGHT_B,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, NUM, X, Y},
+    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
+};
+use elysian_proc_macros::elysian_block;
+
+use crate::combine::K;
+
+pub const EXPONENTIAL_SMOOTH_UNION: FunctionIdentifier =
+    FunctionIdentifier::new("exponential_smooth_union", 1441956657762870552);
+
+/// Union via an exponential smooth minimum over `k` units
+///
+/// Unlike [`SmoothUnion`](crate::combine::SmoothUnion), the blend never fully
+/// reaches the harder of the two inputs, and its result is associative
+/// regardless of evaluation order.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ExponentialSmoothUnion {
+    prop: PropertyIdentifier,
+    k: Expr,
+}
+
+impl ExponentialSmoothUnion {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr) -> Self {
+        ExponentialSmoothUnion {
+            prop: prop.into(),
+            k: k.expr(),
+        }
+    }
+}
+
+impl Hash for ExponentialSmoothUnion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        EXPONENTIAL_SMOOTH_UNION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+    }
+}
+
+impl Domains for ExponentialSmoothUnion {}
+
+impl AsModule for ExponentialSmoothUnion {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        // Offset both exponents by the minimum to keep them within [0, 1]
+        Module::new(
+            self,
+            spec,
+            blend_function(
+                &EXPONENTIAL_SMOOTH_UNION,
+                BlendOp::Union,
+                &self.prop,
+                &[K],
+                elysian_block! {
+                    let DISTANCE = DISTANCE_A.min(DISTANCE_B);
+                    let X = ((DISTANCE - DISTANCE_A) / K).exp2();
+                    let Y = ((DISTANCE - DISTANCE_B) / K).exp2();
+                    let NUM = X / (X + Y);
+                },
+                elysian_block! {
+                    let DISTANCE = DISTANCE - K * (X + Y).log2();
+                    let WEIGHT_A = NUM;
+                    let WEIGHT_B = 1.0 - NUM;
+                },
+            ),
+        )
+        .with_args([self.k.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for ExponentialSmoothUnion {}
@@ -1,16 +1,164 @@
-use elysian_core::identifier::Identifier;
+use crate::combine::{LEFT, OUT, RIGHT};
+use elysian_core::{identifier::Identifier, property_identifier::PropertyIdentifier};
 use elysian_ir::{
-    module::{NumericType, Type},
+    ast::{Block, IntoLiteral, COMBINE_CONTEXT, DISTANCE, GRADIENT_2D, GRADIENT_3D, NUM},
+    module::{FunctionDefinition, FunctionIdentifier, InputDefinition, NumericType, Type},
     property,
 };
+use elysian_proc_macros::{elysian_block, elysian_stmt};
 
 pub const K: Identifier = Identifier::new("k", 12632115441234896764);
 property!(K, K_PROP, Type::Number(NumericType::Float));
 
+pub const N: Identifier = Identifier::new("n", 4085277507852566996);
+property!(N, N_PROP, Type::Number(NumericType::Float));
+
+pub const DISTANCE_A: Identifier = Identifier::new("distance_a", 6396550844707650093);
+property!(
+    DISTANCE_A,
+    DISTANCE_A_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const DISTANCE_B: Identifier = Identifier::new("distance_b", 119569567491451633);
+property!(
+    DISTANCE_B,
+    DISTANCE_B_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const WEIGHT_A: Identifier = Identifier::new("weight_a", 9221830410236574113);
+property!(WEIGHT_A, WEIGHT_A_PROP, Type::Number(NumericType::Float));
+
+pub const WEIGHT_B: Identifier = Identifier::new("weight_b", 2718264053301972598);
+property!(WEIGHT_B, WEIGHT_B_PROP, Type::Number(NumericType::Float));
+
+pub const COLUMN_RADIUS: Identifier = Identifier::new("column_radius", 1440544514758411974);
+property!(
+    COLUMN_RADIUS,
+    COLUMN_RADIUS_PROP,
+    Type::Number(NumericType::Float)
+);
+
+/// Boolean operation underlying a blend
+///
+/// Each is expressed in terms of a union-style blend `f` via sign flips,
+/// such that `intersection(a, b) = -f(-a, -b)` and `subtraction(a, b) = -f(-a, b)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum BlendOp {
+    Union,
+    Intersection,
+    Subtraction,
+}
+
+impl BlendOp {
+    /// Signs applied to the left input, right input and output respectively
+    fn signs(&self) -> [f64; 3] {
+        match self {
+            BlendOp::Union => [1.0, 1.0, 1.0],
+            BlendOp::Intersection => [-1.0, -1.0, -1.0],
+            BlendOp::Subtraction => [-1.0, 1.0, -1.0],
+        }
+    }
+}
+
+/// Linear blend weight over `K`, as used by [`SmoothUnion`](crate::combine::SmoothUnion)
+pub(crate) fn linear_weight() -> Block {
+    elysian_block! {
+        let NUM = (0.5 + 0.5 * (DISTANCE_B - DISTANCE_A) / K).max(0.0).min(1.0);
+    }
+}
+
+/// Build the function for a per-property blend combinator
+///
+/// `weight` binds `NUM` to the weight of the left input given `DISTANCE_A` and `DISTANCE_B`,
+/// and `distance` binds `DISTANCE` to the union-style blended distance,
+/// along with `WEIGHT_A` and `WEIGHT_B` to its partial derivatives over each input.
+/// Both see the inputs with `op`'s signs applied.
+///
+/// Gradients are combined via the partial derivatives, so follow the blended distance.
+/// Other properties have no such derivative, and are mixed by `NUM`.
+pub(crate) fn blend_function(
+    id: &FunctionIdentifier,
+    op: BlendOp,
+    prop: &PropertyIdentifier,
+    inputs: &[Identifier],
+    weight: Block,
+    distance: Block,
+) -> FunctionDefinition {
+    let [sign_a, sign_b, sign_out] = op.signs();
+    let (sa, sb) = (sign_a.literal(), sign_b.literal());
+    let (ra, rb, so) = (
+        (sign_a * sign_out).literal(),
+        (sign_b * sign_out).literal(),
+        sign_out.literal(),
+    );
+
+    let prop_id = (**prop).clone();
+
+    let mut block = elysian_block! {
+        let DISTANCE_A = COMBINE_CONTEXT.LEFT.DISTANCE * #sa;
+        let DISTANCE_B = COMBINE_CONTEXT.RIGHT.DISTANCE * #sb;
+    };
+
+    block.extend(weight);
+
+    if prop_id == DISTANCE {
+        block.extend(distance);
+        block.push(elysian_stmt! {
+            COMBINE_CONTEXT.OUT.DISTANCE = DISTANCE * #so
+        });
+    } else if prop_id == GRADIENT_2D || prop_id == GRADIENT_3D {
+        block.extend(distance);
+        block.push(elysian_stmt! {
+            COMBINE_CONTEXT.OUT.prop_id = COMBINE_CONTEXT.LEFT.prop_id * (WEIGHT_A * #ra)
+                + COMBINE_CONTEXT.RIGHT.prop_id * (WEIGHT_B * #rb)
+        });
+    } else {
+        block.push(elysian_stmt! {
+            COMBINE_CONTEXT.OUT.prop_id = (COMBINE_CONTEXT.RIGHT.prop_id * #rb).mix(
+                COMBINE_CONTEXT.LEFT.prop_id * #ra,
+                NUM
+            )
+        });
+    }
+
+    block.push(elysian_stmt!(return COMBINE_CONTEXT));
+
+    FunctionDefinition {
+        id: FunctionIdentifier(id.0.concat(prop)),
+        public: false,
+        inputs: inputs
+            .iter()
+            .map(|id| InputDefinition {
+                id: id.clone().into(),
+                mutable: false,
+            })
+            .chain([InputDefinition {
+                id: COMBINE_CONTEXT.into(),
+                mutable: true,
+            }])
+            .collect(),
+        output: COMBINE_CONTEXT.into(),
+        block,
+        provenance: None,
+    }
+}
+
+mod chamfer;
+mod columns;
+mod exponential_smooth_union;
+mod power_smooth_union;
 mod smooth_intersection;
 mod smooth_subtraction;
 mod smooth_union;
+mod stairs;
 
+pub use chamfer::*;
+pub use columns::*;
+pub use exponential_smooth_union::*;
+pub use power_smooth_union::*;
 pub use smooth_intersection::*;
 pub use smooth_subtraction::*;
 pub use smooth_union::*;
+pub use stairs::*;
@@ -0,0 +1,94 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::combine::{
+    blend_function, BlendOp, Combinator, DISTANCE_A, DISTANCE_B, WEIGHT_A, WEIGHT_B,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, NUM},
+    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
+};
+use elysian_proc_macros::elysian_block;
+
+use crate::combine::K;
+
+pub const POWER_SMOOTH_UNION: FunctionIdentifier =
+    FunctionIdentifier::new("power_smooth_union", 384715357004455824);
+
+/// Union via a power smooth minimum with exponent `k`
+///
+/// Larger exponents approach a hard union.
+/// The blend only applies where both distances are positive,
+/// falling back to a hard union inside either shape.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerSmoothUnion {
+    prop: PropertyIdentifier,
+    k: Expr,
+}
+
+impl PowerSmoothUnion {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr) -> Self {
+        PowerSmoothUnion {
+            prop: prop.into(),
+            k: k.expr(),
+        }
+    }
+}
+
+impl Hash for PowerSmoothUnion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        POWER_SMOOTH_UNION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+    }
+}
+
+impl Domains for PowerSmoothUnion {}
+
+impl AsModule for PowerSmoothUnion {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        // Divide through by the larger distance so powers stay within [0, 1]
+        Module::new(
+            self,
+            spec,
+            blend_function(
+                &POWER_SMOOTH_UNION,
+                BlendOp::Union,
+                &self.prop,
+                &[K],
+                elysian_block! {
+                    let mut NUM = 1.0;
+                    if DISTANCE_B < DISTANCE_A {
+                        NUM = 0.0;
+                    }
+                    if DISTANCE_A > 0.0 && DISTANCE_B > 0.0 {
+                        NUM = 1.0 / (1.0 + (K * (DISTANCE_A / DISTANCE_B).log2()).exp2());
+                    }
+                },
+                elysian_block! {
+                    let mut DISTANCE = DISTANCE_A.min(DISTANCE_B);
+                    let mut WEIGHT_A = NUM;
+                    let mut WEIGHT_B = 1.0 - NUM;
+                    if DISTANCE_A > 0.0 && DISTANCE_B > 0.0 {
+                        DISTANCE = DISTANCE * (
+                            -(1.0 + (K * (DISTANCE / DISTANCE_A.max(DISTANCE_B)).log2()).exp2())
+                                .log2()
+                                / K
+                        ).exp2();
+                        // The partial derivative over each input is (DISTANCE / input) ^ (K + 1)
+                        WEIGHT_A = ((K + 1.0) * (DISTANCE / DISTANCE_A).log2()).exp2();
+                        WEIGHT_B = ((K + 1.0) * (DISTANCE / DISTANCE_B).log2()).exp2();
+                    }
+                },
+            ),
+        )
+        .with_args([self.k.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for PowerSmoothUnion {}
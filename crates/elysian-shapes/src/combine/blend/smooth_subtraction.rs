@@ -80,6 +80,7 @@ impl AsModule for SmoothSubtraction {
                 ],
                 output: COMBINE_CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.k.clone().into()])
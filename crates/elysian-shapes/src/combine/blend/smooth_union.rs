@@ -82,6 +82,7 @@ impl AsModule for SmoothUnion {
                 ],
                 output: COMBINE_CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.k.clone().into()])
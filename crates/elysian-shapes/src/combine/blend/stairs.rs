@@ -0,0 +1,214 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::combine::{
+    blend_function, linear_weight, BlendOp, Combinator, DISTANCE_A, DISTANCE_B, WEIGHT_A, WEIGHT_B,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, NUM, X, Y},
+    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
+};
+use elysian_proc_macros::elysian_block;
+
+use crate::combine::{K, N};
+
+pub const STAIRS_UNION: FunctionIdentifier =
+    FunctionIdentifier::new("stairs_union", 5736641629713206996);
+
+pub const STAIRS_INTERSECTION: FunctionIdentifier =
+    FunctionIdentifier::new("stairs_intersection", 173237243944831683);
+
+pub const STAIRS_SUBTRACTION: FunctionIdentifier =
+    FunctionIdentifier::new("stairs_subtraction", 767729361637769826);
+
+fn stairs_module<T: Hash + Domains>(
+    this: &T,
+    spec: &SpecializationData,
+    id: &FunctionIdentifier,
+    op: BlendOp,
+    prop: &PropertyIdentifier,
+    k: &Expr,
+    n: &Expr,
+) -> Module {
+    Module::new(
+        this,
+        spec,
+        blend_function(
+            id,
+            op,
+            prop,
+            &[K, N],
+            linear_weight(),
+            elysian_block! {
+                let mut DISTANCE = DISTANCE_A;
+                let mut WEIGHT_A = 1.0;
+                let mut WEIGHT_B = 0.0;
+                if DISTANCE_B < DISTANCE {
+                    DISTANCE = DISTANCE_B;
+                    WEIGHT_A = 0.0;
+                    WEIGHT_B = 1.0;
+                }
+                let NUM = K / N;
+                let X = DISTANCE_B - K;
+                let Y = (X - DISTANCE_A + NUM) % (NUM * 2.0) - NUM;
+                let X = 0.5 * (X + DISTANCE_A + Y.abs());
+                // Each step follows one input, depending on which side of its riser we are
+                if X < DISTANCE {
+                    DISTANCE = X;
+                    if Y > 0.0 {
+                        WEIGHT_A = 0.0;
+                        WEIGHT_B = 1.0;
+                    } else {
+                        WEIGHT_A = 1.0;
+                        WEIGHT_B = 0.0;
+                    }
+                }
+            },
+        ),
+    )
+    .with_args([k.clone().into(), n.clone().into()])
+}
+
+/// Union with `n` steps spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StairsUnion {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl StairsUnion {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        StairsUnion {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for StairsUnion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        STAIRS_UNION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for StairsUnion {}
+
+impl AsModule for StairsUnion {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        stairs_module(
+            self,
+            spec,
+            &STAIRS_UNION,
+            BlendOp::Union,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for StairsUnion {}
+
+/// Intersection with `n` steps spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StairsIntersection {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl StairsIntersection {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        StairsIntersection {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for StairsIntersection {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        STAIRS_INTERSECTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for StairsIntersection {}
+
+impl AsModule for StairsIntersection {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        stairs_module(
+            self,
+            spec,
+            &STAIRS_INTERSECTION,
+            BlendOp::Intersection,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for StairsIntersection {}
+
+/// Subtract the right shape from the left, with `n` steps spanning `k` units along the seam
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StairsSubtraction {
+    prop: PropertyIdentifier,
+    k: Expr,
+    n: Expr,
+}
+
+impl StairsSubtraction {
+    pub fn new(prop: impl Into<PropertyIdentifier>, k: impl IntoExpr, n: impl IntoExpr) -> Self {
+        StairsSubtraction {
+            prop: prop.into(),
+            k: k.expr(),
+            n: n.expr(),
+        }
+    }
+}
+
+impl Hash for StairsSubtraction {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        STAIRS_SUBTRACTION.uuid().hash(state);
+        self.prop.hash(state);
+        self.k.hash(state);
+        self.n.hash(state);
+    }
+}
+
+impl Domains for StairsSubtraction {}
+
+impl AsModule for StairsSubtraction {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        stairs_module(
+            self,
+            spec,
+            &STAIRS_SUBTRACTION,
+            BlendOp::Subtraction,
+            &self.prop,
+            &self.k,
+            &self.n,
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Combinator for StairsSubtraction {}
@@ -1,10 +1,13 @@
 use std::fmt::Debug;
 
-use crate::combine::{Combinator, LEFT, OUT, RIGHT};
+use crate::{
+    combine::{Combinator, Combine, CombineBuilder, Sided, LEFT, OUT, RIGHT},
+    shape::IntoShape,
+};
 use elysian_core::property_identifier::PropertyIdentifier;
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
-    ast::COMBINE_CONTEXT,
+    ast::{COMBINE_CONTEXT, DISTANCE},
     module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData},
 };
 
@@ -44,3 +47,22 @@ impl AsModule for Displace {
 
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl Combinator for Displace {}
+
+pub trait IntoDisplace {
+    /// Add the distance of `field` to that of this shape, keeping its other properties
+    fn displace(self, field: impl IntoShape + 'static) -> Combine;
+}
+
+impl<T> IntoDisplace for T
+where
+    T: IntoShape + 'static,
+{
+    fn displace(self, field: impl IntoShape + 'static) -> Combine {
+        CombineBuilder::build()
+            .push(Sided::left())
+            .push(Displace::new(DISTANCE))
+            .combine()
+            .push(self)
+            .push(field)
+    }
+}
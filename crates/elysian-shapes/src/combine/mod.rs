@@ -24,7 +24,8 @@ use elysian_ir::{
     ast::{Block, Expr, COMBINE_CONTEXT},
     module::{
         DomainsDyn, ErasedHash, FieldDefinition, FunctionDefinition, FunctionIdentifier,
-        InputDefinition, SpecializationData, StructDefinition, StructIdentifier, Type, CONTEXT,
+        InputDefinition, Origin, SpecializationData, StructDefinition, StructIdentifier, Type,
+        CONTEXT,
     },
     property,
 };
@@ -180,12 +181,9 @@ impl AsModule for Combine {
 
         let mut module = combinators
             .into_iter()
+            .chain(prepared_shapes)
             .fold(Module::default(), |acc, next| acc.concat(next))
-            .concat(
-                prepared_shapes
-                    .into_iter()
-                    .fold(Module::default(), |acc, next| acc.concat(next)),
-            )
+            .within(&Origin::of(self))
             .concat(Module::new(
                 self,
                 spec,
@@ -198,6 +196,7 @@ impl AsModule for Combine {
                     }],
                     output: PropertyIdentifier(CONTEXT),
                     block,
+                    provenance: None,
                 },
             ));
 
@@ -8,7 +8,7 @@ use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     ast::{DISTANCE, ERROR, POSITION_2D, POSITION_3D},
     module::{
-        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, SpecializationData,
+        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, Origin, SpecializationData,
         StructIdentifier, Type, CONTEXT,
     },
     property,
@@ -65,7 +65,7 @@ impl AsModule for DeriveBoundingError {
         let field_call_context = field_module.call(elysian_expr! { CONTEXT });
         let field_call_derive_context = field_module.call(elysian_expr! { DERIVE_CONTEXT });
 
-        field_module.concat(Module::new(
+        field_module.within(&Origin::of(self)).concat(Module::new(
             self,
             spec,
             elysian_function! {
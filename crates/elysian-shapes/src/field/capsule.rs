@@ -8,7 +8,7 @@ use elysian_core::{
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     ast::{POSITION_2D, POSITION_3D},
-    module::{AsModule, Domains, FunctionIdentifier, Module, SpecializationData, CONTEXT},
+    module::{AsModule, Domains, FunctionIdentifier, Module, Origin, SpecializationData, CONTEXT},
 };
 
 use crate::{
@@ -18,7 +18,7 @@ use crate::{
 
 use super::{Line, RADIUS};
 
-pub const CAPSULE: FunctionIdentifier = FunctionIdentifier::new("capsule", 14339483921749952476);
+pub const CAPSULE: FunctionIdentifier = FunctionIdentifier::new("capsule", 7364104836272840618);
 
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -80,7 +80,10 @@ impl AsModule for Capsule {
         )
         .with_args([self.dir.clone().into(), self.radius.clone().into()]);
 
-        line_module.concat(isosurface_module).concat(capsule_module)
+        line_module
+            .concat(isosurface_module)
+            .within(&Origin::of(self))
+            .concat(capsule_module)
     }
 }
 
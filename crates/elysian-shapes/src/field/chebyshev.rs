@@ -164,6 +164,7 @@ impl AsModule for Chebyshev {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
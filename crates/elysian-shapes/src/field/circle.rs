@@ -7,7 +7,7 @@ use elysian_core::{
 };
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
-    module::{AsModule, Domains, IntoRead, Module},
+    module::{AsModule, Domains, IntoRead, Module, Origin},
     module::{FunctionIdentifier, NumericType, SpecializationData, Type, CONTEXT},
     property,
 };
@@ -69,6 +69,7 @@ impl AsModule for Circle {
 
         point_module
             .concat(isosurface_module)
+            .within(&Origin::of(self))
             .concat(Module::new(
                 self,
                 spec,
@@ -0,0 +1,182 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{
+        IntoLiteral, DISTANCE, GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, UV,
+        VECTOR2, VECTOR3, X, Y, Z,
+    },
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::shape::Shape;
+
+use super::{HEIGHT, RADIUS};
+
+pub const CONE: FunctionIdentifier = FunctionIdentifier::new("cone", 4836746725369896380);
+
+pub const SLANT: Identifier = Identifier::new("slant", 9132423934337472817);
+property!(SLANT, SLANT_PROP, Type::Struct(StructIdentifier(VECTOR2)));
+
+pub const BASE: Identifier = Identifier::new("base", 6969409793661129823);
+property!(BASE, BASE_PROP, Type::Struct(StructIdentifier(VECTOR2)));
+
+/// Capped cone aligned to the Y axis,
+/// with its apex at the origin and a base of the given radius `height` units below it
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cone {
+    radius: Expr,
+    height: Expr,
+}
+
+impl Cone {
+    pub fn new(radius: impl IntoExpr, height: impl IntoExpr) -> Self {
+        Cone {
+            radius: radius.expr(),
+            height: height.expr(),
+        }
+    }
+}
+
+impl Hash for Cone {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CONE.uuid().hash(state);
+        self.radius.hash(state);
+        self.height.hash(state);
+    }
+}
+
+impl Domains for Cone {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Cone {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Cone requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Cone requires the Distance domain"
+        );
+
+        // Work in the cross-section through the axis,
+        // measuring the offset to the nearest point on the slant and the base
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let POSITION_2D = VECTOR2 {
+                X: VECTOR2 {
+                    X: POSITION_3D.X,
+                    Y: POSITION_3D.Z,
+                }.length(),
+                Y: POSITION_3D.Y,
+            };
+
+            let SLANT = POSITION_2D - VECTOR2 {
+                X: RADIUS,
+                Y: -HEIGHT,
+            } * (POSITION_2D.dot(VECTOR2 {
+                X: RADIUS,
+                Y: -HEIGHT,
+            }) / (RADIUS * RADIUS + HEIGHT * HEIGHT)).clamp(0.0, 1.0);
+
+            let BASE = VECTOR2 {
+                X: POSITION_2D.X - RADIUS * (POSITION_2D.X / RADIUS).clamp(0.0, 1.0),
+                Y: POSITION_2D.Y + HEIGHT,
+            };
+
+            let NUM = (HEIGHT * POSITION_2D.X + RADIUS * POSITION_2D.Y)
+                .max(-POSITION_2D.Y - HEIGHT)
+                .sign();
+
+            let mut GRADIENT_2D = SLANT;
+            if BASE.dot(BASE) < SLANT.dot(SLANT) {
+                GRADIENT_2D = BASE;
+            }
+
+            let DISTANCE = GRADIENT_2D.length();
+            CONTEXT.DISTANCE = DISTANCE * NUM;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: POSITION_3D.X / POSITION_2D.X * GRADIENT_2D.X,
+                    Y: GRADIENT_2D.Y,
+                    Z: POSITION_3D.Z / POSITION_2D.X * GRADIENT_2D.X,
+                } * (NUM / DISTANCE)
+            });
+        }
+
+        let pi = core::f32::consts::PI.literal();
+
+        if spec.contains(&UV.into()) {
+            // Planar on the base, cylindrical on the slant
+            block.extend(elysian_block! {
+                if BASE.dot(BASE) < SLANT.dot(SLANT) {
+                    CONTEXT.UV = VECTOR2 {
+                        X: POSITION_3D.X,
+                        Y: POSITION_3D.Z,
+                    };
+                }
+                else {
+                    CONTEXT.UV = VECTOR2 {
+                        X: (POSITION_3D.Z.atan2(POSITION_3D.X) / #pi) * 0.5 + 0.5,
+                        Y: POSITION_3D.Y,
+                    };
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: CONE,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: HEIGHT.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radius.clone().into(), self.height.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Cone {}
@@ -5,7 +5,8 @@ use elysian_core::{expr::Expr, property_identifier::PropertyIdentifier};
 use elysian_ir::{
     ast::{DISTANCE, POSITION_2D, POSITION_3D},
     module::{
-        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, SpecializationData, CONTEXT,
+        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, Origin, SpecializationData,
+        CONTEXT,
     },
 };
 
@@ -92,7 +93,9 @@ impl AsModule for Corner {
             .struct_definitions
             .push(COMBINE_CONTEXT_STRUCT.clone());
 
-        field_module.concat(corner_module)
+        field_module
+            .within(&Origin::of(self))
+            .concat(corner_module)
     }
 }
 
@@ -0,0 +1,197 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_3D, NUM, POSITION_3D, UV, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{field::RADIUS, shape::Shape};
+
+pub const CUBOID: FunctionIdentifier = FunctionIdentifier::new("cuboid", 5426975992165113308);
+
+pub const EXTENT: Identifier = Identifier::new("extent", 4558829451196564597);
+property!(EXTENT, EXTENT_PROP, Type::Struct(StructIdentifier(VECTOR3)));
+
+/// Axis-aligned box with the given half-extents,
+/// optionally with its edges and corners rounded
+///
+/// Rounding preserves the overall extent.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cuboid {
+    extent: Expr,
+    radius: Option<Expr>,
+}
+
+impl Cuboid {
+    pub fn new(extent: impl IntoExpr) -> Self {
+        Cuboid {
+            extent: extent.expr(),
+            radius: None,
+        }
+    }
+
+    pub fn rounded(extent: impl IntoExpr, radius: impl IntoExpr) -> Self {
+        Cuboid {
+            extent: extent.expr(),
+            radius: Some(radius.expr()),
+        }
+    }
+}
+
+impl Hash for Cuboid {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CUBOID.uuid().hash(state);
+        self.extent.hash(state);
+        self.radius.hash(state);
+    }
+}
+
+impl Domains for Cuboid {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Cuboid {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Cuboid requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Cuboid requires the Distance domain"
+        );
+
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let X = POSITION_3D.X.abs() - EXTENT.X + RADIUS;
+            let Y = POSITION_3D.Y.abs() - EXTENT.Y + RADIUS;
+            let Z = POSITION_3D.Z.abs() - EXTENT.Z + RADIUS;
+            let NUM = X.max(Y).max(Z);
+
+            CONTEXT.DISTANCE = VECTOR3 {
+                X: X.max(0.0),
+                Y: Y.max(0.0),
+                Z: Z.max(0.0),
+            }.length() + NUM.min(0.0) - RADIUS;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            // Outside, the gradient points away from the nearest feature;
+            // inside, it is the normal of the nearest face
+            block.extend(elysian_block! {
+                if NUM > 0.0 {
+                    CONTEXT.GRADIENT_3D = VECTOR3 {
+                        X: X.max(0.0) * POSITION_3D.X.sign(),
+                        Y: Y.max(0.0) * POSITION_3D.Y.sign(),
+                        Z: Z.max(0.0) * POSITION_3D.Z.sign(),
+                    }.normalize();
+                }
+                else {
+                    if X >= Y && X >= Z {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: POSITION_3D.X.sign(),
+                            Y: 0.0,
+                            Z: 0.0,
+                        };
+                    }
+                    else {
+                        if Y >= Z {
+                            CONTEXT.GRADIENT_3D = VECTOR3 {
+                                X: 0.0,
+                                Y: POSITION_3D.Y.sign(),
+                                Z: 0.0,
+                            };
+                        }
+                        else {
+                            CONTEXT.GRADIENT_3D = VECTOR3 {
+                                X: 0.0,
+                                Y: 0.0,
+                                Z: POSITION_3D.Z.sign(),
+                            };
+                        }
+                    }
+                }
+            });
+        }
+
+        if spec.contains(&UV.into()) {
+            // Project onto the plane of the nearest face
+            block.extend(elysian_block! {
+                if X >= Y && X >= Z {
+                    CONTEXT.UV = VECTOR2 {
+                        X: POSITION_3D.Z,
+                        Y: POSITION_3D.Y,
+                    };
+                }
+                else {
+                    if Y >= Z {
+                        CONTEXT.UV = VECTOR2 {
+                            X: POSITION_3D.X,
+                            Y: -POSITION_3D.Z,
+                        };
+                    }
+                    else {
+                        CONTEXT.UV = VECTOR2 {
+                            X: POSITION_3D.X,
+                            Y: POSITION_3D.Y,
+                        };
+                    }
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: CUBOID,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: EXTENT.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.extent.clone().into(),
+            self.radius.clone().unwrap_or(0.0.expr()).into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Cuboid {}
@@ -0,0 +1,171 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, GRADIENT_3D, NUM, POSITION_3D, UV, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::shape::Shape;
+
+use super::RADIUS;
+
+pub const CYLINDER: FunctionIdentifier = FunctionIdentifier::new("cylinder", 1065004043349901707);
+
+pub const HEIGHT: Identifier = Identifier::new("height", 1472008550093095403);
+property!(HEIGHT, HEIGHT_PROP, Type::Number(NumericType::Float));
+
+/// Capped cylinder centered on the origin and aligned to the Y axis,
+/// extending `height` above and below the XZ plane
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cylinder {
+    radius: Expr,
+    height: Expr,
+}
+
+impl Cylinder {
+    pub fn new(radius: impl IntoExpr, height: impl IntoExpr) -> Self {
+        Cylinder {
+            radius: radius.expr(),
+            height: height.expr(),
+        }
+    }
+}
+
+impl Hash for Cylinder {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        CYLINDER.uuid().hash(state);
+        self.radius.hash(state);
+        self.height.hash(state);
+    }
+}
+
+impl Domains for Cylinder {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Cylinder {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Cylinder requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Cylinder requires the Distance domain"
+        );
+
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let NUM = VECTOR2 {
+                X: POSITION_3D.X,
+                Y: POSITION_3D.Z,
+            }.length();
+            let X = NUM - RADIUS;
+            let Y = POSITION_3D.Y.abs() - HEIGHT;
+
+            CONTEXT.DISTANCE = X.max(Y).min(0.0) + VECTOR2 {
+                X: X.max(0.0),
+                Y: Y.max(0.0),
+            }.length();
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.extend(elysian_block! {
+                if X > 0.0 && Y > 0.0 {
+                    CONTEXT.GRADIENT_3D = VECTOR3 {
+                        X: POSITION_3D.X / NUM * X,
+                        Y: POSITION_3D.Y.sign() * Y,
+                        Z: POSITION_3D.Z / NUM * X,
+                    }.normalize();
+                }
+                else {
+                    if X > Y {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: POSITION_3D.X / NUM,
+                            Y: 0.0,
+                            Z: POSITION_3D.Z / NUM,
+                        };
+                    }
+                    else {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: 0.0,
+                            Y: POSITION_3D.Y.sign(),
+                            Z: 0.0,
+                        };
+                    }
+                }
+            });
+        }
+
+        let pi = core::f32::consts::PI.literal();
+
+        if spec.contains(&UV.into()) {
+            // Planar on the caps, cylindrical on the side
+            block.extend(elysian_block! {
+                if Y > X {
+                    CONTEXT.UV = VECTOR2 {
+                        X: POSITION_3D.X,
+                        Y: POSITION_3D.Z,
+                    };
+                }
+                else {
+                    CONTEXT.UV = VECTOR2 {
+                        X: (POSITION_3D.Z.atan2(POSITION_3D.X) / #pi) * 0.5 + 0.5,
+                        Y: POSITION_3D.Y,
+                    };
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: CYLINDER,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: HEIGHT.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radius.clone().into(), self.height.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Cylinder {}
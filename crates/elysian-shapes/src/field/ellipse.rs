@@ -0,0 +1,171 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_2D, NUM, POSITION_2D, VECTOR2, X, Y},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    modify::{DELTA_2D, DIR_2D},
+    shape::Shape,
+};
+
+pub const ELLIPSE: FunctionIdentifier = FunctionIdentifier::new("ellipse", 5895171041259639526);
+
+pub const RADII_2D: Identifier = Identifier::new("radii_2d", 5890850065841961548);
+property!(
+    RADII_2D,
+    RADII_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+/// Number of Newton steps taken toward the closest point on the ellipse
+const ELLIPSE_ITERATIONS: usize = 5;
+
+/// Axis-aligned ellipse with the given radii
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ellipse {
+    radii: Expr,
+}
+
+impl Ellipse {
+    pub fn new(radii: impl IntoExpr) -> Self {
+        Ellipse {
+            radii: radii.expr(),
+        }
+    }
+}
+
+impl Hash for Ellipse {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ELLIPSE.uuid().hash(state);
+        self.radii.hash(state);
+    }
+}
+
+impl Domains for Ellipse {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for Ellipse {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "Ellipse requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Ellipse requires the Distance domain"
+        );
+
+        // Fold into the positive quadrant,
+        // then solve for the angle of the closest point on the ellipse,
+        // starting from whichever axis the position is nearer
+        let mut block = elysian_block! {
+            let POSITION_2D = CONTEXT.POSITION_2D;
+            let DELTA_2D = VECTOR2 {
+                X: POSITION_2D.X.abs(),
+                Y: POSITION_2D.Y.abs(),
+            };
+
+            let mut X = RADII_2D.X * (DELTA_2D.X - RADII_2D.X);
+            let mut Y = RADII_2D.Y * (DELTA_2D.Y - RADII_2D.Y);
+            let mut NUM = 0.0;
+
+            let mut DIR_2D = VECTOR2 {
+                X: 1.0,
+                Y: 0.01,
+            }.normalize();
+            if X < Y {
+                DIR_2D = VECTOR2 {
+                    X: 0.01,
+                    Y: 1.0,
+                }.normalize();
+            }
+        };
+
+        // Each step rotates the cosine / sine pair by the angular error
+        for _ in 0..ELLIPSE_ITERATIONS {
+            block.extend(elysian_block! {
+                X = (DELTA_2D - RADII_2D * DIR_2D).dot(RADII_2D * VECTOR2 {
+                    X: -DIR_2D.Y,
+                    Y: DIR_2D.X,
+                });
+                Y = (DELTA_2D - RADII_2D * DIR_2D).dot(RADII_2D * DIR_2D)
+                    + (RADII_2D * VECTOR2 {
+                        X: -DIR_2D.Y,
+                        Y: DIR_2D.X,
+                    }).dot(RADII_2D * VECTOR2 {
+                        X: -DIR_2D.Y,
+                        Y: DIR_2D.X,
+                    });
+                NUM = (X / Y).asin();
+                DIR_2D = VECTOR2 {
+                    X: DIR_2D.X * NUM.cos() - DIR_2D.Y * NUM.sin(),
+                    Y: DIR_2D.Y * NUM.cos() + DIR_2D.X * NUM.sin(),
+                };
+            });
+        }
+
+        block.extend(elysian_block! {
+            let DIR_2D = DELTA_2D - RADII_2D * DIR_2D;
+            let DISTANCE = DIR_2D.length();
+            let mut NUM = -1.0;
+            if (DELTA_2D / RADII_2D).dot(DELTA_2D / RADII_2D) > 1.0 {
+                NUM = 1.0;
+            }
+            CONTEXT.DISTANCE = DISTANCE * NUM;
+        });
+
+        if spec.contains(&GRADIENT_2D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_2D = VECTOR2 {
+                    X: DIR_2D.X * POSITION_2D.X.sign(),
+                    Y: DIR_2D.Y * POSITION_2D.Y.sign(),
+                } * (NUM / DISTANCE)
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: ELLIPSE,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADII_2D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radii.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Ellipse {}
@@ -0,0 +1,132 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, GRADIENT_3D, POSITION_3D, UV, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::shape::Shape;
+
+pub const ELLIPSOID: FunctionIdentifier = FunctionIdentifier::new("ellipsoid", 772439362517211186);
+
+pub const RADII: Identifier = Identifier::new("radii", 3032048764495427445);
+property!(RADII, RADII_PROP, Type::Struct(StructIdentifier(VECTOR3)));
+
+/// Axis-aligned ellipsoid with the given radii
+///
+/// The distance is a bound rather than exact,
+/// though it converges on the true distance near the surface.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ellipsoid {
+    radii: Expr,
+}
+
+impl Ellipsoid {
+    pub fn new(radii: impl IntoExpr) -> Self {
+        Ellipsoid {
+            radii: radii.expr(),
+        }
+    }
+}
+
+impl Hash for Ellipsoid {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ELLIPSOID.uuid().hash(state);
+        self.radii.hash(state);
+    }
+}
+
+impl Domains for Ellipsoid {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Ellipsoid {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Ellipsoid requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Ellipsoid requires the Distance domain"
+        );
+
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let X = (POSITION_3D / RADII).length();
+            let Y = (POSITION_3D / (RADII * RADII)).length();
+            CONTEXT.DISTANCE = X * (X - 1.0) / Y;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_3D = (POSITION_3D / (RADII * RADII)).normalize()
+            });
+        }
+
+        let pi = core::f32::consts::PI.literal();
+
+        if spec.contains(&UV.into()) {
+            // Spherical coordinates of the position mapped onto the unit sphere
+            block.extend(elysian_block! {
+                let POSITION_3D = POSITION_3D / RADII;
+                CONTEXT.UV = VECTOR2 {
+                    X: (POSITION_3D.Z.sign() * (
+                        POSITION_3D.X / VECTOR2 {
+                            X: POSITION_3D.X,
+                            Y: POSITION_3D.Z,
+                        }.length()
+                    ).acos() / #pi) * -2.0 + 1.0,
+                    Y: ((POSITION_3D.Y / X).acos() / #pi) * -2.0 + 1.0,
+                };
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: ELLIPSOID,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADII.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radii.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Ellipsoid {}
@@ -0,0 +1,196 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{
+        IntoLiteral, DISTANCE, GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, UV,
+        VECTOR2, VECTOR3, X, Y, Z,
+    },
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
+
+use crate::shape::Shape;
+
+use super::{HEIGHT, RADIUS};
+
+pub const HEX_PRISM: FunctionIdentifier = FunctionIdentifier::new("hex_prism", 7445708995785265100);
+
+/// Hexagonal prism centered on the origin and aligned to the Y axis,
+/// with flat sides `radius` units from the axis
+/// and caps `height` units above and below the XZ plane
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HexPrism {
+    radius: Expr,
+    height: Expr,
+}
+
+impl HexPrism {
+    pub fn new(radius: impl IntoExpr, height: impl IntoExpr) -> Self {
+        HexPrism {
+            radius: radius.expr(),
+            height: height.expr(),
+        }
+    }
+}
+
+impl Hash for HexPrism {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        HEX_PRISM.uuid().hash(state);
+        self.radius.hash(state);
+        self.height.hash(state);
+    }
+}
+
+impl Domains for HexPrism {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for HexPrism {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "HexPrism requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "HexPrism requires the Distance domain"
+        );
+
+        let k = elysian_expr! {
+            VECTOR2 {
+                X: -0.8660254,
+                Y: 0.5,
+            }
+        };
+
+        // Fold the cross-section onto a single side of the hexagon,
+        // then combine its distance with that of the caps as for a cylinder
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let POSITION_2D = VECTOR2 {
+                X: POSITION_3D.X.abs(),
+                Y: POSITION_3D.Z.abs(),
+            };
+            let NUM = (#k.dot(POSITION_2D) * 2.0).min(0.0);
+            let POSITION_2D = POSITION_2D - #k * NUM;
+
+            let X = POSITION_2D.Y - RADIUS;
+            let POSITION_2D = VECTOR2 {
+                X: POSITION_2D.X - POSITION_2D.X.clamp(RADIUS * -0.57735, RADIUS * 0.57735),
+                Y: X,
+            };
+            let DISTANCE = POSITION_2D.length() * X.sign();
+            let Y = POSITION_3D.Y.abs() - HEIGHT;
+
+            CONTEXT.DISTANCE = DISTANCE.max(Y).min(0.0) + VECTOR2 {
+                X: DISTANCE.max(0.0),
+                Y: Y.max(0.0),
+            }.length();
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.extend(elysian_block! {
+                let mut GRADIENT_2D = POSITION_2D * (1.0 / DISTANCE);
+                if NUM < 0.0 {
+                    GRADIENT_2D = GRADIENT_2D - #k * (#k.dot(GRADIENT_2D) * 2.0);
+                }
+                let GRADIENT_2D = VECTOR2 {
+                    X: GRADIENT_2D.X * POSITION_3D.X.sign(),
+                    Y: GRADIENT_2D.Y * POSITION_3D.Z.sign(),
+                };
+
+                if DISTANCE > 0.0 && Y > 0.0 {
+                    CONTEXT.GRADIENT_3D = VECTOR3 {
+                        X: GRADIENT_2D.X * DISTANCE,
+                        Y: POSITION_3D.Y.sign() * Y,
+                        Z: GRADIENT_2D.Y * DISTANCE,
+                    }.normalize();
+                }
+                else {
+                    if DISTANCE > Y {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: GRADIENT_2D.X,
+                            Y: 0.0,
+                            Z: GRADIENT_2D.Y,
+                        };
+                    }
+                    else {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: 0.0,
+                            Y: POSITION_3D.Y.sign(),
+                            Z: 0.0,
+                        };
+                    }
+                }
+            });
+        }
+
+        let pi = core::f32::consts::PI.literal();
+
+        if spec.contains(&UV.into()) {
+            // Planar on the caps, cylindrical on the sides
+            block.extend(elysian_block! {
+                if Y > DISTANCE {
+                    CONTEXT.UV = VECTOR2 {
+                        X: POSITION_3D.X,
+                        Y: POSITION_3D.Z,
+                    };
+                }
+                else {
+                    CONTEXT.UV = VECTOR2 {
+                        X: (POSITION_3D.Z.atan2(POSITION_3D.X) / #pi) * 0.5 + 0.5,
+                        Y: POSITION_3D.Y,
+                    };
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: HEX_PRISM,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: HEIGHT.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radius.clone().into(), self.height.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for HexPrism {}
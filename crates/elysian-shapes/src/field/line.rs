@@ -8,7 +8,8 @@ use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     ast::{POSITION_2D, POSITION_3D},
     module::{
-        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, SpecializationData, CONTEXT,
+        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, Origin, SpecializationData,
+        CONTEXT,
     },
 };
 use elysian_proc_macros::elysian_stmt;
@@ -108,18 +109,21 @@ impl AsModule for Line {
             self.mode.to_string().into(),
         ));
 
-        elongate_module.concat(point_module).concat(
-            Module::new(
-                self,
-                spec,
-                elysian_function! {
-                    fn line(dir, CONTEXT) -> CONTEXT {
-                        return #point_call;
-                    }
-                },
+        elongate_module
+            .concat(point_module)
+            .within(&Origin::of(self))
+            .concat(
+                Module::new(
+                    self,
+                    spec,
+                    elysian_function! {
+                        fn line(dir, CONTEXT) -> CONTEXT {
+                            return #point_call;
+                        }
+                    },
+                )
+                .with_args([self.dir.clone().into()]),
             )
-            .with_args([self.dir.clone().into()]),
-        )
     }
 }
 
@@ -2,20 +2,50 @@ mod arc;
 mod capsule;
 mod chebyshev;
 mod circle;
+mod cone;
 mod corner;
+mod cuboid;
+mod cylinder;
+mod ellipse;
+mod ellipsoid;
+mod hex_prism;
 mod infinity;
 mod line;
+mod octahedron;
+mod plane;
 mod point;
+mod polygon;
 mod quad;
+mod quadratic_bezier;
+mod regular_polygon;
 mod ring;
+mod rounded_box;
+mod star;
+mod torus;
+mod triangle;
 
 pub use arc::*;
 pub use capsule::*;
 pub use chebyshev::*;
 pub use circle::*;
+pub use cone::*;
 pub use corner::*;
+pub use cuboid::*;
+pub use cylinder::*;
+pub use ellipse::*;
+pub use ellipsoid::*;
+pub use hex_prism::*;
 pub use infinity::*;
 pub use line::*;
+pub use octahedron::*;
+pub use plane::*;
 pub use point::*;
+pub use polygon::*;
 pub use quad::*;
+pub use quadratic_bezier::*;
+pub use regular_polygon::*;
 pub use ring::*;
+pub use rounded_box::*;
+pub use star::*;
+pub use torus::*;
+pub use triangle::*;
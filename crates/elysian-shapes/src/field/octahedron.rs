@@ -0,0 +1,174 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_3D, NUM, POSITION_3D, UV, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{modify::DIR_3D, shape::Shape};
+
+use super::RADIUS;
+
+pub const OCTAHEDRON: FunctionIdentifier =
+    FunctionIdentifier::new("octahedron", 912377299952981997);
+
+/// Regular octahedron with its vertices on the axes, `radius` units from the origin
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Octahedron {
+    radius: Expr,
+}
+
+impl Octahedron {
+    pub fn new(radius: impl IntoExpr) -> Self {
+        Octahedron {
+            radius: radius.expr(),
+        }
+    }
+}
+
+impl Hash for Octahedron {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        OCTAHEDRON.uuid().hash(state);
+        self.radius.hash(state);
+    }
+}
+
+impl Domains for Octahedron {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Octahedron {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Octahedron requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Octahedron requires the Distance domain"
+        );
+
+        // Fold into the positive octant, then measure the offset to the nearest face,
+        // or to the nearest edge of that face for points beyond its extent
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let X = POSITION_3D.X.abs();
+            let Y = POSITION_3D.Y.abs();
+            let Z = POSITION_3D.Z.abs();
+            let NUM = X + Y + Z - RADIUS;
+
+            let mut DIR_3D = VECTOR3 {
+                X: NUM / 3.0,
+                Y: NUM / 3.0,
+                Z: NUM / 3.0,
+            };
+            let mut DISTANCE = NUM * 0.57735027;
+
+            if X * 3.0 < NUM {
+                DIR_3D = VECTOR3 {
+                    X: X,
+                    Y: Y - RADIUS + ((Z - Y + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                    Z: Z - ((Z - Y + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                };
+                DISTANCE = DIR_3D.length();
+            }
+            else {
+                if Y * 3.0 < NUM {
+                    DIR_3D = VECTOR3 {
+                        X: X - ((X - Z + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                        Y: Y,
+                        Z: Z - RADIUS + ((X - Z + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                    };
+                    DISTANCE = DIR_3D.length();
+                }
+                else {
+                    if Z * 3.0 < NUM {
+                        DIR_3D = VECTOR3 {
+                            X: X - RADIUS + ((Y - X + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                            Y: Y - ((Y - X + RADIUS) * 0.5).clamp(0.0, RADIUS),
+                            Z: Z,
+                        };
+                        DISTANCE = DIR_3D.length();
+                    }
+                }
+            }
+
+            CONTEXT.DISTANCE = DISTANCE;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            // Unfold the offset back out of the positive octant
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: DIR_3D.X * POSITION_3D.X.sign(),
+                    Y: DIR_3D.Y * POSITION_3D.Y.sign(),
+                    Z: DIR_3D.Z * POSITION_3D.Z.sign(),
+                } * (1.0 / DISTANCE)
+            });
+        }
+
+        if spec.contains(&UV.into()) {
+            // Octahedral mapping, unfolding the lower half around the upper
+            block.extend(elysian_block! {
+                let NUM = X + Y + Z;
+                if POSITION_3D.Y >= 0.0 {
+                    CONTEXT.UV = VECTOR2 {
+                        X: POSITION_3D.X / NUM,
+                        Y: POSITION_3D.Z / NUM,
+                    };
+                }
+                else {
+                    CONTEXT.UV = VECTOR2 {
+                        X: (1.0 - Z / NUM) * POSITION_3D.X.sign(),
+                        Y: (1.0 - X / NUM) * POSITION_3D.Z.sign(),
+                    };
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: OCTAHEDRON,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radius.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Octahedron {}
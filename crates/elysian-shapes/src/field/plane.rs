@@ -0,0 +1,144 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_3D, POSITION_3D, UV, VECTOR2, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{modify::DIR_3D, shape::Shape};
+
+pub const PLANE: FunctionIdentifier = FunctionIdentifier::new("plane", 5673606075469142230);
+
+pub const OFFSET: Identifier = Identifier::new("offset", 4633760730015145162);
+property!(OFFSET, OFFSET_PROP, Type::Number(NumericType::Float));
+
+/// Infinite plane facing along `normal`, displaced `offset` units from the origin
+///
+/// The normal need not be unit length.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Plane {
+    normal: Expr,
+    offset: Expr,
+}
+
+impl Plane {
+    pub fn new(normal: impl IntoExpr, offset: impl IntoExpr) -> Self {
+        Plane {
+            normal: normal.expr(),
+            offset: offset.expr(),
+        }
+    }
+}
+
+impl Hash for Plane {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        PLANE.uuid().hash(state);
+        self.normal.hash(state);
+        self.offset.hash(state);
+    }
+}
+
+impl Domains for Plane {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Plane {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Plane requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Plane requires the Distance domain"
+        );
+
+        let mut block = elysian_block! {
+            let DIR_3D = DIR_3D.normalize();
+            CONTEXT.DISTANCE = CONTEXT.POSITION_3D.dot(DIR_3D) - OFFSET;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_3D = DIR_3D
+            });
+        }
+
+        if spec.contains(&UV.into()) {
+            // Project onto the axis-aligned plane closest to facing the normal
+            block.extend(elysian_block! {
+                if DIR_3D.X.abs() >= DIR_3D.Y.abs() && DIR_3D.X.abs() >= DIR_3D.Z.abs() {
+                    CONTEXT.UV = VECTOR2 {
+                        X: CONTEXT.POSITION_3D.Z,
+                        Y: CONTEXT.POSITION_3D.Y,
+                    };
+                }
+                else {
+                    if DIR_3D.Y.abs() >= DIR_3D.Z.abs() {
+                        CONTEXT.UV = VECTOR2 {
+                            X: CONTEXT.POSITION_3D.X,
+                            Y: -CONTEXT.POSITION_3D.Z,
+                        };
+                    }
+                    else {
+                        CONTEXT.UV = VECTOR2 {
+                            X: CONTEXT.POSITION_3D.X,
+                            Y: CONTEXT.POSITION_3D.Y,
+                        };
+                    }
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: PLANE,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: DIR_3D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: OFFSET.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.normal.clone().into(), self.offset.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Plane {}
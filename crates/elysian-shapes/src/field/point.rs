@@ -122,6 +122,7 @@ impl AsModule for Point {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
@@ -0,0 +1,150 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, DISTANCE, GRADIENT_2D, NUM, POSITION_2D, X, Y},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    modify::{DELTA_2D, DIR_2D},
+    shape::Shape,
+};
+
+pub const POLYGON: FunctionIdentifier = FunctionIdentifier::new("polygon", 3617026408465359965);
+
+/// Exact distance and gradient of the closed polygon through `vertices`,
+/// which may be listed in either winding order and need not be convex
+///
+/// Tracks the offset to the nearest edge and flips the sign on each edge
+/// crossed by a ray cast along the X axis, as per the even-odd rule.
+pub(super) fn polygon_block(
+    vertices: &[elysian_ir::ast::Expr],
+    spec: &SpecializationData,
+) -> Block {
+    let first = &vertices[0];
+
+    let mut block = elysian_block! {
+        let POSITION_2D = CONTEXT.POSITION_2D;
+        let mut GRADIENT_2D = POSITION_2D - #first;
+        let mut DISTANCE = GRADIENT_2D.dot(GRADIENT_2D);
+        let mut NUM = 1.0;
+    };
+
+    for (i, vi) in vertices.iter().enumerate() {
+        let vj = &vertices[(i + vertices.len() - 1) % vertices.len()];
+
+        block.extend(elysian_block! {
+            let DIR_2D = #vj - #vi;
+            let DELTA_2D = POSITION_2D - #vi;
+
+            if DELTA_2D.Y >= 0.0 && DELTA_2D.Y < DIR_2D.Y && DIR_2D.X * DELTA_2D.Y > DIR_2D.Y * DELTA_2D.X {
+                NUM = -NUM;
+            }
+            else {
+                if DELTA_2D.Y < 0.0 && DELTA_2D.Y >= DIR_2D.Y && DIR_2D.Y * DELTA_2D.X >= DIR_2D.X * DELTA_2D.Y {
+                    NUM = -NUM;
+                }
+            }
+
+            let DELTA_2D = DELTA_2D - DIR_2D * (DELTA_2D.dot(DIR_2D) / DIR_2D.dot(DIR_2D)).clamp(0.0, 1.0);
+            if DELTA_2D.dot(DELTA_2D) < DISTANCE {
+                DISTANCE = DELTA_2D.dot(DELTA_2D);
+                GRADIENT_2D = DELTA_2D;
+            }
+        });
+    }
+
+    block.extend(elysian_block! {
+        let DISTANCE = GRADIENT_2D.length();
+        CONTEXT.DISTANCE = DISTANCE * NUM;
+    });
+
+    if spec.contains(&GRADIENT_2D.into()) {
+        block.push(elysian_stmt! {
+            CONTEXT.GRADIENT_2D = GRADIENT_2D * (NUM / DISTANCE)
+        });
+    }
+
+    block.push(elysian_stmt! { return CONTEXT });
+
+    block
+}
+
+/// Closed polygon through an arbitrary list of 2D vertices
+///
+/// The vertices are baked into the generated function,
+/// which is unrolled over each edge.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon {
+    vertices: Vec<Expr>,
+}
+
+impl Polygon {
+    pub fn new(vertices: impl IntoIterator<Item = impl IntoExpr>) -> Self {
+        let vertices: Vec<_> = vertices.into_iter().map(IntoExpr::expr).collect();
+        assert!(vertices.len() >= 3, "Polygon requires at least 3 vertices");
+        Polygon { vertices }
+    }
+}
+
+impl Hash for Polygon {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        POLYGON.uuid().hash(state);
+        self.vertices.hash(state);
+    }
+}
+
+impl Domains for Polygon {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for Polygon {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "Polygon requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Polygon requires the Distance domain"
+        );
+
+        let vertices: Vec<elysian_ir::ast::Expr> =
+            self.vertices.iter().cloned().map(Into::into).collect();
+
+        let polygon = POLYGON.concat(&FunctionIdentifier::new_dynamic(
+            vertices.len().to_string().into(),
+        ));
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: polygon,
+                public: false,
+                inputs: vec![InputDefinition {
+                    id: CONTEXT.into(),
+                    mutable: true,
+                }],
+                output: CONTEXT.into(),
+                block: polygon_block(&vertices, spec),
+                provenance: None,
+            },
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Polygon {}
@@ -8,7 +8,7 @@ use elysian_ir::{
     ast::{POSITION_2D, POSITION_3D, UV, VECTOR2, X, Y, Z},
     module::{
         AsModule, Domains, DomainsDyn, FunctionDefinition, FunctionIdentifier, InputDefinition,
-        Module, SpecializationData, CONTEXT,
+        Module, Origin, SpecializationData, CONTEXT,
     },
 };
 use elysian_proc_macros::{elysian_block, elysian_stmt};
@@ -120,7 +120,7 @@ impl AsModule for Quad {
             return CONTEXT
         });
 
-        field_module.concat(Module::new(
+        field_module.within(&Origin::of(self)).concat(Module::new(
             self,
             spec,
             FunctionDefinition {
@@ -132,6 +132,7 @@ impl AsModule for Quad {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         ))
     }
@@ -0,0 +1,195 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, GRADIENT_2D, POSITION_2D, VECTOR2, X, Y},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    modify::{DELTA_2D, DIR_2D},
+    raymarch::T,
+    shape::Shape,
+};
+
+pub const QUADRATIC_BEZIER: FunctionIdentifier =
+    FunctionIdentifier::new("quadratic_bezier", 2800980219438182427);
+
+pub const BEZIER_START: Identifier = Identifier::new("bezier_start", 7954670353890230510);
+property!(
+    BEZIER_START,
+    BEZIER_START_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const BEZIER_CONTROL: Identifier = Identifier::new("bezier_control", 1054026965349808025);
+property!(
+    BEZIER_CONTROL,
+    BEZIER_CONTROL_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const BEZIER_END: Identifier = Identifier::new("bezier_end", 7786079891673492151);
+property!(
+    BEZIER_END,
+    BEZIER_END_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+/// Number of evenly-spaced samples along the curve used to seed the solver
+const BEZIER_SAMPLES: usize = 8;
+
+/// Number of Newton steps taken from the nearest sample
+const BEZIER_ITERATIONS: usize = 6;
+
+/// Quadratic Bézier segment from `start` to `end`, pulled toward `control`
+///
+/// The distance is unsigned.
+/// The closest point is found by sampling the curve,
+/// then refining the nearest sample with Newton's method.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuadraticBezier {
+    start: Expr,
+    control: Expr,
+    end: Expr,
+}
+
+impl QuadraticBezier {
+    pub fn new(start: impl IntoExpr, control: impl IntoExpr, end: impl IntoExpr) -> Self {
+        QuadraticBezier {
+            start: start.expr(),
+            control: control.expr(),
+            end: end.expr(),
+        }
+    }
+}
+
+impl Hash for QuadraticBezier {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        QUADRATIC_BEZIER.uuid().hash(state);
+        self.start.hash(state);
+        self.control.hash(state);
+        self.end.hash(state);
+    }
+}
+
+impl Domains for QuadraticBezier {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for QuadraticBezier {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "QuadraticBezier requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "QuadraticBezier requires the Distance domain"
+        );
+
+        // In power form, the curve is START + (DIR_2D * 2 + DELTA_2D * T) * T,
+        // and DISTANCE tracks the squared distance of the closest sample
+        let mut block = elysian_block! {
+            let POSITION_2D = CONTEXT.POSITION_2D;
+            let DIR_2D = BEZIER_CONTROL - BEZIER_START;
+            let DELTA_2D = BEZIER_START - BEZIER_CONTROL * 2.0 + BEZIER_END;
+
+            let mut GRADIENT_2D = BEZIER_START - POSITION_2D;
+            let mut DISTANCE = GRADIENT_2D.dot(GRADIENT_2D);
+            let mut T = 0.0;
+            let mut X = 0.0;
+            let mut Y = 0.0;
+        };
+
+        for i in 1..=BEZIER_SAMPLES {
+            let t = (i as f32 / BEZIER_SAMPLES as f32).literal();
+            block.extend(elysian_block! {
+                GRADIENT_2D = BEZIER_START + (DIR_2D * 2.0 + DELTA_2D * #t) * #t - POSITION_2D;
+                if GRADIENT_2D.dot(GRADIENT_2D) < DISTANCE {
+                    DISTANCE = GRADIENT_2D.dot(GRADIENT_2D);
+                    T = #t;
+                }
+            });
+        }
+
+        // Newton's method on the derivative of the squared distance,
+        // with its curvature floored and its steps limited to the sample spacing
+        // to keep it within the basin of the nearest sample
+        let step = (1.0 / BEZIER_SAMPLES as f32).literal();
+        for _ in 0..BEZIER_ITERATIONS {
+            block.extend(elysian_block! {
+                GRADIENT_2D = BEZIER_START + (DIR_2D * 2.0 + DELTA_2D * T) * T - POSITION_2D;
+                X = GRADIENT_2D.dot(DIR_2D + DELTA_2D * T);
+                Y = ((DIR_2D + DELTA_2D * T).dot(DIR_2D + DELTA_2D * T) * 2.0
+                    + GRADIENT_2D.dot(DELTA_2D)).max(0.0001);
+                T = (T - (X / Y).clamp(-#step, #step)).clamp(0.0, 1.0);
+            });
+        }
+
+        block.extend(elysian_block! {
+            let GRADIENT_2D = POSITION_2D - BEZIER_START - (DIR_2D * 2.0 + DELTA_2D * T) * T;
+            let DISTANCE = GRADIENT_2D.length();
+            CONTEXT.DISTANCE = DISTANCE;
+        });
+
+        if spec.contains(&GRADIENT_2D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_2D = GRADIENT_2D * (1.0 / DISTANCE)
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: QUADRATIC_BEZIER,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: BEZIER_START.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: BEZIER_CONTROL.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: BEZIER_END.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.start.clone().into(),
+            self.control.clone().into(),
+            self.end.clone().into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for QuadraticBezier {}
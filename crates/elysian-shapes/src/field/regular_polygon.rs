@@ -0,0 +1,105 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_2D, NUM, POSITION_2D},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_expr;
+
+use crate::shape::Shape;
+
+use super::{star_block, RADIUS};
+
+pub const REGULAR_POLYGON: FunctionIdentifier =
+    FunctionIdentifier::new("regular_polygon", 2000835918049782231);
+
+pub const SIDES: Identifier = Identifier::new("sides", 2630555020955898225);
+property!(SIDES, SIDES_PROP, Type::Number(NumericType::Float));
+
+/// Regular polygon with `sides` sides,
+/// its vertices `radius` units from the origin and one on the positive Y axis
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RegularPolygon {
+    sides: Expr,
+    radius: Expr,
+}
+
+impl RegularPolygon {
+    pub fn new(sides: impl IntoExpr, radius: impl IntoExpr) -> Self {
+        RegularPolygon {
+            sides: sides.expr(),
+            radius: radius.expr(),
+        }
+    }
+}
+
+impl Hash for RegularPolygon {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        REGULAR_POLYGON.uuid().hash(state);
+        self.sides.hash(state);
+        self.radius.hash(state);
+    }
+}
+
+impl Domains for RegularPolygon {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for RegularPolygon {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "RegularPolygon requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "RegularPolygon requires the Distance domain"
+        );
+
+        // A star whose inner vertices lie at the midpoints of its edges
+        let inner = elysian_expr! { RADIUS * NUM.cos() };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: REGULAR_POLYGON,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: SIDES.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block: star_block(inner, spec),
+                provenance: None,
+            },
+        )
+        .with_args([self.sides.clone().into(), self.radius.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for RegularPolygon {}
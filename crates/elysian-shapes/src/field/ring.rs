@@ -8,8 +8,8 @@ use elysian_core::{
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     module::{
-        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, NumericType, SpecializationData,
-        Type, CONTEXT,
+        AsModule, Domains, DomainsDyn, FunctionIdentifier, Module, NumericType, Origin,
+        SpecializationData, Type, CONTEXT,
     },
     property,
 };
@@ -78,6 +78,7 @@ impl AsModule for Ring {
         circle_module
             .concat(manifold_module)
             .concat(isosurface_module)
+            .within(&Origin::of(self))
             .concat(
                 Module::new(
                     self,
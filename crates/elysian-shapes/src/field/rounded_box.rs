@@ -0,0 +1,177 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_2D, NUM, POSITION_2D, VECTOR2, VECTOR4, W, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{modify::DELTA_2D, shape::Shape, wrap::elongate_basis::EXTENT_2D};
+
+pub const ROUNDED_BOX: FunctionIdentifier =
+    FunctionIdentifier::new("rounded_box", 7718870054187607042);
+
+pub const CORNER_RADII: Identifier = Identifier::new("corner_radii", 4330937499111850293);
+property!(
+    CORNER_RADII,
+    CORNER_RADII_PROP,
+    Type::Struct(StructIdentifier(VECTOR4))
+);
+
+/// Rectangle extending `extent` units from the origin along each axis,
+/// with each corner rounded by its own radius
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundedBox {
+    extent: Expr,
+    radii: Expr,
+}
+
+impl RoundedBox {
+    /// Rectangle with all four corners rounded by `radius`
+    pub fn new(extent: impl IntoExpr, radius: impl IntoExpr) -> Self {
+        let radius = radius.expr();
+        RoundedBox {
+            extent: extent.expr(),
+            radii: Expr::vector4(radius.clone(), radius.clone(), radius.clone(), radius),
+        }
+    }
+
+    /// Rectangle with its corners rounded by the X, Y, Z and W components of `radii`,
+    /// in top-right, bottom-right, top-left, bottom-left order
+    pub fn corners(extent: impl IntoExpr, radii: impl IntoExpr) -> Self {
+        RoundedBox {
+            extent: extent.expr(),
+            radii: radii.expr(),
+        }
+    }
+}
+
+impl Hash for RoundedBox {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ROUNDED_BOX.uuid().hash(state);
+        self.extent.hash(state);
+        self.radii.hash(state);
+    }
+}
+
+impl Domains for RoundedBox {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for RoundedBox {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "RoundedBox requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "RoundedBox requires the Distance domain"
+        );
+
+        // Pick the radius of the quadrant's corner,
+        // then measure against a box shrunk by that radius
+        let mut block = elysian_block! {
+            let POSITION_2D = CONTEXT.POSITION_2D;
+            let mut NUM = CORNER_RADII.W;
+            if POSITION_2D.X > 0.0 {
+                if POSITION_2D.Y > 0.0 {
+                    NUM = CORNER_RADII.X;
+                }
+                else {
+                    NUM = CORNER_RADII.Y;
+                }
+            }
+            else {
+                if POSITION_2D.Y > 0.0 {
+                    NUM = CORNER_RADII.Z;
+                }
+            }
+
+            let DELTA_2D = VECTOR2 {
+                X: POSITION_2D.X.abs() - EXTENT_2D.X + NUM,
+                Y: POSITION_2D.Y.abs() - EXTENT_2D.Y + NUM,
+            };
+            let X = DELTA_2D.X.max(DELTA_2D.Y);
+            let Y = VECTOR2 {
+                X: DELTA_2D.X.max(0.0),
+                Y: DELTA_2D.Y.max(0.0),
+            }.length();
+
+            CONTEXT.DISTANCE = X.min(0.0) + Y - NUM;
+        };
+
+        if spec.contains(&GRADIENT_2D.into()) {
+            block.extend(elysian_block! {
+                let mut GRADIENT_2D = VECTOR2 {
+                    X: 0.0,
+                    Y: 1.0,
+                };
+                if X > 0.0 {
+                    GRADIENT_2D = VECTOR2 {
+                        X: DELTA_2D.X.max(0.0),
+                        Y: DELTA_2D.Y.max(0.0),
+                    } * (1.0 / Y);
+                }
+                else {
+                    if DELTA_2D.X > DELTA_2D.Y {
+                        GRADIENT_2D = VECTOR2 {
+                            X: 1.0,
+                            Y: 0.0,
+                        };
+                    }
+                }
+
+                CONTEXT.GRADIENT_2D = VECTOR2 {
+                    X: GRADIENT_2D.X * POSITION_2D.X.sign(),
+                    Y: GRADIENT_2D.Y * POSITION_2D.Y.sign(),
+                };
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: ROUNDED_BOX,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: EXTENT_2D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CORNER_RADII.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.extent.clone().into(), self.radii.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for RoundedBox {}
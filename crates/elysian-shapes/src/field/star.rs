@@ -0,0 +1,173 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, IntoLiteral, DISTANCE, GRADIENT_2D, NUM, POSITION_2D, VECTOR2, X, Y},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
+
+use crate::{
+    modify::{DELTA_2D, DIR_2D},
+    shape::Shape,
+};
+
+use super::{RADIUS, SIDES};
+
+pub const STAR: FunctionIdentifier = FunctionIdentifier::new("star", 5754239867880249483);
+
+pub const INNER_RADIUS: Identifier = Identifier::new("inner_radius", 7394766700273877232);
+property!(
+    INNER_RADIUS,
+    INNER_RADIUS_PROP,
+    Type::Number(NumericType::Float)
+);
+
+/// Exact distance and gradient of a star with `SIDES` points `RADIUS` units from the origin,
+/// joined by edges through the `inner` radius at the bisector of each pair of points
+///
+/// Folds the position into half of a single sector,
+/// measures the offset to its edge,
+/// then unfolds that offset to recover the gradient.
+pub(super) fn star_block(inner: elysian_ir::ast::Expr, spec: &SpecializationData) -> Block {
+    let pi = core::f32::consts::PI.literal();
+
+    let mut block = elysian_block! {
+        let POSITION_2D = CONTEXT.POSITION_2D;
+        let NUM = #pi / SIDES;
+
+        let X = POSITION_2D.X.atan2(POSITION_2D.Y) - NUM;
+        let X = X - (X / (NUM * 2.0)).round() * NUM * 2.0;
+        let Y = POSITION_2D.X.atan2(POSITION_2D.Y) - X;
+
+        let DIR_2D = VECTOR2 {
+            X: NUM.cos(),
+            Y: NUM.sin(),
+        } * RADIUS;
+        let DELTA_2D = VECTOR2 {
+            X: X.cos(),
+            Y: X.sin().abs(),
+        } * POSITION_2D.length() - DIR_2D;
+        let DIR_2D = DIR_2D - VECTOR2 {
+            X: #inner,
+            Y: 0.0,
+        };
+        let DELTA_2D = DELTA_2D + DIR_2D * (-DELTA_2D.dot(DIR_2D) / DIR_2D.dot(DIR_2D)).clamp(0.0, 1.0);
+
+        let DISTANCE = DELTA_2D.length();
+        CONTEXT.DISTANCE = DISTANCE * DELTA_2D.X.sign();
+    };
+
+    if spec.contains(&GRADIENT_2D.into()) {
+        // Rotate out of the sector, whose bisector lies at angle Y
+        block.extend(elysian_block! {
+            let GRADIENT_2D = DELTA_2D * (DELTA_2D.X.sign() / DISTANCE);
+            CONTEXT.GRADIENT_2D = VECTOR2 {
+                X: Y.sin(),
+                Y: Y.cos(),
+            } * GRADIENT_2D.X + VECTOR2 {
+                X: Y.cos(),
+                Y: -Y.sin(),
+            } * (GRADIENT_2D.Y * X.sin().sign());
+        });
+    }
+
+    block.push(elysian_stmt! { return CONTEXT });
+
+    block
+}
+
+/// Star with `points` points `radius` units from the origin, one on the positive Y axis,
+/// and concave vertices `inner_radius` units from the origin
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Star {
+    points: Expr,
+    radius: Expr,
+    inner_radius: Expr,
+}
+
+impl Star {
+    pub fn new(points: impl IntoExpr, radius: impl IntoExpr, inner_radius: impl IntoExpr) -> Self {
+        Star {
+            points: points.expr(),
+            radius: radius.expr(),
+            inner_radius: inner_radius.expr(),
+        }
+    }
+}
+
+impl Hash for Star {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        STAR.uuid().hash(state);
+        self.points.hash(state);
+        self.radius.hash(state);
+        self.inner_radius.hash(state);
+    }
+}
+
+impl Domains for Star {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for Star {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "Star requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Star requires the Distance domain"
+        );
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: STAR,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: SIDES.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: INNER_RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block: star_block(elysian_expr! { INNER_RADIUS }, spec),
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.points.clone().into(),
+            self.radius.clone().into(),
+            self.inner_radius.clone().into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Star {}
@@ -0,0 +1,140 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{IntoLiteral, DISTANCE, GRADIENT_3D, NUM, POSITION_3D, UV, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::shape::Shape;
+
+use super::{RADIUS, WIDTH};
+
+pub const TORUS: FunctionIdentifier = FunctionIdentifier::new("torus", 1192465137314536164);
+
+/// Torus lying in the XZ plane,
+/// formed by revolving a circle of radius `width` at distance `radius` around the Y axis
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Torus {
+    radius: Expr,
+    width: Expr,
+}
+
+impl Torus {
+    pub fn new(radius: impl IntoExpr, width: impl IntoExpr) -> Self {
+        Torus {
+            radius: radius.expr(),
+            width: width.expr(),
+        }
+    }
+}
+
+impl Hash for Torus {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TORUS.uuid().hash(state);
+        self.radius.hash(state);
+        self.width.hash(state);
+    }
+}
+
+impl Domains for Torus {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Torus {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Torus requires the 3D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Torus requires the Distance domain"
+        );
+
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let NUM = VECTOR2 {
+                X: POSITION_3D.X,
+                Y: POSITION_3D.Z,
+            }.length();
+            let X = NUM - RADIUS;
+            let DISTANCE = VECTOR2 {
+                X: X,
+                Y: POSITION_3D.Y,
+            }.length();
+
+            CONTEXT.DISTANCE = DISTANCE - WIDTH;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: POSITION_3D.X / NUM * X / DISTANCE,
+                    Y: POSITION_3D.Y / DISTANCE,
+                    Z: POSITION_3D.Z / NUM * X / DISTANCE,
+                }
+            });
+        }
+
+        let pi = core::f32::consts::PI.literal();
+
+        if spec.contains(&UV.into()) {
+            // Angle around the Y axis, then angle around the tube
+            block.push(elysian_stmt! {
+                CONTEXT.UV = VECTOR2 {
+                    X: (POSITION_3D.Z.atan2(POSITION_3D.X) / #pi) * 0.5 + 0.5,
+                    Y: (POSITION_3D.Y.atan2(X) / #pi) * 0.5 + 0.5,
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TORUS,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: RADIUS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: WIDTH.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.radius.clone().into(), self.width.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Torus {}
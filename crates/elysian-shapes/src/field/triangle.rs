@@ -0,0 +1,138 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{DISTANCE, GRADIENT_2D, POSITION_2D, VECTOR2},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_expr;
+
+use crate::shape::Shape;
+
+use super::polygon_block;
+
+pub const TRIANGLE: FunctionIdentifier = FunctionIdentifier::new("triangle", 8724383809707592652);
+
+pub const VERTEX_A: Identifier = Identifier::new("vertex_a", 6473885800239746524);
+property!(
+    VERTEX_A,
+    VERTEX_A_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const VERTEX_B: Identifier = Identifier::new("vertex_b", 45759227708964827);
+property!(
+    VERTEX_B,
+    VERTEX_B_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const VERTEX_C: Identifier = Identifier::new("vertex_c", 7382013718669170023);
+property!(
+    VERTEX_C,
+    VERTEX_C_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+/// Triangle with the given vertices, in either winding order
+///
+/// Unlike [`Polygon`](super::Polygon), the vertices are passed as arguments,
+/// so every triangle shares a single function.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Triangle {
+    a: Expr,
+    b: Expr,
+    c: Expr,
+}
+
+impl Triangle {
+    pub fn new(a: impl IntoExpr, b: impl IntoExpr, c: impl IntoExpr) -> Self {
+        Triangle {
+            a: a.expr(),
+            b: b.expr(),
+            c: c.expr(),
+        }
+    }
+}
+
+impl Hash for Triangle {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TRIANGLE.uuid().hash(state);
+        self.a.hash(state);
+        self.b.hash(state);
+        self.c.hash(state);
+    }
+}
+
+impl Domains for Triangle {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), DISTANCE.into(), GRADIENT_2D.into()]
+    }
+}
+
+impl AsModule for Triangle {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        assert!(
+            spec.contains(&POSITION_2D.into()),
+            "Triangle requires the 2D Position domain"
+        );
+
+        assert!(
+            spec.contains(&DISTANCE.into()),
+            "Triangle requires the Distance domain"
+        );
+
+        let vertices = [
+            elysian_expr! { VERTEX_A },
+            elysian_expr! { VERTEX_B },
+            elysian_expr! { VERTEX_C },
+        ];
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TRIANGLE,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: VERTEX_A.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: VERTEX_B.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: VERTEX_C.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block: polygon_block(&vertices, spec),
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.a.clone().into(),
+            self.b.clone().into(),
+            self.c.clone().into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Triangle {}
@@ -6,6 +6,7 @@ pub mod combine;
 pub mod derive_bounding_error;
 pub mod field;
 pub mod modify;
+pub mod noise;
 pub mod prepass;
 pub mod raymarch;
 pub mod select;
@@ -15,7 +15,7 @@ use elysian_ir::{
     ast::IntoBlock,
     module::{
         AsModule, DomainsDyn, ErasedHash, FunctionDefinition, FunctionIdentifier, InputDefinition,
-        Module, SpecializationData, CONTEXT,
+        Module, Origin, SpecializationData, CONTEXT,
     },
 };
 
@@ -133,6 +133,7 @@ impl AsModule for Modify {
                     )
                     .output()
                     .block(),
+                provenance: None,
             },
         );
 
@@ -147,6 +148,7 @@ impl AsModule for Modify {
                     .cloned()
                     .fold(Module::default(), |acc, next| acc.concat(next)),
             )
+            .within(&Origin::of(self))
             .concat(modify_module);
 
         module
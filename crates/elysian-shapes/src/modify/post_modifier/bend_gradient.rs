@@ -0,0 +1,100 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{PostModifier, BEND, BEND_POSITION_2D, BEND_POSITION_3D};
+use elysian_core::{expr::Expr, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::elysian_block;
+
+pub const BEND_GRADIENT: FunctionIdentifier =
+    FunctionIdentifier::new("bend_gradient", 4884047978774913241);
+
+/// Map gradients out of the frame of a preceding [`Bend`](crate::modify::Bend)
+/// by the transpose of its Jacobian, evaluated at [`BEND_POSITION_2D`] or [`BEND_POSITION_3D`]
+///
+/// The Jacobian factors into the bend's rotation and a shear along X,
+/// so gradients are rotated back before the shear's transpose is applied.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BendGradient {
+    pub rate: Expr,
+}
+
+impl Hash for BendGradient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        BEND_GRADIENT.uuid().hash(state);
+        self.rate.hash(state);
+    }
+}
+
+impl Domains for BendGradient {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![GRADIENT_2D.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for BendGradient {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let block = if spec.contains(&GRADIENT_2D.into()) {
+            elysian_block! {
+                let POSITION_2D = CONTEXT.BEND_POSITION_2D;
+                let NUM = BEND * POSITION_2D.X;
+                let GRADIENT_2D = CONTEXT.GRADIENT_2D;
+                let X = NUM.cos() * GRADIENT_2D.X + NUM.sin() * GRADIENT_2D.Y;
+                let Y = NUM.cos() * GRADIENT_2D.Y - NUM.sin() * GRADIENT_2D.X;
+                CONTEXT.GRADIENT_2D = VECTOR2 {
+                    X: (1.0 - BEND * POSITION_2D.Y) * X + BEND * POSITION_2D.X * Y,
+                    Y: Y,
+                };
+                return CONTEXT;
+            }
+        } else if spec.contains(&GRADIENT_3D.into()) {
+            elysian_block! {
+                let POSITION_3D = CONTEXT.BEND_POSITION_3D;
+                let NUM = BEND * POSITION_3D.X;
+                let GRADIENT_3D = CONTEXT.GRADIENT_3D;
+                let X = NUM.cos() * GRADIENT_3D.X + NUM.sin() * GRADIENT_3D.Y;
+                let Y = NUM.cos() * GRADIENT_3D.Y - NUM.sin() * GRADIENT_3D.X;
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: (1.0 - BEND * POSITION_3D.Y) * X + BEND * POSITION_3D.X * Y,
+                    Y: Y,
+                    Z: GRADIENT_3D.Z,
+                };
+                return CONTEXT;
+            }
+        } else {
+            elysian_block! { return CONTEXT; }
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: BEND_GRADIENT,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: BEND.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.rate.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for BendGradient {}
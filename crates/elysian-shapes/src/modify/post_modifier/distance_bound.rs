@@ -91,6 +91,7 @@ impl AsModule for DistanceBound {
                 ],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.bound.clone().into()])
@@ -64,6 +64,7 @@ impl AsModule for GradientNormals {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
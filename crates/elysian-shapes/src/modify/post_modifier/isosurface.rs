@@ -22,6 +22,11 @@ pub const ISOSURFACE: FunctionIdentifier =
 pub const DIST: Identifier = Identifier::new("dist", 463524741302033362);
 property!(DIST, DIST_PROP, Type::Number(NumericType::Float));
 
+/// Offset a shape's surface `dist` units outward along its gradient
+///
+/// Gradients are unchanged, as the offset surface shares its normals
+/// with the original wherever distance is exact.
+/// Color is likewise carried through from the source surface untouched.
 #[derive(Debug, Clone)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Isosurface {
@@ -94,6 +99,7 @@ impl AsModule for Isosurface {
                 ],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.dist.clone().into()])
@@ -115,3 +121,17 @@ where
         self.modify().push_post(Isosurface::new(dist))
     }
 }
+
+pub trait IntoRound {
+    /// Round off a shape's edges by growing it `radius` units along its gradient
+    fn round(self, radius: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoRound for T
+where
+    T: IntoModify,
+{
+    fn round(self, radius: impl IntoExpr) -> Modify {
+        self.modify().push_post(Isosurface::new(radius))
+    }
+}
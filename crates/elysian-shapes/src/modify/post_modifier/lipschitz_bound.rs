@@ -0,0 +1,119 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{IntoModify, Modify, PostModifier};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Value, DISTANCE, DISTANCE_BOUND, GRADIENT_2D, GRADIENT_3D},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+pub const LIPSCHITZ_BOUND: FunctionIdentifier =
+    FunctionIdentifier::new("lipschitz_bound", 4032878071922499921);
+
+pub const LIPSCHITZ: Identifier = Identifier::new("lipschitz", 4610129046949443454);
+property!(LIPSCHITZ, LIPSCHITZ_PROP, Type::Number(NumericType::Float));
+
+/// Space deformation with a known bound on how far it can stretch distances
+pub trait Lipschitz {
+    /// Upper bound on the norm of the deformation's Jacobian,
+    /// by which the deformed distance must be divided to remain a lower bound
+    fn lipschitz(&self) -> Expr;
+}
+
+/// Divide distance and gradient by the Lipschitz factor of a preceding deformation,
+/// and flag distance as a bound via [`DISTANCE_BOUND`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LipschitzBound {
+    pub factor: Expr,
+}
+
+impl LipschitzBound {
+    pub fn new(factor: impl IntoExpr) -> Self {
+        LipschitzBound {
+            factor: factor.expr(),
+        }
+    }
+}
+
+impl Hash for LipschitzBound {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        LIPSCHITZ_BOUND.uuid().hash(state);
+        self.factor.hash(state);
+    }
+}
+
+impl Domains for LipschitzBound {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![DISTANCE.into(), GRADIENT_2D.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for LipschitzBound {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let mut block = elysian_block! {
+            CONTEXT.DISTANCE = CONTEXT.DISTANCE / LIPSCHITZ;
+            CONTEXT.DISTANCE_BOUND = true;
+        };
+
+        for gradient in [GRADIENT_2D, GRADIENT_3D] {
+            if spec.contains(&gradient.clone().into()) {
+                block.push(elysian_stmt! {
+                    CONTEXT.gradient = CONTEXT.gradient * (1.0 / LIPSCHITZ)
+                });
+            }
+        }
+
+        block.push(elysian_stmt! {
+            return CONTEXT
+        });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: LIPSCHITZ_BOUND,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: LIPSCHITZ.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.factor.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for LipschitzBound {}
+
+pub trait IntoLipschitzBound {
+    fn lipschitz_bound(self, factor: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoLipschitzBound for T
+where
+    T: IntoModify,
+{
+    fn lipschitz_bound(self, factor: impl IntoExpr) -> Modify {
+        self.modify().push_post(LipschitzBound::new(factor))
+    }
+}
@@ -72,6 +72,7 @@ impl AsModule for Manifold {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
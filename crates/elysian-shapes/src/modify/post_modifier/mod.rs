@@ -1,15 +1,27 @@
+mod bend_gradient;
 mod derive_support_vector;
 mod distance_bound;
 mod gradient_normals;
 mod isosurface;
+mod lipschitz_bound;
 mod manifold;
+mod onion;
+mod repeat_polar_gradient;
 mod set;
+mod taper_gradient;
+mod twist_gradient;
 mod uv_map;
 
+pub use bend_gradient::*;
 pub use derive_support_vector::*;
 pub use distance_bound::*;
 pub use gradient_normals::*;
 pub use isosurface::*;
+pub use lipschitz_bound::*;
 pub use manifold::*;
+pub use onion::*;
+pub use repeat_polar_gradient::*;
 pub use set::*;
+pub use taper_gradient::*;
+pub use twist_gradient::*;
 pub use uv_map::*;
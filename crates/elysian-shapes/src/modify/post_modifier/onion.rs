@@ -0,0 +1,158 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{IntoModify, Isosurface, Modify, PostModifier};
+use elysian_core::{
+    expr::IntoExpr, identifier::Identifier, property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, DISTANCE, GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, UV, X},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, Type, CONTEXT,
+    },
+    property,
+};
+
+use elysian_core::expr::Expr as AstExpr;
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+pub const ONION: FunctionIdentifier = FunctionIdentifier::new("onion", 5128395388757628780);
+
+pub const THICKNESS: Identifier = Identifier::new("thickness", 7115345768857584401);
+property!(THICKNESS, THICKNESS_PROP, Type::Number(NumericType::Float));
+
+/// Replace a shape with a shell `thickness` units to either side of its surface
+///
+/// Applying it again splits each shell in two, producing layered shells.
+/// Gradients are flipped inside the original surface to point out of the shell,
+/// while color is carried through from the source surface untouched.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Onion {
+    thickness: AstExpr,
+}
+
+impl Onion {
+    pub fn new(thickness: impl IntoExpr) -> Self {
+        Onion {
+            thickness: thickness.expr(),
+        }
+    }
+
+    pub fn thickness(&self) -> &AstExpr {
+        &self.thickness
+    }
+}
+
+impl Hash for Onion {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        ONION.uuid().hash(state);
+        self.thickness.hash(state);
+    }
+}
+
+impl Domains for Onion {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![
+            POSITION_2D.into(),
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_2D.into(),
+            GRADIENT_3D.into(),
+            UV.into(),
+        ]
+    }
+}
+
+impl AsModule for Onion {
+    fn module(&self, spec: &SpecializationData) -> elysian_ir::module::Module {
+        let mut block = Block::default();
+
+        if spec.contains(&DISTANCE.into()) {
+            block.extend(elysian_block! {
+                let NUM = CONTEXT.DISTANCE;
+                CONTEXT.DISTANCE = NUM.abs() - THICKNESS;
+            });
+
+            let gradient = if spec.contains(&GRADIENT_2D.into()) {
+                Some(GRADIENT_2D)
+            } else if spec.contains(&GRADIENT_3D.into()) {
+                Some(GRADIENT_3D)
+            } else {
+                None
+            };
+
+            if let Some(gradient) = gradient {
+                block.push(elysian_stmt! {
+                    CONTEXT.gradient = CONTEXT.gradient * NUM.sign()
+                })
+            }
+
+            if spec.contains(&UV.into()) && spec.contains(&POSITION_2D.into()) {
+                block.push(elysian_stmt! {
+                    CONTEXT.UV.X = CONTEXT.UV.X * NUM.sign() - THICKNESS
+                })
+            }
+        }
+
+        block.push(elysian_stmt! {
+            return CONTEXT
+        });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: ONION,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: THICKNESS.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.thickness.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for Onion {}
+
+pub trait IntoOnion {
+    fn onion(self, thickness: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoOnion for T
+where
+    T: IntoModify,
+{
+    fn onion(self, thickness: impl IntoExpr) -> Modify {
+        self.modify().push_post(Onion::new(thickness))
+    }
+}
+
+pub trait IntoHollow {
+    /// Hollow out a shape, leaving a wall `thickness` units deep inside its original surface
+    fn hollow(self, thickness: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoHollow for T
+where
+    T: IntoModify,
+{
+    fn hollow(self, thickness: impl IntoExpr) -> Modify {
+        let half = thickness.expr() * 0.5;
+        self.modify()
+            .push_post(Isosurface::new(-half.clone()))
+            .push_post(Onion::new(half))
+    }
+}
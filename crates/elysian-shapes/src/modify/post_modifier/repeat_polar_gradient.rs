@@ -0,0 +1,112 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{
+    modify::{polar_basis, PostModifier, DELTA_3D, DIR_3D, ORTHO_3D, REPEAT_ID_POLAR},
+    wrap::rotate::ANGLE,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{GRADIENT_2D, GRADIENT_3D, NUM, VECTOR2, X, Y},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::elysian_block;
+
+pub const REPEAT_POLAR_GRADIENT: FunctionIdentifier =
+    FunctionIdentifier::new("repeat_polar_gradient", 7272114683055670553);
+
+/// Rotate gradients out of sector 0 of a preceding [`RepeatPolar`](crate::modify::RepeatPolar),
+/// into the sector given by [`REPEAT_ID_POLAR`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RepeatPolarGradient {
+    pub count: Expr,
+    pub axis: Expr,
+}
+
+impl Hash for RepeatPolarGradient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        REPEAT_POLAR_GRADIENT.uuid().hash(state);
+        self.count.hash(state);
+        self.axis.hash(state);
+    }
+}
+
+impl Domains for RepeatPolarGradient {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![GRADIENT_2D.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for RepeatPolarGradient {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let angle = std::f64::consts::TAU.expr() / self.count.clone();
+
+        let (block, inputs, args) = if spec.contains(&GRADIENT_2D.into()) {
+            (
+                elysian_block! {
+                    let NUM = ANGLE * CONTEXT.REPEAT_ID_POLAR;
+                    let GRADIENT_2D = CONTEXT.GRADIENT_2D;
+                    CONTEXT.GRADIENT_2D = VECTOR2 {
+                        X: NUM.cos() * GRADIENT_2D.X - NUM.sin() * GRADIENT_2D.Y,
+                        Y: NUM.sin() * GRADIENT_2D.X + NUM.cos() * GRADIENT_2D.Y,
+                    };
+                    return CONTEXT;
+                },
+                vec![ANGLE],
+                vec![angle.into()],
+            )
+        } else if spec.contains(&GRADIENT_3D.into()) {
+            let mut block = polar_basis();
+            block.extend(elysian_block! {
+                let NUM = ANGLE * CONTEXT.REPEAT_ID_POLAR;
+                let GRADIENT_3D = CONTEXT.GRADIENT_3D;
+                let X = GRADIENT_3D.dot(DELTA_3D);
+                let Y = GRADIENT_3D.dot(ORTHO_3D);
+                CONTEXT.GRADIENT_3D = DIR_3D * DIR_3D.dot(GRADIENT_3D)
+                    + DELTA_3D * (NUM.cos() * X - NUM.sin() * Y)
+                    + ORTHO_3D * (NUM.sin() * X + NUM.cos() * Y);
+                return CONTEXT;
+            });
+            (
+                block,
+                vec![DIR_3D, ANGLE],
+                vec![self.axis.clone().normalize().into(), angle.into()],
+            )
+        } else {
+            (elysian_block! { return CONTEXT; }, vec![], vec![])
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: REPEAT_POLAR_GRADIENT,
+                public: false,
+                inputs: inputs
+                    .into_iter()
+                    .map(|id| InputDefinition {
+                        id: id.into(),
+                        mutable: false,
+                    })
+                    .chain([InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    }])
+                    .collect(),
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args(args)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for RepeatPolarGradient {}
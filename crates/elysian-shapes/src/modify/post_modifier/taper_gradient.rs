@@ -0,0 +1,96 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{PostModifier, TAPER, TAPER_POSITION_2D, TAPER_POSITION_3D};
+use elysian_core::{expr::Expr, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::elysian_block;
+
+pub const TAPER_GRADIENT: FunctionIdentifier =
+    FunctionIdentifier::new("taper_gradient", 5439912627744986170);
+
+/// Map gradients out of the frame of a preceding [`Taper`](crate::modify::Taper)
+/// by the transpose of its Jacobian, evaluated at [`TAPER_POSITION_2D`] or [`TAPER_POSITION_3D`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TaperGradient {
+    pub rate: Expr,
+}
+
+impl Hash for TaperGradient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TAPER_GRADIENT.uuid().hash(state);
+        self.rate.hash(state);
+    }
+}
+
+impl Domains for TaperGradient {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![GRADIENT_2D.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for TaperGradient {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let block = if spec.contains(&GRADIENT_2D.into()) {
+            elysian_block! {
+                let POSITION_2D = CONTEXT.TAPER_POSITION_2D;
+                let NUM = 1.0 + TAPER * POSITION_2D.Y;
+                let GRADIENT_2D = CONTEXT.GRADIENT_2D;
+                CONTEXT.GRADIENT_2D = VECTOR2 {
+                    X: GRADIENT_2D.X / NUM,
+                    Y: GRADIENT_2D.Y - TAPER * POSITION_2D.X * GRADIENT_2D.X / (NUM * NUM),
+                };
+                return CONTEXT;
+            }
+        } else if spec.contains(&GRADIENT_3D.into()) {
+            elysian_block! {
+                let POSITION_3D = CONTEXT.TAPER_POSITION_3D;
+                let NUM = 1.0 + TAPER * POSITION_3D.Y;
+                let GRADIENT_3D = CONTEXT.GRADIENT_3D;
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: GRADIENT_3D.X / NUM,
+                    Y: GRADIENT_3D.Y
+                        - TAPER
+                            * (POSITION_3D.X * GRADIENT_3D.X + POSITION_3D.Z * GRADIENT_3D.Z)
+                            / (NUM * NUM),
+                    Z: GRADIENT_3D.Z / NUM,
+                };
+                return CONTEXT;
+            }
+        } else {
+            elysian_block! { return CONTEXT; }
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TAPER_GRADIENT,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: TAPER.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.rate.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for TaperGradient {}
@@ -0,0 +1,111 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{PostModifier, DIR_3D, TWIST, TWIST_POSITION_3D};
+use elysian_core::{expr::Expr, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{GRADIENT_3D, NUM, POSITION_3D, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::elysian_block;
+
+pub const TWIST_GRADIENT: FunctionIdentifier =
+    FunctionIdentifier::new("twist_gradient", 5858938776093369111);
+
+/// Map gradients out of the frame of a preceding [`Twist`](crate::modify::Twist)
+/// by the transpose of its Jacobian, evaluated at [`TWIST_POSITION_3D`]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TwistGradient {
+    pub axis: Expr,
+    pub rate: Expr,
+}
+
+impl Hash for TwistGradient {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TWIST_GRADIENT.uuid().hash(state);
+        self.axis.hash(state);
+        self.rate.hash(state);
+    }
+}
+
+impl Domains for TwistGradient {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for TwistGradient {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let block = if spec.contains(&GRADIENT_3D.into()) {
+            elysian_block! {
+                let POSITION_3D = CONTEXT.TWIST_POSITION_3D;
+                let Y = POSITION_3D.dot(DIR_3D);
+                let NUM = TWIST * Y;
+                let X = NUM.cos();
+                let Z = NUM.sin();
+                let POSITION_3D = POSITION_3D * X
+                    - VECTOR3 {
+                        X: DIR_3D.Y * POSITION_3D.Z - DIR_3D.Z * POSITION_3D.Y,
+                        Y: DIR_3D.Z * POSITION_3D.X - DIR_3D.X * POSITION_3D.Z,
+                        Z: DIR_3D.X * POSITION_3D.Y - DIR_3D.Y * POSITION_3D.X,
+                    } * Z
+                    + DIR_3D * (Y * (1.0 - X));
+                let GRADIENT_3D = CONTEXT.GRADIENT_3D;
+                // Rotate back by NUM, plus the change in angle along the axis
+                CONTEXT.GRADIENT_3D = GRADIENT_3D * X
+                    + VECTOR3 {
+                        X: DIR_3D.Y * GRADIENT_3D.Z - DIR_3D.Z * GRADIENT_3D.Y,
+                        Y: DIR_3D.Z * GRADIENT_3D.X - DIR_3D.X * GRADIENT_3D.Z,
+                        Z: DIR_3D.X * GRADIENT_3D.Y - DIR_3D.Y * GRADIENT_3D.X,
+                    } * Z
+                    + DIR_3D * (
+                        GRADIENT_3D.dot(DIR_3D) * (1.0 - X)
+                            - TWIST * VECTOR3 {
+                                X: DIR_3D.Y * POSITION_3D.Z - DIR_3D.Z * POSITION_3D.Y,
+                                Y: DIR_3D.Z * POSITION_3D.X - DIR_3D.X * POSITION_3D.Z,
+                                Z: DIR_3D.X * POSITION_3D.Y - DIR_3D.Y * POSITION_3D.X,
+                            }.dot(GRADIENT_3D)
+                    );
+                return CONTEXT;
+            }
+        } else {
+            elysian_block! { return CONTEXT; }
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TWIST_GRADIENT,
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: DIR_3D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: TWIST.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.axis.clone().normalize().into(),
+            self.rate.clone().into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PostModifier for TwistGradient {}
@@ -11,7 +11,8 @@ use elysian_decl_macros::elysian_function;
 use elysian_ir::{
     ast::{POSITION_2D, UV},
     module::{
-        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, SpecializationData, CONTEXT,
+        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, Origin, SpecializationData,
+        CONTEXT,
     },
 };
 use elysian_proc_macros::elysian_stmt;
@@ -50,7 +51,7 @@ impl AsModule for UvMap {
 
         let uv_map = FunctionIdentifier::new_dynamic("uv_map".into());
 
-        field_module.concat(Module::new(
+        field_module.within(&Origin::of(self)).concat(Module::new(
             self,
             spec,
             elysian_function! {
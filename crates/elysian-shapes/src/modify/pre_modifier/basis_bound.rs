@@ -148,6 +148,7 @@ impl AsModule for BasisBound {
                 ],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.bound.clone().into()])
@@ -0,0 +1,157 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{BendGradient, IntoModify, Lipschitz, LipschitzBound, Modify, PreModifier};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, InputDefinition, IntoFunctionIdentifier, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_block;
+
+pub const BEND: Identifier = Identifier::new("bend", 441895841151807357);
+property!(BEND, BEND_PROP, Type::Number(NumericType::Float));
+
+pub const BEND_POSITION_2D: Identifier = Identifier::new("bend_position_2d", 6049466559971850306);
+property!(
+    BEND_POSITION_2D,
+    BEND_POSITION_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const BEND_POSITION_3D: Identifier = Identifier::new("bend_position_3d", 1755085230313182550);
+property!(
+    BEND_POSITION_3D,
+    BEND_POSITION_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+/// Bend the X axis toward Y by `rate` radians per unit along it
+///
+/// The Lipschitz factor holds within `radius` units of the origin.
+///
+/// The unbent position is written to [`BEND_POSITION_2D`] or [`BEND_POSITION_3D`],
+/// from which [`BendGradient`] maps gradients back out of the bent frame.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bend {
+    pub rate: Expr,
+    pub radius: Expr,
+}
+
+impl Hash for Bend {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        BEND.uuid().hash(state);
+        self.rate.hash(state);
+        self.radius.hash(state);
+    }
+}
+
+impl Bend {
+    /// Post-modifier mapping gradients out of the bent frame
+    pub fn gradient(&self) -> BendGradient {
+        BendGradient {
+            rate: self.rate.clone(),
+        }
+    }
+}
+
+impl Lipschitz for Bend {
+    /// The Jacobian adds a rank-one term of norm at most `rate * radius`
+    fn lipschitz(&self) -> Expr {
+        (self.rate.clone() * self.radius.clone()).abs() + 1.0
+    }
+}
+
+impl Domains for Bend {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), POSITION_3D.into()]
+    }
+}
+
+impl AsModule for Bend {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let block: Block = if spec.contains(&POSITION_2D.into()) {
+            elysian_block! {
+                let POSITION_2D = CONTEXT.POSITION_2D;
+                CONTEXT.BEND_POSITION_2D = POSITION_2D;
+                let NUM = BEND * POSITION_2D.X;
+                CONTEXT.POSITION_2D = VECTOR2 {
+                    X: NUM.cos() * POSITION_2D.X - NUM.sin() * POSITION_2D.Y,
+                    Y: NUM.sin() * POSITION_2D.X + NUM.cos() * POSITION_2D.Y,
+                };
+                return CONTEXT;
+            }
+        } else if spec.contains(&POSITION_3D.into()) {
+            elysian_block! {
+                let POSITION_3D = CONTEXT.POSITION_3D;
+                CONTEXT.BEND_POSITION_3D = POSITION_3D;
+                let NUM = BEND * POSITION_3D.X;
+                CONTEXT.POSITION_3D = VECTOR3 {
+                    X: NUM.cos() * POSITION_3D.X - NUM.sin() * POSITION_3D.Y,
+                    Y: NUM.sin() * POSITION_3D.X + NUM.cos() * POSITION_3D.Y,
+                    Z: POSITION_3D.Z,
+                };
+                return CONTEXT;
+            }
+        } else {
+            panic!("No position domain")
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: BEND.function(),
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: BEND.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.rate.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PreModifier for Bend {}
+
+pub trait IntoBend {
+    /// Bend, then correct distance and gradient by the bend's Jacobian and Lipschitz factor
+    fn bend(self, rate: impl IntoExpr, radius: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoBend for T
+where
+    T: IntoModify,
+{
+    fn bend(self, rate: impl IntoExpr, radius: impl IntoExpr) -> Modify {
+        let bend = Bend {
+            rate: rate.expr(),
+            radius: radius.expr(),
+        };
+        let gradient = bend.gradient();
+        let bound = LipschitzBound::new(bend.lipschitz());
+        self.modify()
+            .push_pre(bend)
+            .push_post(gradient)
+            .push_post(bound)
+    }
+}
@@ -133,6 +133,7 @@ impl AsModule for ElongateAxis {
                 ],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.dir.clone().into()])
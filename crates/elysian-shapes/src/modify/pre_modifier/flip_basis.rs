@@ -10,7 +10,7 @@ use elysian_ir::{
     },
     module::{
         AsModule, DomainsDyn, ErasedHash, FunctionDefinition, FunctionIdentifier, InputDefinition,
-        Module, SpecializationData, StructIdentifier, Type, CONTEXT,
+        Module, Origin, SpecializationData, StructIdentifier, Type, CONTEXT,
     },
     property,
 };
@@ -101,7 +101,7 @@ impl AsModule for FlipBasis {
             return CONTEXT
         });
 
-        field_module.concat(
+        field_module.within(&Origin::of(self)).concat(
             Module::new(
                 self,
                 spec,
@@ -120,6 +120,7 @@ impl AsModule for FlipBasis {
                     ],
                     output: CONTEXT.into(),
                     block,
+                    provenance: None,
                 },
             )
             .with_args([self.basis.clone().into()]),
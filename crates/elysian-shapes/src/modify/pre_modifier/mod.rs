@@ -1,17 +1,27 @@
 mod aspect;
 mod basis_bound;
+mod bend;
 mod cartesian_to_polar;
 mod elongate_axis;
 mod flip_basis;
 mod polar_to_cartesian;
 mod repeat;
+mod repeat_polar;
+mod taper;
 mod translate;
+mod twist;
+mod warp;
 
 pub use aspect::*;
 pub use basis_bound::*;
+pub use bend::*;
 pub use cartesian_to_polar::*;
 pub use elongate_axis::*;
 pub use flip_basis::*;
 pub use polar_to_cartesian::*;
 pub use repeat::*;
+pub use repeat_polar::*;
+pub use taper::*;
 pub use translate::*;
+pub use twist::*;
+pub use warp::*;
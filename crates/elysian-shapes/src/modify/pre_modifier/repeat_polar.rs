@@ -0,0 +1,211 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{
+    modify::{IntoModify, Modify, PreModifier, RepeatPolarGradient, DELTA_3D, DIR_3D},
+    wrap::rotate::ANGLE,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, IntoLiteral, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_block;
+
+pub const REPEAT_POLAR: FunctionIdentifier =
+    FunctionIdentifier::new("repeat_polar", 2518793040974816969);
+
+pub const REPEAT_ID_POLAR: Identifier = Identifier::new("repeat_id_polar", 4301466982916877321);
+property!(
+    REPEAT_ID_POLAR,
+    REPEAT_ID_POLAR_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const ORTHO_3D: Identifier = Identifier::new("ortho_3d", 5149297546520014362);
+property!(
+    ORTHO_3D,
+    ORTHO_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+/// Bind `DELTA_3D` and `ORTHO_3D` to an orthonormal basis
+/// of the plane perpendicular to `DIR_3D`, such that `DIR_3D × DELTA_3D = ORTHO_3D`
+pub(crate) fn polar_basis() -> Block {
+    elysian_block! {
+        let mut DELTA_3D = VECTOR3 { X: 1.0, Y: 0.0, Z: 0.0 };
+        if DIR_3D.X.abs() > 0.5 {
+            DELTA_3D = VECTOR3 { X: 0.0, Y: 1.0, Z: 0.0 };
+        }
+        let DELTA_3D = (DELTA_3D - DIR_3D * DIR_3D.dot(DELTA_3D)).normalize();
+        let ORTHO_3D = VECTOR3 {
+            X: DIR_3D.Y * DELTA_3D.Z - DIR_3D.Z * DELTA_3D.Y,
+            Y: DIR_3D.Z * DELTA_3D.X - DIR_3D.X * DELTA_3D.Z,
+            Z: DIR_3D.X * DELTA_3D.Y - DIR_3D.Y * DELTA_3D.X,
+        };
+    }
+}
+
+/// Repeat space `count` times around the origin, writing the sector index to [`REPEAT_ID_POLAR`]
+///
+/// Sector 0 is centered on the X axis, and indices increase counter-clockwise up to `count - 1`.
+/// In 3D, repetition is around `axis`, and sector 0 is centered on
+/// the X axis projected into the plane perpendicular to it,
+/// or the Y axis where `axis` lies closer to X.
+///
+/// Position is rotated into sector 0, so gradients are sector-local;
+/// [`IntoRepeatPolar`] pairs this with a [`RepeatPolarGradient`] to rotate them back.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RepeatPolar {
+    pub count: Expr,
+    pub axis: Expr,
+}
+
+impl RepeatPolar {
+    pub fn new(count: impl IntoExpr) -> Self {
+        RepeatPolar {
+            count: count.expr(),
+            axis: [0.0, 0.0, 1.0].expr(),
+        }
+    }
+
+    pub fn axis(mut self, axis: impl IntoExpr) -> Self {
+        self.axis = axis.expr();
+        self
+    }
+
+    /// Angle subtended by each sector
+    pub fn sector_angle(&self) -> Expr {
+        std::f64::consts::TAU.expr() / self.count.clone()
+    }
+
+    /// Post-modifier rotating gradients out of sector 0
+    pub fn gradient(&self) -> RepeatPolarGradient {
+        RepeatPolarGradient {
+            count: self.count.clone(),
+            axis: self.axis.clone(),
+        }
+    }
+}
+
+impl Hash for RepeatPolar {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        REPEAT_POLAR.uuid().hash(state);
+        self.count.hash(state);
+        self.axis.hash(state);
+    }
+}
+
+impl Domains for RepeatPolar {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), POSITION_3D.into()]
+    }
+}
+
+impl AsModule for RepeatPolar {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let tau = std::f64::consts::TAU.literal();
+
+        let (block, inputs, args) = if spec.contains(&POSITION_2D.into()) {
+            (
+                elysian_block! {
+                    let POSITION_2D = CONTEXT.POSITION_2D;
+                    let REPEAT_ID_POLAR = (POSITION_2D.Y.atan2(POSITION_2D.X) / ANGLE).round();
+                    let NUM = ANGLE * REPEAT_ID_POLAR;
+                    CONTEXT.POSITION_2D = VECTOR2 {
+                        X: NUM.cos() * POSITION_2D.X + NUM.sin() * POSITION_2D.Y,
+                        Y: NUM.cos() * POSITION_2D.Y - NUM.sin() * POSITION_2D.X,
+                    };
+                    CONTEXT.REPEAT_ID_POLAR = REPEAT_ID_POLAR % (#tau / ANGLE).round();
+                    return CONTEXT;
+                },
+                vec![ANGLE],
+                vec![self.sector_angle().into()],
+            )
+        } else if spec.contains(&POSITION_3D.into()) {
+            let mut block = polar_basis();
+            block.extend(elysian_block! {
+                let POSITION_3D = CONTEXT.POSITION_3D;
+                let X = POSITION_3D.dot(DELTA_3D);
+                let Y = POSITION_3D.dot(ORTHO_3D);
+                let REPEAT_ID_POLAR = (Y.atan2(X) / ANGLE).round();
+                let NUM = ANGLE * REPEAT_ID_POLAR;
+                CONTEXT.POSITION_3D = DIR_3D * DIR_3D.dot(POSITION_3D)
+                    + DELTA_3D * (NUM.cos() * X + NUM.sin() * Y)
+                    + ORTHO_3D * (NUM.cos() * Y - NUM.sin() * X);
+                CONTEXT.REPEAT_ID_POLAR = REPEAT_ID_POLAR % (#tau / ANGLE).round();
+                return CONTEXT;
+            });
+            (
+                block,
+                vec![DIR_3D, ANGLE],
+                vec![
+                    self.axis.clone().normalize().into(),
+                    self.sector_angle().into(),
+                ],
+            )
+        } else {
+            panic!("No position domain")
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: REPEAT_POLAR,
+                public: false,
+                inputs: inputs
+                    .into_iter()
+                    .map(|id| InputDefinition {
+                        id: id.into(),
+                        mutable: false,
+                    })
+                    .chain([InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    }])
+                    .collect(),
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args(args)
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PreModifier for RepeatPolar {}
+
+pub trait IntoRepeatPolar {
+    /// Repeat `count` times around the origin, or the Z axis in 3D
+    fn repeat_polar(self, count: impl IntoExpr) -> Modify;
+
+    /// Repeat `count` times around `axis`
+    fn repeat_polar_axis(self, count: impl IntoExpr, axis: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoRepeatPolar for T
+where
+    T: IntoModify,
+{
+    fn repeat_polar(self, count: impl IntoExpr) -> Modify {
+        let repeat = RepeatPolar::new(count);
+        let gradient = repeat.gradient();
+        self.modify().push_pre(repeat).push_post(gradient)
+    }
+
+    fn repeat_polar_axis(self, count: impl IntoExpr, axis: impl IntoExpr) -> Modify {
+        let repeat = RepeatPolar::new(count).axis(axis);
+        let gradient = repeat.gradient();
+        self.modify().push_pre(repeat).push_post(gradient)
+    }
+}
@@ -0,0 +1,171 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{IntoModify, Lipschitz, LipschitzBound, Modify, PreModifier, TaperGradient};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, InputDefinition, IntoFunctionIdentifier, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_block;
+
+pub const TAPER: Identifier = Identifier::new("taper", 8862022554651950124);
+property!(TAPER, TAPER_PROP, Type::Number(NumericType::Float));
+
+pub const TAPER_POSITION_2D: Identifier = Identifier::new("taper_position_2d", 5468294975341096153);
+property!(
+    TAPER_POSITION_2D,
+    TAPER_POSITION_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const TAPER_POSITION_3D: Identifier = Identifier::new("taper_position_3d", 2049884157814671524);
+property!(
+    TAPER_POSITION_3D,
+    TAPER_POSITION_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+/// Smallest cross-section scale assumed by the Lipschitz factor of a [`Taper`]
+pub const MIN_TAPER_SCALE: f64 = 1e-3;
+
+/// Scale the cross-section perpendicular to the Y axis by `1 + rate * y`
+///
+/// The Lipschitz factor holds within `radius` units of the axis
+/// and `height` units of the XZ plane, where the scale must remain positive.
+///
+/// The untapered position is written to [`TAPER_POSITION_2D`] or [`TAPER_POSITION_3D`],
+/// from which [`TaperGradient`] maps gradients back out of the tapered frame.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Taper {
+    pub rate: Expr,
+    pub radius: Expr,
+    pub height: Expr,
+}
+
+impl Hash for Taper {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TAPER.uuid().hash(state);
+        self.rate.hash(state);
+        self.radius.hash(state);
+        self.height.hash(state);
+    }
+}
+
+impl Taper {
+    /// Post-modifier mapping gradients out of the tapered frame
+    pub fn gradient(&self) -> TaperGradient {
+        TaperGradient {
+            rate: self.rate.clone(),
+        }
+    }
+}
+
+impl Lipschitz for Taper {
+    /// The Jacobian scales the cross-section by at most `1 / s`
+    /// and adds a shear of at most `rate * radius / s²`,
+    /// for the smallest scale `s` within `height`
+    ///
+    /// `s` is clamped to [`MIN_TAPER_SCALE`], as the cross-section collapses
+    /// where `rate * height` reaches 1 and the factor would otherwise be infinite or negative.
+    fn lipschitz(&self) -> Expr {
+        let rate = self.rate.clone().abs();
+        let scale = (1.0.expr() - rate.clone() * self.height.clone()).max(MIN_TAPER_SCALE);
+        1.0.expr() / scale.clone() + rate * self.radius.clone() / (scale.clone() * scale)
+    }
+}
+
+impl Domains for Taper {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), POSITION_3D.into()]
+    }
+}
+
+impl AsModule for Taper {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let block: Block = if spec.contains(&POSITION_2D.into()) {
+            elysian_block! {
+                let POSITION_2D = CONTEXT.POSITION_2D;
+                CONTEXT.TAPER_POSITION_2D = POSITION_2D;
+                let NUM = 1.0 + TAPER * POSITION_2D.Y;
+                CONTEXT.POSITION_2D = VECTOR2 {
+                    X: POSITION_2D.X / NUM,
+                    Y: POSITION_2D.Y,
+                };
+                return CONTEXT;
+            }
+        } else if spec.contains(&POSITION_3D.into()) {
+            elysian_block! {
+                let POSITION_3D = CONTEXT.POSITION_3D;
+                CONTEXT.TAPER_POSITION_3D = POSITION_3D;
+                let NUM = 1.0 + TAPER * POSITION_3D.Y;
+                CONTEXT.POSITION_3D = VECTOR3 {
+                    X: POSITION_3D.X / NUM,
+                    Y: POSITION_3D.Y,
+                    Z: POSITION_3D.Z / NUM,
+                };
+                return CONTEXT;
+            }
+        } else {
+            panic!("No position domain")
+        };
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TAPER.function(),
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: TAPER.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.rate.clone().into()])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PreModifier for Taper {}
+
+pub trait IntoTaper {
+    /// Taper, then correct distance and gradient by the taper's Jacobian and Lipschitz factor
+    fn taper(self, rate: impl IntoExpr, radius: impl IntoExpr, height: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoTaper for T
+where
+    T: IntoModify,
+{
+    fn taper(self, rate: impl IntoExpr, radius: impl IntoExpr, height: impl IntoExpr) -> Modify {
+        let taper = Taper {
+            rate: rate.expr(),
+            radius: radius.expr(),
+            height: height.expr(),
+        };
+        let gradient = taper.gradient();
+        let bound = LipschitzBound::new(taper.lipschitz());
+        self.modify()
+            .push_pre(taper)
+            .push_post(gradient)
+            .push_post(bound)
+    }
+}
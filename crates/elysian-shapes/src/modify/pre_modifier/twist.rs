@@ -0,0 +1,163 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::modify::{
+    IntoModify, Lipschitz, LipschitzBound, Modify, PreModifier, TwistGradient, DIR_3D,
+};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{NUM, POSITION_3D, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, InputDefinition, IntoFunctionIdentifier, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::elysian_block;
+
+pub const TWIST: Identifier = Identifier::new("twist", 1319864081191837876);
+property!(TWIST, TWIST_PROP, Type::Number(NumericType::Float));
+
+pub const TWIST_POSITION_3D: Identifier = Identifier::new("twist_position_3d", 8421341904936485414);
+property!(
+    TWIST_POSITION_3D,
+    TWIST_POSITION_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+/// Twist around `axis` by `rate` radians per unit along it
+///
+/// The Lipschitz factor only holds within `radius` units of the axis,
+/// beyond which the corrected distance may overestimate.
+///
+/// The untwisted position is written to [`TWIST_POSITION_3D`],
+/// from which [`TwistGradient`] maps gradients back out of the twisted frame.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Twist {
+    pub axis: Expr,
+    pub rate: Expr,
+    pub radius: Expr,
+}
+
+impl Hash for Twist {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        TWIST.uuid().hash(state);
+        self.axis.hash(state);
+        self.rate.hash(state);
+        self.radius.hash(state);
+    }
+}
+
+impl Twist {
+    /// Post-modifier mapping gradients out of the twisted frame
+    pub fn gradient(&self) -> TwistGradient {
+        TwistGradient {
+            axis: self.axis.clone(),
+            rate: self.rate.clone(),
+        }
+    }
+}
+
+impl Lipschitz for Twist {
+    /// The Jacobian adds a shear of `rate * radius` orthogonal to the axis,
+    /// whose largest singular value is `(s + sqrt(s² + 4)) / 2`
+    fn lipschitz(&self) -> Expr {
+        let shear = (self.rate.clone() * self.radius.clone()).abs();
+        (shear.clone() + Expr::vector2(shear, 2.0).length()) * 0.5
+    }
+}
+
+impl Domains for Twist {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_3D.into()]
+    }
+}
+
+impl AsModule for Twist {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Twist requires the 3D Position domain"
+        );
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: TWIST.function(),
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: DIR_3D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: TWIST.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block: elysian_block! {
+                    let POSITION_3D = CONTEXT.POSITION_3D;
+                    let Y = POSITION_3D.dot(DIR_3D);
+                    let NUM = TWIST * Y;
+                    let X = NUM.cos();
+                    let Z = NUM.sin();
+                    CONTEXT.TWIST_POSITION_3D = POSITION_3D;
+                    // Rotate by -NUM around the axis via Rodrigues' formula
+                    CONTEXT.POSITION_3D = POSITION_3D * X
+                        - VECTOR3 {
+                            X: DIR_3D.Y * POSITION_3D.Z - DIR_3D.Z * POSITION_3D.Y,
+                            Y: DIR_3D.Z * POSITION_3D.X - DIR_3D.X * POSITION_3D.Z,
+                            Z: DIR_3D.X * POSITION_3D.Y - DIR_3D.Y * POSITION_3D.X,
+                        } * Z
+                        + DIR_3D * (Y * (1.0 - X));
+                    return CONTEXT;
+                },
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.axis.clone().normalize().into(),
+            self.rate.clone().into(),
+        ])
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PreModifier for Twist {}
+
+pub trait IntoTwist {
+    /// Twist around `axis`, then correct distance and gradient
+    /// by the twist's Jacobian and Lipschitz factor
+    ///
+    /// The resulting distance is only a bound within `radius` units of the axis.
+    fn twist(self, axis: impl IntoExpr, rate: impl IntoExpr, radius: impl IntoExpr) -> Modify;
+}
+
+impl<T> IntoTwist for T
+where
+    T: IntoModify,
+{
+    fn twist(self, axis: impl IntoExpr, rate: impl IntoExpr, radius: impl IntoExpr) -> Modify {
+        let twist = Twist {
+            axis: axis.expr(),
+            rate: rate.expr(),
+            radius: radius.expr(),
+        };
+        let gradient = twist.gradient();
+        let bound = LipschitzBound::new(twist.lipschitz());
+        self.modify()
+            .push_pre(twist)
+            .push_post(gradient)
+            .push_post(bound)
+    }
+}
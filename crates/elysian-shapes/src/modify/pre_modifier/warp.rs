@@ -0,0 +1,113 @@
+use std::{fmt::Debug, hash::Hash};
+
+use crate::{
+    modify::{IntoModify, Modify, PreModifier},
+    noise::{Noise, NOISE},
+};
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::{
+    ast::{POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
+
+pub const WARP: FunctionIdentifier = FunctionIdentifier::new("warp", 2407597502136928841);
+
+/// Seed offset between the noise fields driving each axis
+const AXIS_SEED: u32 = 37;
+
+/// Offset position by a vector of independent noise fields
+///
+/// Distance is no longer exact, and may overestimate
+/// where the warp compresses space.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Warp {
+    pub noise: Noise,
+}
+
+impl Hash for Warp {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        WARP.uuid().hash(state);
+        self.noise.hash(state);
+    }
+}
+
+impl Domains for Warp {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), POSITION_3D.into()]
+    }
+}
+
+impl AsModule for Warp {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let (position, axes) = if spec.contains(&POSITION_2D.into()) {
+            (POSITION_2D, vec![X, Y])
+        } else if spec.contains(&POSITION_3D.into()) {
+            (POSITION_3D, vec![X, Y, Z])
+        } else {
+            panic!("No position domain")
+        };
+
+        let mut block = elysian_block! {
+            let position = CONTEXT.position;
+        };
+
+        for (i, axis) in axes.iter().enumerate() {
+            let noise = self
+                .noise
+                .clone()
+                .seed(self.noise.seed.wrapping_add(AXIS_SEED * i as u32));
+
+            block.extend(noise.block(elysian_expr! { position }, spec));
+            block.push(elysian_stmt! { let axis = NOISE });
+        }
+
+        block.extend(if axes.len() == 2 {
+            elysian_block! {
+                CONTEXT.POSITION_2D = POSITION_2D + VECTOR2 { X: X, Y: Y };
+                return CONTEXT;
+            }
+        } else {
+            elysian_block! {
+                CONTEXT.POSITION_3D = POSITION_3D + VECTOR3 { X: X, Y: Y, Z: Z };
+                return CONTEXT;
+            }
+        });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: FunctionIdentifier::new_dynamic("warp".into()),
+                public: false,
+                inputs: vec![InputDefinition {
+                    id: CONTEXT.into(),
+                    mutable: true,
+                }],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl PreModifier for Warp {}
+
+pub trait IntoWarp {
+    fn warp(self, noise: Noise) -> Modify;
+}
+
+impl<T> IntoWarp for T
+where
+    T: IntoModify,
+{
+    fn warp(self, noise: Noise) -> Modify {
+        self.modify().push_pre(Warp { noise })
+    }
+}
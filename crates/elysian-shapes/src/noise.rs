@@ -0,0 +1,479 @@
+use std::hash::Hash;
+
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+    property_identifier::PropertyIdentifier,
+};
+use elysian_ir::{
+    ast::{Block, IntoLiteral, DISTANCE, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+    module::{
+        AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
+
+use crate::shape::Shape;
+
+pub const NOISE: Identifier = Identifier::new("noise", 136592648929720698);
+property!(NOISE, NOISE_PROP, Type::Number(NumericType::Float));
+
+pub const NOISE_SUM: Identifier = Identifier::new("noise_sum", 3969434659881784351);
+property!(NOISE_SUM, NOISE_SUM_PROP, Type::Number(NumericType::Float));
+
+pub const NOISE_WEIGHT: Identifier = Identifier::new("noise_weight", 8791175829858114086);
+property!(
+    NOISE_WEIGHT,
+    NOISE_WEIGHT_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const NOISE_FREQUENCY: Identifier = Identifier::new("noise_frequency", 427100132373813604);
+property!(
+    NOISE_FREQUENCY,
+    NOISE_FREQUENCY_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const NOISE_AMPLITUDE: Identifier = Identifier::new("noise_amplitude", 7018210370944484832);
+property!(
+    NOISE_AMPLITUDE,
+    NOISE_AMPLITUDE_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const NOISE_HASH: Identifier = Identifier::new("noise_hash", 7279002080344755938);
+property!(
+    NOISE_HASH,
+    NOISE_HASH_PROP,
+    Type::Number(NumericType::Float)
+);
+
+pub const NOISE_POSITION_2D: Identifier = Identifier::new("noise_position_2d", 2099124999671080634);
+property!(
+    NOISE_POSITION_2D,
+    NOISE_POSITION_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const NOISE_POSITION_3D: Identifier = Identifier::new("noise_position_3d", 7674533867288637125);
+property!(
+    NOISE_POSITION_3D,
+    NOISE_POSITION_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+pub const NOISE_CELL_2D: Identifier = Identifier::new("noise_cell_2d", 6490270264518818177);
+property!(
+    NOISE_CELL_2D,
+    NOISE_CELL_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const NOISE_CELL_3D: Identifier = Identifier::new("noise_cell_3d", 855382276652893155);
+property!(
+    NOISE_CELL_3D,
+    NOISE_CELL_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+pub const NOISE_FRACT_2D: Identifier = Identifier::new("noise_fract_2d", 2069261587432130705);
+property!(
+    NOISE_FRACT_2D,
+    NOISE_FRACT_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const NOISE_FRACT_3D: Identifier = Identifier::new("noise_fract_3d", 9086279494924631273);
+property!(
+    NOISE_FRACT_3D,
+    NOISE_FRACT_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+pub const NOISE_FADE_2D: Identifier = Identifier::new("noise_fade_2d", 5892544983935125520);
+property!(
+    NOISE_FADE_2D,
+    NOISE_FADE_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const NOISE_FADE_3D: Identifier = Identifier::new("noise_fade_3d", 3662980731131920211);
+property!(
+    NOISE_FADE_3D,
+    NOISE_FADE_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
+/// Period of the hash lattice
+///
+/// `(34x² + x) mod 289` permutes the residues mod 289,
+/// and every intermediate product of hashing a lattice point
+/// stays an integer below 2²⁴, so hashes are exact in single precision
+/// and identical across backends.
+const PERIOD: f32 = 289.0;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoiseKind {
+    /// Pseudorandom values interpolated between lattice points
+    Value,
+    /// Pseudorandom gradients interpolated between lattice points, after Perlin
+    Gradient,
+}
+
+/// Deterministic lattice noise in the range `[-amplitude, amplitude]`,
+/// optionally summed over octaves as fBm
+///
+/// As a shape, writes the noise at the current position to `DISTANCE`.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Noise {
+    pub kind: NoiseKind,
+    pub frequency: Expr,
+    pub amplitude: Expr,
+    pub octaves: usize,
+    pub lacunarity: Expr,
+    pub gain: Expr,
+    pub seed: u32,
+}
+
+impl Hash for Noise {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        NOISE.uuid().hash(state);
+        self.kind.hash(state);
+        self.frequency.hash(state);
+        self.amplitude.hash(state);
+        self.octaves.hash(state);
+        self.lacunarity.hash(state);
+        self.gain.hash(state);
+        self.seed.hash(state);
+    }
+}
+
+impl Noise {
+    pub fn new(kind: NoiseKind, frequency: impl IntoExpr) -> Self {
+        Noise {
+            kind,
+            frequency: frequency.expr(),
+            amplitude: 1.0.expr(),
+            octaves: 1,
+            lacunarity: 2.0.expr(),
+            gain: 0.5.expr(),
+            seed: 0,
+        }
+    }
+
+    pub fn value(frequency: impl IntoExpr) -> Self {
+        Noise::new(NoiseKind::Value, frequency)
+    }
+
+    pub fn gradient(frequency: impl IntoExpr) -> Self {
+        Noise::new(NoiseKind::Gradient, frequency)
+    }
+
+    pub fn amplitude(mut self, amplitude: impl IntoExpr) -> Self {
+        self.amplitude = amplitude.expr();
+        self
+    }
+
+    /// Sum `octaves` layers, each with `lacunarity` times the frequency
+    /// and `gain` times the amplitude of the last
+    pub fn fbm(mut self, octaves: usize, lacunarity: impl IntoExpr, gain: impl IntoExpr) -> Self {
+        assert!(octaves > 0, "fBm requires at least one octave");
+        self.octaves = octaves;
+        self.lacunarity = lacunarity.expr();
+        self.gain = gain.expr();
+        self
+    }
+
+    pub fn seed(mut self, seed: u32) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Bind `NOISE` to the noise at `position`,
+    /// whose dimension is given by the position domain in `spec`
+    pub fn block(&self, position: elysian_ir::ast::Expr, spec: &SpecializationData) -> Block {
+        let (spatial, noise_position) = if spec.contains(&POSITION_2D.into()) {
+            (false, NOISE_POSITION_2D)
+        } else if spec.contains(&POSITION_3D.into()) {
+            (true, NOISE_POSITION_3D)
+        } else {
+            panic!("No position domain")
+        };
+
+        let frequency: elysian_ir::ast::Expr = self.frequency.clone().into();
+        let lacunarity: elysian_ir::ast::Expr = self.lacunarity.clone().into();
+        let gain: elysian_ir::ast::Expr = self.gain.clone().into();
+        let amplitude: elysian_ir::ast::Expr = self.amplitude.clone().into();
+
+        let mut block = elysian_block! {
+            let NOISE_SUM = 0.0;
+            let NOISE_WEIGHT = 0.0;
+            let NOISE_FREQUENCY = #frequency;
+            let NOISE_AMPLITUDE = 1.0;
+        };
+
+        for octave in 0..self.octaves {
+            let seed = ((self.seed as usize + octave * 101) % PERIOD as usize) as f32;
+            let position = position.clone();
+
+            block.push(elysian_stmt! {
+                let noise_position = #position * NOISE_FREQUENCY
+            });
+
+            block.extend(if spatial {
+                lattice_3d(self.kind, seed)
+            } else {
+                lattice_2d(self.kind, seed)
+            });
+
+            block.extend(elysian_block! {
+                let NOISE_SUM = NOISE_SUM + NOISE * NOISE_AMPLITUDE;
+                let NOISE_WEIGHT = NOISE_WEIGHT + NOISE_AMPLITUDE;
+                let NOISE_FREQUENCY = NOISE_FREQUENCY * #lacunarity;
+                let NOISE_AMPLITUDE = NOISE_AMPLITUDE * #gain;
+            });
+        }
+
+        block.push(elysian_stmt! {
+            let NOISE = NOISE_SUM / NOISE_WEIGHT * #amplitude
+        });
+
+        block
+    }
+}
+
+/// Quintic smoothstep, with zero first and second derivatives at 0 and 1
+fn fade(t: elysian_ir::ast::Expr) -> elysian_ir::ast::Expr {
+    elysian_expr! { #t * #t * #t * (#t * (#t * 6.0 - 15.0) + 10.0) }
+}
+
+fn read(root: Identifier, component: Identifier) -> elysian_ir::ast::Expr {
+    elysian_ir::ast::Expr::Read(vec![root.into(), component.into()])
+}
+
+/// Hash the lattice point `cell + offset` into `NOISE_HASH`,
+/// one component at a time
+fn hash_block(cell: Identifier, components: &[Identifier], offset: &[f32], seed: f32) -> Block {
+    let mut block = Block::default();
+
+    for (i, (component, offset)) in components.iter().zip(offset).enumerate() {
+        let coord = read(cell.clone(), component.clone());
+        let offset = offset.literal();
+
+        block.push(if i == 0 {
+            let seed = seed.literal();
+            elysian_stmt! { let NOISE_HASH = #coord + #offset + #seed }
+        } else {
+            elysian_stmt! { let NOISE_HASH = NOISE_HASH + #coord + #offset }
+        });
+
+        block.push(elysian_stmt! {
+            let NOISE_HASH = ((NOISE_HASH * 34.0 + 1.0) * NOISE_HASH) % 289.0
+        });
+    }
+
+    block
+}
+
+/// Interpolation weight of the lattice corner at `offset`
+fn weight(fade: Identifier, components: &[Identifier], offset: &[f32]) -> elysian_ir::ast::Expr {
+    components
+        .iter()
+        .zip(offset)
+        .map(|(component, offset)| {
+            let t = read(fade.clone(), component.clone());
+            if *offset > 0.0 {
+                t
+            } else {
+                elysian_expr! { 1.0 - #t }
+            }
+        })
+        .reduce(|acc, next| elysian_expr! { #acc * #next })
+        .unwrap()
+}
+
+/// Split `noise_position` into a wrapped lattice cell and the offset within it
+fn cell_block(
+    noise_position: Identifier,
+    cell: Identifier,
+    fract: Identifier,
+    ones: elysian_ir::ast::Expr,
+    periods: elysian_ir::ast::Expr,
+) -> Block {
+    elysian_block! {
+        let fract = noise_position % #ones;
+        let cell = (noise_position - fract).round() % #periods;
+    }
+}
+
+/// Sum the contributions of the four corners of the 2D lattice cell around `NOISE_POSITION_2D` into `NOISE`
+fn lattice_2d(kind: NoiseKind, seed: f32) -> Block {
+    let components = [X, Y];
+    let tau = (2.0 * core::f32::consts::PI / PERIOD).literal();
+
+    let mut block = cell_block(
+        NOISE_POSITION_2D,
+        NOISE_CELL_2D,
+        NOISE_FRACT_2D,
+        [1.0, 1.0].literal(),
+        [PERIOD, PERIOD].literal(),
+    );
+
+    let fx = fade(read(NOISE_FRACT_2D, X));
+    let fy = fade(read(NOISE_FRACT_2D, Y));
+
+    block.extend(elysian_block! {
+        let NOISE_FADE_2D = VECTOR2 {
+            X: #fx,
+            Y: #fy,
+        };
+        let NOISE = 0.0;
+    });
+
+    for corner in 0..4 {
+        let offset = [(corner & 1) as f32, (corner >> 1 & 1) as f32];
+        let [ox, oy] = offset.map(|o| o.literal());
+
+        block.extend(hash_block(NOISE_CELL_2D, &components, &offset, seed));
+
+        let weight = weight(NOISE_FADE_2D, &components, &offset);
+        let tau = tau.clone();
+
+        let value = match kind {
+            NoiseKind::Value => elysian_expr! { NOISE_HASH / 144.0 - 1.0 },
+            NoiseKind::Gradient => elysian_expr! {
+                (NOISE_HASH * #tau).cos() * (NOISE_FRACT_2D.X - #ox)
+                    + (NOISE_HASH * #tau).sin() * (NOISE_FRACT_2D.Y - #oy)
+            },
+        };
+
+        block.push(elysian_stmt! {
+            let NOISE = NOISE + #weight * #value
+        });
+    }
+
+    // Gradient noise peaks at half the diagonal of a cell
+    if kind == NoiseKind::Gradient {
+        let scale = core::f32::consts::SQRT_2.literal();
+        block.push(elysian_stmt! {
+            let NOISE = NOISE * #scale
+        });
+    }
+
+    block
+}
+
+/// Sum the contributions of the eight corners of the 3D lattice cell around `NOISE_POSITION_3D` into `NOISE`
+fn lattice_3d(kind: NoiseKind, seed: f32) -> Block {
+    let components = [X, Y, Z];
+    let tau = (2.0 * core::f32::consts::PI).literal();
+
+    let mut block = cell_block(
+        NOISE_POSITION_3D,
+        NOISE_CELL_3D,
+        NOISE_FRACT_3D,
+        [1.0, 1.0, 1.0].literal(),
+        [PERIOD, PERIOD, PERIOD].literal(),
+    );
+
+    let fx = fade(read(NOISE_FRACT_3D, X));
+    let fy = fade(read(NOISE_FRACT_3D, Y));
+    let fz = fade(read(NOISE_FRACT_3D, Z));
+
+    block.extend(elysian_block! {
+        let NOISE_FADE_3D = VECTOR3 {
+            X: #fx,
+            Y: #fy,
+            Z: #fz,
+        };
+        let NOISE = 0.0;
+    });
+
+    for corner in 0..8 {
+        let offset = [
+            (corner & 1) as f32,
+            (corner >> 1 & 1) as f32,
+            (corner >> 2 & 1) as f32,
+        ];
+        let [ox, oy, oz] = offset.map(|o| o.literal());
+
+        block.extend(hash_block(NOISE_CELL_3D, &components, &offset, seed));
+
+        let weight = weight(NOISE_FADE_3D, &components, &offset);
+        let tau = tau.clone();
+
+        // Gradients lie on a Fibonacci sphere indexed by the hash
+        let value = match kind {
+            NoiseKind::Value => elysian_expr! { NOISE_HASH / 144.0 - 1.0 },
+            NoiseKind::Gradient => elysian_expr! {
+                (1.0 - (NOISE_HASH * 2.0 + 1.0) / 289.0).acos().sin()
+                    * (((NOISE_HASH * 0.618034) % 1.0 * #tau).cos() * (NOISE_FRACT_3D.X - #ox)
+                        + ((NOISE_HASH * 0.618034) % 1.0 * #tau).sin() * (NOISE_FRACT_3D.Y - #oy))
+                    + (1.0 - (NOISE_HASH * 2.0 + 1.0) / 289.0) * (NOISE_FRACT_3D.Z - #oz)
+            },
+        };
+
+        block.push(elysian_stmt! {
+            let NOISE = NOISE + #weight * #value
+        });
+    }
+
+    // Gradient noise peaks at half the diagonal of a cell
+    if kind == NoiseKind::Gradient {
+        let scale = (2.0 / 3.0f32.sqrt()).literal();
+        block.push(elysian_stmt! {
+            let NOISE = NOISE * #scale
+        });
+    }
+
+    block
+}
+
+impl Domains for Noise {
+    fn domains() -> Vec<PropertyIdentifier> {
+        vec![POSITION_2D.into(), POSITION_3D.into(), DISTANCE.into()]
+    }
+}
+
+impl AsModule for Noise {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        let position = if spec.contains(&POSITION_2D.into()) {
+            elysian_expr! { CONTEXT.POSITION_2D }
+        } else if spec.contains(&POSITION_3D.into()) {
+            elysian_expr! { CONTEXT.POSITION_3D }
+        } else {
+            panic!("No position domain")
+        };
+
+        let mut block = self.block(position, spec);
+
+        block.extend(elysian_block! {
+            CONTEXT.DISTANCE = NOISE;
+            return CONTEXT;
+        });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: FunctionIdentifier::new_dynamic("noise".into()),
+                public: false,
+                inputs: vec![InputDefinition {
+                    id: CONTEXT.into(),
+                    mutable: true,
+                }],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Noise {}
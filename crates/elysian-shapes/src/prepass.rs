@@ -3,7 +3,8 @@ use std::hash::Hash;
 use elysian_core::property_identifier::PropertyIdentifier;
 use elysian_decl_macros::elysian_function;
 use elysian_ir::module::{
-    AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, SpecializationData, CONTEXT,
+    AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, Origin, SpecializationData,
+    CONTEXT,
 };
 use elysian_proc_macros::elysian_stmt;
 
@@ -43,17 +44,20 @@ impl AsModule for Prepass {
 
         let prepass = FunctionIdentifier::new_dynamic("prepass".into());
 
-        prepass_module.concat(field_module).concat(Module::new(
-            self,
-            spec,
-            elysian_function! {
-                fn prepass(CONTEXT) -> CONTEXT {
-                    let CONTEXT = #prepass_call;
-                    let CONTEXT = #field_call;
-                    return CONTEXT;
-                }
-            },
-        ))
+        prepass_module
+            .concat(field_module)
+            .within(&Origin::of(self))
+            .concat(Module::new(
+                self,
+                spec,
+                elysian_function! {
+                    fn prepass(CONTEXT) -> CONTEXT {
+                        let CONTEXT = #prepass_call;
+                        let CONTEXT = #field_call;
+                        return CONTEXT;
+                    }
+                },
+            ))
     }
 }
 
@@ -11,7 +11,7 @@ use elysian_ir::{
         X, Y, Z,
     },
     module::{
-        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, NumericType,
+        AsModule, DomainsDyn, ErasedHash, FunctionIdentifier, Module, NumericType, Origin,
         SpecializationData, StructIdentifier, Type, CONTEXT,
     },
     property,
@@ -228,7 +228,7 @@ impl AsModule for Raymarch {
             }
         };
 
-        field_module.concat(Module::new(
+        field_module.within(&Origin::of(self)).concat(Module::new(
             self,
             spec,
             elysian_function! {
@@ -3,7 +3,7 @@ use std::hash::{Hash, Hasher};
 
 use elysian_core::property_identifier::PropertyIdentifier;
 use elysian_ir::ast::IntoBlock;
-use elysian_ir::module::{AsModule, Module};
+use elysian_ir::module::{AsModule, Module, Origin};
 use elysian_proc_macros::elysian_stmt;
 
 use elysian_core::expr::IntoExpr;
@@ -84,6 +84,7 @@ impl AsModule for Select {
             .into_iter()
             .fold(Module::default(), |acc, (_, next)| acc.concat(next))
             .concat(default_module)
+            .within(&Origin::of(self))
             .concat(Module::new(
                 self,
                 spec,
@@ -96,6 +97,7 @@ impl AsModule for Select {
                     }],
                     output: CONTEXT.into(),
                     block,
+                    provenance: None,
                 },
             ))
     }
@@ -103,6 +103,7 @@ impl Wrapper for ElongateBasis {
                 ],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
         .with_args([self.extent.clone().into()])
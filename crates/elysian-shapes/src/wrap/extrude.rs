@@ -0,0 +1,156 @@
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use elysian_core::expr::IntoExpr;
+use elysian_ir::{
+    ast::{
+        DISTANCE, GRADIENT_2D, GRADIENT_3D, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z,
+    },
+    module::{
+        AsModule, DomainsDyn, ErasedHash, FunctionDefinition, FunctionIdentifier, InputDefinition,
+        Module, Origin, SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    field::HEIGHT,
+    shape::{DynShape, IntoShape, Shape},
+};
+
+/// 2D shape extruded along the Z axis,
+/// with caps `height` units above and below the XY plane
+///
+/// Distance is exact wherever that of the 2D shape is.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Extrude {
+    pub field: DynShape,
+    pub height: elysian_core::expr::Expr,
+}
+
+impl Extrude {
+    pub fn new(field: impl IntoShape, height: impl IntoExpr) -> Self {
+        Extrude {
+            field: field.shape(),
+            height: height.expr(),
+        }
+    }
+}
+
+impl Hash for Extrude {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.field.erased_hash());
+        self.height.hash(state);
+    }
+}
+
+impl DomainsDyn for Extrude {
+    fn domains_dyn(&self) -> Vec<elysian_core::property_identifier::PropertyIdentifier> {
+        vec![POSITION_3D.into(), DISTANCE.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for Extrude {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Extrude requires the 3D Position domain"
+        );
+
+        let field_module = self.field.module(&SpecializationData::new_2d());
+        let field_call = field_module.call(elysian_stmt! { CONTEXT });
+
+        // Distance to the profile and to the caps,
+        // combined as for the corner of a box
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            CONTEXT.POSITION_2D = VECTOR2 {
+                X: POSITION_3D.X,
+                Y: POSITION_3D.Y,
+            };
+            CONTEXT = #field_call;
+
+            let X = CONTEXT.DISTANCE;
+            let Y = POSITION_3D.Z.abs() - HEIGHT;
+            CONTEXT.DISTANCE = X.max(Y).min(0.0) + VECTOR2 {
+                X: X.max(0.0),
+                Y: Y.max(0.0),
+            }.length();
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.extend(elysian_block! {
+                let GRADIENT_2D = CONTEXT.GRADIENT_2D;
+                if X > 0.0 && Y > 0.0 {
+                    CONTEXT.GRADIENT_3D = VECTOR3 {
+                        X: GRADIENT_2D.X * X,
+                        Y: GRADIENT_2D.Y * X,
+                        Z: POSITION_3D.Z.sign() * Y,
+                    }.normalize();
+                }
+                else {
+                    if X > Y {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: GRADIENT_2D.X,
+                            Y: GRADIENT_2D.Y,
+                            Z: 0.0,
+                        };
+                    }
+                    else {
+                        CONTEXT.GRADIENT_3D = VECTOR3 {
+                            X: 0.0,
+                            Y: 0.0,
+                            Z: POSITION_3D.Z.sign(),
+                        };
+                    }
+                }
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        field_module.within(&Origin::of(self)).concat(
+            Module::new(
+                self,
+                spec,
+                FunctionDefinition {
+                    id: FunctionIdentifier::new_dynamic("extrude".into()),
+                    public: false,
+                    inputs: vec![
+                        InputDefinition {
+                            id: HEIGHT.into(),
+                            mutable: false,
+                        },
+                        InputDefinition {
+                            id: CONTEXT.into(),
+                            mutable: true,
+                        },
+                    ],
+                    output: CONTEXT.into(),
+                    block,
+                    provenance: None,
+                },
+            )
+            .with_args([self.height.clone().into()]),
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Extrude {}
+
+pub trait IntoExtrude {
+    fn extrude(self, height: impl IntoExpr) -> Extrude;
+}
+
+impl<T> IntoExtrude for T
+where
+    T: IntoShape,
+{
+    fn extrude(self, height: impl IntoExpr) -> Extrude {
+        Extrude::new(self, height)
+    }
+}
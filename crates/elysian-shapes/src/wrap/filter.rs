@@ -73,6 +73,7 @@ impl Wrapper for Filter {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
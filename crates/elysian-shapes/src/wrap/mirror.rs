@@ -154,6 +154,7 @@ impl Wrapper for Mirror {
                 }],
                 output: CONTEXT.into(),
                 block,
+                provenance: None,
             },
         )
     }
@@ -2,8 +2,11 @@ pub mod cross_section;
 pub mod filter;
 pub mod rotate;
 pub mod elongate_basis;
+pub mod extrude;
 pub mod mirror;
+pub mod revolve;
 pub mod scale;
+pub mod transform;
 
 use std::{
     fmt::Debug,
@@ -63,6 +66,12 @@ impl AsModule for Wrap {
 
         let wrapper_module = self.wrapper.module(spec, field_call);
 
+        // Attribute the wrapped shape to the concrete wrapper type
+        let shape_module = match wrapper_module.origin() {
+            Some(origin) => shape_module.within(origin),
+            None => shape_module,
+        };
+
         shape_module.concat(wrapper_module)
     }
 }
@@ -0,0 +1,152 @@
+use std::{
+    fmt::Debug,
+    hash::{Hash, Hasher},
+};
+
+use elysian_core::expr::IntoExpr;
+use elysian_ir::{
+    ast::{
+        DISTANCE, GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y,
+        Z,
+    },
+    module::{
+        AsModule, DomainsDyn, ErasedHash, FunctionDefinition, FunctionIdentifier, InputDefinition,
+        Module, Origin, SpecializationData, CONTEXT,
+    },
+};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    field::OFFSET,
+    modify::{DELTA_3D, DIR_3D},
+    shape::{DynShape, IntoShape, Shape},
+};
+
+/// 2D profile revolved around `axis`, `offset` units from it
+///
+/// The profile's X axis points away from the axis of revolution,
+/// and its Y axis along it.
+/// Distance is exact wherever that of the profile is.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Revolve {
+    pub field: DynShape,
+    pub axis: elysian_core::expr::Expr,
+    pub offset: elysian_core::expr::Expr,
+}
+
+impl Revolve {
+    pub fn new(field: impl IntoShape, axis: impl IntoExpr, offset: impl IntoExpr) -> Self {
+        Revolve {
+            field: field.shape(),
+            axis: axis.expr(),
+            offset: offset.expr(),
+        }
+    }
+}
+
+impl Hash for Revolve {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.field.erased_hash());
+        self.axis.hash(state);
+        self.offset.hash(state);
+    }
+}
+
+impl DomainsDyn for Revolve {
+    fn domains_dyn(&self) -> Vec<elysian_core::property_identifier::PropertyIdentifier> {
+        vec![POSITION_3D.into(), DISTANCE.into(), GRADIENT_3D.into()]
+    }
+}
+
+impl AsModule for Revolve {
+    fn module(&self, spec: &SpecializationData) -> Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Revolve requires the 3D Position domain"
+        );
+
+        let field_module = self.field.module(&SpecializationData::new_2d());
+        let field_call = field_module.call(elysian_stmt! { CONTEXT });
+
+        // Split position into components along and away from the axis,
+        // falling back to an arbitrary perpendicular for points on the axis itself
+        let mut block = elysian_block! {
+            let POSITION_3D = CONTEXT.POSITION_3D;
+            let NUM = POSITION_3D.dot(DIR_3D);
+            let mut DELTA_3D = POSITION_3D - DIR_3D * NUM;
+            let X = DELTA_3D.length();
+            if X > 0.0 {
+                DELTA_3D = DELTA_3D.normalize();
+            } else {
+                DELTA_3D = VECTOR3 { X: -DIR_3D.Y, Y: DIR_3D.X, Z: 0.0 };
+                if DIR_3D.Z.abs() > 0.5 {
+                    DELTA_3D = VECTOR3 { X: 0.0, Y: -DIR_3D.Z, Z: DIR_3D.Y };
+                }
+                DELTA_3D = DELTA_3D.normalize();
+            }
+            CONTEXT.POSITION_2D = VECTOR2 {
+                X: X - OFFSET,
+                Y: NUM,
+            };
+            CONTEXT = #field_call;
+        };
+
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.extend(elysian_block! {
+                let GRADIENT_2D = CONTEXT.GRADIENT_2D;
+                CONTEXT.GRADIENT_3D = DELTA_3D * GRADIENT_2D.X + DIR_3D * GRADIENT_2D.Y;
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        field_module.within(&Origin::of(self)).concat(
+            Module::new(
+                self,
+                spec,
+                FunctionDefinition {
+                    id: FunctionIdentifier::new_dynamic("revolve".into()),
+                    public: false,
+                    inputs: vec![
+                        InputDefinition {
+                            id: DIR_3D.into(),
+                            mutable: false,
+                        },
+                        InputDefinition {
+                            id: OFFSET.into(),
+                            mutable: false,
+                        },
+                        InputDefinition {
+                            id: CONTEXT.into(),
+                            mutable: true,
+                        },
+                    ],
+                    output: CONTEXT.into(),
+                    block,
+                    provenance: None,
+                },
+            )
+            .with_args([
+                self.axis.clone().normalize().into(),
+                self.offset.clone().into(),
+            ]),
+        )
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Shape for Revolve {}
+
+pub trait IntoRevolve {
+    fn revolve(self, axis: impl IntoExpr, offset: impl IntoExpr) -> Revolve;
+}
+
+impl<T> IntoRevolve for T
+where
+    T: IntoShape,
+{
+    fn revolve(self, axis: impl IntoExpr, offset: impl IntoExpr) -> Revolve {
+        Revolve::new(self, axis, offset)
+    }
+}
@@ -1,27 +1,54 @@
-use std::{
-    fmt::Debug,
-    hash::Hash,
-};
+use std::{fmt::Debug, hash::Hash};
 
 use elysian_core::{
-    expr::{Expr, IntoExpr},
+    expr::{BoxExpr, Expr, IntoExpr, IntoLiteral},
     identifier::Identifier,
 };
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
-    ast::{GRADIENT_2D, POSITION_2D, POSITION_3D, VECTOR2, X, Y},
+    ast::{
+        Block, GRADIENT_2D, GRADIENT_3D, MATRIX3, NORMAL, POSITION_2D, POSITION_3D, VECTOR2,
+        VECTOR3, VECTOR4, W, X, X_AXIS_3, Y, Y_AXIS_3, Z, Z_AXIS_3,
+    },
     module::{
-        DomainsDyn, FunctionIdentifier, Module, NumericType, SpecializationData,
-        Type, CONTEXT,
+        DomainsDyn, ErasedHash, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        NumericType, SpecializationData, StructIdentifier, Type, CONTEXT,
     },
     property,
 };
+use elysian_math::glam::{Mat3, Quat};
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
 
-use crate::{shape::Shape, wrap::{Wrapper, Wrap}};
+use crate::{
+    modify::DIR_3D,
+    shape::Shape,
+    wrap::{Wrap, Wrapper},
+};
 
 pub const ANGLE: Identifier = Identifier::new("angle", 17396665761465842676);
 property!(ANGLE, ANGLE_PROP, Type::Number(NumericType::Float));
 
+pub const ROTATION_3D: Identifier = Identifier::new("rotation_3d", 6786345134881561194);
+property!(
+    ROTATION_3D,
+    ROTATION_3D_PROP,
+    Type::Struct(StructIdentifier(MATRIX3))
+);
+
+pub const QUATERNION: Identifier = Identifier::new("quaternion", 7863241333920590753);
+property!(
+    QUATERNION,
+    QUATERNION_PROP,
+    Type::Struct(StructIdentifier(VECTOR4))
+);
+
+pub const EULER_ANGLES: Identifier = Identifier::new("euler_angles", 3548581118779587835);
+property!(
+    EULER_ANGLES,
+    EULER_ANGLES_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
 #[derive(Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rotate {
@@ -36,7 +63,13 @@ impl DomainsDyn for Rotate {
 
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl Wrapper for Rotate {
-    fn module(&self,spec: &SpecializationData,field_call:elysian_ir::ast::Expr) -> Module {
+    fn module(&self, spec: &SpecializationData, field_call: elysian_ir::ast::Expr) -> Module {
+        // In 3D, rotate about the Z axis to match the planar case
+        if spec.contains(&POSITION_3D.into()) {
+            let rotation = Rotation::axis_angle([0.0, 0.0, 1.0], -self.angle.clone());
+            return rotate_3d(self, spec, field_call, &rotation);
+        }
+
         let rotate = FunctionIdentifier::new_dynamic("rotate".into());
 
         Module::new(self, spec, elysian_function! {
@@ -56,9 +89,8 @@ impl Wrapper for Rotate {
 
                 return CONTEXT;
             }
-        }).with_args(
-            [self.angle.clone().into()], 
-        )
+        })
+        .with_args([self.angle.clone().into()])
     }
 }
 
@@ -79,3 +111,283 @@ where
         )
     }
 }
+
+/// Order in which [`Rotation::Euler`] composes its per-axis rotations
+///
+/// Matches the convention of [`glam::EulerRot`](elysian_math::glam::EulerRot):
+/// the first axis is outermost, so `XYZ` yields `Rx * Ry * Rz`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+}
+
+/// 3D rotation from local to world space
+#[derive(Debug, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Rotation {
+    /// `angle` radians around `axis`, which need not be unit length
+    AxisAngle { axis: Expr, angle: BoxExpr },
+    /// Per-axis angles in radians, composed in the given order
+    Euler { order: EulerOrder, angles: Expr },
+    /// `X`, `Y`, `Z`, `W` quaternion, which need not be unit length
+    Quaternion(Expr),
+    /// Orthonormal `MATRIX3`
+    Matrix(Expr),
+}
+
+impl Rotation {
+    pub fn axis_angle(axis: impl IntoExpr, angle: impl IntoExpr) -> Self {
+        Rotation::AxisAngle {
+            axis: axis.expr(),
+            angle: angle.box_expr(),
+        }
+    }
+
+    pub fn euler(order: EulerOrder, angles: impl IntoExpr) -> Self {
+        Rotation::Euler {
+            order,
+            angles: angles.expr(),
+        }
+    }
+
+    pub fn quaternion(quaternion: impl IntoExpr) -> Self {
+        Rotation::Quaternion(quaternion.expr())
+    }
+
+    pub fn matrix(matrix: impl IntoExpr) -> Self {
+        Rotation::Matrix(matrix.expr())
+    }
+
+    /// Function parameters and their arguments, followed by
+    /// statements binding the local-to-world matrix to [`ROTATION_3D`]
+    fn prologue(&self) -> (Vec<(Identifier, Expr)>, Block) {
+        match self {
+            Rotation::AxisAngle { axis, angle } => (
+                vec![(DIR_3D, axis.clone().normalize()), (ANGLE, *angle.clone())],
+                // Rodrigues' rotation formula
+                elysian_block! {
+                    let X = ANGLE.cos();
+                    let Y = ANGLE.sin();
+                    let Z = 1.0 - X;
+                    let ROTATION_3D = MATRIX3 {
+                        X_AXIS_3: VECTOR3 {
+                            X: Z * DIR_3D.X * DIR_3D.X + X,
+                            Y: Z * DIR_3D.X * DIR_3D.Y + Y * DIR_3D.Z,
+                            Z: Z * DIR_3D.X * DIR_3D.Z - Y * DIR_3D.Y,
+                        },
+                        Y_AXIS_3: VECTOR3 {
+                            X: Z * DIR_3D.X * DIR_3D.Y - Y * DIR_3D.Z,
+                            Y: Z * DIR_3D.Y * DIR_3D.Y + X,
+                            Z: Z * DIR_3D.Y * DIR_3D.Z + Y * DIR_3D.X,
+                        },
+                        Z_AXIS_3: VECTOR3 {
+                            X: Z * DIR_3D.X * DIR_3D.Z + Y * DIR_3D.Y,
+                            Y: Z * DIR_3D.Y * DIR_3D.Z - Y * DIR_3D.X,
+                            Z: Z * DIR_3D.Z * DIR_3D.Z + X,
+                        },
+                    };
+                },
+            ),
+            Rotation::Euler { order, angles } => {
+                let x = elysian_expr! {
+                    MATRIX3 {
+                        X_AXIS_3: VECTOR3 { X: 1.0, Y: 0.0, Z: 0.0 },
+                        Y_AXIS_3: VECTOR3 { X: 0.0, Y: EULER_ANGLES.X.cos(), Z: EULER_ANGLES.X.sin() },
+                        Z_AXIS_3: VECTOR3 { X: 0.0, Y: -EULER_ANGLES.X.sin(), Z: EULER_ANGLES.X.cos() },
+                    }
+                };
+
+                let y = elysian_expr! {
+                    MATRIX3 {
+                        X_AXIS_3: VECTOR3 { X: EULER_ANGLES.Y.cos(), Y: 0.0, Z: -EULER_ANGLES.Y.sin() },
+                        Y_AXIS_3: VECTOR3 { X: 0.0, Y: 1.0, Z: 0.0 },
+                        Z_AXIS_3: VECTOR3 { X: EULER_ANGLES.Y.sin(), Y: 0.0, Z: EULER_ANGLES.Y.cos() },
+                    }
+                };
+
+                let z = elysian_expr! {
+                    MATRIX3 {
+                        X_AXIS_3: VECTOR3 { X: EULER_ANGLES.Z.cos(), Y: EULER_ANGLES.Z.sin(), Z: 0.0 },
+                        Y_AXIS_3: VECTOR3 { X: -EULER_ANGLES.Z.sin(), Y: EULER_ANGLES.Z.cos(), Z: 0.0 },
+                        Z_AXIS_3: VECTOR3 { X: 0.0, Y: 0.0, Z: 1.0 },
+                    }
+                };
+
+                let (a, b, c) = match order {
+                    EulerOrder::XYZ => (x, y, z),
+                    EulerOrder::XZY => (x, z, y),
+                    EulerOrder::YXZ => (y, x, z),
+                    EulerOrder::YZX => (y, z, x),
+                    EulerOrder::ZXY => (z, x, y),
+                    EulerOrder::ZYX => (z, y, x),
+                };
+
+                (
+                    vec![(EULER_ANGLES, angles.clone())],
+                    elysian_block! {
+                        let ROTATION_3D = #a * #b * #c;
+                    },
+                )
+            }
+            Rotation::Quaternion(quaternion) => (
+                vec![(QUATERNION, quaternion.clone().normalize())],
+                elysian_block! {
+                    let X = QUATERNION.X;
+                    let Y = QUATERNION.Y;
+                    let Z = QUATERNION.Z;
+                    let W = QUATERNION.W;
+                    let ROTATION_3D = MATRIX3 {
+                        X_AXIS_3: VECTOR3 {
+                            X: 1.0 - (Y * Y + Z * Z) * 2.0,
+                            Y: (X * Y + W * Z) * 2.0,
+                            Z: (X * Z - W * Y) * 2.0,
+                        },
+                        Y_AXIS_3: VECTOR3 {
+                            X: (X * Y - W * Z) * 2.0,
+                            Y: 1.0 - (X * X + Z * Z) * 2.0,
+                            Z: (Y * Z + W * X) * 2.0,
+                        },
+                        Z_AXIS_3: VECTOR3 {
+                            X: (X * Z + W * Y) * 2.0,
+                            Y: (Y * Z - W * X) * 2.0,
+                            Z: 1.0 - (X * X + Y * Y) * 2.0,
+                        },
+                    };
+                },
+            ),
+            Rotation::Matrix(matrix) => (vec![(ROTATION_3D, matrix.clone())], Block::default()),
+        }
+    }
+}
+
+impl From<Quat> for Rotation {
+    fn from(value: Quat) -> Self {
+        Rotation::Quaternion(value.to_array().literal())
+    }
+}
+
+impl From<Mat3> for Rotation {
+    fn from(value: Mat3) -> Self {
+        Rotation::Matrix(value.literal())
+    }
+}
+
+/// Shared body of [`Rotate3d`], and of [`Rotate`] in the 3D domain
+///
+/// Transforms position into local space by the transpose of the rotation,
+/// then rotates the resulting gradient and normal back out into world space.
+fn rotate_3d<T: ErasedHash + DomainsDyn>(
+    wrapper: &T,
+    spec: &SpecializationData,
+    field_call: elysian_ir::ast::Expr,
+    rotation: &Rotation,
+) -> Module {
+    let (params, mut block) = rotation.prologue();
+
+    block.extend(elysian_block! {
+        let POSITION_3D = CONTEXT.POSITION_3D;
+        CONTEXT.POSITION_3D = VECTOR3 {
+            X: ROTATION_3D.X_AXIS_3.dot(POSITION_3D),
+            Y: ROTATION_3D.Y_AXIS_3.dot(POSITION_3D),
+            Z: ROTATION_3D.Z_AXIS_3.dot(POSITION_3D),
+        };
+
+        CONTEXT = #field_call;
+    });
+
+    if spec.contains(&GRADIENT_3D.into()) {
+        block.push(elysian_stmt! {
+            CONTEXT.GRADIENT_3D = ROTATION_3D * CONTEXT.GRADIENT_3D
+        });
+    }
+
+    if spec.contains(&NORMAL.into()) {
+        block.push(elysian_stmt! {
+            CONTEXT.NORMAL = ROTATION_3D * CONTEXT.NORMAL
+        });
+    }
+
+    block.push(elysian_stmt! { return CONTEXT });
+
+    let (inputs, args): (Vec<_>, Vec<_>) = params
+        .into_iter()
+        .map(|(id, arg)| {
+            (
+                InputDefinition {
+                    id: id.into(),
+                    mutable: false,
+                },
+                elysian_ir::ast::Expr::from(arg),
+            )
+        })
+        .unzip();
+
+    Module::new(
+        wrapper,
+        spec,
+        FunctionDefinition {
+            id: FunctionIdentifier::new_dynamic("rotate_3d".into()),
+            public: false,
+            inputs: inputs
+                .into_iter()
+                .chain([InputDefinition {
+                    id: CONTEXT.into(),
+                    mutable: true,
+                }])
+                .collect(),
+            output: CONTEXT.into(),
+            block,
+            provenance: None,
+        },
+    )
+    .with_args(args)
+}
+
+/// Rotation of a 3D shape by an arbitrary [`Rotation`]
+#[derive(Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rotate3d {
+    pub rotation: Rotation,
+}
+
+impl DomainsDyn for Rotate3d {
+    fn domains_dyn(&self) -> Vec<elysian_core::property_identifier::PropertyIdentifier> {
+        vec![POSITION_3D.into(), GRADIENT_3D.into(), NORMAL.into()]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Wrapper for Rotate3d {
+    fn module(&self, spec: &SpecializationData, field_call: elysian_ir::ast::Expr) -> Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Rotate3d requires the 3D Position domain"
+        );
+
+        rotate_3d(self, spec, field_call, &self.rotation)
+    }
+}
+
+pub trait IntoRotate3d {
+    fn rotate_3d(self, rotation: impl Into<Rotation>) -> Wrap;
+}
+
+impl<T> IntoRotate3d for T
+where
+    T: 'static + Shape,
+{
+    fn rotate_3d(self, rotation: impl Into<Rotation>) -> Wrap {
+        Wrap::new(
+            Rotate3d {
+                rotation: rotation.into(),
+            },
+            self,
+        )
+    }
+}
@@ -1,18 +1,42 @@
 use std::{fmt::Debug, hash::Hash};
 
-use elysian_core::expr::{Expr, IntoExpr};
+use elysian_core::{
+    expr::{Expr, IntoExpr},
+    identifier::Identifier,
+};
 use elysian_decl_macros::elysian_function;
 use elysian_ir::{
-    ast::{DISTANCE, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
-    module::{DomainsDyn, FunctionIdentifier, Module, SpecializationData, CONTEXT},
+    ast::{
+        Value, DISTANCE, DISTANCE_BOUND, GRADIENT_2D, GRADIENT_3D, NUM, POSITION_2D, POSITION_3D,
+        VECTOR2, VECTOR3, X, Y, Z,
+    },
+    module::{
+        DomainsDyn, FunctionDefinition, FunctionIdentifier, InputDefinition, Module,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
 };
-use elysian_proc_macros::elysian_expr;
+use elysian_proc_macros::{elysian_block, elysian_expr, elysian_stmt};
 
 use crate::{
     shape::Shape,
     wrap::{Wrap, Wrapper},
 };
 
+pub const SCALE_2D: Identifier = Identifier::new("scale_2d", 484027026944987421);
+property!(
+    SCALE_2D,
+    SCALE_2D_PROP,
+    Type::Struct(StructIdentifier(VECTOR2))
+);
+
+pub const SCALE_3D: Identifier = Identifier::new("scale_3d", 7066486255043409584);
+property!(
+    SCALE_3D,
+    SCALE_3D_PROP,
+    Type::Struct(StructIdentifier(VECTOR3))
+);
+
 #[derive(Debug, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Scale {
@@ -83,3 +107,119 @@ where
         )
     }
 }
+
+/// Per-axis scale by a `VECTOR2` or `VECTOR3` of factors
+///
+/// Negative factors mirror along their axis.
+/// Distance is multiplied by the smallest absolute factor, which keeps it
+/// a lower bound on the true distance without flipping its sign,
+/// and each gradient component is divided by its signed factor to mirror with it.
+///
+/// Where the absolute factors differ, [`DISTANCE_BOUND`] is set.
+/// The flag is advisory only, as neither raymarching nor meshing read it yet.
+#[derive(Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScaleAxes {
+    pub factors: Expr,
+}
+
+impl DomainsDyn for ScaleAxes {
+    fn domains_dyn(&self) -> Vec<elysian_core::property_identifier::PropertyIdentifier> {
+        vec![
+            POSITION_2D.into(),
+            POSITION_3D.into(),
+            GRADIENT_2D.into(),
+            GRADIENT_3D.into(),
+        ]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Wrapper for ScaleAxes {
+    fn module(
+        &self,
+        spec: &SpecializationData,
+        field_call: elysian_ir::ast::Expr,
+    ) -> elysian_ir::module::Module {
+        let (position, gradient, factors, min, max) = match (
+            spec.contains(&POSITION_2D.into()),
+            spec.contains(&POSITION_3D.into()),
+        ) {
+            (true, false) => (
+                POSITION_2D,
+                GRADIENT_2D,
+                SCALE_2D,
+                elysian_expr! { SCALE_2D.X.abs().min(SCALE_2D.Y.abs()) },
+                elysian_expr! { SCALE_2D.X.abs().max(SCALE_2D.Y.abs()) },
+            ),
+            (false, true) => (
+                POSITION_3D,
+                GRADIENT_3D,
+                SCALE_3D,
+                elysian_expr! { SCALE_3D.X.abs().min(SCALE_3D.Y.abs()).min(SCALE_3D.Z.abs()) },
+                elysian_expr! { SCALE_3D.X.abs().max(SCALE_3D.Y.abs()).max(SCALE_3D.Z.abs()) },
+            ),
+            _ => panic!("Invalid position domain"),
+        };
+
+        let mut block = elysian_block! {
+            CONTEXT.position = CONTEXT.position / factors;
+            CONTEXT = #field_call;
+
+            let NUM = #min;
+            CONTEXT.DISTANCE = CONTEXT.DISTANCE * NUM;
+            if NUM < #max {
+                CONTEXT.DISTANCE_BOUND = true;
+            }
+        };
+
+        if spec.contains(&gradient.clone().into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.gradient = CONTEXT.gradient / factors * NUM
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: FunctionIdentifier::new_dynamic("scale_axes".into()),
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: factors.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([self.factors.clone().into()])
+    }
+}
+
+pub trait IntoScaleAxes {
+    fn scale_axes(self, factors: impl IntoExpr) -> Wrap;
+}
+
+impl<T> IntoScaleAxes for T
+where
+    T: 'static + Shape,
+{
+    fn scale_axes(self, factors: impl IntoExpr) -> Wrap {
+        Wrap::new(
+            ScaleAxes {
+                factors: factors.expr(),
+            },
+            self,
+        )
+    }
+}
@@ -0,0 +1,222 @@
+use std::{fmt::Debug, hash::Hash};
+
+use elysian_core::{
+    expr::{Expr, IntoLiteral},
+    identifier::Identifier,
+};
+use elysian_ir::{
+    ast::{
+        Value, DISTANCE, DISTANCE_BOUND, GRADIENT_3D, MATRIX3, NORMAL, POSITION_3D, VECTOR3, X,
+        X_AXIS_3, Y, Y_AXIS_3, Z, Z_AXIS_3,
+    },
+    module::{
+        DomainsDyn, FunctionDefinition, FunctionIdentifier, InputDefinition, Module, NumericType,
+        SpecializationData, StructIdentifier, Type, CONTEXT,
+    },
+    property,
+};
+use elysian_math::glam::{Mat3, Mat4};
+use elysian_proc_macros::{elysian_block, elysian_stmt};
+
+use crate::{
+    modify::DELTA_3D,
+    shape::Shape,
+    wrap::{Wrap, Wrapper},
+};
+
+pub const LINEAR_3D: Identifier = Identifier::new("linear_3d", 2358604474934767532);
+property!(
+    LINEAR_3D,
+    LINEAR_3D_PROP,
+    Type::Struct(StructIdentifier(MATRIX3))
+);
+
+pub const DISTANCE_FACTOR: Identifier = Identifier::new("distance_factor", 7387551014759389135);
+property!(
+    DISTANCE_FACTOR,
+    DISTANCE_FACTOR_PROP,
+    Type::Number(NumericType::Float)
+);
+
+/// General affine transformation of a 3D shape
+///
+/// Position is mapped into local space by `linear * position + translation`,
+/// and the resulting distance multiplied by `factor`.
+///
+/// Distance is exact for rigid transforms and uniform scales;
+/// otherwise, the constructors pick the largest `factor`
+/// that still yields a lower bound, and set `bound`
+/// to flag it via [`DISTANCE_BOUND`].
+#[derive(Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform {
+    pub linear: Expr,
+    pub translation: Expr,
+    pub factor: Expr,
+    pub bound: bool,
+}
+
+impl Transform {
+    /// Transform from the local-to-world affine `matrix`
+    pub fn new(matrix: Mat4) -> Self {
+        let inverse = matrix.inverse();
+        let linear = Mat3::from_mat4(inverse);
+        let (factor, bound) = distance_factor(linear);
+
+        Transform {
+            linear: linear.literal(),
+            translation: inverse.w_axis.truncate().to_array().literal(),
+            factor: factor.literal(),
+            bound,
+        }
+    }
+
+    /// Transform from the local-to-world linear `matrix`
+    pub fn linear(matrix: Mat3) -> Self {
+        let inverse = matrix.inverse();
+        let (factor, bound) = distance_factor(inverse);
+
+        Transform {
+            linear: inverse.literal(),
+            translation: [0.0, 0.0, 0.0].literal(),
+            factor: factor.literal(),
+            bound,
+        }
+    }
+}
+
+/// Reciprocal of the spectral norm of `linear`,
+/// i.e. the smallest singular value of its inverse,
+/// and whether it differs from the largest
+fn distance_factor(linear: Mat3) -> (f32, bool) {
+    let a = linear.transpose() * linear;
+
+    // Largest eigenvalue of the symmetric matrix `a`
+    let q = (a.x_axis.x + a.y_axis.y + a.z_axis.z) / 3.0;
+    let p1 = a.y_axis.x.powi(2) + a.z_axis.x.powi(2) + a.z_axis.y.powi(2);
+    let p2 =
+        (a.x_axis.x - q).powi(2) + (a.y_axis.y - q).powi(2) + (a.z_axis.z - q).powi(2) + 2.0 * p1;
+
+    let bound = p2 > f32::EPSILON * q * q;
+
+    let max = if !bound {
+        q
+    } else {
+        let p = (p2 / 6.0).sqrt();
+        let r = ((a - Mat3::from_diagonal([q; 3].into())) * (1.0 / p)).determinant() / 2.0;
+        q + 2.0 * p * (r.clamp(-1.0, 1.0).acos() / 3.0).cos()
+    };
+
+    (1.0 / max.sqrt(), bound)
+}
+
+impl DomainsDyn for Transform {
+    fn domains_dyn(&self) -> Vec<elysian_core::property_identifier::PropertyIdentifier> {
+        vec![
+            POSITION_3D.into(),
+            DISTANCE.into(),
+            GRADIENT_3D.into(),
+            NORMAL.into(),
+        ]
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl Wrapper for Transform {
+    fn module(&self, spec: &SpecializationData, field_call: elysian_ir::ast::Expr) -> Module {
+        assert!(
+            spec.contains(&POSITION_3D.into()),
+            "Transform requires the 3D Position domain"
+        );
+
+        let mut block = elysian_block! {
+            CONTEXT.POSITION_3D = LINEAR_3D * CONTEXT.POSITION_3D + DELTA_3D;
+            CONTEXT = #field_call;
+        };
+
+        if spec.contains(&DISTANCE.into()) {
+            block.push(elysian_stmt! {
+                CONTEXT.DISTANCE = CONTEXT.DISTANCE * DISTANCE_FACTOR
+            });
+
+            if self.bound {
+                block.push(elysian_stmt! {
+                    CONTEXT.DISTANCE_BOUND = true
+                });
+            }
+        }
+
+        // Pull covectors back through the transpose of the linear part
+        if spec.contains(&GRADIENT_3D.into()) {
+            block.extend(elysian_block! {
+                let GRADIENT_3D = CONTEXT.GRADIENT_3D;
+                CONTEXT.GRADIENT_3D = VECTOR3 {
+                    X: LINEAR_3D.X_AXIS_3.dot(GRADIENT_3D),
+                    Y: LINEAR_3D.Y_AXIS_3.dot(GRADIENT_3D),
+                    Z: LINEAR_3D.Z_AXIS_3.dot(GRADIENT_3D),
+                } * DISTANCE_FACTOR;
+            });
+        }
+
+        if spec.contains(&NORMAL.into()) {
+            block.extend(elysian_block! {
+                let NORMAL = CONTEXT.NORMAL;
+                CONTEXT.NORMAL = VECTOR3 {
+                    X: LINEAR_3D.X_AXIS_3.dot(NORMAL),
+                    Y: LINEAR_3D.Y_AXIS_3.dot(NORMAL),
+                    Z: LINEAR_3D.Z_AXIS_3.dot(NORMAL),
+                }.normalize();
+            });
+        }
+
+        block.push(elysian_stmt! { return CONTEXT });
+
+        Module::new(
+            self,
+            spec,
+            FunctionDefinition {
+                id: FunctionIdentifier::new_dynamic("transform".into()),
+                public: false,
+                inputs: vec![
+                    InputDefinition {
+                        id: LINEAR_3D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: DELTA_3D.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: DISTANCE_FACTOR.into(),
+                        mutable: false,
+                    },
+                    InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    },
+                ],
+                output: CONTEXT.into(),
+                block,
+                provenance: None,
+            },
+        )
+        .with_args([
+            self.linear.clone().into(),
+            self.translation.clone().into(),
+            self.factor.clone().into(),
+        ])
+    }
+}
+
+pub trait IntoTransform {
+    fn transform(self, matrix: Mat4) -> Wrap;
+}
+
+impl<T> IntoTransform for T
+where
+    T: 'static + Shape,
+{
+    fn transform(self, matrix: Mat4) -> Wrap {
+        Wrap::new(Transform::new(matrix), self)
+    }
+}
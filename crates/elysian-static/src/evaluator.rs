@@ -1,11 +1,16 @@
-use std::{error::Error, fmt::Display};
+use std::{
+    collections::BTreeSet,
+    error::Error,
+    fmt::Display,
+    sync::{Mutex, RwLock},
+};
 
 use elysian_ir::{
     ast::Struct,
-    module::{Evaluate, Module, EvaluateError},
+    module::{Evaluate, EvaluateError, Module},
 };
 
-use crate::static_shapes_map;
+use crate::{registered_shapes, static_shape, ShapeKey};
 
 // Evaluator for precompiled functions
 #[derive(Debug, Copy, Clone, Hash)]
@@ -15,9 +20,11 @@ impl<'a> Evaluate<'a> for Precompiled<'a> {
     fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
         let module = &self.0;
 
-        let Some(f) = static_shapes_map().get(&module.hash) else {
-        return Err(Box::new(PrecompiledError::MissingFunction(module.entry_point.name_unique())))
-    };
+        let Some(shape) = static_shape(module) else {
+            let miss = PrecompiledMiss::new(module);
+            report_miss(&miss);
+            return Err(Box::new(PrecompiledError::MissingShape(miss)));
+        };
 
         /*
         println!(
@@ -26,21 +33,91 @@ impl<'a> Evaluate<'a> for Precompiled<'a> {
         );
         */
 
-        Ok(f(context))
+        Ok((shape.function)(context))
+    }
+}
+
+/// Description of a module with no matching precompiled shape
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PrecompiledMiss {
+    pub entry_point: String,
+    pub key: ShapeKey,
+    /// Paths of shapes registered with the same hash under other specializations
+    pub candidates: Vec<&'static str>,
+}
+
+impl PrecompiledMiss {
+    pub fn new(module: &Module) -> Self {
+        let key = ShapeKey::new(module);
+        PrecompiledMiss {
+            entry_point: module.entry_point.name_unique(),
+            key,
+            candidates: registered_shapes()
+                .filter(|shape| shape.key.hash == key.hash)
+                .map(|shape| shape.path)
+                .collect(),
+        }
+    }
+}
+
+impl Display for PrecompiledMiss {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Missing precompiled shape for {} ({})",
+            self.entry_point, self.key
+        )?;
+
+        if !self.candidates.is_empty() {
+            write!(
+                f,
+                "; registered with other specializations as {}",
+                self.candidates.join(", ")
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Callback invoked the first time a given module misses the precompiled registry
+pub type MissHandler = fn(&PrecompiledMiss);
+
+static MISS_HANDLER: RwLock<Option<MissHandler>> = RwLock::new(None);
+static REPORTED_MISSES: Mutex<BTreeSet<ShapeKey>> = Mutex::new(BTreeSet::new());
+
+/// Install a handler to be notified when [`Precompiled`] misses,
+/// such as before a [`Dispatch`](elysian_ir::module::Dispatch) falls back to a slower evaluator
+///
+/// Each missing shape is reported once per process.
+pub fn set_miss_handler(handler: MissHandler) {
+    *MISS_HANDLER.write().unwrap() = Some(handler);
+}
+
+/// Miss handler that prints a warning to stderr
+pub fn warn_on_miss(miss: &PrecompiledMiss) {
+    eprintln!("warning: {miss}");
+}
+
+fn report_miss(miss: &PrecompiledMiss) {
+    let Some(handler) = *MISS_HANDLER.read().unwrap() else {
+        return;
+    };
+
+    if REPORTED_MISSES.lock().unwrap().insert(miss.key) {
+        handler(miss)
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum PrecompiledError {
-    MissingFunction(String),
+    MissingShape(PrecompiledMiss),
 }
 
 impl Display for PrecompiledError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            PrecompiledError::MissingFunction(name) => {
-                f.write_str(&format!("Missing precompiled shape for {}", name))
-            }
+            PrecompiledError::MissingShape(miss) => miss.fmt(f),
         }
     }
 }
@@ -4,62 +4,159 @@ mod evaluator;
 
 pub use evaluator::*;
 
-use elysian_ir::module::Module;
-use elysian_syn::module_to_string;
+use elysian_ir::module::{ErasedHash, Module};
+use elysian_syn::modules_to_string;
 
-use std::{collections::BTreeMap, sync::OnceLock};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    path::PathBuf,
+    sync::OnceLock,
+};
 
 use elysian_ir::ast::Struct;
 
 pub type ShapeHash = u64;
 pub type ShapeFn = fn(Struct) -> Struct;
 
-pub struct StaticShape {
+/// Registry key for a precompiled shape
+///
+/// Modules built from the same shape share a hash across specializations,
+/// so the name of the specialized entry point is hashed to tell them apart.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ShapeKey {
     pub hash: ShapeHash,
-    pub function: ShapeFn,
+    pub specialization: u64,
 }
 
-impl Clone for StaticShape {
-    fn clone(&self) -> Self {
-        Self {
-            hash: self.hash.clone(),
-            function: self.function.clone(),
+impl ShapeKey {
+    pub fn new(module: &Module) -> Self {
+        ShapeKey {
+            hash: module.hash,
+            specialization: module.entry_point.name().erased_hash(),
         }
     }
 }
 
-impl Copy for StaticShape {}
+impl Display for ShapeKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:016x}:{:016x}", self.hash, self.specialization)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct StaticShape {
+    /// Name passed to the build.rs registrar
+    pub name: &'static str,
+    /// Module path of the generated code, including its crate and namespace
+    pub path: &'static str,
+    pub key: ShapeKey,
+    pub function: ShapeFn,
+}
 
-/// Distributed slice of shape hash -> shape function pairs
+/// Distributed slice of precompiled shapes
 /// Populated at link-time by auto-generated shape modules
 #[linkme::distributed_slice]
 pub static STATIC_SHAPES: [StaticShape] = [..];
 
 /// Runtime storage for static shape data
-static STATIC_SHAPES_MAP: OnceLock<BTreeMap<ShapeHash, ShapeFn>> = OnceLock::new();
+static STATIC_SHAPES_MAP: OnceLock<BTreeMap<ShapeKey, &'static StaticShape>> = OnceLock::new();
 
-/// Accessor for STATIC_SHAPES_MAP_F32
-pub fn static_shapes_map() -> &'static BTreeMap<ShapeHash, ShapeFn> {
+/// Accessor for STATIC_SHAPES_MAP
+///
+/// If several crates register the same shape, the first one linked is used.
+pub fn static_shapes_map() -> &'static BTreeMap<ShapeKey, &'static StaticShape> {
     STATIC_SHAPES_MAP.get_or_init(|| {
-        STATIC_SHAPES
-            .into_iter()
-            .copied()
-            .map(|t| (t.hash, t.function))
-            .collect()
+        let mut map = BTreeMap::new();
+        for shape in STATIC_SHAPES.into_iter() {
+            map.entry(shape.key).or_insert(shape);
+        }
+        map
     })
-    //STATIC_SHAPES_MAP.get_or_init(Default::default)
+}
+
+/// Look up the precompiled shape for a module
+pub fn static_shape(module: &Module) -> Option<&'static StaticShape> {
+    static_shapes_map().get(&ShapeKey::new(module)).copied()
+}
+
+/// Every precompiled shape linked into the current binary, in link order
+pub fn registered_shapes() -> impl Iterator<Item = &'static StaticShape> {
+    STATIC_SHAPES.into_iter()
 }
 
 /// Build.rs static shape registrar
+///
+/// Shorthand for [`StaticShapes`] with the default namespace,
+/// included via `include_static_shapes!()`.
 pub fn static_shapes<'a, T: IntoIterator<Item = (&'a str, Module)>>(t: T) {
-    let source: String = t
-        .into_iter()
-        .map(|(name, module)| module_to_string(&module, name))
-        .collect();
-
-    let out_dir = std::env::var_os("OUT_DIR").expect("No OUT_DIR environment variable");
-    let dest_path = std::path::Path::new(&out_dir).join("static_shapes.rs");
-    std::fs::write(&dest_path, source).unwrap();
+    StaticShapes::new()
+        .shapes(t)
+        .write()
+        .expect("Failed to write static shapes");
+}
+
+/// Builder for a file of generated shape code
+///
+/// Each namespace is written to its own file in `OUT_DIR`
+/// and wrapped in a module of the same name,
+/// so that several sets of shapes can be included side by side.
+#[derive(Debug, Default, Clone)]
+pub struct StaticShapes {
+    namespace: Option<String>,
+    shapes: Vec<(String, Module)>,
+}
+
+impl StaticShapes {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Set the namespace to generate into,
+    /// included via `include_static_shapes!(namespace)`
+    pub fn namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    pub fn shape(mut self, name: impl Into<String>, module: Module) -> Self {
+        self.shapes.push((name.into(), module));
+        self
+    }
+
+    pub fn shapes<'a, T: IntoIterator<Item = (&'a str, Module)>>(mut self, t: T) -> Self {
+        self.shapes.extend(
+            t.into_iter()
+                .map(|(name, module)| (name.to_string(), module)),
+        );
+        self
+    }
+
+    /// Generate source for the registered shapes
+    pub fn source(&self) -> String {
+        let mut names = BTreeSet::new();
+        for (name, _) in self.shapes.iter() {
+            if !names.insert(name) {
+                panic!("Duplicate static shape {name}");
+            }
+        }
+
+        modules_to_string(
+            self.shapes
+                .iter()
+                .map(|(name, module)| (module, name.as_str())),
+            self.namespace.as_deref(),
+        )
+    }
+
+    /// Write generated source to `OUT_DIR`, returning its path
+    pub fn write(&self) -> std::io::Result<PathBuf> {
+        let out_dir = std::env::var_os("OUT_DIR").expect("No OUT_DIR environment variable");
+        let file_name = self.namespace.as_deref().unwrap_or("static_shapes");
+        let dest_path = std::path::Path::new(&out_dir).join(format!("{file_name}.rs"));
+        std::fs::write(&dest_path, self.source())?;
+        Ok(dest_path)
+    }
 }
 
 /// Convenience macro for including generated static shape code
@@ -68,4 +165,7 @@ macro_rules! include_static_shapes {
     () => {
         include!(concat!(env!("OUT_DIR"), "/static_shapes.rs"));
     };
+    ($namespace:ident) => {
+        include!(concat!(env!("OUT_DIR"), "/", stringify!($namespace), ".rs"));
+    };
 }
@@ -6,18 +6,18 @@ use elysian_ir::{
         MATRIX2, MATRIX3, MATRIX4, VECTOR2, VECTOR3, VECTOR4, W, W_AXIS_4, X, X_AXIS_2, X_AXIS_3,
         X_AXIS_4, Y, Y_AXIS_2, Y_AXIS_3, Y_AXIS_4, Z, Z_AXIS_3, Z_AXIS_4,
     },
-    module::{properties, Module, NumericType, StructIdentifier, CONTEXT},
+    module::{properties, ErasedHash, Layout, Module, NumericType, StructIdentifier, CONTEXT},
 };
 pub use prettyplease;
 
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use syn::{
-    parse_quote, token::Mut, BinOp, Block, Expr, ExprAssign, ExprBinary, ExprBlock, ExprCall,
-    ExprField, ExprIf, ExprLit, ExprLoop, ExprMethodCall, ExprPath, ExprReturn, ExprStruct,
-    ExprUnary, Field, FieldMutability, FieldValue, Fields, FieldsNamed, File, FnArg, Generics,
-    Item, ItemFn, ItemMod, ItemStruct, Lit, LitBool, LitFloat, LitInt, Pat, PatIdent, Path,
-    PathSegment, ReturnType, Signature, Stmt, Type, TypePath, Visibility,
+    parse_quote, token::Mut, Attribute, BinOp, Block, Expr, ExprAssign, ExprBinary, ExprBlock,
+    ExprCall, ExprField, ExprIf, ExprLit, ExprLoop, ExprMethodCall, ExprPath, ExprReturn,
+    ExprStruct, ExprUnary, Field, FieldMutability, FieldValue, Fields, FieldsNamed, File, FnArg,
+    Generics, Item, ItemFn, ItemMod, ItemStruct, Lit, LitBool, LitFloat, LitInt, Pat, PatIdent,
+    Path, PathSegment, ReturnType, Signature, Stmt, Type, TypePath, Visibility,
 };
 
 use elysian_ir::ast::{Block as IrBlock, Expr as IrExpr, Stmt as IrStmt};
@@ -62,13 +62,48 @@ pub fn module_to_string(input: &Module, name: &str) -> String {
     prettyplease::unparse(&module_to_syn(input, name))
 }
 
+/// Format several modules into a single file,
+/// optionally nested inside a `namespace` module
+pub fn modules_to_string<'a>(
+    modules: impl IntoIterator<Item = (&'a Module, &'a str)>,
+    namespace: Option<&str>,
+) -> String {
+    let items: Vec<Item> = modules
+        .into_iter()
+        .flat_map(|(module, name)| module_to_syn(module, name).items)
+        .collect();
+
+    // Nest items directly rather than via quote,
+    // since round-tripping them through tokens doesn't preserve statements
+    let items = match namespace {
+        Some(namespace) => vec![Item::Mod(ItemMod {
+            attrs: vec![],
+            vis: Visibility::Public(Default::default()),
+            unsafety: None,
+            mod_token: Default::default(),
+            ident: Ident::new(namespace, Span::call_site()),
+            content: Some((Default::default(), items)),
+            semi: None,
+        })],
+        None => items,
+    };
+
+    prettyplease::unparse(&File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    })
+}
+
 pub fn module_to_syn(module: &Module, name: &str) -> File {
     let name = Ident::new(name, Span::call_site());
 
     let mut attrs = vec![];
 
+    // Generated modules are included into user crates,
+    // so must not trip their lints, clippy's included
     attrs.push(parse_quote! {
-        #![allow(unused_parens, non_camel_case_types)]
+        #![allow(unused, unused_parens, non_camel_case_types, non_snake_case, clippy::all)]
     });
 
     let mut items = vec![];
@@ -83,10 +118,251 @@ pub fn module_to_syn(module: &Module, name: &str) -> File {
                 module::{CONTEXT, StructIdentifier},
             },
             math::{glam::*, *},
-            r#static::StaticShape,
+            r#static::{ShapeKey, StaticShape},
+        };
+    });
+
+    items.extend(module_items(module));
+
+    let def = module
+        .struct_definitions
+        .iter()
+        .find(|cand| cand.id == StructIdentifier(CONTEXT))
+        .unwrap();
+
+    let struct_name = Ident::new(&def.id.name_unique(), Span::call_site());
+
+    let members: Vec<_> = def
+        .fields
+        .iter()
+        .map(|field| property_to_syn(&field.id))
+        .collect();
+
+    let names: Vec<_> = def
+        .fields
+        .iter()
+        .map(|field| Ident::new(&field.id.name_unique(), Span::call_site()))
+        .collect();
+
+    items.push(syn::parse_quote! {
+        impl From<Struct> for #struct_name {
+            fn from(s: Struct) -> Self {
+                let mut out = Self::default();
+
+                #(
+                    if let Some(v) = s.try_get(&#members) {
+                        out.#names = v.into();
+                    }
+                )*
+
+                out
+            }
+        }
+    });
+
+    items.push(syn::parse_quote! {
+        impl From<#struct_name> for Struct {
+            fn from(s: #struct_name) -> Self {
+                let mut out = Self::new(StructIdentifier(CONTEXT));
+
+                #(
+                    out.set_mut(#members, s.#names.into());
+                )*
+
+                out
+            }
+        }
+    });
+
+    let context = Ident::new(
+        &PropertyIdentifier(CONTEXT).name_unique(),
+        Span::call_site(),
+    );
+    let call = expr_to_syn(module, &module.call(IrExpr::Read(vec![CONTEXT.into()])));
+
+    // Concretely-typed entry point, for callers that can avoid
+    // round-tripping through the dynamic Struct representation
+    items.push(parse_quote! {
+        pub type Context = #struct_name;
+    });
+
+    items.push(parse_quote! {
+        pub fn evaluate(#context: Context) -> Context {
+            #call
+        }
+    });
+
+    items.push(parse_quote! {
+        pub fn #name(context: Struct) -> Struct {
+            evaluate(context.into()).into()
+        }
+    });
+
+    let name_str = name.to_string();
+    let hash = module.hash;
+    let specialization = module.entry_point.name().erased_hash();
+    items.push(parse_quote! {
+        #[linkme::distributed_slice(elysian::r#static::STATIC_SHAPES)]
+        static STATIC_SHAPE: StaticShape = StaticShape {
+            name: #name_str,
+            path: module_path!(),
+            key: ShapeKey {
+                hash: #hash,
+                specialization: #specialization,
+            },
+            function: #name
         };
     });
 
+    let items = vec![Item::Mod(ItemMod {
+        attrs,
+        vis: Visibility::Public(Default::default()),
+        unsafety: None,
+        mod_token: Default::default(),
+        ident: name,
+        content: Some((Default::default(), items)),
+        semi: None,
+    })];
+
+    File {
+        shebang: None,
+        attrs: vec![],
+        items,
+    }
+}
+
+/// Symbol of the evaluation function exported by [`module_to_cdylib`]
+///
+/// Has the signature `unsafe extern "C" fn(input: *const f64, output: *mut f64)`,
+/// where both buffers hold [`cdylib_context_len`] slots.
+pub const CDYLIB_EVALUATE: &str = "elysian_evaluate";
+
+pub fn module_to_cdylib_string(module: &Module) -> String {
+    prettyplease::unparse(&module_to_cdylib(module))
+}
+
+/// Generate the source of a standalone `cdylib` crate depending only on `elysian-math`
+///
+/// The context is passed across the C ABI as a flat buffer of `f64` slots,
+/// one per scalar, in context field order.
+/// Vectors and matrices occupy one slot per component, with matrices in column-major order.
+pub fn module_to_cdylib(module: &Module) -> File {
+    let mut items = vec![];
+
+    items.push(parse_quote! {
+        use elysian_math::{glam::*, *};
+    });
+
+    items.extend(module_items(module));
+
+    let def = module
+        .struct_definitions
+        .iter()
+        .find(|cand| cand.id == StructIdentifier(CONTEXT))
+        .unwrap();
+
+    let struct_name = Ident::new(&def.id.name_unique(), Span::call_site());
+    let context = Ident::new(
+        &PropertyIdentifier(CONTEXT).name_unique(),
+        Span::call_site(),
+    );
+    let call = expr_to_syn(module, &module.call(IrExpr::Read(vec![CONTEXT.into()])));
+    let symbol = Ident::new(CDYLIB_EVALUATE, Span::call_site());
+    let len = cdylib_context_len(module);
+
+    let mut offset = 0;
+    let mut reads: Vec<FieldValue> = vec![];
+    let mut writes: Vec<Stmt> = vec![];
+    for field in def.fields.iter() {
+        let name = Ident::new(&field.id.name_unique(), Span::call_site());
+        let ty = properties()
+            .get(&field.id)
+            .unwrap_or_else(|| panic!("No type for {}", field.id.name()));
+
+        match ty {
+            elysian_ir::module::Type::Boolean => {
+                let slot = syn::Index::from(offset);
+                reads.push(parse_quote!(#name: input[#slot] != 0.0));
+                writes.push(parse_quote!(output[#slot] = out.#name as u8 as f64;));
+                offset += 1;
+            }
+            elysian_ir::module::Type::Number(n) => {
+                let slot = syn::Index::from(offset);
+                let ty = Ident::new(builtin_types(n.name()), Span::call_site());
+                reads.push(parse_quote!(#name: input[#slot] as #ty));
+                writes.push(parse_quote!(output[#slot] = out.#name as f64;));
+                offset += 1;
+            }
+            elysian_ir::module::Type::Struct(s) => {
+                let (count, from, to) = match s.name() {
+                    "Vector2" => (2, "from_slice", "to_array"),
+                    "Vector3" => (3, "from_slice", "to_array"),
+                    "Vector4" => (4, "from_slice", "to_array"),
+                    "Matrix2" => (4, "from_cols_slice", "to_cols_array"),
+                    "Matrix3" => (9, "from_cols_slice", "to_cols_array"),
+                    "Matrix4" => (16, "from_cols_slice", "to_cols_array"),
+                    _ => panic!("Unsupported context field type {}", s.name()),
+                };
+
+                let ty = Ident::new(builtin_types(s.name()), Span::call_site());
+                let from = Ident::new(from, Span::call_site());
+                let to = Ident::new(to, Span::call_site());
+                let slots: Vec<_> = (offset..offset + count).map(syn::Index::from).collect();
+                let components: Vec<_> = (0..count).map(syn::Index::from).collect();
+
+                reads.push(parse_quote!(#name: #ty::#from(&[#(input[#slots] as f32),*])));
+                writes.push(parse_quote! {
+                    {
+                        let components = out.#name.#to();
+                        #(output[#slots] = components[#components] as f64;)*
+                    }
+                });
+                offset += count;
+            }
+        }
+    }
+
+    let safety = format!(" `input` and `output` must each point to a buffer of {len} `f64` slots.");
+    items.push(parse_quote! {
+        /// # Safety
+        ///
+        #[doc = #safety]
+        #[no_mangle]
+        pub unsafe extern "C" fn #symbol(input: *const f64, output: *mut f64) {
+            let input = std::slice::from_raw_parts(input, #len);
+            let output = std::slice::from_raw_parts_mut(output, #len);
+
+            let #context = #struct_name {
+                #(#reads,)*
+            };
+            let out = #call;
+
+            #(#writes)*
+        }
+    });
+
+    File {
+        shebang: None,
+        attrs: vec![parse_quote! {
+            #![allow(unused, unused_parens, non_camel_case_types, non_snake_case)]
+        }],
+        items,
+    }
+}
+
+/// Number of `f64` slots in the context buffer of a [`module_to_cdylib`] function
+pub fn cdylib_context_len(module: &Module) -> usize {
+    Layout::new(module)
+        .leaves(&elysian_ir::module::Type::Struct(StructIdentifier(CONTEXT)))
+        .unwrap_or_else(|e| panic!("{e}"))
+        .len()
+}
+
+/// Struct and function items for a module,
+/// shared between code generation targets
+fn module_items(module: &Module) -> Vec<Item> {
+    let mut items = vec![];
+
     for def in &module.struct_definitions {
         match &def.id {
             v if *v == StructIdentifier(VECTOR2)
@@ -150,56 +426,6 @@ pub fn module_to_syn(module: &Module, name: &str) -> File {
         }
     }
 
-    let def = module
-        .struct_definitions
-        .iter()
-        .find(|cand| cand.id == StructIdentifier(CONTEXT))
-        .unwrap();
-
-    let struct_name = Ident::new(&def.id.name_unique(), Span::call_site());
-
-    let members: Vec<_> = def
-        .fields
-        .iter()
-        .map(|field| property_to_syn(&field.id))
-        .collect();
-
-    let names: Vec<_> = def
-        .fields
-        .iter()
-        .map(|field| Ident::new(&field.id.name_unique(), Span::call_site()))
-        .collect();
-
-    items.push(syn::parse_quote! {
-        impl From<Struct> for #struct_name {
-            fn from(s: Struct) -> Self {
-                let mut out = Self::default();
-
-                #(
-                    if let Some(v) = s.try_get(&#members) {
-                        out.#names = v.into();
-                    }
-                )*
-
-                out
-            }
-        }
-    });
-
-    items.push(syn::parse_quote! {
-        impl From<#struct_name> for Struct {
-            fn from(s: #struct_name) -> Self {
-                let mut out = Self::new(StructIdentifier(CONTEXT));
-
-                #(
-                    out.set_mut(#members, s.#names.into());
-                )*
-
-                out
-            }
-        }
-    });
-
     for def in &module.function_definitions {
         let name = Ident::new(&def.name_unique(), Span::call_site());
 
@@ -250,8 +476,18 @@ pub fn module_to_syn(module: &Module, name: &str) -> File {
                 .collect(),
         };
 
+        // Document the originating IR function and its provenance,
+        // so that generated code can be traced back to the shape that produced it
+        let doc = format!(" `{}` ({})", def.name(), def.id.uuid());
+        let mut attrs: Vec<Attribute> = vec![parse_quote!(#[doc = #doc])];
+        if let Some(provenance) = &def.provenance {
+            let doc = format!(" Generated by {provenance}");
+            attrs.push(parse_quote!(#[doc = ""]));
+            attrs.push(parse_quote!(#[doc = #doc]));
+        }
+
         let item = Item::Fn(ItemFn {
-            attrs: vec![],
+            attrs,
             vis: Visibility::Inherited,
             sig: Signature {
                 constness: None,
@@ -291,41 +527,12 @@ pub fn module_to_syn(module: &Module, name: &str) -> File {
         items.push(item);
     }
 
-    let entry_point_name = Ident::new(&module.entry_point.name_unique(), Span::call_site());
-    items.push(parse_quote! {
-        pub fn #name(context: Struct) -> Struct {
-            #entry_point_name(context.into()).into()
-        }
-    });
-
-    let hash = module.hash;
-    items.push(parse_quote! {
-        #[linkme::distributed_slice(elysian::r#static::STATIC_SHAPES)]
-        static STATIC_SHAPE: StaticShape = StaticShape {
-            hash: #hash,
-            function: #name
-        };
-    });
-
-    let items = vec![Item::Mod(ItemMod {
-        attrs,
-        vis: Visibility::Inherited,
-        unsafety: None,
-        mod_token: Default::default(),
-        ident: name,
-        content: Some((Default::default(), items)),
-        semi: None,
-    })];
-
-    File {
-        shebang: None,
-        attrs: vec![],
-        items,
-    }
+    items
 }
 
 fn builtin_types(name: &str) -> &str {
     match name {
+        "Bool" => "bool",
         "UInt" => "u32",
         "SInt" => "i32",
         "Float" => "f32",
@@ -837,7 +1044,9 @@ fn expr_to_syn(module: &Module, expr: &IrExpr) -> Expr {
         | IrExpr::Tan(t)
         | IrExpr::Asin(t)
         | IrExpr::Acos(t)
-        | IrExpr::Atan(t) => Expr::MethodCall(ExprMethodCall {
+        | IrExpr::Atan(t)
+        | IrExpr::Exp2(t)
+        | IrExpr::Log2(t) => Expr::MethodCall(ExprMethodCall {
             attrs: vec![],
             receiver: Box::new(expr_to_syn(module, t)),
             dot_token: Default::default(),
@@ -854,6 +1063,8 @@ fn expr_to_syn(module: &Module, expr: &IrExpr) -> Expr {
                     IrExpr::Asin(_) => "asin",
                     IrExpr::Acos(_) => "acos",
                     IrExpr::Atan(_) => "atan",
+                    IrExpr::Exp2(_) => "exp2",
+                    IrExpr::Log2(_) => "log2",
                     _ => unreachable!(),
                 },
                 Span::call_site(),
@@ -887,3 +1098,67 @@ fn path_to_syn(path: &Vec<PropertyIdentifier>) -> Expr {
         })
     })
 }
+
+#[cfg(test)]
+mod test {
+    use elysian_ir::module::{AsModule, SpecializationData};
+    use elysian_shapes::{
+        field::Point,
+        modify::{IntoGradientNormals, IntoTranslate},
+    };
+
+    use super::*;
+
+    fn translated_point() -> Module {
+        Point
+            .translate([0.25, -0.5])
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize()
+    }
+
+    #[test]
+    fn test_module_to_string() {
+        let module = translated_point();
+        let source = module_to_string(&module, "translated_point");
+
+        assert!(source.lines().count() > 1);
+        syn::parse_file(&source).unwrap();
+
+        for def in module.function_definitions.iter() {
+            let doc = format!("/// `{}` ({})", def.name(), def.id.uuid());
+            let line = source
+                .lines()
+                .position(|line| line.trim() == doc)
+                .unwrap_or_else(|| panic!("No doc comment for {}", def.name()));
+
+            // Doc comments directly precede their function
+            let item = source
+                .lines()
+                .skip(line)
+                .find(|line| !line.trim().starts_with("///"))
+                .unwrap();
+            assert!(
+                item.trim()
+                    .starts_with(&format!("fn {}(", def.name_unique())),
+                "{item}"
+            );
+        }
+
+        assert!(source.contains("/// Generated by Translate ("));
+    }
+
+    #[test]
+    fn test_modules_to_string() {
+        let module = translated_point();
+        let source = modules_to_string([(&module, "a"), (&module, "b")], Some("shapes"));
+
+        let file = syn::parse_file(&source).unwrap();
+        let [Item::Mod(namespace)] = &file.items[..] else {
+            panic!("Expected a single namespace module");
+        };
+        assert_eq!(namespace.ident, "shapes");
+        assert_eq!(namespace.content.as_ref().unwrap().1.len(), 2);
+        assert!(source.contains("\n    pub mod a {\n"));
+    }
+}
@@ -0,0 +1,38 @@
+use std::{error::Error, fmt::Display};
+
+use elysian_ir::module::LayoutError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WasmError {
+    MissingFunction(String),
+    Layout(LayoutError),
+    MissingField { field: String, ty: String },
+    InvalidRead(String),
+    InvalidExpr(String),
+    InvalidStmt(String),
+}
+
+impl Display for WasmError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WasmError::MissingFunction(name) => {
+                f.write_str(&format!("No function definition for {name}"))
+            }
+            WasmError::Layout(e) => e.fmt(f),
+            WasmError::MissingField { field, ty } => {
+                f.write_str(&format!("No field {field} in struct {ty}"))
+            }
+            WasmError::InvalidRead(path) => f.write_str(&format!("Invalid read of {path}")),
+            WasmError::InvalidExpr(expr) => f.write_str(&format!("Invalid expression {expr}")),
+            WasmError::InvalidStmt(stmt) => f.write_str(&format!("Invalid statement {stmt}")),
+        }
+    }
+}
+
+impl Error for WasmError {}
+
+impl From<LayoutError> for WasmError {
+    fn from(value: LayoutError) -> Self {
+        WasmError::Layout(value)
+    }
+}
@@ -0,0 +1,21 @@
+use elysian_ir::module::{Layout, NumericType, Type};
+use wasm_encoder::ValType;
+
+use crate::WasmError;
+
+/// WASM types of the scalar leaves making up a value
+///
+/// Struct values are held in one WASM local per scalar leaf,
+/// in the order given by [`Layout::leaves`].
+pub(crate) fn val_types(layout: &Layout, ty: &Type) -> Result<Vec<ValType>, WasmError> {
+    Ok(layout.leaves(ty)?.iter().map(val_type).collect())
+}
+
+/// WASM representation of a scalar type
+pub(crate) fn val_type(ty: &Type) -> ValType {
+    match ty {
+        Type::Boolean | Type::Number(NumericType::UInt | NumericType::SInt) => ValType::I32,
+        Type::Number(NumericType::Float) => ValType::F32,
+        Type::Struct(_) => panic!("Struct {} has no scalar type", ty.name()),
+    }
+}
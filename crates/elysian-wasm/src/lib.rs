@@ -0,0 +1,484 @@
+//! Compile Elysian IR into self-contained WASM binaries
+
+mod error;
+mod layout;
+mod lower;
+
+pub use error::*;
+
+use elysian_core::property_identifier::PropertyIdentifier;
+use elysian_ir::{
+    ast::{Expr, DISTANCE, POSITION_2D, POSITION_3D},
+    module::{property_type, Layout, Module, NumericType, StructIdentifier, Type, CONTEXT},
+};
+use wasm_encoder::{
+    CodeSection, ConstExpr, EntityType, ExportKind, ExportSection, Function, FunctionSection,
+    GlobalSection, GlobalType, ImportSection, Instruction, TypeSection, ValType,
+};
+
+use crate::{
+    layout::val_type,
+    lower::{signature, FunctionLowering, Functions, Intrinsic},
+};
+
+/// Builder for a WASM module implementing an Elysian module
+///
+/// The binary exports a single sampling function,
+/// taking the position at which to evaluate the shape and returning its distance:
+///
+/// ```wat
+/// (func (export "sample") (param $x f32) (param $y f32) (result f32))
+/// ```
+///
+/// Shapes specialized for 3D take an additional `z` parameter.
+///
+/// Other context properties can be read back after each call to `sample`
+/// through getters exported under the property's name,
+/// or `<property>_<field>` for each field of a struct property.
+///
+/// Math routines without a native instruction are imported from the `Math` module,
+/// so that a JavaScript host can instantiate the binary with `{ Math }`.
+/// Only those the module calls are imported.
+#[derive(Debug, Clone)]
+pub struct WasmBuilder<'a> {
+    module: &'a Module,
+    properties: Vec<PropertyIdentifier>,
+}
+
+impl<'a> WasmBuilder<'a> {
+    pub fn new(module: &'a Module) -> Self {
+        WasmBuilder {
+            module,
+            properties: vec![],
+        }
+    }
+
+    /// Export getters for a context property
+    pub fn property(mut self, prop: impl Into<PropertyIdentifier>) -> Self {
+        self.properties.push(prop.into());
+        self
+    }
+
+    pub fn properties<T: IntoIterator<Item = impl Into<PropertyIdentifier>>>(
+        mut self,
+        t: T,
+    ) -> Self {
+        self.properties.extend(t.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn build(self) -> Result<Vec<u8>, WasmError> {
+        let module = self.module;
+        let layout = Layout::new(module);
+        let mut types = Types::default();
+
+        // Intrinsics precede all defined functions in the function index space,
+        // so functions are lowered against all of them to find those called,
+        // then lowered again against only those, which are imported
+        let (_, intrinsics) = lower_functions(layout, module, &Intrinsic::ALL)?;
+        let (bodies, _) = lower_functions(layout, module, &intrinsics)?;
+
+        let mut imports = ImportSection::new();
+        for intrinsic in intrinsics.iter() {
+            let ty = types.index(vec![ValType::F32; intrinsic.arity()], vec![ValType::F32]);
+            imports.import(
+                Intrinsic::MODULE,
+                intrinsic.name(),
+                EntityType::Function(ty),
+            );
+        }
+
+        let functions = function_indices(module, &intrinsics);
+        let mut index = (intrinsics.len() + functions.len()) as u32;
+
+        let mut function_section = FunctionSection::new();
+        let mut code = CodeSection::new();
+        for (def, body) in module.function_definitions.iter().zip(bodies) {
+            let (params, results) = signature(&layout, def)?;
+            function_section.function(types.index(params, results));
+            code.function(&body);
+        }
+
+        // Sampling function
+        let context = PropertyIdentifier(CONTEXT);
+        let context_id = StructIdentifier(CONTEXT);
+        let fields = layout.fields(&context_id)?;
+        let has_field = |prop: &PropertyIdentifier| fields.iter().any(|(field, _)| *field == prop);
+        let missing_field = |prop: &PropertyIdentifier| WasmError::MissingField {
+            field: prop.name().to_string(),
+            ty: context_id.name().to_string(),
+        };
+
+        let position: PropertyIdentifier = if has_field(&POSITION_3D.into()) {
+            POSITION_3D.into()
+        } else {
+            POSITION_2D.into()
+        };
+        if !has_field(&position) {
+            return Err(missing_field(&position));
+        }
+
+        let distance: PropertyIdentifier = DISTANCE.into();
+        if property_type(&distance)? != &Type::Number(NumericType::Float) || !has_field(&distance) {
+            return Err(missing_field(&distance));
+        }
+
+        let mut globals = GlobalSection::new();
+        let mut getters = vec![];
+        for prop in self.properties.iter() {
+            if !has_field(prop) {
+                return Err(missing_field(prop));
+            }
+            for (path, ty) in layout.leaf_paths(vec![prop.clone()], property_type(prop)?)? {
+                let val_type = val_type(&ty);
+                globals.global(
+                    GlobalType {
+                        val_type,
+                        mutable: true,
+                        shared: false,
+                    },
+                    &match val_type {
+                        ValType::F32 => ConstExpr::f32_const(0.0),
+                        _ => ConstExpr::i32_const(0),
+                    },
+                );
+                getters.push((path, val_type));
+            }
+        }
+
+        let params = vec![ValType::F32; layout.leaves(property_type(&position)?)?.len()];
+        function_section.function(types.index(params.clone(), vec![ValType::F32]));
+        code.function(
+            &FunctionLowering::new(layout, &functions, &intrinsics, &params).sample(
+                &context,
+                &position,
+                &module.call(Expr::Read(vec![context.clone()])),
+                &getters
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (path, _))| (path.clone(), i as u32))
+                    .collect::<Vec<_>>(),
+                &[distance],
+            )?,
+        );
+
+        let mut exports = ExportSection::new();
+        exports.export("sample", ExportKind::Func, index);
+        index += 1;
+
+        // Property getters
+        for (global, (path, val_type)) in getters.iter().enumerate() {
+            function_section.function(types.index(vec![], vec![*val_type]));
+
+            let mut function = Function::new([]);
+            function.instruction(&Instruction::GlobalGet(global as u32));
+            function.instruction(&Instruction::End);
+            code.function(&function);
+
+            let name = path
+                .iter()
+                .map(|prop| prop.name())
+                .collect::<Vec<_>>()
+                .join("_");
+            exports.export(&name, ExportKind::Func, index);
+            index += 1;
+        }
+
+        let mut out = wasm_encoder::Module::new();
+        out.section(&types.section())
+            .section(&imports)
+            .section(&function_section)
+            .section(&globals)
+            .section(&exports)
+            .section(&code);
+
+        Ok(out.finish())
+    }
+}
+
+/// Convert a module into a WASM binary exporting only its sampling function
+pub fn module_to_wasm(module: &Module) -> Result<Vec<u8>, WasmError> {
+    WasmBuilder::new(module).build()
+}
+
+/// Indices of a module's functions, which follow its imported intrinsics
+fn function_indices<'a>(module: &'a Module, intrinsics: &[Intrinsic]) -> Functions<'a> {
+    module
+        .function_definitions
+        .iter()
+        .enumerate()
+        .map(|(i, def)| (def.id.clone(), ((intrinsics.len() + i) as u32, def)))
+        .collect()
+}
+
+/// Lower a module's functions against the given imported intrinsics,
+/// returning their bodies alongside the intrinsics they call in import order
+fn lower_functions(
+    layout: Layout,
+    module: &Module,
+    intrinsics: &[Intrinsic],
+) -> Result<(Vec<Function>, Vec<Intrinsic>), WasmError> {
+    let functions = function_indices(module, intrinsics);
+
+    let mut bodies = vec![];
+    let mut calls = vec![];
+    for def in module.function_definitions.iter() {
+        let (params, _) = signature(&layout, def)?;
+        let (body, called) =
+            FunctionLowering::new(layout, &functions, intrinsics, &params).function(def)?;
+        bodies.push(body);
+        calls.extend(called);
+    }
+
+    let calls = Intrinsic::ALL
+        .into_iter()
+        .filter(|intrinsic| calls.contains(intrinsic))
+        .collect();
+
+    Ok((bodies, calls))
+}
+
+/// Deduplicated function types
+#[derive(Debug, Default, Clone)]
+struct Types(Vec<(Vec<ValType>, Vec<ValType>)>);
+
+impl Types {
+    fn index(&mut self, params: Vec<ValType>, results: Vec<ValType>) -> u32 {
+        let ty = (params, results);
+        match self.0.iter().position(|cand| *cand == ty) {
+            Some(index) => index as u32,
+            None => {
+                self.0.push(ty);
+                self.0.len() as u32 - 1
+            }
+        }
+    }
+
+    fn section(&self) -> TypeSection {
+        let mut section = TypeSection::new();
+        for (params, results) in self.0.iter() {
+            section
+                .ty()
+                .function(params.iter().copied(), results.iter().copied());
+        }
+        section
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use elysian_core::number::Number;
+    use elysian_interpreter::Interpreted;
+    use elysian_ir::{
+        ast::{Struct, Value, DISTANCE, GRADIENT_2D, GRADIENT_3D, VECTOR2, VECTOR3, X, Y, Z},
+        module::{AsModule, Evaluate, SpecializationData},
+    };
+    use elysian_shapes::{
+        combine::{CombineBuilder, ExponentialSmoothUnion, Union},
+        field::{Circle, Point},
+        modify::{IntoGradientNormals, IntoIsosurface, IntoTranslate},
+    };
+    use wasmi::{Engine, Linker, Store};
+
+    use super::*;
+
+    fn instantiate(bytes: &[u8]) -> (Store<()>, wasmi::Instance) {
+        let engine = Engine::default();
+        let module = wasmi::Module::new(&engine, bytes).unwrap();
+        let mut store = Store::new(&engine, ());
+
+        let mut linker = Linker::<()>::new(&engine);
+        for (name, f) in [
+            ("sin", f32::sin as fn(f32) -> f32),
+            ("cos", f32::cos),
+            ("tan", f32::tan),
+            ("asin", f32::asin),
+            ("acos", f32::acos),
+            ("atan", f32::atan),
+            ("log2", f32::log2),
+        ] {
+            linker.func_wrap("Math", name, move |x: f32| f(x)).unwrap();
+        }
+        linker
+            .func_wrap("Math", "atan2", |y: f32, x: f32| y.atan2(x))
+            .unwrap();
+        linker
+            .func_wrap("Math", "pow", |x: f32, y: f32| x.powf(y))
+            .unwrap();
+
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .unwrap()
+            .start(&mut store)
+            .unwrap();
+
+        (store, instance)
+    }
+
+    fn get(s: &Struct, path: &[PropertyIdentifier]) -> f64 {
+        let (first, rest) = path.split_first().unwrap();
+        match (s.members.get(first).unwrap(), rest.is_empty()) {
+            (Value::Struct(s), false) => get(s, rest),
+            (Value::Boolean(b), true) => *b as u8 as f64,
+            (Value::Number(Number::Float(n)), true) => *n,
+            (Value::Number(Number::SInt(n)), true) => *n as f64,
+            (Value::Number(Number::UInt(n)), true) => *n as f64,
+            (value, _) => panic!("Unexpected {value:?}"),
+        }
+    }
+
+    /// Run a module's sampling function and getters under wasmi,
+    /// and check them against the interpreter at each of the given points
+    fn compare(module: &Module, properties: &[PropertyIdentifier], points: &[&[f32]]) {
+        let bytes = WasmBuilder::new(module)
+            .properties(properties.iter().cloned())
+            .build()
+            .unwrap();
+        let (mut store, instance) = instantiate(&bytes);
+
+        let layout = Layout::new(module);
+        let mut getters = vec![];
+        for prop in properties {
+            getters.extend(
+                layout
+                    .leaf_paths(vec![prop.clone()], property_type(prop).unwrap())
+                    .unwrap(),
+            );
+        }
+
+        for point in points {
+            let (position, vector, axes) = match point.len() {
+                2 => (POSITION_2D, VECTOR2, &[X, Y][..]),
+                _ => (POSITION_3D, VECTOR3, &[X, Y, Z][..]),
+            };
+
+            let mut value = Struct::new(StructIdentifier(vector));
+            for (axis, n) in axes.iter().zip(point.iter()) {
+                value = value.set(axis.clone().into(), (*n as f64).into());
+            }
+            let context =
+                Struct::new(StructIdentifier(CONTEXT)).set(position.into(), Value::Struct(value));
+            let expected = Interpreted(module).evaluate(context).unwrap();
+
+            let found = match point {
+                [x, y] => instance
+                    .get_typed_func::<(f32, f32), f32>(&store, "sample")
+                    .unwrap()
+                    .call(&mut store, (*x, *y)),
+                [x, y, z] => instance
+                    .get_typed_func::<(f32, f32, f32), f32>(&store, "sample")
+                    .unwrap()
+                    .call(&mut store, (*x, *y, *z)),
+                _ => unreachable!(),
+            }
+            .unwrap();
+
+            let mut checks = vec![(vec![DISTANCE.into()], found as f64)];
+            for (path, ty) in getters.iter() {
+                let name = path
+                    .iter()
+                    .map(|prop| prop.name())
+                    .collect::<Vec<_>>()
+                    .join("_");
+                let func = instance.get_func(&store, &name).unwrap();
+                let found = match ty {
+                    Type::Number(NumericType::Float) => {
+                        { func.typed::<(), f32>(&store).unwrap().call(&mut store, ()) }.unwrap()
+                            as f64
+                    }
+                    _ => func
+                        .typed::<(), i32>(&store)
+                        .unwrap()
+                        .call(&mut store, ())
+                        .unwrap() as f64,
+                };
+                checks.push((path.clone(), found));
+            }
+
+            for (path, found) in checks {
+                let expected = get(&expected, &path);
+                assert!(
+                    (expected - found).abs() <= 1e-4 * expected.abs().max(1.0),
+                    "{path:?} at {point:?}: {expected} != {found}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_wasm_circle() {
+        let module = Circle::new(0.5)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            &[GRADIENT_2D.into()],
+            &[&[0.0, 0.0], &[0.25, -0.5], &[1.0, 1.0], &[-1.5, 0.75]],
+        );
+    }
+
+    #[test]
+    fn test_wasm_translated_point() {
+        let module = Point
+            .translate([0.25, -0.5])
+            .isosurface(0.75)
+            .gradient_normals()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        compare(
+            &module,
+            &[GRADIENT_2D.into()],
+            &[&[0.0, 0.0], &[0.25, -0.5], &[-1.0, 2.0]],
+        );
+    }
+
+    #[test]
+    fn test_wasm_point_3d() {
+        let module = Point.module(&SpecializationData::new_3d()).finalize();
+
+        compare(&module, &[], &[&[0.0, 0.0, 0.0], &[1.0, -2.0, 0.5]]);
+    }
+
+    #[test]
+    fn test_wasm_missing_property() {
+        let module = Point.module(&SpecializationData::new_2d()).finalize();
+
+        assert!(matches!(
+            WasmBuilder::new(&module).property(GRADIENT_3D).build(),
+            Err(WasmError::MissingField { .. })
+        ));
+    }
+
+    /// Only the intrinsics a module calls are imported
+    #[test]
+    fn test_wasm_imports() {
+        let imports = |module: &Module| {
+            let bytes = module_to_wasm(module).unwrap();
+            wasmi::Module::new(&Engine::default(), &bytes[..])
+                .unwrap()
+                .imports()
+                .map(|import| import.name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Circles derive their UVs from atan2, and need nothing else
+        let circle = Circle::new(0.5)
+            .module(&SpecializationData::new_2d())
+            .finalize();
+        assert_eq!(imports(&circle), ["atan2"]);
+
+        let blend = CombineBuilder::build()
+            .push(Union)
+            .push(ExponentialSmoothUnion::new(DISTANCE, 0.1))
+            .combine()
+            .push(Circle::new(0.5).translate([-0.25, 0.0]))
+            .push(Circle::new(0.5).translate([0.25, 0.0]))
+            .module(&SpecializationData::new_2d())
+            .finalize();
+        assert_eq!(imports(&blend), ["atan2", "log2", "pow"]);
+        compare(&blend, &[], &[&[0.0, 0.0], &[0.6, 0.3], &[-1.0, 0.5]]);
+    }
+}
@@ -0,0 +1,1264 @@
+use std::collections::HashMap;
+
+use elysian_core::{number::Number, property_identifier::PropertyIdentifier};
+use elysian_ir::{
+    ast::{Block, Expr, Stmt, Value},
+    module::{
+        property_type, FunctionDefinition, FunctionIdentifier, Layout, NumericType,
+        StructIdentifier, Type,
+    },
+};
+use wasm_encoder::{BlockType, Function, Instruction, ValType};
+
+use crate::{
+    layout::{val_type, val_types},
+    WasmError,
+};
+
+/// Function definitions alongside their indices in the WASM function index space
+pub(crate) type Functions<'a> = HashMap<FunctionIdentifier, (u32, &'a FunctionDefinition)>;
+
+/// Math routines without a native WASM instruction,
+/// imported from the host under the same names as the JavaScript `Math` object
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Intrinsic {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Atan2,
+    Log2,
+    Pow,
+}
+
+impl Intrinsic {
+    pub const MODULE: &'static str = "Math";
+
+    pub const ALL: [Intrinsic; 9] = [
+        Intrinsic::Sin,
+        Intrinsic::Cos,
+        Intrinsic::Tan,
+        Intrinsic::Asin,
+        Intrinsic::Acos,
+        Intrinsic::Atan,
+        Intrinsic::Atan2,
+        Intrinsic::Log2,
+        Intrinsic::Pow,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Intrinsic::Sin => "sin",
+            Intrinsic::Cos => "cos",
+            Intrinsic::Tan => "tan",
+            Intrinsic::Asin => "asin",
+            Intrinsic::Acos => "acos",
+            Intrinsic::Atan => "atan",
+            Intrinsic::Atan2 => "atan2",
+            Intrinsic::Log2 => "log2",
+            Intrinsic::Pow => "pow",
+        }
+    }
+
+    pub fn arity(&self) -> usize {
+        match self {
+            Intrinsic::Atan2 | Intrinsic::Pow => 2,
+            _ => 1,
+        }
+    }
+}
+
+/// Result of lowering an expression
+///
+/// Structs are kept as their individual members,
+/// each of which lives in its own WASM local.
+#[derive(Debug, Clone)]
+pub(crate) enum Lowered {
+    Boolean(u32),
+    Number(NumericType, u32),
+    Struct(StructIdentifier, Vec<Lowered>),
+}
+
+impl Lowered {
+    fn ty(&self) -> Type {
+        match self {
+            Lowered::Boolean(_) => Type::Boolean,
+            Lowered::Number(n, _) => Type::Number(*n),
+            Lowered::Struct(id, _) => Type::Struct(id.clone()),
+        }
+    }
+
+    fn leaves(&self, out: &mut Vec<u32>) {
+        match self {
+            Lowered::Boolean(v) | Lowered::Number(_, v) => out.push(*v),
+            Lowered::Struct(_, members) => {
+                for member in members {
+                    member.leaves(out)
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Unary {
+    Neg,
+    Abs,
+    Sign,
+    Round,
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Exp2,
+    Log2,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Binary {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Min,
+    Max,
+}
+
+/// Parameter and result types of a lowered function
+///
+/// Inputs and outputs are passed as their scalar leaves,
+/// with multiple results for struct outputs.
+pub(crate) fn signature(
+    layout: &Layout,
+    def: &FunctionDefinition,
+) -> Result<(Vec<ValType>, Vec<ValType>), WasmError> {
+    let mut params = vec![];
+    for input in def.inputs.iter() {
+        params.extend(val_types(layout, property_type(&input.id)?)?);
+    }
+    let results = val_types(layout, property_type(&def.output)?)?;
+    Ok((params, results))
+}
+
+/// Lowers the body of a single function into WASM instructions
+pub(crate) struct FunctionLowering<'a> {
+    layout: Layout<'a>,
+    functions: &'a Functions<'a>,
+    /// Imported intrinsics, in import order
+    imports: &'a [Intrinsic],
+    /// Intrinsics called so far
+    calls: Vec<Intrinsic>,
+    params: u32,
+    locals: Vec<ValType>,
+    properties: HashMap<PropertyIdentifier, Lowered>,
+    instructions: Vec<Instruction<'static>>,
+    output: Option<Lowered>,
+    /// Number of enclosing control frames
+    depth: u32,
+    /// Depth of the exit block of each enclosing loop
+    loops: Vec<u32>,
+}
+
+impl<'a> FunctionLowering<'a> {
+    pub fn new(
+        layout: Layout<'a>,
+        functions: &'a Functions<'a>,
+        imports: &'a [Intrinsic],
+        params: &[ValType],
+    ) -> Self {
+        FunctionLowering {
+            layout,
+            functions,
+            imports,
+            calls: Default::default(),
+            params: params.len() as u32,
+            locals: Default::default(),
+            properties: Default::default(),
+            instructions: Default::default(),
+            output: None,
+            depth: 0,
+            loops: Default::default(),
+        }
+    }
+
+    /// Lower a function definition, alongside the intrinsics it calls
+    pub fn function(
+        mut self,
+        def: &FunctionDefinition,
+    ) -> Result<(Function, Vec<Intrinsic>), WasmError> {
+        let mut param = 0;
+        for input in def.inputs.iter() {
+            let value = self.read_params(property_type(&input.id)?, &mut param)?;
+            self.properties.insert(input.id.clone(), value);
+        }
+
+        let output = self.create_local(property_type(&def.output)?)?;
+        self.output = Some(output.clone());
+
+        self.block(&def.block)?;
+
+        let mut leaves = vec![];
+        output.leaves(&mut leaves);
+        for leaf in leaves {
+            self.emit(Instruction::LocalGet(leaf));
+        }
+
+        let calls = std::mem::take(&mut self.calls);
+        Ok((self.finish(), calls))
+    }
+
+    /// Lower an exported sampling function
+    ///
+    /// Its parameters are written into the fields of `position` in a zeroed context,
+    /// which is passed to `call`.
+    /// The leaves at each of `globals` are then stored into their globals,
+    /// and the leaf at `result` returned.
+    pub fn sample(
+        mut self,
+        context: &PropertyIdentifier,
+        position: &PropertyIdentifier,
+        call: &Expr,
+        globals: &[(Vec<PropertyIdentifier>, u32)],
+        result: &[PropertyIdentifier],
+    ) -> Result<Function, WasmError> {
+        // Locals are zero-initialized on function entry
+        let value = self.create_local(property_type(context)?)?;
+        self.properties.insert(context.clone(), value);
+
+        let path = [context.clone(), position.clone()];
+        let mut leaves = vec![];
+        self.local(&path)?.leaves(&mut leaves);
+        if leaves.len() != self.params as usize {
+            return Err(WasmError::InvalidRead(path_name(&path)));
+        }
+        for (param, leaf) in leaves.into_iter().enumerate() {
+            self.emit(Instruction::LocalGet(param as u32));
+            self.emit(Instruction::LocalSet(leaf));
+        }
+
+        let value = self.expr(call)?;
+        for (path, global) in globals {
+            let leaf = scalar(find(&self.layout, &value, path)?, path)?;
+            self.emit(Instruction::LocalGet(leaf));
+            self.emit(Instruction::GlobalSet(*global));
+        }
+
+        let leaf = scalar(find(&self.layout, &value, result)?, result)?;
+        self.emit(Instruction::LocalGet(leaf));
+
+        Ok(self.finish())
+    }
+
+    fn finish(mut self) -> Function {
+        self.emit(Instruction::End);
+
+        let mut function = Function::new(self.locals.iter().map(|ty| (1, *ty)));
+        for instruction in self.instructions.iter() {
+            function.instruction(instruction);
+        }
+        function
+    }
+
+    fn emit(&mut self, instruction: Instruction<'static>) {
+        self.instructions.push(instruction);
+    }
+
+    fn new_local(&mut self, ty: ValType) -> u32 {
+        let index = self.params + self.locals.len() as u32;
+        self.locals.push(ty);
+        index
+    }
+
+    /// Pop the value on top of the stack into a new local
+    fn pop(&mut self, ty: ValType) -> u32 {
+        let local = self.new_local(ty);
+        self.emit(Instruction::LocalSet(local));
+        local
+    }
+
+    fn f32_const(&mut self, n: f32) {
+        self.emit(Instruction::F32Const(n));
+    }
+
+    fn i32_const(&mut self, n: i32) {
+        self.emit(Instruction::I32Const(n));
+    }
+
+    fn get(&mut self, local: u32) {
+        self.emit(Instruction::LocalGet(local));
+    }
+
+    fn block(&mut self, Block(stmts): &Block) -> Result<(), WasmError> {
+        for stmt in stmts {
+            self.stmt(stmt)?;
+        }
+
+        Ok(())
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> Result<(), WasmError> {
+        match stmt {
+            Stmt::Block(block) => self.block(block)?,
+            Stmt::Bind { prop, expr } => {
+                let value = self.expr(expr)?;
+                self.bind(prop, value)?;
+            }
+            Stmt::Write { path, expr } => {
+                let value = self.expr(expr)?;
+                let (root, _) = path
+                    .split_first()
+                    .ok_or_else(|| WasmError::InvalidStmt("Write to empty path".into()))?;
+
+                if path.len() == 1 {
+                    self.bind(root, value)?;
+                } else {
+                    let local = self.local(path)?.clone();
+                    self.assign(&local, value)?;
+                }
+            }
+            Stmt::If {
+                cond,
+                then,
+                otherwise,
+            } => {
+                let Lowered::Boolean(cond) = self.expr(cond)? else {
+                    return Err(WasmError::InvalidStmt(format!("If {cond:?}")));
+                };
+
+                self.get(cond);
+                self.emit(Instruction::If(BlockType::Empty));
+                self.depth += 1;
+                self.stmt(then)?;
+                if let Some(otherwise) = otherwise {
+                    self.emit(Instruction::Else);
+                    self.stmt(otherwise)?;
+                }
+                self.depth -= 1;
+                self.emit(Instruction::End);
+            }
+            Stmt::Loop { stmt } => {
+                self.emit(Instruction::Block(BlockType::Empty));
+                self.depth += 1;
+                self.loops.push(self.depth);
+
+                self.emit(Instruction::Loop(BlockType::Empty));
+                self.depth += 1;
+                self.stmt(stmt)?;
+                self.emit(Instruction::Br(0));
+                self.emit(Instruction::End);
+                self.depth -= 1;
+
+                self.loops.pop();
+                self.emit(Instruction::End);
+                self.depth -= 1;
+            }
+            Stmt::Break => {
+                let exit = *self
+                    .loops
+                    .last()
+                    .ok_or_else(|| WasmError::InvalidStmt("Break outside of Loop".into()))?;
+                self.emit(Instruction::Br(self.depth - exit));
+            }
+            Stmt::Output(expr) => {
+                let value = self.expr(expr)?;
+                let output = self
+                    .output
+                    .clone()
+                    .ok_or_else(|| WasmError::InvalidStmt(format!("Output {expr:?}")))?;
+                self.assign(&output, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Lowered, WasmError> {
+        Ok(match expr {
+            Expr::Literal(value) => self.literal(value)?,
+            Expr::Struct(id, members) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(id)? {
+                    out.push(match members.get(prop) {
+                        Some(expr) => {
+                            let value = self.expr(expr)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Expr::Read(path) => self.local(path)?.clone(),
+            Expr::Call { function, args } => {
+                let args = args
+                    .iter()
+                    .map(|arg| self.expr(arg))
+                    .collect::<Result<_, _>>()?;
+                self.call(function, args)?
+            }
+            Expr::Neg(t) => self.unary(Unary::Neg, t)?,
+            Expr::Abs(t) => self.unary(Unary::Abs, t)?,
+            Expr::Sign(t) => self.unary(Unary::Sign, t)?,
+            Expr::Round(t) => self.unary(Unary::Round, t)?,
+            Expr::Sin(t) => self.unary(Unary::Sin, t)?,
+            Expr::Cos(t) => self.unary(Unary::Cos, t)?,
+            Expr::Tan(t) => self.unary(Unary::Tan, t)?,
+            Expr::Asin(t) => self.unary(Unary::Asin, t)?,
+            Expr::Acos(t) => self.unary(Unary::Acos, t)?,
+            Expr::Atan(t) => self.unary(Unary::Atan, t)?,
+            Expr::Exp2(t) => self.unary(Unary::Exp2, t)?,
+            Expr::Log2(t) => self.unary(Unary::Log2, t)?,
+            Expr::Length(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Abs, v)?,
+                v @ Lowered::Struct(..) => {
+                    let dot = self.dot(v.clone(), v)?;
+                    self.get(dot);
+                    self.emit(Instruction::F32Sqrt);
+                    Lowered::Number(NumericType::Float, self.pop(ValType::F32))
+                }
+                v => return Err(invalid_unary("Length", &v)),
+            },
+            Expr::Normalize(t) => match self.expr(t)? {
+                v @ Lowered::Number(..) => self.map_unary(Unary::Sign, v)?,
+                v @ Lowered::Struct(..) => self.normalize(v)?,
+                v => return Err(invalid_unary("Normalize", &v)),
+            },
+            Expr::Add(lhs, rhs) => self.binary(Binary::Add, lhs, rhs)?,
+            Expr::Sub(lhs, rhs) => self.binary(Binary::Sub, lhs, rhs)?,
+            Expr::Mul(lhs, rhs) => self.binary(Binary::Mul, lhs, rhs)?,
+            Expr::Div(lhs, rhs) => self.binary(Binary::Div, lhs, rhs)?,
+            Expr::Mod(lhs, rhs) => self.binary(Binary::Mod, lhs, rhs)?,
+            Expr::Min(lhs, rhs) => self.binary(Binary::Min, lhs, rhs)?,
+            Expr::Max(lhs, rhs) => self.binary(Binary::Max, lhs, rhs)?,
+            Expr::Eq(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                self.equal(lhs, rhs)?;
+                Lowered::Boolean(self.pop(ValType::I32))
+            }
+            Expr::Ne(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                self.equal(lhs, rhs)?;
+                self.emit(Instruction::I32Eqz);
+                Lowered::Boolean(self.pop(ValType::I32))
+            }
+            Expr::Lt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(true, lhs, rhs)?)
+            }
+            Expr::Gt(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                Lowered::Boolean(self.compare(false, lhs, rhs)?)
+            }
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (Lowered::Boolean(lhs), Lowered::Boolean(rhs)) => {
+                    self.get(lhs);
+                    self.get(rhs);
+                    self.emit(if let Expr::And(..) = expr {
+                        Instruction::I32And
+                    } else {
+                        Instruction::I32Or
+                    });
+                    Lowered::Boolean(self.pop(ValType::I32))
+                }
+                (lhs, rhs) => return Err(invalid_binary("Logic", &lhs, &rhs)),
+            },
+            Expr::Dot(lhs, rhs) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                match (lhs, rhs) {
+                    (lhs @ Lowered::Number(..), rhs @ Lowered::Number(..)) => {
+                        self.map_binary(Binary::Mul, lhs, rhs)?
+                    }
+                    (lhs, rhs) => Lowered::Number(NumericType::Float, self.dot(lhs, rhs)?),
+                }
+            }
+            Expr::Atan2(lhs, rhs) => match (self.expr(lhs)?, self.expr(rhs)?) {
+                (
+                    Lowered::Number(NumericType::Float, y),
+                    Lowered::Number(NumericType::Float, x),
+                ) => Lowered::Number(
+                    NumericType::Float,
+                    self.intrinsic(Intrinsic::Atan2, &[y, x]),
+                ),
+                (lhs, rhs) => return Err(invalid_binary("Atan2", &lhs, &rhs)),
+            },
+            Expr::Mix(lhs, rhs, t) => {
+                let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+                let Lowered::Number(NumericType::Float, t) = self.expr(t)? else {
+                    return Err(WasmError::InvalidExpr(format!("Mix factor {t:?}")));
+                };
+                self.mix(lhs, rhs, t)?
+            }
+            Expr::Clamp(t, min, max) => {
+                let (t, min, max) = (self.expr(t)?, self.expr(min)?, self.expr(max)?);
+                self.clamp(t, min, max)?
+            }
+        })
+    }
+
+    fn literal(&mut self, value: &Value) -> Result<Lowered, WasmError> {
+        Ok(match value {
+            Value::Boolean(b) => {
+                self.i32_const(*b as i32);
+                Lowered::Boolean(self.pop(ValType::I32))
+            }
+            Value::Number(Number::UInt(n)) => {
+                self.i32_const(*n as i32);
+                Lowered::Number(NumericType::UInt, self.pop(ValType::I32))
+            }
+            Value::Number(Number::SInt(n)) => {
+                self.i32_const(*n as i32);
+                Lowered::Number(NumericType::SInt, self.pop(ValType::I32))
+            }
+            Value::Number(Number::Float(n)) => {
+                self.f32_const(*n as f32);
+                Lowered::Number(NumericType::Float, self.pop(ValType::F32))
+            }
+            Value::Struct(s) => {
+                let mut out = vec![];
+                for (prop, ty) in self.layout.fields(&s.id)? {
+                    out.push(match s.members.get(prop) {
+                        Some(value) => {
+                            let value = self.literal(value)?;
+                            self.cast(value, ty)?
+                        }
+                        None => self.zero(ty)?,
+                    });
+                }
+                Lowered::Struct(s.id.clone(), out)
+            }
+        })
+    }
+
+    /// Explicitly zeroed value,
+    /// since temporaries may be revisited with stale contents inside loops
+    fn zero(&mut self, ty: &Type) -> Result<Lowered, WasmError> {
+        Ok(match ty {
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.zero(ty)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Type::Number(NumericType::Float) => {
+                self.f32_const(0.0);
+                Lowered::Number(NumericType::Float, self.pop(ValType::F32))
+            }
+            Type::Number(n) => {
+                self.i32_const(0);
+                Lowered::Number(*n, self.pop(ValType::I32))
+            }
+            Type::Boolean => {
+                self.i32_const(0);
+                Lowered::Boolean(self.pop(ValType::I32))
+            }
+        })
+    }
+
+    /// Convert a value to the given type, as required when binding it
+    fn cast(&mut self, value: Lowered, ty: &Type) -> Result<Lowered, WasmError> {
+        use NumericType::*;
+
+        Ok(match (value, ty) {
+            (value @ Lowered::Boolean(_), Type::Boolean) => value,
+            (Lowered::Number(from, v), Type::Number(to)) => {
+                let instruction = match (from, to) {
+                    (Float, SInt) => Instruction::I32TruncSatF32S,
+                    (Float, UInt) => Instruction::I32TruncSatF32U,
+                    (SInt, Float) => Instruction::F32ConvertI32S,
+                    (UInt, Float) => Instruction::F32ConvertI32U,
+                    _ => return Ok(Lowered::Number(*to, v)),
+                };
+                self.get(v);
+                self.emit(instruction);
+                let v = self.pop(val_type(ty));
+                Lowered::Number(*to, v)
+            }
+            (Lowered::Struct(id, members), Type::Struct(to)) if id == *to => {
+                let mut out = vec![];
+                for (member, (_, ty)) in members.into_iter().zip(self.layout.fields(to)?) {
+                    out.push(self.cast(member, ty)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (value, ty) => {
+                return Err(WasmError::InvalidExpr(format!(
+                    "Can't convert {} to {}",
+                    value.ty().name(),
+                    ty.name()
+                )))
+            }
+        })
+    }
+
+    /// Rebuild a value of the given type from consecutive parameters
+    fn read_params(&mut self, ty: &Type, param: &mut u32) -> Result<Lowered, WasmError> {
+        Ok(match ty {
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.read_params(ty, param)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Type::Boolean => {
+                *param += 1;
+                Lowered::Boolean(*param - 1)
+            }
+            Type::Number(n) => {
+                *param += 1;
+                Lowered::Number(*n, *param - 1)
+            }
+        })
+    }
+
+    fn create_local(&mut self, ty: &Type) -> Result<Lowered, WasmError> {
+        Ok(match ty {
+            Type::Struct(id) => {
+                let mut out = vec![];
+                for (_, ty) in self.layout.fields(id)? {
+                    out.push(self.create_local(ty)?);
+                }
+                Lowered::Struct(id.clone(), out)
+            }
+            Type::Boolean => Lowered::Boolean(self.new_local(ValType::I32)),
+            Type::Number(n) => Lowered::Number(*n, self.new_local(val_type(ty))),
+        })
+    }
+
+    /// Find the locals at the end of a path
+    fn local(&self, path: &[PropertyIdentifier]) -> Result<&Lowered, WasmError> {
+        let (root, rest) = path
+            .split_first()
+            .ok_or_else(|| WasmError::InvalidRead(path_name(path)))?;
+
+        let local = self
+            .properties
+            .get(root)
+            .ok_or_else(|| WasmError::InvalidRead(path_name(path)))?;
+
+        find(&self.layout, local, rest)
+    }
+
+    /// Bind a value to a property, creating its locals if necessary
+    fn bind(&mut self, prop: &PropertyIdentifier, value: Lowered) -> Result<(), WasmError> {
+        let local = match self.properties.get(prop) {
+            Some(local) => local.clone(),
+            None => {
+                let local = self.create_local(property_type(prop)?)?;
+                self.properties.insert(prop.clone(), local.clone());
+                local
+            }
+        };
+
+        self.assign(&local, value)
+    }
+
+    /// Copy a value into a set of locals
+    ///
+    /// Every source leaf is pushed before any destination is written,
+    /// so that values aliasing their destination are copied correctly.
+    fn assign(&mut self, local: &Lowered, value: Lowered) -> Result<(), WasmError> {
+        let value = self.cast(value, &local.ty())?;
+
+        let mut sources = vec![];
+        value.leaves(&mut sources);
+        let mut destinations = vec![];
+        local.leaves(&mut destinations);
+
+        for source in sources {
+            self.get(source);
+        }
+        for destination in destinations.into_iter().rev() {
+            self.emit(Instruction::LocalSet(destination));
+        }
+
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        function: &FunctionIdentifier,
+        args: Vec<Lowered>,
+    ) -> Result<Lowered, WasmError> {
+        let (index, def) = *self
+            .functions
+            .get(function)
+            .ok_or_else(|| WasmError::MissingFunction(function.name_unique()))?;
+
+        if args.len() != def.inputs.len() {
+            return Err(WasmError::InvalidExpr(format!(
+                "{} takes {} arguments, got {}",
+                function.name_unique(),
+                def.inputs.len(),
+                args.len()
+            )));
+        }
+
+        let mut params = vec![];
+        for (arg, input) in args.into_iter().zip(def.inputs.iter()) {
+            self.cast(arg, property_type(&input.id)?)?
+                .leaves(&mut params);
+        }
+        for param in params {
+            self.get(param);
+        }
+        self.emit(Instruction::Call(index));
+
+        let out = self.create_local(property_type(&def.output)?)?;
+        let mut results = vec![];
+        out.leaves(&mut results);
+        for result in results.into_iter().rev() {
+            self.emit(Instruction::LocalSet(result));
+        }
+
+        Ok(out)
+    }
+
+    fn intrinsic(&mut self, intrinsic: Intrinsic, args: &[u32]) -> u32 {
+        // Imports are chosen by a prior lowering against all intrinsics,
+        // so any intrinsic called here is among them
+        let index = self
+            .imports
+            .iter()
+            .position(|i| *i == intrinsic)
+            .expect("Intrinsic is not imported") as u32;
+
+        if !self.calls.contains(&intrinsic) {
+            self.calls.push(intrinsic);
+        }
+
+        for arg in args {
+            self.get(*arg);
+        }
+        self.emit(Instruction::Call(index));
+        self.pop(ValType::F32)
+    }
+
+    fn unary(&mut self, op: Unary, t: &Expr) -> Result<Lowered, WasmError> {
+        let t = self.expr(t)?;
+        self.map_unary(op, t)
+    }
+
+    /// Apply a unary operator to a value, or to each member of a struct
+    fn map_unary(&mut self, op: Unary, t: Lowered) -> Result<Lowered, WasmError> {
+        let (n, v) = match t {
+            Lowered::Struct(id, members) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_unary(op, member)?);
+                }
+                return Ok(Lowered::Struct(id, out));
+            }
+            Lowered::Number(n, v) => (n, v),
+            t => return Err(invalid_unary(&format!("{op:?}"), &t)),
+        };
+
+        let v = match (op, n) {
+            (Unary::Neg, NumericType::Float) => {
+                self.get(v);
+                self.emit(Instruction::F32Neg);
+                self.pop(ValType::F32)
+            }
+            (Unary::Neg, NumericType::SInt) => {
+                self.i32_const(0);
+                self.get(v);
+                self.emit(Instruction::I32Sub);
+                self.pop(ValType::I32)
+            }
+            (Unary::Abs, NumericType::Float) => {
+                self.get(v);
+                self.emit(Instruction::F32Abs);
+                self.pop(ValType::F32)
+            }
+            (Unary::Abs, NumericType::SInt) => {
+                self.get(v);
+                self.i32_const(0);
+                self.get(v);
+                self.emit(Instruction::I32Sub);
+                self.get(v);
+                self.i32_const(0);
+                self.emit(Instruction::I32GeS);
+                self.emit(Instruction::Select);
+                self.pop(ValType::I32)
+            }
+            (Unary::Sign, NumericType::Float) => {
+                // Matches f32::signum: +-1 for signed zeroes, NaN for NaN
+                self.get(v);
+                self.f32_const(1.0);
+                self.get(v);
+                self.emit(Instruction::F32Copysign);
+                self.get(v);
+                self.get(v);
+                self.emit(Instruction::F32Ne);
+                self.emit(Instruction::Select);
+                self.pop(ValType::F32)
+            }
+            (Unary::Sign, NumericType::SInt) => {
+                self.get(v);
+                self.i32_const(0);
+                self.emit(Instruction::I32GtS);
+                self.get(v);
+                self.i32_const(0);
+                self.emit(Instruction::I32LtS);
+                self.emit(Instruction::I32Sub);
+                self.pop(ValType::I32)
+            }
+            (Unary::Round, NumericType::Float) => {
+                // Matches f32::round, rounding half-way cases away from zero
+                self.get(v);
+                self.emit(Instruction::F32Trunc);
+                let t = self.pop(ValType::F32);
+
+                self.get(t);
+                self.f32_const(1.0);
+                self.get(v);
+                self.emit(Instruction::F32Copysign);
+                self.emit(Instruction::F32Add);
+                self.get(t);
+                self.get(v);
+                self.get(t);
+                self.emit(Instruction::F32Sub);
+                self.emit(Instruction::F32Abs);
+                self.f32_const(0.5);
+                self.emit(Instruction::F32Ge);
+                self.emit(Instruction::Select);
+                self.pop(ValType::F32)
+            }
+            (Unary::Sin, NumericType::Float) => self.intrinsic(Intrinsic::Sin, &[v]),
+            (Unary::Cos, NumericType::Float) => self.intrinsic(Intrinsic::Cos, &[v]),
+            (Unary::Tan, NumericType::Float) => self.intrinsic(Intrinsic::Tan, &[v]),
+            (Unary::Asin, NumericType::Float) => self.intrinsic(Intrinsic::Asin, &[v]),
+            (Unary::Acos, NumericType::Float) => self.intrinsic(Intrinsic::Acos, &[v]),
+            (Unary::Atan, NumericType::Float) => self.intrinsic(Intrinsic::Atan, &[v]),
+            // `Math` has no `exp2`
+            (Unary::Exp2, NumericType::Float) => {
+                self.f32_const(2.0);
+                let two = self.pop(ValType::F32);
+                self.intrinsic(Intrinsic::Pow, &[two, v])
+            }
+            (Unary::Log2, NumericType::Float) => self.intrinsic(Intrinsic::Log2, &[v]),
+            _ => return Err(invalid_unary(&format!("{op:?}"), &Lowered::Number(n, v))),
+        };
+
+        Ok(Lowered::Number(n, v))
+    }
+
+    fn binary(&mut self, op: Binary, lhs: &Expr, rhs: &Expr) -> Result<Lowered, WasmError> {
+        let (lhs, rhs) = (self.expr(lhs)?, self.expr(rhs)?);
+        self.map_binary(op, lhs, rhs)
+    }
+
+    /// Apply a binary operator to a pair of values
+    ///
+    /// Operations between a struct and a number apply to each member of the struct,
+    /// and operations between structs of the same type apply memberwise,
+    /// except for matrix multiplication.
+    fn map_binary(&mut self, op: Binary, lhs: Lowered, rhs: Lowered) -> Result<Lowered, WasmError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => {
+                Lowered::Number(a, self.scalar_binary(op, a, x, y))
+            }
+            (Lowered::Struct(id, members), rhs @ Lowered::Number(..)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, member, rhs.clone())?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (lhs @ Lowered::Number(..), Lowered::Struct(id, members)) => {
+                let mut out = vec![];
+                for member in members {
+                    out.push(self.map_binary(op, lhs.clone(), member)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (Lowered::Struct(id, columns), rhs @ Lowered::Struct(..))
+                if op == Binary::Mul && is_matrix(&id) =>
+            {
+                self.matrix_mul(id, columns, rhs)?
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.map_binary(op, lhs, rhs)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary(&format!("{op:?}"), &lhs, &rhs)),
+        })
+    }
+
+    fn scalar_binary(&mut self, op: Binary, n: NumericType, x: u32, y: u32) -> u32 {
+        use NumericType::*;
+
+        let ty = val_type(&Type::Number(n));
+        let instruction = match (op, n) {
+            (Binary::Add, Float) => Instruction::F32Add,
+            (Binary::Add, _) => Instruction::I32Add,
+            (Binary::Sub, Float) => Instruction::F32Sub,
+            (Binary::Sub, _) => Instruction::I32Sub,
+            (Binary::Mul, Float) => Instruction::F32Mul,
+            (Binary::Mul, _) => Instruction::I32Mul,
+            (Binary::Div, Float) => Instruction::F32Div,
+            (Binary::Div, SInt) => Instruction::I32DivS,
+            (Binary::Div, UInt) => Instruction::I32DivU,
+            (Binary::Mod, UInt) => Instruction::I32RemU,
+            (Binary::Mod, n) => return self.rem_euclid(n, x, y),
+            (Binary::Min, Float) => Instruction::F32Min,
+            (Binary::Max, Float) => Instruction::F32Max,
+            (Binary::Min | Binary::Max, n) => {
+                // select(x, y, x < y) for min, and x > y for max
+                self.get(x);
+                self.get(y);
+                self.get(x);
+                self.get(y);
+                self.emit(match (op, n) {
+                    (Binary::Min, SInt) => Instruction::I32LtS,
+                    (Binary::Min, _) => Instruction::I32LtU,
+                    (_, SInt) => Instruction::I32GtS,
+                    _ => Instruction::I32GtU,
+                });
+                self.emit(Instruction::Select);
+                return self.pop(ty);
+            }
+        };
+
+        self.get(x);
+        self.get(y);
+        self.emit(instruction);
+        self.pop(ty)
+    }
+
+    /// Matches `rem_euclid`, whose result is never negative
+    fn rem_euclid(&mut self, n: NumericType, x: u32, y: u32) -> u32 {
+        let float = n == NumericType::Float;
+        let ty = val_type(&Type::Number(n));
+
+        // Remainder truncated towards zero
+        self.get(x);
+        if float {
+            self.get(x);
+            self.get(y);
+            self.emit(Instruction::F32Div);
+            self.emit(Instruction::F32Trunc);
+            self.get(y);
+            self.emit(Instruction::F32Mul);
+            self.emit(Instruction::F32Sub);
+        } else {
+            self.get(y);
+            self.emit(Instruction::I32RemS);
+        }
+        let r = self.pop(ty);
+
+        let Ok(Lowered::Number(_, abs_y)) = self.map_unary(Unary::Abs, Lowered::Number(n, y))
+        else {
+            unreachable!()
+        };
+
+        // r < 0 ? r + |y| : r
+        self.get(r);
+        self.get(abs_y);
+        self.emit(if float {
+            Instruction::F32Add
+        } else {
+            Instruction::I32Add
+        });
+        self.get(r);
+        self.get(r);
+        if float {
+            self.f32_const(0.0);
+            self.emit(Instruction::F32Lt);
+        } else {
+            self.i32_const(0);
+            self.emit(Instruction::I32LtS);
+        }
+        self.emit(Instruction::Select);
+        self.pop(ty)
+    }
+
+    /// Multiply a column-major matrix by a vector or another matrix
+    fn matrix_mul(
+        &mut self,
+        id: StructIdentifier,
+        columns: Vec<Lowered>,
+        rhs: Lowered,
+    ) -> Result<Lowered, WasmError> {
+        let Lowered::Struct(rhs_id, rhs_members) = rhs else {
+            unreachable!()
+        };
+
+        if !is_matrix(&rhs_id) {
+            return self.matrix_vector_mul(&columns, Lowered::Struct(rhs_id, rhs_members));
+        }
+
+        let mut out = vec![];
+        for column in rhs_members {
+            out.push(self.matrix_vector_mul(&columns, column)?);
+        }
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn matrix_vector_mul(
+        &mut self,
+        columns: &[Lowered],
+        vector: Lowered,
+    ) -> Result<Lowered, WasmError> {
+        let invalid = || WasmError::InvalidExpr("Matrix multiplication".into());
+
+        let Lowered::Struct(id, components) = vector else {
+            return Err(invalid());
+        };
+
+        let columns = columns
+            .iter()
+            .map(|column| floats(column).ok_or_else(invalid))
+            .collect::<Result<Vec<_>, _>>()?;
+        let components = floats(&Lowered::Struct(id.clone(), components)).ok_or_else(invalid)?;
+
+        if columns.len() != components.len() || columns.iter().any(|c| c.len() != columns.len()) {
+            return Err(invalid());
+        }
+
+        let mut out = vec![];
+        for row in 0..columns.len() {
+            for (i, (column, component)) in columns.iter().zip(components.iter()).enumerate() {
+                self.get(column[row]);
+                self.get(*component);
+                self.emit(Instruction::F32Mul);
+                if i > 0 {
+                    self.emit(Instruction::F32Add);
+                }
+            }
+            out.push(Lowered::Number(NumericType::Float, self.pop(ValType::F32)));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    fn dot(&mut self, lhs: Lowered, rhs: Lowered) -> Result<u32, WasmError> {
+        let (a, b) = match (floats(&lhs), floats(&rhs)) {
+            (Some(a), Some(b)) if lhs.ty() == rhs.ty() && !a.is_empty() => (a, b),
+            _ => return Err(invalid_binary("Dot", &lhs, &rhs)),
+        };
+
+        for (i, (a, b)) in a.into_iter().zip(b).enumerate() {
+            self.get(a);
+            self.get(b);
+            self.emit(Instruction::F32Mul);
+            if i > 0 {
+                self.emit(Instruction::F32Add);
+            }
+        }
+        Ok(self.pop(ValType::F32))
+    }
+
+    /// Matches glam's `normalize_or_zero`
+    fn normalize(&mut self, v: Lowered) -> Result<Lowered, WasmError> {
+        let dot = self.dot(v.clone(), v.clone())?;
+
+        self.f32_const(1.0);
+        self.get(dot);
+        self.emit(Instruction::F32Sqrt);
+        self.emit(Instruction::F32Div);
+        let rcp = self.pop(ValType::F32);
+
+        self.get(rcp);
+        self.emit(Instruction::F32Abs);
+        self.f32_const(f32::INFINITY);
+        self.emit(Instruction::F32Lt);
+        self.get(rcp);
+        self.f32_const(0.0);
+        self.emit(Instruction::F32Gt);
+        self.emit(Instruction::I32And);
+        let valid = self.pop(ValType::I32);
+
+        let Lowered::Struct(id, members) = v else {
+            unreachable!()
+        };
+
+        let mut out = vec![];
+        for member in members {
+            let Lowered::Number(NumericType::Float, member) = member else {
+                return Err(invalid_unary("Normalize", &member));
+            };
+            self.get(member);
+            self.get(rcp);
+            self.emit(Instruction::F32Mul);
+            self.f32_const(0.0);
+            self.get(valid);
+            self.emit(Instruction::Select);
+            out.push(Lowered::Number(NumericType::Float, self.pop(ValType::F32)));
+        }
+
+        Ok(Lowered::Struct(id, out))
+    }
+
+    /// Push whether two values are equal onto the stack
+    fn equal(&mut self, lhs: Lowered, rhs: Lowered) -> Result<(), WasmError> {
+        match (lhs, rhs) {
+            (Lowered::Boolean(x), Lowered::Boolean(y)) => {
+                self.get(x);
+                self.get(y);
+                self.emit(Instruction::I32Eq);
+            }
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => {
+                self.get(x);
+                self.get(y);
+                self.emit(match a {
+                    NumericType::Float => Instruction::F32Eq,
+                    _ => Instruction::I32Eq,
+                });
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                self.i32_const(1);
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    self.equal(lhs, rhs)?;
+                    self.emit(Instruction::I32And);
+                }
+            }
+            (lhs, rhs) => return Err(invalid_binary("Eq", &lhs, &rhs)),
+        }
+
+        Ok(())
+    }
+
+    /// Ordered comparison of two numbers
+    fn compare(&mut self, less: bool, lhs: Lowered, rhs: Lowered) -> Result<u32, WasmError> {
+        let (n, x, y) = match (lhs, rhs) {
+            (Lowered::Number(a, x), Lowered::Number(b, y)) if a == b => (a, x, y),
+            (lhs, rhs) => return Err(invalid_binary(if less { "Lt" } else { "Gt" }, &lhs, &rhs)),
+        };
+
+        self.get(x);
+        self.get(y);
+        self.emit(match (n, less) {
+            (NumericType::Float, true) => Instruction::F32Lt,
+            (NumericType::Float, false) => Instruction::F32Gt,
+            (NumericType::SInt, true) => Instruction::I32LtS,
+            (NumericType::SInt, false) => Instruction::I32GtS,
+            (NumericType::UInt, true) => Instruction::I32LtU,
+            (NumericType::UInt, false) => Instruction::I32GtU,
+        });
+        Ok(self.pop(ValType::I32))
+    }
+
+    fn mix(&mut self, lhs: Lowered, rhs: Lowered, t: u32) -> Result<Lowered, WasmError> {
+        Ok(match (lhs, rhs) {
+            (Lowered::Number(NumericType::Float, a), Lowered::Number(NumericType::Float, b)) => {
+                self.f32_const(1.0);
+                self.get(t);
+                self.emit(Instruction::F32Sub);
+                self.get(a);
+                self.emit(Instruction::F32Mul);
+                self.get(t);
+                self.get(b);
+                self.emit(Instruction::F32Mul);
+                self.emit(Instruction::F32Add);
+                Lowered::Number(NumericType::Float, self.pop(ValType::F32))
+            }
+            (Lowered::Struct(a, lhs), Lowered::Struct(b, rhs)) if a == b => {
+                let mut out = vec![];
+                for (lhs, rhs) in lhs.into_iter().zip(rhs) {
+                    out.push(self.mix(lhs, rhs, t)?);
+                }
+                Lowered::Struct(a, out)
+            }
+            (lhs, rhs) => return Err(invalid_binary("Mix", &lhs, &rhs)),
+        })
+    }
+
+    fn clamp(&mut self, t: Lowered, min: Lowered, max: Lowered) -> Result<Lowered, WasmError> {
+        Ok(match (t, min, max) {
+            (Lowered::Number(n, t), Lowered::Number(a, min), Lowered::Number(b, max))
+                if n == a && n == b =>
+            {
+                let t = self.scalar_binary(Binary::Max, n, t, min);
+                Lowered::Number(n, self.scalar_binary(Binary::Min, n, t, max))
+            }
+            (Lowered::Struct(id, t), Lowered::Struct(a, min), Lowered::Struct(b, max))
+                if id == a && id == b =>
+            {
+                let mut out = vec![];
+                for ((t, min), max) in t.into_iter().zip(min).zip(max) {
+                    out.push(self.clamp(t, min, max)?);
+                }
+                Lowered::Struct(id, out)
+            }
+            (t, min, _) => return Err(invalid_binary("Clamp", &t, &min)),
+        })
+    }
+}
+
+/// Find the member of a value at the end of a path
+pub(crate) fn find<'a>(
+    layout: &Layout,
+    mut value: &'a Lowered,
+    path: &[PropertyIdentifier],
+) -> Result<&'a Lowered, WasmError> {
+    for prop in path {
+        let Lowered::Struct(id, members) = value else {
+            return Err(WasmError::InvalidRead(path_name(path)));
+        };
+
+        let index = layout
+            .fields(id)?
+            .iter()
+            .position(|(cand, _)| *cand == prop)
+            .ok_or_else(|| WasmError::MissingField {
+                field: prop.name().to_string(),
+                ty: id.name().to_string(),
+            })?;
+
+        value = &members[index];
+    }
+
+    Ok(value)
+}
+
+/// Local holding a scalar value
+fn scalar(value: &Lowered, path: &[PropertyIdentifier]) -> Result<u32, WasmError> {
+    match value {
+        Lowered::Boolean(v) | Lowered::Number(_, v) => Ok(*v),
+        Lowered::Struct(..) => Err(WasmError::InvalidRead(path_name(path))),
+    }
+}
+
+fn path_name(path: &[PropertyIdentifier]) -> String {
+    path.iter()
+        .map(|prop| prop.name())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn is_matrix(id: &StructIdentifier) -> bool {
+    matches!(id.name(), "Matrix2" | "Matrix3" | "Matrix4")
+}
+
+/// Leaves of a value made up entirely of floats
+fn floats(value: &Lowered) -> Option<Vec<u32>> {
+    match value {
+        Lowered::Number(NumericType::Float, v) => Some(vec![*v]),
+        Lowered::Struct(_, members) => {
+            let mut out = vec![];
+            for member in members {
+                out.extend(floats(member)?);
+            }
+            Some(out)
+        }
+        _ => None,
+    }
+}
+
+fn invalid_unary(op: &str, t: &Lowered) -> WasmError {
+    WasmError::InvalidExpr(format!("{op}({})", t.ty().name()))
+}
+
+fn invalid_binary(op: &str, lhs: &Lowered, rhs: &Lowered) -> WasmError {
+    WasmError::InvalidExpr(format!("{op}({}, {})", lhs.ty().name(), rhs.ty().name()))
+}
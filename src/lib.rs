@@ -64,3 +64,38 @@ pub mod shadertoy {
     pub use elysian_shadertoy::*;
 }
 
+
+#[cfg(feature = "glsl")]
+pub mod glsl {
+    pub use elysian_glsl::*;
+}
+
+#[cfg(feature = "jit")]
+pub mod jit {
+    pub use elysian_jit::*;
+}
+
+#[cfg(feature = "cranelift")]
+pub mod cranelift {
+    pub use elysian_cranelift::*;
+}
+
+#[cfg(feature = "c")]
+pub mod c {
+    pub use elysian_c::*;
+}
+
+#[cfg(feature = "wasm")]
+pub mod wasm {
+    pub use elysian_wasm::*;
+}
+
+#[cfg(feature = "py")]
+pub mod py {
+    pub use elysian_py::*;
+}
+
+#[cfg(feature = "bytecode")]
+pub mod bytecode {
+    pub use elysian_bytecode::*;
+}
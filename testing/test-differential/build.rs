@@ -0,0 +1,13 @@
+use elysian_static::{static_shapes, StaticShapes};
+
+fn main() {
+    static_shapes(test_shapes::all_shapes());
+
+    StaticShapes::new()
+        .namespace("spatial")
+        .shapes(test_shapes::spatial_shapes())
+        .write()
+        .expect("Failed to write spatial shapes");
+
+    println!("cargo:rerun-if-changed=build.rs");
+}
@@ -0,0 +1,1741 @@
+//! Differential testing harness
+//!
+//! Evaluates shapes through several [`Evaluate`] implementations
+//! and checks that their output contexts agree within a tolerance.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use elysian::{
+    core::{number::Number, property_identifier::PropertyIdentifier},
+    ir::{
+        ast::{Struct, Value, POSITION_2D, POSITION_3D, VECTOR2, VECTOR3, X, Y, Z},
+        module::{
+            properties, Evaluate, EvaluateError, Module, NumericType, StructIdentifier, Type,
+            CONTEXT,
+        },
+    },
+    shapes::modify::ASPECT,
+};
+
+elysian::r#static::include_static_shapes!();
+elysian::r#static::include_static_shapes!(spatial);
+
+/// A single output property on which two evaluators disagree
+#[derive(Debug, Clone)]
+pub struct Divergence {
+    pub context: Struct,
+    pub evaluator: String,
+    pub path: Vec<PropertyIdentifier>,
+    pub expected: Value,
+    pub found: Value,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = self
+            .path
+            .iter()
+            .map(|prop| prop.name())
+            .collect::<Vec<_>>()
+            .join(".");
+
+        write!(
+            f,
+            "{}: {path} expected {}, found {} for input {}",
+            self.evaluator, self.expected, self.found, self.context
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum DifferentialError {
+    Evaluate {
+        evaluator: String,
+        error: EvaluateError,
+    },
+    Divergence(Vec<Divergence>),
+}
+
+impl Display for DifferentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DifferentialError::Evaluate { evaluator, error } => {
+                write!(f, "Evaluator {evaluator} failed: {error}")
+            }
+            DifferentialError::Divergence(divergences) => {
+                writeln!(f, "{} divergent properties:", divergences.len())?;
+                for divergence in divergences {
+                    writeln!(f, "{divergence}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for DifferentialError {}
+
+/// Set of named evaluators to be compared against the first
+pub struct Differential<'a> {
+    evaluators: Vec<(String, Box<dyn Evaluate<'a>>)>,
+    tolerance: f64,
+}
+
+impl<'a> Default for Differential<'a> {
+    fn default() -> Self {
+        Differential {
+            evaluators: Default::default(),
+            tolerance: 1e-4,
+        }
+    }
+}
+
+impl<'a> Differential<'a> {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add an evaluator
+    ///
+    /// The first evaluator added is used as the reference.
+    pub fn evaluator(mut self, name: impl Into<String>, evaluator: impl Evaluate<'a>) -> Self {
+        self.evaluators.push((name.into(), Box::new(evaluator)));
+        self
+    }
+
+    /// Set the tolerance for numeric comparison
+    ///
+    /// Applied absolutely for magnitudes below 1, and relatively above.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Evaluate each context through every evaluator,
+    /// collecting any divergence from the reference
+    pub fn run(&self, contexts: impl IntoIterator<Item = Struct>) -> Result<(), DifferentialError> {
+        let Some(((_, reference), rest)) = self.evaluators.split_first() else {
+            return Ok(());
+        };
+
+        let mut divergences = vec![];
+
+        for context in contexts {
+            let expected = evaluate(&self.evaluators[0].0, reference.as_ref(), &context)?;
+
+            for (name, evaluator) in rest {
+                let found = evaluate(name, evaluator.as_ref(), &context)?;
+
+                let mut diverge = |path: Vec<PropertyIdentifier>, expected, found| {
+                    divergences.push(Divergence {
+                        context: context.clone(),
+                        evaluator: name.clone(),
+                        path,
+                        expected,
+                        found,
+                    })
+                };
+
+                compare_struct(&mut vec![], &expected, &found, self.tolerance, &mut diverge);
+            }
+        }
+
+        if divergences.is_empty() {
+            Ok(())
+        } else {
+            Err(DifferentialError::Divergence(divergences))
+        }
+    }
+}
+
+/// Evaluate a context, treating panics as evaluation failures
+fn evaluate<'a, E: Evaluate<'a> + ?Sized>(
+    name: &str,
+    evaluator: &E,
+    context: &Struct,
+) -> Result<Struct, DifferentialError> {
+    let result = catch_unwind(AssertUnwindSafe(|| evaluator.evaluate(context.clone())))
+        .unwrap_or_else(|panic| {
+            let message = panic
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| panic.downcast_ref::<&str>().map(ToString::to_string))
+                .unwrap_or_else(|| "Evaluator panicked".to_string());
+            Err(message.into())
+        });
+
+    result.map_err(|error| DifferentialError::Evaluate {
+        evaluator: name.to_string(),
+        error,
+    })
+}
+
+/// Compare the properties present in both structs
+///
+/// Evaluators differ in whether they carry through context members
+/// a shape never touches, so absent properties are not treated as divergent.
+fn compare_struct(
+    path: &mut Vec<PropertyIdentifier>,
+    expected: &Struct,
+    found: &Struct,
+    tolerance: f64,
+    diverge: &mut impl FnMut(Vec<PropertyIdentifier>, Value, Value),
+) {
+    for (prop, lhs) in expected.members.iter() {
+        let Some(rhs) = found.members.get(prop) else {
+            continue;
+        };
+
+        path.push(prop.clone());
+        match (lhs, rhs) {
+            (Value::Struct(lhs), Value::Struct(rhs)) => {
+                compare_struct(path, lhs, rhs, tolerance, diverge)
+            }
+            (lhs, rhs) if values_agree(lhs, rhs, tolerance) => (),
+            (lhs, rhs) => diverge(path.clone(), lhs.clone(), rhs.clone()),
+        }
+        path.pop();
+    }
+}
+
+fn values_agree(lhs: &Value, rhs: &Value, tolerance: f64) -> bool {
+    match (lhs, rhs) {
+        (Value::Boolean(lhs), Value::Boolean(rhs)) => lhs == rhs,
+        (Value::Number(lhs), Value::Number(rhs)) => numbers_agree(*lhs, *rhs, tolerance),
+        _ => false,
+    }
+}
+
+fn numbers_agree(lhs: Number, rhs: Number, tolerance: f64) -> bool {
+    match (lhs, rhs) {
+        (Number::UInt(lhs), Number::UInt(rhs)) => lhs == rhs,
+        (Number::SInt(lhs), Number::SInt(rhs)) => lhs == rhs,
+        (Number::Float(lhs), Number::Float(rhs)) => {
+            (lhs.is_nan() && rhs.is_nan())
+                || lhs == rhs
+                || (lhs - rhs).abs() <= tolerance * lhs.abs().max(rhs.abs()).max(1.0)
+        }
+        _ => false,
+    }
+}
+
+/// Default value for a type, recursing through the module's struct definitions
+pub fn default_value(module: &Module, ty: &Type) -> Value {
+    match ty {
+        Type::Boolean => Value::Boolean(false),
+        Type::Number(NumericType::UInt) => Value::Number(Number::UInt(0)),
+        Type::Number(NumericType::SInt) => Value::Number(Number::SInt(0)),
+        Type::Number(NumericType::Float) => Value::Number(Number::Float(0.0)),
+        Type::Struct(id) => Value::Struct(default_struct(module, id, Struct::new(id.clone()))),
+    }
+}
+
+/// Fill any fields of `base` absent from the module's definition of `id`
+/// with default values
+fn default_struct(module: &Module, id: &StructIdentifier, mut base: Struct) -> Struct {
+    let Some(def) = module.struct_definitions.iter().find(|def| def.id == *id) else {
+        return base;
+    };
+
+    for field in def.fields.iter() {
+        if base.members.contains_key(&field.id) {
+            continue;
+        }
+
+        if let Some(ty) = properties().get(&field.id) {
+            base.set_mut(field.id.clone(), default_value(module, ty));
+        }
+    }
+
+    base
+}
+
+/// Fill any context members a finalized module reads but the input lacks
+///
+/// Compiled backends zero-initialize their context struct,
+/// so inputs are completed up front to compare like with like.
+pub fn complete_context(module: &Module, context: Struct) -> Struct {
+    default_struct(module, &StructIdentifier(CONTEXT), context)
+}
+
+/// Contexts sampling a 2D grid of points
+/// spanning `min` to `max` inclusive
+pub fn grid_2d(
+    [min_x, min_y]: [f64; 2],
+    [max_x, max_y]: [f64; 2],
+    [width, height]: [usize; 2],
+) -> impl Iterator<Item = Struct> {
+    let lerp = |min: f64, max: f64, i: usize, count: usize| {
+        if count > 1 {
+            min + (max - min) * i as f64 / (count - 1) as f64
+        } else {
+            min
+        }
+    };
+
+    (0..height).flat_map(move |iy| {
+        (0..width).map(move |ix| {
+            Struct::new(StructIdentifier(CONTEXT))
+                .set(
+                    POSITION_2D.into(),
+                    Value::Struct(
+                        Struct::new(StructIdentifier(VECTOR2))
+                            .set(X.into(), lerp(min_x, max_x, ix, width).into())
+                            .set(Y.into(), lerp(min_y, max_y, iy, height).into()),
+                    ),
+                )
+                .set(ASPECT.into(), Value::Number(Number::Float(1.0)))
+        })
+    })
+}
+
+/// Contexts sampling a 3D grid of points
+/// spanning `min` to `max` inclusive
+pub fn grid_3d(
+    [min_x, min_y, min_z]: [f64; 3],
+    [max_x, max_y, max_z]: [f64; 3],
+    [width, height, depth]: [usize; 3],
+) -> impl Iterator<Item = Struct> {
+    let lerp = |min: f64, max: f64, i: usize, count: usize| {
+        if count > 1 {
+            min + (max - min) * i as f64 / (count - 1) as f64
+        } else {
+            min
+        }
+    };
+
+    (0..depth).flat_map(move |iz| {
+        (0..height).flat_map(move |iy| {
+            (0..width).map(move |ix| {
+                Struct::new(StructIdentifier(CONTEXT)).set(
+                    POSITION_3D.into(),
+                    Value::Struct(
+                        Struct::new(StructIdentifier(VECTOR3))
+                            .set(X.into(), lerp(min_x, max_x, ix, width).into())
+                            .set(Y.into(), lerp(min_y, max_y, iy, height).into())
+                            .set(Z.into(), lerp(min_z, max_z, iz, depth).into()),
+                    ),
+                )
+            })
+        })
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use elysian::{
+        bytecode::module_to_bytecode,
+        core::{expr::IntoExpr, identifier::Identifier},
+        cranelift::CraneliftCompiled,
+        image::{color_to_rgb8, rasterize},
+        interpreter::Interpreted,
+        ir::{
+            ast::{
+                Block, IntoLiteral, Stmt, COLOR, DISTANCE, DISTANCE_BOUND, GRADIENT_2D,
+                GRADIENT_3D, UV, W,
+            },
+            module::{
+                AsModule, Domains, FunctionDefinition, FunctionIdentifier, InputDefinition,
+                IntoRead, IntoWrite, SpecializationData,
+            },
+        },
+        jit::JitCompiler,
+        math::glam::{EulerRot, Mat3, Quat, Vec3},
+        naga::NagaEvaluated,
+        r#static::{registered_shapes, Precompiled, PrecompiledError},
+        shapes::{
+            color::distance_color,
+            combine::{
+                ChamferIntersection, ChamferSubtraction, ChamferUnion, ColumnsIntersection,
+                ColumnsSubtraction, ColumnsUnion, CombineBuilder, ExponentialSmoothUnion,
+                Intersection, PowerSmoothUnion, StairsIntersection, StairsSubtraction, StairsUnion,
+                Subtraction, Union,
+            },
+            field::{Circle, Cuboid, Ring, RoundedBox, Torus},
+            modify::{
+                IntoGradientNormals, IntoHollow, IntoModify, IntoOnion, IntoRepeatPolar, IntoRound,
+                IntoSet, IntoTaper, IntoTranslate, IntoTwist, Taper, REPEAT_ID_POLAR,
+            },
+            noise::Noise,
+            shape::{DynShape, IntoShape},
+            wrap::{
+                extrude::IntoExtrude,
+                revolve::IntoRevolve,
+                rotate::{EulerOrder, IntoRotate3d, Rotation},
+                scale::IntoScaleAxes,
+            },
+        },
+    };
+    use image::Rgb;
+
+    use super::*;
+
+    /// Shapes too slow to interpret for the default test run
+    const EXPENSIVE: &[&str] = &["raymarched", "composite"];
+
+    /// 2D primitives with exact distances and analytic gradients
+    const PRIMITIVES: &[&str] = &[
+        "triangle",
+        "polygon",
+        "regular_polygon",
+        "star",
+        "rounded_box",
+        "ellipse",
+        "quadratic_bezier",
+    ];
+
+    /// Noise fields, and shapes warped or displaced by them, which carry no analytic gradient
+    const NOISY: &[&str] = &[
+        "value_noise",
+        "gradient_noise",
+        "warped_circle",
+        "displaced_circle",
+    ];
+
+    /// Deformed shapes whose distance is divided by a Lipschitz factor
+    const DEFORMED: &[&str] = &[
+        "bent_box",
+        "tapered_box",
+        "twisted_cuboid",
+        "bent_cuboid",
+        "tapered_cylinder",
+    ];
+
+    // Sample points are offset from the axes and cell boundaries:
+    // WGSL's sign(0) is 0 and its round() breaks ties to even,
+    // where the CPU backends yield 1 and round away from zero.
+
+    fn shapes_agree(
+        shapes: impl IntoIterator<Item = (&'static str, Module)>,
+        filter: impl Fn(&str) -> bool,
+        contexts: &[Struct],
+        jit: Option<&JitCompiler>,
+    ) {
+        let mut failures = vec![];
+
+        for (name, module) in shapes {
+            if !filter(name) {
+                continue;
+            }
+
+            let naga = match NagaEvaluated::new(&module) {
+                Ok(naga) => naga,
+                Err(e) => {
+                    failures.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+
+            let cranelift = match CraneliftCompiled::new(&module) {
+                Ok(cranelift) => cranelift,
+                Err(e) => {
+                    failures.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+
+            let bytecode = match module_to_bytecode(&module) {
+                Ok(bytecode) => bytecode,
+                Err(e) => {
+                    failures.push(format!("{name}: {e}"));
+                    continue;
+                }
+            };
+
+            let mut differential = Differential::new()
+                .evaluator("interpreter", Interpreted(&module))
+                .evaluator("static", Precompiled(&module))
+                .evaluator("naga", naga)
+                .evaluator("cranelift", cranelift)
+                .evaluator("bytecode", bytecode);
+
+            if let Some(jit) = jit {
+                match jit.compile(&module) {
+                    Ok(compiled) => differential = differential.evaluator("jit", compiled),
+                    Err(e) => {
+                        failures.push(format!("{name}: {e}"));
+                        continue;
+                    }
+                }
+            }
+
+            let result = differential.run(
+                contexts
+                    .iter()
+                    .map(|context| complete_context(&module, context.clone())),
+            );
+
+            if let Err(e) = result {
+                failures.push(format!("{name}: {e}"));
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    fn grid() -> Vec<Struct> {
+        grid_2d([-1.87, -1.73], [2.13, 2.27], [5, 5]).collect()
+    }
+
+    fn spatial_grid() -> Vec<Struct> {
+        grid_3d([-1.87, -1.73, -1.61], [2.13, 2.27, 2.39], [5, 5, 5]).collect()
+    }
+
+    #[test]
+    fn test_shapes_agree() {
+        shapes_agree(
+            test_shapes::all_shapes(),
+            |name| !EXPENSIVE.contains(&name),
+            &grid(),
+            None,
+        )
+    }
+
+    #[test]
+    fn test_spatial_shapes_agree() {
+        shapes_agree(
+            test_shapes::spatial_shapes(),
+            |_| true,
+            &spatial_grid(),
+            None,
+        )
+    }
+
+    fn number(value: Value) -> f64 {
+        match value {
+            Value::Number(Number::Float(n)) => n,
+            value => panic!("Expected a float, found {value}"),
+        }
+    }
+
+    fn vector2(value: Value) -> [f64; 2] {
+        let Value::Struct(s) = value else {
+            panic!("Expected a vector, found {value}");
+        };
+        [X, Y].map(|axis| number(s.get(&axis.into())))
+    }
+
+    fn vector3(value: Value) -> [f64; 3] {
+        let Value::Struct(s) = value else {
+            panic!("Expected a vector, found {value}");
+        };
+        [X, Y, Z].map(|axis| number(s.get(&axis.into())))
+    }
+
+    fn vector4(value: Value) -> [f64; 4] {
+        let Value::Struct(s) = value else {
+            panic!("Expected a vector, found {value}");
+        };
+        [X, Y, Z, W].map(|axis| number(s.get(&axis.into())))
+    }
+
+    fn vectors_agree(lhs: &[f64], rhs: &[f64]) -> bool {
+        lhs.iter()
+            .zip(rhs)
+            .all(|(lhs, rhs)| (lhs - rhs).abs() <= 1e-4)
+    }
+
+    /// Context positioned at the given 2D point
+    fn context_2d([x, y]: [f64; 2]) -> Struct {
+        Struct::new(StructIdentifier(CONTEXT)).set(
+            POSITION_2D.into(),
+            Value::Struct(
+                Struct::new(StructIdentifier(VECTOR2))
+                    .set(X.into(), x.into())
+                    .set(Y.into(), y.into()),
+            ),
+        )
+    }
+
+    /// Context positioned at the given 3D point
+    fn context_3d([x, y, z]: [f64; 3]) -> Struct {
+        Struct::new(StructIdentifier(CONTEXT)).set(
+            POSITION_3D.into(),
+            Value::Struct(
+                Struct::new(StructIdentifier(VECTOR3))
+                    .set(X.into(), x.into())
+                    .set(Y.into(), y.into())
+                    .set(Z.into(), z.into()),
+            ),
+        )
+    }
+
+    /// Evaluate a module with the interpreter, defaulting any context members left unset
+    fn eval(module: &Module, context: Struct) -> Struct {
+        Interpreted(module)
+            .evaluate(complete_context(module, context))
+            .unwrap()
+    }
+
+    fn eval_distance(module: &Module, context: Struct) -> f64 {
+        number(eval(module, context).get(&DISTANCE.into()))
+    }
+
+    fn assert_no_failures(failures: Vec<String>) {
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+
+    /// Analytic 3D gradients agree with central differences of the distance
+    #[test]
+    fn test_spatial_gradients() {
+        const H: f64 = 1e-3;
+
+        let mut failures = vec![];
+
+        for (name, module) in test_shapes::spatial_shapes() {
+            // The ellipsoid's distance is a bound,
+            // so its gradient only matches the surface normal
+            if name == "ellipsoid" || NOISY.contains(&name) {
+                continue;
+            }
+
+            let distance = |position: [f64; 3]| eval_distance(&module, context_3d(position));
+
+            for context in spatial_grid() {
+                let position = vector3(context.get(&POSITION_3D.into()));
+                let gradient = vector3(eval(&module, context).get(&GRADIENT_3D.into()));
+
+                for axis in 0..3 {
+                    let mut lo = position;
+                    let mut hi = position;
+                    lo[axis] -= H;
+                    hi[axis] += H;
+                    let (lo, mid, hi) = (distance(lo), distance(position), distance(hi));
+
+                    // Skip points on a crease, where the gradient is undefined
+                    if ((hi - mid) - (mid - lo)).abs() > 1e-2 * H {
+                        continue;
+                    }
+
+                    let numeric = (hi - lo) / (2.0 * H);
+                    if (numeric - gradient[axis]).abs() > 1e-2 {
+                        failures.push(format!(
+                            "{name}: gradient {gradient:?} differs from {numeric} on axis {axis} at {position:?}"
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Every representation of a 3D rotation agrees with the equivalent glam rotation
+    #[test]
+    fn test_rotation_representations() {
+        const ANGLES: [f32; 3] = [0.3, -0.6, 0.9];
+
+        let spec = SpecializationData::new_3d();
+
+        let distance = |shape: DynShape, position: Vec3| {
+            let module = shape.gradient_normals().module(&spec).finalize();
+            eval_distance(&module, context_3d(position.as_dvec3().to_array()))
+        };
+
+        let cuboid = || Cuboid::new([0.75, 0.5, 0.25]);
+
+        let [x, y, z] = ANGLES;
+        let orders = [
+            (EulerOrder::XYZ, Quat::from_euler(EulerRot::XYZ, x, y, z)),
+            (EulerOrder::XZY, Quat::from_euler(EulerRot::XZY, x, z, y)),
+            (EulerOrder::YXZ, Quat::from_euler(EulerRot::YXZ, y, x, z)),
+            (EulerOrder::YZX, Quat::from_euler(EulerRot::YZX, y, z, x)),
+            (EulerOrder::ZXY, Quat::from_euler(EulerRot::ZXY, z, x, y)),
+            (EulerOrder::ZYX, Quat::from_euler(EulerRot::ZYX, z, y, x)),
+        ];
+
+        let positions = [
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::new(0.3, 0.8, -0.4),
+            Vec3::new(-0.7, 0.2, 0.9),
+        ];
+
+        let mut failures = vec![];
+
+        for (order, quat) in orders {
+            let (axis, angle) = quat.to_axis_angle();
+
+            let rotations = [
+                ("euler", Rotation::euler(order, ANGLES)),
+                ("quaternion", Rotation::from(quat * 2.0)),
+                ("matrix", Rotation::from(Mat3::from_quat(quat))),
+                (
+                    "axis_angle",
+                    Rotation::axis_angle((axis * 3.0).to_array(), angle),
+                ),
+            ];
+
+            for (name, rotation) in rotations {
+                for position in positions {
+                    let expected = distance(cuboid().shape(), quat.inverse() * position);
+                    let found = distance(cuboid().rotate_3d(rotation.clone()).shape(), position);
+
+                    if (expected - found).abs() > 1e-4 {
+                        failures.push(format!(
+                            "{order:?} {name}: expected {expected}, found {found} at {position}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Twists around any axis match the equivalent glam rotation of each point,
+    /// and their gradients agree with central differences of the distance
+    #[test]
+    fn test_twist_axes() {
+        const H: f64 = 1e-3;
+        const RATE: f32 = 1.0;
+        const RADIUS: f32 = 3.5;
+
+        let spec = SpecializationData::new_3d();
+
+        let cuboid = || Cuboid::new([0.5, 1.0, 0.5]);
+        let cuboid_module = cuboid().gradient_normals().module(&spec).finalize();
+
+        // Twisted distances are divided by the Lipschitz factor of the shear
+        let shear = RATE * RADIUS;
+        let lipschitz = ((shear + (shear * shear + 4.0).sqrt()) * 0.5) as f64;
+
+        let mut failures = vec![];
+
+        for axis in [Vec3::X, Vec3::Z, Vec3::new(1.0, 1.0, 0.0)] {
+            let module = cuboid()
+                .twist(axis.to_array(), RATE, RADIUS)
+                .module(&spec)
+                .finalize();
+
+            let distance = |position: [f64; 3]| eval_distance(&module, context_3d(position));
+
+            for context in spatial_grid() {
+                let position = vector3(context.get(&POSITION_3D.into()));
+                let out = eval(&module, context);
+                let found = number(out.get(&DISTANCE.into()));
+                let gradient = vector3(out.get(&GRADIENT_3D.into()));
+
+                let p = Vec3::from_array(position.map(|x| x as f32));
+                let untwisted =
+                    Quat::from_axis_angle(axis.normalize(), -RATE * p.dot(axis.normalize())) * p;
+                let expected =
+                    eval_distance(&cuboid_module, context_3d(untwisted.as_dvec3().to_array()))
+                        / lipschitz;
+
+                if (expected - found).abs() > 1e-4 {
+                    failures.push(format!(
+                        "{axis}: expected {expected}, found {found} at {position:?}"
+                    ));
+                }
+
+                for i in 0..3 {
+                    let mut lo = position;
+                    let mut hi = position;
+                    lo[i] -= H;
+                    hi[i] += H;
+                    let (lo, hi) = (distance(lo), distance(hi));
+
+                    // Skip points on a crease, where the gradient is undefined
+                    if ((hi - found) - (found - lo)).abs() > 1e-2 * H {
+                        continue;
+                    }
+
+                    let numeric = (hi - lo) / (2.0 * H);
+                    if (numeric - gradient[i]).abs() > 1e-2 {
+                        failures.push(format!(
+                            "{axis}: gradient {gradient:?} differs from {numeric} on axis {i} at {position:?}"
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Extruded and revolved profiles match the equivalent 3D primitives
+    #[test]
+    fn test_lifted_profiles() {
+        let spec = SpecializationData::new_3d();
+
+        let pairs = [
+            (
+                "extrude",
+                RoundedBox::new([0.75, 0.5], 0.0).extrude(0.25).shape(),
+                Cuboid::new([0.75, 0.5, 0.25]).shape(),
+                vec![],
+            ),
+            (
+                "revolve",
+                Circle::new(0.25).revolve([0.0, 2.0, 0.0], 1.0).shape(),
+                Torus::new(1.0, 0.25).shape(),
+                // The grid is offset from the axes, so sample the axis of revolution explicitly
+                [-0.5, 0.0, 0.7].map(|y| context_3d([0.0, y, 0.0])).to_vec(),
+            ),
+        ];
+
+        let mut failures = vec![];
+
+        for (name, lifted, primitive, on_axis) in pairs {
+            let lifted = lifted.gradient_normals().module(&spec).finalize();
+            let primitive = primitive.gradient_normals().module(&spec).finalize();
+
+            if let Err(e) = Differential::new()
+                .evaluator("interpreter", Interpreted(&lifted))
+                .evaluator("naga", NagaEvaluated::new(&lifted).unwrap())
+                .evaluator("cranelift", CraneliftCompiled::new(&lifted).unwrap())
+                .run(
+                    on_axis
+                        .iter()
+                        .map(|context| complete_context(&lifted, context.clone())),
+                )
+            {
+                failures.push(format!("{name}: {e}"));
+            }
+
+            for context in spatial_grid().into_iter().chain(on_axis) {
+                let position = vector3(context.get(&POSITION_3D.into()));
+                let found = eval(&lifted, context.clone());
+                let d_found = number(found.get(&DISTANCE.into()));
+                let d_expected = eval_distance(&primitive, context);
+
+                if !d_found.is_finite() || (d_found - d_expected).abs() > 1e-4 {
+                    failures.push(format!(
+                        "{name}: expected {d_expected}, found {d_found} at {position:?}"
+                    ));
+                }
+
+                // Exact distances have unit gradients, even on the axis
+                let gradient = vector3(found.get(&GRADIENT_3D.into()));
+                let length = gradient.iter().map(|d| d * d).sum::<f64>().sqrt();
+                if !((1.0 - 1e-4)..=(1.0 + 1e-4)).contains(&length) {
+                    failures.push(format!(
+                        "{name}: gradient {gradient:?} is not unit length at {position:?}"
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Polar repetition indexes sectors counter-clockwise from zero,
+    /// and evaluates every sector as sector 0
+    #[test]
+    fn test_repeat_polar_sectors() {
+        const COUNT: usize = 6;
+
+        // Point in the repetition plane at `radius` and `angle`, `height` along the axis
+        let planar = |[radius, angle, _]: [f64; 3]| {
+            Value::Struct(
+                Struct::new(StructIdentifier(VECTOR2))
+                    .set(X.into(), (radius * angle.cos()).into())
+                    .set(Y.into(), (radius * angle.sin()).into()),
+            )
+        };
+
+        // Around the X axis, sector 0 is centered on Y, and angles increase toward Z
+        let spatial = |[radius, angle, height]: [f64; 3]| {
+            Value::Struct(
+                Struct::new(StructIdentifier(VECTOR3))
+                    .set(X.into(), height.into())
+                    .set(Y.into(), (radius * angle.cos()).into())
+                    .set(Z.into(), (radius * angle.sin()).into()),
+            )
+        };
+
+        let cases: [(_, _, &dyn Fn([f64; 3]) -> Value, _); 2] = [
+            (
+                SpecializationData::new_2d(),
+                POSITION_2D,
+                &planar,
+                Circle::new(0.25)
+                    .translate([1.25, 0.0])
+                    .repeat_polar(COUNT as f64),
+            ),
+            (
+                SpecializationData::new_3d(),
+                POSITION_3D,
+                &spatial,
+                Circle::new(0.25)
+                    .translate([0.0, 1.25, 0.0])
+                    .repeat_polar_axis(COUNT as f64, [2.0, 0.0, 0.0]),
+            ),
+        ];
+
+        let mut failures = vec![];
+
+        for (spec, position, point, shape) in cases {
+            let module = shape.gradient_normals().module(&spec).finalize();
+            let evaluate = |coords: [f64; 3]| {
+                eval(
+                    &module,
+                    Struct::new(StructIdentifier(CONTEXT))
+                        .set(position.clone().into(), point(coords)),
+                )
+            };
+
+            for [radius, offset, height] in [[0.4, 0.1, 0.3], [1.3, -0.3, -0.2], [2.1, 0.45, 0.0]] {
+                let expected = number(evaluate([radius, offset, height]).get(&DISTANCE.into()));
+
+                for sector in 0..COUNT {
+                    let angle = sector as f64 * std::f64::consts::TAU / COUNT as f64 + offset;
+                    let out = evaluate([radius, angle, height]);
+
+                    let id = number(out.get(&REPEAT_ID_POLAR.into()));
+                    if id != sector as f64 {
+                        failures.push(format!(
+                            "{}: expected sector {sector}, found {id} at angle {angle}",
+                            position.name()
+                        ));
+                    }
+
+                    let found = number(out.get(&DISTANCE.into()));
+                    if (found - expected).abs() > 1e-4 {
+                        failures.push(format!(
+                            "{}: expected {expected}, found {found} in sector {sector}",
+                            position.name()
+                        ));
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Shell and rounding modifiers reproduce the equivalent circles and rings,
+    /// down to their gradients and UVs
+    #[test]
+    fn test_shell_modifiers() {
+        let spec = SpecializationData::new_2d();
+
+        let pairs = [
+            (
+                "onion",
+                Circle::new(1.0).onion(0.25).shape(),
+                Ring::new(1.0, 0.25).shape(),
+            ),
+            (
+                "round",
+                Circle::new(0.75).round(0.25).shape(),
+                Circle::new(1.0).shape(),
+            ),
+            (
+                "hollow",
+                Circle::new(1.0).hollow(0.5).shape(),
+                Ring::new(0.75, 0.25).shape(),
+            ),
+        ];
+
+        let mut failures = vec![];
+
+        for (name, modified, expected) in pairs {
+            let modified = modified.gradient_normals().module(&spec).finalize();
+            let expected = expected.gradient_normals().module(&spec).finalize();
+
+            for context in grid() {
+                let position = vector2(context.get(&POSITION_2D.into()));
+                let found = eval(&modified, context.clone());
+                let reference = eval(&expected, context);
+
+                let d_found = number(found.get(&DISTANCE.into()));
+                let d_expected = number(reference.get(&DISTANCE.into()));
+                if (d_found - d_expected).abs() > 1e-4 {
+                    failures.push(format!(
+                        "{name}: expected distance {d_expected}, found {d_found} at {position:?}"
+                    ));
+                }
+
+                for prop in [GRADIENT_2D, UV] {
+                    let v_found = vector2(found.get(&prop.clone().into()));
+                    let v_expected = vector2(reference.get(&prop.clone().into()));
+                    if !vectors_agree(&v_found, &v_expected) {
+                        failures.push(format!(
+                            "{name}: expected {} {v_expected:?}, found {v_found:?} at {position:?}",
+                            prop.name()
+                        ));
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Color set on the source shape survives onion, round and hollow unchanged
+    #[test]
+    fn test_shell_modifiers_preserve_color() {
+        let spec = SpecializationData::new_2d();
+
+        let source = || Circle::new(1.0).set_post(COLOR, distance_color(1.0));
+
+        let cases = [
+            ("onion", source().onion(0.25).shape()),
+            ("round", source().round(0.25).shape()),
+            ("hollow", source().hollow(0.5).shape()),
+        ];
+
+        let expected = source().module(&spec).finalize();
+
+        let mut failures = vec![];
+
+        for (name, modified) in cases {
+            let modified = modified.module(&spec).finalize();
+
+            for context in grid() {
+                let position = vector2(context.get(&POSITION_2D.into()));
+                let c_found = vector4(eval(&modified, context.clone()).get(&COLOR.into()));
+                let c_expected = vector4(eval(&expected, context).get(&COLOR.into()));
+                if !vectors_agree(&c_found, &c_expected) {
+                    failures.push(format!(
+                        "{name}: expected color {c_expected:?}, found {c_found:?} at {position:?}"
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Blends never cross their hard boolean, their gradients agree with
+    /// central differences of the distance, and the chamfer, stairs and columns
+    /// families meet the hard boolean away from the seam
+    #[test]
+    fn test_blend_combinators() {
+        const H: f64 = 1e-3;
+
+        let spec = SpecializationData::new_2d();
+        let k = 0.3;
+
+        // Signs of the left input, right input and output
+        // that reduce each boolean to a union
+        let union = [1.0, 1.0, 1.0];
+        let intersection = [-1.0, -1.0, -1.0];
+        let subtraction = [-1.0, 1.0, -1.0];
+
+        let blends = [
+            (
+                "chamfer_union",
+                union,
+                true,
+                CombineBuilder::build()
+                    .push(Union)
+                    .push(ChamferUnion::new(DISTANCE, k))
+                    .push(ChamferUnion::new(GRADIENT_2D, k)),
+            ),
+            (
+                "chamfer_intersection",
+                intersection,
+                true,
+                CombineBuilder::build()
+                    .push(Intersection)
+                    .push(ChamferIntersection::new(DISTANCE, k))
+                    .push(ChamferIntersection::new(GRADIENT_2D, k)),
+            ),
+            (
+                "chamfer_subtraction",
+                subtraction,
+                true,
+                CombineBuilder::build()
+                    .push(Subtraction)
+                    .push(ChamferSubtraction::new(DISTANCE, k))
+                    .push(ChamferSubtraction::new(GRADIENT_2D, k)),
+            ),
+            (
+                "stairs_union",
+                union,
+                true,
+                CombineBuilder::build()
+                    .push(Union)
+                    .push(StairsUnion::new(DISTANCE, k, 4.0))
+                    .push(StairsUnion::new(GRADIENT_2D, k, 4.0)),
+            ),
+            (
+                "stairs_intersection",
+                intersection,
+                true,
+                CombineBuilder::build()
+                    .push(Intersection)
+                    .push(StairsIntersection::new(DISTANCE, k, 4.0))
+                    .push(StairsIntersection::new(GRADIENT_2D, k, 4.0)),
+            ),
+            (
+                "stairs_subtraction",
+                subtraction,
+                true,
+                CombineBuilder::build()
+                    .push(Subtraction)
+                    .push(StairsSubtraction::new(DISTANCE, k, 4.0))
+                    .push(StairsSubtraction::new(GRADIENT_2D, k, 4.0)),
+            ),
+            (
+                "columns_union",
+                union,
+                true,
+                CombineBuilder::build()
+                    .push(Union)
+                    .push(ColumnsUnion::new(DISTANCE, k, 3.0))
+                    .push(ColumnsUnion::new(GRADIENT_2D, k, 3.0)),
+            ),
+            (
+                "columns_intersection",
+                intersection,
+                true,
+                CombineBuilder::build()
+                    .push(Intersection)
+                    .push(ColumnsIntersection::new(DISTANCE, k, 3.0))
+                    .push(ColumnsIntersection::new(GRADIENT_2D, k, 3.0)),
+            ),
+            (
+                "columns_subtraction",
+                subtraction,
+                true,
+                CombineBuilder::build()
+                    .push(Subtraction)
+                    .push(ColumnsSubtraction::new(DISTANCE, k, 3.0))
+                    .push(ColumnsSubtraction::new(GRADIENT_2D, k, 3.0)),
+            ),
+            (
+                "exponential_smooth_union",
+                union,
+                false,
+                CombineBuilder::build()
+                    .push(Union)
+                    .push(ExponentialSmoothUnion::new(DISTANCE, 0.1))
+                    .push(ExponentialSmoothUnion::new(GRADIENT_2D, 0.1)),
+            ),
+            (
+                "power_smooth_union",
+                union,
+                false,
+                CombineBuilder::build()
+                    .push(Union)
+                    .push(PowerSmoothUnion::new(DISTANCE, 8.0))
+                    .push(PowerSmoothUnion::new(GRADIENT_2D, 8.0)),
+            ),
+        ];
+
+        let left = || Circle::new(0.5).translate([-0.3, 0.0]);
+        let right = || Circle::new(0.5).translate([0.3, 0.0]);
+
+        let left_module = left().module(&spec).finalize();
+        let right_module = right().module(&spec).finalize();
+
+        let mut failures = vec![];
+
+        for (name, [sign_a, sign_b, sign_out], exact, builder) in blends {
+            let module = builder
+                .combine()
+                .push(left())
+                .push(right())
+                .module(&spec)
+                .finalize();
+
+            for context in grid_2d([-1.5, -1.0], [1.5, 1.0], [31, 21]) {
+                let distance = |module: &Module| eval_distance(module, context.clone());
+
+                let a = distance(&left_module) * sign_a;
+                let b = distance(&right_module) * sign_b;
+                let found = distance(&module) * sign_out;
+                let hard = a.min(b);
+
+                let position = vector2(context.get(&POSITION_2D.into()));
+
+                if found > hard + 1e-4 {
+                    failures.push(format!(
+                        "{name}: blend {found} crosses hard boolean {hard} at {position:?}"
+                    ));
+                }
+
+                if exact && hard >= 0.0 && (a - b).abs() >= 2.0 * k && (found - hard).abs() > 1e-4 {
+                    failures.push(format!(
+                        "{name}: expected {hard} away from the seam, found {found} at {position:?}"
+                    ));
+                }
+            }
+
+            for context in grid_2d([-1.47, -0.97], [1.53, 1.03], [16, 11]) {
+                let position = vector2(context.get(&POSITION_2D.into()));
+                let out = eval(&module, context);
+                let found = number(out.get(&DISTANCE.into()));
+                let gradient = vector2(out.get(&GRADIENT_2D.into()));
+
+                for axis in 0..2 {
+                    let offset = |h: f64| {
+                        let mut position = position;
+                        position[axis] += h;
+                        eval_distance(&module, context_2d(position))
+                    };
+                    let forward = (offset(H) - found) / H;
+                    let backward = (found - offset(-H)) / H;
+
+                    // Skip points on a crease, where the gradient is undefined
+                    if (forward - backward).abs() > 1e-2 {
+                        continue;
+                    }
+
+                    let numeric = (forward + backward) * 0.5;
+                    if (numeric - gradient[axis]).abs() > 1e-2 {
+                        failures.push(format!(
+                            "{name}: gradient {gradient:?} differs from {numeric} on axis {axis} at {position:?}"
+                        ));
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Writes float remainders of negative positions to the context
+    #[derive(Debug, Hash)]
+    struct Remainder;
+
+    impl Domains for Remainder {
+        fn domains() -> Vec<PropertyIdentifier> {
+            vec![POSITION_2D.into(), DISTANCE.into(), UV.into()]
+        }
+    }
+
+    impl AsModule for Remainder {
+        fn module(&self, spec: &SpecializationData) -> Module {
+            let position = |axis: Identifier| [CONTEXT, POSITION_2D, axis].map(Into::into).read();
+
+            let mut block = Block::default();
+            block.push(
+                [CONTEXT, DISTANCE]
+                    .map(Into::into)
+                    .write(position(X) % 0.75.literal()),
+            );
+            block.push(
+                [CONTEXT, UV, X]
+                    .map(Into::into)
+                    .write(position(Y) % (-0.5).literal()),
+            );
+            block.push(Stmt::Output([CONTEXT.into()].read()));
+
+            Module::new(
+                self,
+                spec,
+                FunctionDefinition {
+                    id: FunctionIdentifier::new("remainder", 3817409518250413921),
+                    public: false,
+                    inputs: vec![InputDefinition {
+                        id: CONTEXT.into(),
+                        mutable: true,
+                    }],
+                    output: CONTEXT.into(),
+                    block,
+                    provenance: None,
+                },
+            )
+        }
+    }
+
+    /// Float `%` is a Euclidean remainder on every backend,
+    /// so is never negative whatever the signs of its operands
+    #[test]
+    fn test_euclidean_remainder() {
+        let module = Remainder.module(&SpecializationData::new_2d()).finalize();
+
+        for context in grid() {
+            let [x, y] = vector2(context.get(&POSITION_2D.into()));
+            let out = eval(&module, context);
+            assert_eq!(number(out.get(&DISTANCE.into())), x.rem_euclid(0.75));
+            assert_eq!(vector2(out.get(&UV.into()))[0], y.rem_euclid(-0.5));
+        }
+
+        Differential::new()
+            .evaluator("interpreter", Interpreted(&module))
+            .evaluator("naga", NagaEvaluated::new(&module).unwrap())
+            .evaluator("cranelift", CraneliftCompiled::new(&module).unwrap())
+            .run(
+                grid()
+                    .into_iter()
+                    .map(|context| complete_context(&module, context)),
+            )
+            .unwrap();
+    }
+
+    /// Noise stays within its amplitude, varies, and repeats with the hash lattice
+    #[test]
+    fn test_noise_range() {
+        let mut failures = vec![];
+
+        for (name, noise) in [
+            ("value", Noise::value(1.0).amplitude(0.5)),
+            ("gradient", Noise::gradient(1.0).amplitude(0.5)),
+        ] {
+            for spec in [SpecializationData::new_2d(), SpecializationData::new_3d()] {
+                let module = noise.clone().module(&spec).finalize();
+                let evaluate = |context: Struct| eval_distance(&module, context);
+
+                let contexts: Vec<_> = if spec.contains(&POSITION_2D.into()) {
+                    grid_2d([-3.1, -2.9], [3.3, 3.5], [17, 17]).collect()
+                } else {
+                    grid_3d([-3.1, -2.9, -2.7], [3.3, 3.5, 3.7], [7, 7, 7]).collect()
+                };
+
+                let samples: Vec<_> = contexts.iter().cloned().map(evaluate).collect();
+                let (min, max) = samples
+                    .iter()
+                    .fold((f64::MAX, f64::MIN), |(lo, hi), d| (lo.min(*d), hi.max(*d)));
+
+                if min < -0.5 - 1e-4 || max > 0.5 + 1e-4 {
+                    failures.push(format!("{name}: {min}..{max} exceeds amplitude"));
+                }
+
+                if max - min < 0.1 {
+                    failures.push(format!("{name}: {min}..{max} is nearly constant"));
+                }
+
+                // Offsetting by one lattice period leaves the noise unchanged
+                for (context, expected) in contexts.iter().zip(&samples) {
+                    let mut context = context.clone();
+                    let position = if spec.contains(&POSITION_2D.into()) {
+                        POSITION_2D
+                    } else {
+                        POSITION_3D
+                    };
+                    let Value::Struct(mut p) = context.get(&position.clone().into()) else {
+                        unreachable!()
+                    };
+                    let x = number(p.get(&X.into()));
+                    p.set_mut(X.into(), (x - 289.0).into());
+                    context.set_mut(position.into(), Value::Struct(p));
+
+                    let found = evaluate(context);
+                    if (found - expected).abs() > 1e-3 {
+                        failures.push(format!("{name}: {found} != {expected} one period away"));
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Non-uniform scale bounds the exact distance to the equivalent ellipse, and flags it as such
+    #[test]
+    fn test_scale_axes_bound() {
+        let shapes: Vec<_> = test_shapes::all_shapes().into_iter().collect();
+        let module = |name: &str| {
+            shapes
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, module)| module)
+                .unwrap()
+        };
+
+        let stretched = module("stretched_circle");
+        let ellipse = module("ellipse");
+
+        let mut failures = vec![];
+
+        for context in grid() {
+            let position = vector2(context.get(&POSITION_2D.into()));
+            let bound = eval(stretched, context.clone());
+            let d_bound = number(bound.get(&DISTANCE.into()));
+            let d_exact = eval_distance(ellipse, context);
+
+            if d_bound.signum() != d_exact.signum() || d_bound.abs() > d_exact.abs() + 1e-4 {
+                failures.push(format!(
+                    "{d_bound} does not bound {d_exact} at {position:?}"
+                ));
+            }
+
+            if bound.get(&DISTANCE_BOUND.into()) != Value::Boolean(true) {
+                failures.push(format!("Distance not flagged as a bound at {position:?}"));
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// A negative factor mirrors the shape along its axis,
+    /// rather than flipping the sign of its distance
+    #[test]
+    fn test_scale_axes_mirror() {
+        let spec = SpecializationData::new_2d();
+
+        let shape = |factors: [f64; 2]| {
+            Circle::new(0.5)
+                .translate([0.3, 0.1])
+                .scale_axes(factors)
+                .gradient_normals()
+                .module(&spec)
+                .finalize()
+        };
+
+        let scaled = shape([1.8, 1.0]);
+        let mirrored = shape([-1.8, 1.0]);
+
+        let mut failures = vec![];
+
+        for context in grid() {
+            let [x, y] = vector2(context.get(&POSITION_2D.into()));
+            let expected = eval(&scaled, context_2d([-x, y]));
+            let found = eval(&mirrored, context);
+
+            let [expected_distance, found_distance] =
+                [&expected, &found].map(|out| number(out.get(&DISTANCE.into())));
+            let [gx, gy] = vector2(expected.get(&GRADIENT_2D.into()));
+            let expected_gradient = [-gx, gy];
+            let found_gradient = vector2(found.get(&GRADIENT_2D.into()));
+
+            if (expected_distance - found_distance).abs() > 1e-4 {
+                failures.push(format!(
+                    "Expected distance {expected_distance}, found {found_distance} at {:?}",
+                    [x, y]
+                ));
+            }
+
+            if expected_gradient
+                .iter()
+                .zip(found_gradient)
+                .any(|(e, f)| (e - f).abs() > 1e-4)
+            {
+                failures.push(format!(
+                    "Expected gradient {expected_gradient:?}, found {found_gradient:?} at {:?}",
+                    [x, y]
+                ));
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// A taper whose cross-section collapses within its bounded height
+    /// still divides distance by a finite, positive factor,
+    /// so the bound keeps the deformed distance's sign instead of flipping or vanishing
+    #[test]
+    fn test_taper_degenerate_scale() {
+        let spec = SpecializationData::new_2d();
+
+        let mut failures = vec![];
+
+        // `rate * height` reaching and exceeding 1
+        for height in [2.0, 4.0] {
+            let raw = RoundedBox::new([0.5, 1.0], 0.0)
+                .modify()
+                .push_pre(Taper {
+                    rate: 0.5.expr(),
+                    radius: 1.5.expr(),
+                    height: height.expr(),
+                })
+                .gradient_normals()
+                .module(&spec)
+                .finalize();
+
+            let bounded = RoundedBox::new([0.5, 1.0], 0.0)
+                .taper(0.5, 1.5, height)
+                .gradient_normals()
+                .module(&spec)
+                .finalize();
+
+            for context in grid() {
+                let distance = |module: &Module| eval_distance(module, context.clone());
+
+                let d_raw = distance(&raw);
+                let d_bounded = distance(&bounded);
+
+                let position = vector2(context.get(&POSITION_2D.into()));
+
+                if !d_bounded.is_finite()
+                    || d_bounded * d_raw <= 0.0
+                    || d_bounded.abs() > d_raw.abs()
+                {
+                    failures.push(format!(
+                        "height {height}: bound {d_bounded} does not bound {d_raw} at {position:?}"
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Lipschitz-corrected deformations never change distance faster than the position,
+    /// and flag it as a bound
+    #[test]
+    fn test_deformation_lipschitz() {
+        const H: f64 = 1e-3;
+
+        let mut failures = vec![];
+
+        let shapes = test_shapes::all_shapes()
+            .into_iter()
+            .map(|(name, module)| (name, module, POSITION_2D, grid()))
+            .chain(
+                test_shapes::spatial_shapes()
+                    .into_iter()
+                    .map(|(name, module)| (name, module, POSITION_3D, spatial_grid())),
+            )
+            .filter(|(name, ..)| DEFORMED.contains(name));
+
+        for (name, module, position, contexts) in shapes {
+            for context in contexts {
+                let out = eval(&module, context.clone());
+
+                if out.get(&DISTANCE_BOUND.into()) != Value::Boolean(true) {
+                    failures.push(format!("{name}: distance not flagged as a bound"));
+                    break;
+                }
+
+                let Value::Struct(p) = context.get(&position.clone().into()) else {
+                    unreachable!()
+                };
+
+                let axes = if position == POSITION_2D {
+                    vec![X, Y]
+                } else {
+                    vec![X, Y, Z]
+                };
+
+                let gradient = axes
+                    .into_iter()
+                    .map(|axis| {
+                        let x = number(p.get(&axis.clone().into()));
+                        let offset = |delta: f64| {
+                            let p = p.clone().set(axis.clone().into(), (x + delta).into());
+                            let context = context
+                                .clone()
+                                .set(position.clone().into(), Value::Struct(p));
+                            eval_distance(&module, context)
+                        };
+                        (offset(H) - offset(-H)) / (2.0 * H)
+                    })
+                    .collect::<Vec<_>>();
+
+                let norm = gradient.iter().map(|d| d * d).sum::<f64>().sqrt();
+                if norm > 1.0 + 1e-3 {
+                    failures.push(format!(
+                        "{name}: gradient {gradient:?} exceeds unit length at {p}"
+                    ));
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Analytic 2D gradients of the primitives and deformed shapes
+    /// agree with central differences of the distance
+    #[test]
+    fn test_primitive_gradients() {
+        const H: f64 = 1e-3;
+
+        let mut failures = vec![];
+
+        for (name, module) in test_shapes::all_shapes() {
+            if !PRIMITIVES.contains(&name) && !DEFORMED.contains(&name) {
+                continue;
+            }
+
+            let distance = |position: [f64; 2]| eval_distance(&module, context_2d(position));
+
+            for context in grid_2d([-1.17, -1.09], [1.23, 1.31], [9, 9]) {
+                let position = vector2(context.get(&POSITION_2D.into()));
+                let gradient = vector2(eval(&module, context).get(&GRADIENT_2D.into()));
+
+                for axis in 0..2 {
+                    let mut lo = position;
+                    let mut hi = position;
+                    lo[axis] -= H;
+                    hi[axis] += H;
+                    let numeric = (distance(hi) - distance(lo)) / (2.0 * H);
+
+                    if (numeric - gradient[axis]).abs() > 1e-2 {
+                        failures.push(format!(
+                            "{name}: gradient {gradient:?} differs from {numeric} on axis {axis} at {position:?}"
+                        ));
+                        break;
+                    }
+                }
+            }
+        }
+
+        assert_no_failures(failures);
+    }
+
+    /// Rasterized primitives match the images checked in under `golden/`
+    ///
+    /// Channels may differ by a small amount to tolerate platform math libraries.
+    #[test]
+    fn test_primitive_goldens() {
+        const SIZE: u32 = 32;
+        const TOLERANCE: u8 = 2;
+
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("golden");
+        let bless = std::env::var_os("ELYSIAN_BLESS").is_some();
+
+        let mut failures = vec![];
+
+        for (name, module) in test_shapes::all_shapes() {
+            if !PRIMITIVES.contains(&name) {
+                continue;
+            }
+
+            let image =
+                rasterize::<Rgb<u8>>(Interpreted(&module), SIZE, SIZE, color_to_rgb8).unwrap();
+            let path = dir.join(format!("{name}.png"));
+
+            if bless {
+                std::fs::create_dir_all(&dir).unwrap();
+                image.save(&path).unwrap();
+                continue;
+            }
+
+            let golden = match image::open(&path) {
+                Ok(golden) => golden.to_rgb8(),
+                Err(e) => {
+                    failures.push(format!("{name}: failed to open {}: {e}", path.display()));
+                    continue;
+                }
+            };
+
+            let mismatches = image
+                .pixels()
+                .zip(golden.pixels())
+                .filter(|(lhs, rhs)| {
+                    lhs.0
+                        .iter()
+                        .zip(rhs.0.iter())
+                        .any(|(lhs, rhs)| lhs.abs_diff(*rhs) > TOLERANCE)
+                })
+                .count();
+
+            if golden.dimensions() != image.dimensions() || mismatches > 0 {
+                failures.push(format!(
+                    "{name}: {mismatches} pixels differ from {}",
+                    path.display()
+                ));
+            }
+        }
+
+        assert!(
+            failures.is_empty(),
+            "{}\nrerun with ELYSIAN_BLESS=1 to update",
+            failures.join("\n")
+        );
+    }
+
+    /// Calls a generated shape's concretely-typed entry point directly
+    struct Typed;
+
+    impl<'a> Evaluate<'a> for Typed {
+        fn evaluate(&self, context: Struct) -> Result<Struct, EvaluateError> {
+            Ok(circle::evaluate(circle::Context::from(context)).into())
+        }
+    }
+
+    #[test]
+    fn test_typed_entry_point() {
+        let (_, module) = test_shapes::all_shapes()
+            .into_iter()
+            .find(|(name, _)| *name == "circle")
+            .unwrap();
+
+        let result = Differential::new()
+            .evaluator("interpreter", Interpreted(&module))
+            .evaluator("typed", Typed)
+            .run(
+                grid()
+                    .into_iter()
+                    .map(|context| complete_context(&module, context)),
+            );
+
+        if let Err(e) = result {
+            panic!("{e}");
+        }
+    }
+
+    #[test]
+    fn test_static_specializations() {
+        let spec_2d = SpecializationData::new_2d();
+        let spec_3d = SpecializationData::new_3d();
+
+        // Both namespaces register a circle without colliding
+        let circles: Vec<_> = registered_shapes()
+            .filter(|shape| shape.name == "circle")
+            .map(|shape| shape.path)
+            .collect();
+        assert_eq!(circles.len(), 2, "{circles:?}");
+
+        let circle_2d = test_shapes::circle().module(&spec_2d).finalize();
+        let circle_3d = test_shapes::circle().module(&spec_3d).finalize();
+        assert_eq!(circle_2d.hash, circle_3d.hash);
+
+        let context =
+            |module: &Module| complete_context(module, Struct::new(StructIdentifier(CONTEXT)));
+
+        Precompiled(&circle_2d)
+            .evaluate(context(&circle_2d))
+            .expect("2D circle should be precompiled");
+        Precompiled(&circle_3d)
+            .evaluate(context(&circle_3d))
+            .expect("3D circle should be precompiled");
+
+        // Only the 2D line is registered, so its 3D specialization misses
+        let line_3d = test_shapes::line().module(&spec_3d).finalize();
+        let err = Precompiled(&line_3d)
+            .evaluate(context(&line_3d))
+            .expect_err("3D line should not be precompiled");
+
+        let Some(PrecompiledError::MissingShape(miss)) = err.downcast_ref::<PrecompiledError>()
+        else {
+            panic!("Unexpected error {err}");
+        };
+        assert_eq!(miss.candidates, vec!["test_differential::line"]);
+    }
+
+    #[test]
+    fn test_naga_rasterize() {
+        let module = test_shapes::test_shape()
+            .module(&SpecializationData::new_2d())
+            .finalize();
+
+        let interpreted =
+            rasterize::<Rgb<u8>>(Interpreted(&module), 33, 33, color_to_rgb8).unwrap();
+        let naga =
+            rasterize::<Rgb<u8>>(NagaEvaluated::new(&module).unwrap(), 33, 33, color_to_rgb8)
+                .unwrap();
+
+        assert_eq!(interpreted, naga);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_expensive_shapes_agree() {
+        shapes_agree(
+            test_shapes::all_shapes(),
+            |name| EXPENSIVE.contains(&name),
+            &grid(),
+            None,
+        )
+    }
+
+    /// Builds a crate per shape with the local toolchain, so is opt-in like the expensive shapes
+    #[test]
+    #[ignore]
+    fn test_jit_shapes_agree() {
+        let jit = JitCompiler::new(
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../crates/elysian-math"),
+        );
+
+        shapes_agree(test_shapes::all_shapes(), |_| true, &grid(), Some(&jit));
+        shapes_agree(
+            test_shapes::spatial_shapes(),
+            |_| true,
+            &spatial_grid(),
+            Some(&jit),
+        );
+    }
+}
@@ -4,7 +4,7 @@ use elysian::{
     image::{color_to_rgb8, rasterize},
     interpreter::Interpreted,
     ir::module::{AsModule, Dispatch, EvaluateError, SpecializationData},
-    r#static::{include_static_shapes, Precompiled},
+    r#static::{include_static_shapes, set_miss_handler, warn_on_miss, Precompiled},
 };
 use image::Rgb;
 use viuer::Config;
@@ -12,6 +12,8 @@ use viuer::Config;
 include_static_shapes!();
 
 fn main() -> Result<(), EvaluateError> {
+    set_miss_handler(warn_on_miss);
+
     let shape = test_shapes::pangram()
         .module(&SpecializationData::new_2d())
         .finalize();
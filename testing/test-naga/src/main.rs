@@ -1,6 +1,28 @@
 use std::error::Error;
 
 use elysian::{ir::module::SpecializationData, shadertoy::module_to_shadertoy};
+use naga::{
+    front::glsl::{Frontend, Options},
+    valid::{Capabilities, ValidationFlags, Validator},
+    ShaderStage,
+};
+
+/// Stand-in for the uniforms and entry point Shadertoy provides
+const SHADERTOY_PRELUDE: &str = "#version 450
+layout(set = 0, binding = 0) uniform Shadertoy {
+    vec3 iResolution;
+    float iTime;
+    vec4 iMouse;
+};
+";
+
+const SHADERTOY_MAIN: &str = "
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    mainImage(outColor, gl_FragCoord.xy);
+}
+";
 
 fn main() {
     env_logger::init();
@@ -8,10 +30,17 @@ fn main() {
 }
 
 fn main_impl() -> Result<(), Box<dyn Error>> {
-    println!(
-        "{:}",
-        module_to_shadertoy(&test_shapes::test_shape().module(&SpecializationData::new_2d()))?
-    );
+    let shadertoy =
+        module_to_shadertoy(&test_shapes::test_shape().module(&SpecializationData::new_2d()))?;
+    println!("{shadertoy:}");
+
+    // Round-trip through naga's GLSL frontend to check the output is well-formed
+    let source = format!("{SHADERTOY_PRELUDE}{shadertoy}{SHADERTOY_MAIN}");
+    let module = Frontend::default()
+        .parse(&Options::from(ShaderStage::Fragment), &source)
+        .map_err(|e| format!("{e:?}"))?;
+    Validator::new(ValidationFlags::all(), Capabilities::default()).validate(&module)?;
+
     Ok(())
 }
 
@@ -9,27 +9,43 @@ use elysian_ir::{
     },
     module::{AsModule, Module, SpecializationData, StructIdentifier, CONTEXT},
 };
-use elysian_math::glam::Mat4;
+use elysian_math::glam::{Mat4, Quat, Vec3};
 use elysian_shapes::{
     color::{
         ambient_light_color, directional_light_color, distance_color, gradient_color, normal_color,
         uv_color,
     },
     combine::{
-        Combinator, Combine, CombineBuilder, Overlay, SmoothSubtraction, SmoothUnion, Subtraction,
-        Union,
+        ChamferIntersection, ChamferUnion, ColumnsSubtraction, ColumnsUnion, Combinator, Combine,
+        CombineBuilder, ExponentialSmoothUnion, Intersection, IntoDisplace, Overlay,
+        PowerSmoothUnion, SmoothSubtraction, SmoothUnion, StairsSubtraction, StairsUnion,
+        Subtraction, Union,
+    },
+    field::{
+        Capsule, Chebyshev, Circle, Cone, Cuboid, Cylinder, Ellipse, Ellipsoid, HexPrism, Infinity,
+        Line, Octahedron, Plane, Point, Polygon, Quad, QuadraticBezier, RegularPolygon, Ring,
+        RoundedBox, Star, Torus, Triangle,
     },
-    field::{Capsule, Chebyshev, Circle, Infinity, Line, Point, Quad, Ring},
     modify::{
-        IntoAspect, IntoGradientNormals, IntoIsosurface, IntoRepeat, IntoSet, IntoTranslate,
-        IntoUvMap, ASPECT, REPEAT_ID_2D,
+        IntoAspect, IntoBend, IntoGradientNormals, IntoHollow, IntoIsosurface, IntoOnion,
+        IntoRepeat, IntoRepeatPolar, IntoRound, IntoSet, IntoTaper, IntoTranslate, IntoTwist,
+        IntoUvMap, IntoWarp, ASPECT, REPEAT_ID_2D, REPEAT_ID_POLAR,
     },
+    noise::Noise,
     prepass::IntoPrepass,
     raymarch::Raymarch,
     select::Select,
     shape::{DynShape, IntoShape, Shape},
     voronoi::{voronoi, CELL_ID},
-    wrap::{filter::IntoFilter, mirror::IntoMirror, scale::IntoScale},
+    wrap::{
+        extrude::IntoExtrude,
+        filter::IntoFilter,
+        mirror::IntoMirror,
+        revolve::IntoRevolve,
+        rotate::{EulerOrder, IntoRotate, IntoRotate3d, Rotation},
+        scale::{IntoScale, IntoScaleAxes},
+        transform::IntoTransform,
+    },
 };
 use elysian_text::glyphs::{greek::sigma, text, Align};
 
@@ -59,6 +75,263 @@ pub fn ring() -> impl IntoShape {
     Ring::new(1.0, 0.2).set_post(COLOR, uv_color())
 }
 
+pub fn triangle() -> impl IntoShape {
+    Triangle::new([-0.9, -0.6], [0.8, -0.4], [0.1, 0.9])
+        .set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn polygon() -> impl IntoShape {
+    Polygon::new([
+        [-0.9, -0.7],
+        [0.0, -0.2],
+        [0.9, -0.7],
+        [0.6, 0.8],
+        [0.0, 0.3],
+        [-0.6, 0.8],
+    ])
+    .set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn regular_polygon() -> impl IntoShape {
+    RegularPolygon::new(5.0, 0.9).set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn star() -> impl IntoShape {
+    Star::new(5.0, 0.9, 0.4).set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn rounded_box() -> impl IntoShape {
+    RoundedBox::corners([0.8, 0.5], [0.1, 0.3, 0.0, 0.5])
+        .set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn ellipse() -> impl IntoShape {
+    Ellipse::new([0.9, 0.5]).set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn stretched_circle() -> impl IntoShape {
+    Circle::new(0.5)
+        .scale_axes([1.8, 1.0])
+        .set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn quadratic_bezier() -> impl IntoShape {
+    QuadraticBezier::new([-0.9, -0.6], [0.2, 1.4], [0.8, -0.5])
+        .set_post(COLOR, gradient_color() * distance_color(1.0))
+}
+
+pub fn cuboid() -> impl IntoShape {
+    Cuboid::new([0.75, 0.5, 0.25])
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn rounded_cuboid() -> impl IntoShape {
+    Cuboid::rounded([0.75, 0.5, 0.25], 0.125)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn torus() -> impl IntoShape {
+    Torus::new(1.0, 0.25)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn cylinder() -> impl IntoShape {
+    Cylinder::new(0.5, 1.0)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn cone() -> impl IntoShape {
+    Cone::new(0.5, 1.0)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn plane() -> impl IntoShape {
+    Plane::new([0.25, 1.0, -0.5], 0.5)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn ellipsoid() -> impl IntoShape {
+    Ellipsoid::new([1.0, 0.5, 0.75])
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn octahedron() -> impl IntoShape {
+    Octahedron::new(1.0)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn hex_prism() -> impl IntoShape {
+    HexPrism::new(0.5, 1.0)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn rotated_cuboid() -> impl IntoShape {
+    Cuboid::new([0.75, 0.5, 0.25])
+        .rotate_3d(Rotation::euler(EulerOrder::XYZ, [0.3, 0.6, 0.9]))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn rotated_octahedron() -> impl IntoShape {
+    Octahedron::new(1.0)
+        .rotate_3d(Quat::from_axis_angle(
+            Vec3::new(1.0, 1.0, 0.0).normalize(),
+            0.7,
+        ))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn rotated_cylinder() -> impl IntoShape {
+    Cylinder::new(0.5, 1.0)
+        .rotate(0.5)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn transformed_torus() -> impl IntoShape {
+    Torus::new(1.0, 0.25)
+        .transform(Mat4::from_scale_rotation_translation(
+            Vec3::new(1.0, 0.5, 0.75),
+            Quat::from_rotation_x(0.4),
+            Vec3::new(0.25, 0.0, -0.125),
+        ))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn stretched_octahedron() -> impl IntoShape {
+    Octahedron::new(1.0)
+        .scale_axes([1.5, 0.75, 1.0])
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn extruded_star() -> impl IntoShape {
+    Star::new(5.0, 1.0, 0.5)
+        .extrude(0.25)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn revolved_box() -> impl IntoShape {
+    RoundedBox::new([0.25, 0.5], 0.1)
+        .revolve([0.0, 1.0, 0.0], 0.75)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn value_noise() -> impl IntoShape {
+    Noise::value(2.0)
+        .fbm(3, 2.0, 0.5)
+        .seed(7)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn gradient_noise() -> impl IntoShape {
+    Noise::gradient(1.5)
+        .fbm(4, 2.0, 0.5)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn warped_circle() -> impl IntoShape {
+    Circle::new(0.75)
+        .warp(Noise::gradient(1.5).amplitude(0.2))
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn displaced_circle() -> impl IntoShape {
+    Circle::new(0.75)
+        .displace(Noise::value(3.0).amplitude(0.1))
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn bent_box() -> impl IntoShape {
+    RoundedBox::new([1.0, 0.25], 0.05)
+        .bend(0.5, 4.0)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn tapered_box() -> impl IntoShape {
+    RoundedBox::new([0.5, 1.0], 0.0)
+        .taper(0.2, 3.5, 2.5)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn twisted_cuboid() -> impl IntoShape {
+    Cuboid::new([0.5, 1.0, 0.5])
+        .twist([0.0, 1.0, 0.0], 1.0, 3.5)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn bent_cuboid() -> impl IntoShape {
+    Cuboid::new([1.0, 0.25, 0.25])
+        .bend(0.5, 4.0)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn tapered_cylinder() -> impl IntoShape {
+    Cylinder::new(0.5, 1.0)
+        .taper(0.2, 3.5, 2.5)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn polar_select() -> impl IntoShape {
+    Select::new(
+        RoundedBox::new([0.25, 0.1], 0.05)
+            .translate([1.25, 0.0])
+            .set_post(COLOR, distance_color(1.0)),
+    )
+    .case(
+        REPEAT_ID_POLAR.prop().read().lt(2.5),
+        Circle::new(0.25)
+            .translate([1.25, 0.0])
+            .set_post(COLOR, distance_color(1.0)),
+    )
+    .repeat_polar(6.0)
+}
+
+pub fn polar_cuboids() -> impl IntoShape {
+    Cuboid::new([0.15, 0.5, 0.15])
+        .translate([1.0, 0.0, 0.0])
+        .repeat_polar_axis(8.0, [0.0, 1.0, 0.0])
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn layered_onion() -> impl IntoShape {
+    Circle::new(1.0)
+        .onion(0.3)
+        .onion(0.1)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn rounded_star() -> impl IntoShape {
+    Star::new(5.0, 1.0, 0.5)
+        .round(0.1)
+        .set_post(COLOR, distance_color(1.0))
+}
+
+pub fn hollow_cuboid() -> impl IntoShape {
+    Cuboid::new([0.75, 0.5, 0.5])
+        .round(0.1)
+        .hollow(0.1)
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
 pub fn union() -> impl IntoShape {
     Combine::from(Union).push(circle()).push(line())
 }
@@ -97,6 +370,99 @@ pub fn kettle_bell() -> impl IntoShape {
         .set_post(COLOR, uv_color())
 }
 
+pub fn chamfer_union() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Union)
+        .push(ChamferUnion::new(DISTANCE, 0.3))
+        .push(ChamferUnion::new(GRADIENT_2D, 0.3))
+        .push(ChamferUnion::new(UV, 0.3))
+        .combine()
+        .push(circle())
+        .push(line())
+}
+
+pub fn stairs_subtraction() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Subtraction)
+        .push(StairsSubtraction::new(DISTANCE, 0.3, 4.0))
+        .push(StairsSubtraction::new(GRADIENT_2D, 0.3, 4.0))
+        .push(StairsSubtraction::new(UV, 0.3, 4.0))
+        .combine()
+        .push(RoundedBox::new([0.8, 0.6], 0.0))
+        .push(Circle::new(0.5).translate([0.8, 0.6]))
+        .gradient_normals()
+        .set_post(COLOR, uv_color())
+}
+
+pub fn columns_union() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Union)
+        .push(ColumnsUnion::new(DISTANCE, 0.4, 3.0))
+        .push(ColumnsUnion::new(GRADIENT_2D, 0.4, 3.0))
+        .push(ColumnsUnion::new(UV, 0.4, 3.0))
+        .combine()
+        .push(circle())
+        .push(line())
+}
+
+pub fn exponential_union() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Union)
+        .push(ExponentialSmoothUnion::new(DISTANCE, 0.1))
+        .push(ExponentialSmoothUnion::new(GRADIENT_2D, 0.1))
+        .push(ExponentialSmoothUnion::new(UV, 0.1))
+        .combine()
+        .push(circle())
+        .push(line())
+}
+
+pub fn power_union() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Union)
+        .push(PowerSmoothUnion::new(DISTANCE, 8.0))
+        .push(PowerSmoothUnion::new(GRADIENT_2D, 8.0))
+        .push(PowerSmoothUnion::new(UV, 8.0))
+        .combine()
+        .push(circle())
+        .push(line())
+}
+
+pub fn stairs_cuboids() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Union)
+        .push(StairsUnion::new(DISTANCE, 0.3, 3.0))
+        .push(StairsUnion::new(GRADIENT_3D, 0.3, 3.0))
+        .combine()
+        .push(Cuboid::new([0.75, 0.25, 0.25]))
+        .push(Cuboid::new([0.25, 0.75, 0.25]).translate([0.25, 0.0, 0.0]))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn chamfered_cuboid() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Intersection)
+        .push(ChamferIntersection::new(DISTANCE, 0.2))
+        .push(ChamferIntersection::new(GRADIENT_3D, 0.2))
+        .combine()
+        .push(Cuboid::new([0.75, 0.5, 0.5]))
+        .push(Octahedron::new(1.2))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
+pub fn columns_cuboid() -> impl IntoShape {
+    CombineBuilder::build()
+        .push(Subtraction)
+        .push(ColumnsSubtraction::new(DISTANCE, 0.3, 3.0))
+        .push(ColumnsSubtraction::new(GRADIENT_3D, 0.3, 3.0))
+        .combine()
+        .push(Cuboid::new([0.75, 0.5, 0.5]))
+        .push(Cylinder::new(0.4, 1.0))
+        .gradient_normals()
+        .set_post(COLOR, normal_color())
+}
+
 pub fn select() -> impl IntoShape {
     let id_x = REPEAT_ID_2D.path().push(X).read();
     let id_x_lt = |t: f64| id_x.clone().lt(t);
@@ -304,3 +670,93 @@ pub fn shapes() -> impl IntoIterator<Item = (&'static str, Module)> {
             .finalize(),
     )]
 }
+
+/// Every named 2D test shape, for harnesses that exercise all of them
+pub fn all_shapes() -> impl IntoIterator<Item = (&'static str, Module)> {
+    let spec = SpecializationData::new_2d();
+
+    [
+        ("point", point().module(&spec)),
+        ("chebyshev", chebyshev().module(&spec)),
+        ("line", line().module(&spec)),
+        ("circle", circle().module(&spec)),
+        ("capsule", capsule().module(&spec)),
+        ("ring", ring().module(&spec)),
+        ("triangle", triangle().module(&spec)),
+        ("polygon", polygon().module(&spec)),
+        ("regular_polygon", regular_polygon().module(&spec)),
+        ("star", star().module(&spec)),
+        ("rounded_box", rounded_box().module(&spec)),
+        ("ellipse", ellipse().module(&spec)),
+        ("quadratic_bezier", quadratic_bezier().module(&spec)),
+        ("stretched_circle", stretched_circle().module(&spec)),
+        ("value_noise", value_noise().module(&spec)),
+        ("gradient_noise", gradient_noise().module(&spec)),
+        ("warped_circle", warped_circle().module(&spec)),
+        ("displaced_circle", displaced_circle().module(&spec)),
+        ("bent_box", bent_box().module(&spec)),
+        ("polar_select", polar_select().module(&spec)),
+        ("layered_onion", layered_onion().module(&spec)),
+        ("rounded_star", rounded_star().module(&spec)),
+        ("tapered_box", tapered_box().module(&spec)),
+        ("union", union().module(&spec)),
+        ("smooth_union", smooth_union().module(&spec)),
+        ("kettle_bell", kettle_bell().module(&spec)),
+        ("chamfer_union", chamfer_union().module(&spec)),
+        ("stairs_subtraction", stairs_subtraction().module(&spec)),
+        ("columns_union", columns_union().module(&spec)),
+        ("exponential_union", exponential_union().module(&spec)),
+        ("power_union", power_union().module(&spec)),
+        ("select", select().module(&spec)),
+        ("raymarched", raymarched().module(&spec)),
+        ("partition", partition().module(&spec)),
+        ("pangram", pangram().module(&spec)),
+        ("composite", composite().module(&spec)),
+        ("ngon", ngon(5, 1.0).module(&spec)),
+        ("test_shape", test_shape().module(&spec)),
+    ]
+    .into_iter()
+    .map(|(name, module)| (name, module.finalize()))
+}
+
+/// 3D specializations of a subset of the test shapes,
+/// sharing names and hashes with their [`all_shapes`] counterparts,
+/// followed by the 3D-only primitives
+pub fn spatial_shapes() -> impl IntoIterator<Item = (&'static str, Module)> {
+    let spec = SpecializationData::new_3d();
+
+    [
+        ("point", point().module(&spec)),
+        ("circle", circle().module(&spec)),
+        ("cuboid", cuboid().module(&spec)),
+        ("rounded_cuboid", rounded_cuboid().module(&spec)),
+        ("torus", torus().module(&spec)),
+        ("cylinder", cylinder().module(&spec)),
+        ("cone", cone().module(&spec)),
+        ("plane", plane().module(&spec)),
+        ("ellipsoid", ellipsoid().module(&spec)),
+        ("octahedron", octahedron().module(&spec)),
+        ("hex_prism", hex_prism().module(&spec)),
+        ("rotated_cuboid", rotated_cuboid().module(&spec)),
+        ("rotated_octahedron", rotated_octahedron().module(&spec)),
+        ("rotated_cylinder", rotated_cylinder().module(&spec)),
+        ("transformed_torus", transformed_torus().module(&spec)),
+        ("stretched_octahedron", stretched_octahedron().module(&spec)),
+        ("extruded_star", extruded_star().module(&spec)),
+        ("revolved_box", revolved_box().module(&spec)),
+        ("value_noise", value_noise().module(&spec)),
+        ("gradient_noise", gradient_noise().module(&spec)),
+        ("warped_circle", warped_circle().module(&spec)),
+        ("displaced_circle", displaced_circle().module(&spec)),
+        ("twisted_cuboid", twisted_cuboid().module(&spec)),
+        ("bent_cuboid", bent_cuboid().module(&spec)),
+        ("tapered_cylinder", tapered_cylinder().module(&spec)),
+        ("polar_cuboids", polar_cuboids().module(&spec)),
+        ("hollow_cuboid", hollow_cuboid().module(&spec)),
+        ("stairs_cuboids", stairs_cuboids().module(&spec)),
+        ("chamfered_cuboid", chamfered_cuboid().module(&spec)),
+        ("columns_cuboid", columns_cuboid().module(&spec)),
+    ]
+    .into_iter()
+    .map(|(name, module)| (name, module.finalize()))
+}